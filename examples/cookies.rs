@@ -45,7 +45,8 @@ fn main() -> wry::Result<()> {
       .inner(),
   )?;
 
-  let cookie_deleted = cookie::Cookie::build(("will_be_deleted", "will_be_deleted"));
+  let cookie_deleted =
+    cookie::Cookie::build(("will_be_deleted", "will_be_deleted")).domain("www.httpbin.org");
 
   webview.set_cookie(cookie_deleted.inner())?;
   println!("Setting Cookies:");
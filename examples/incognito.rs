@@ -0,0 +1,89 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use tao::{
+  event::{Event, WindowEvent},
+  event_loop::{ControlFlow, EventLoop},
+  window::{Window, WindowBuilder},
+};
+use wry::{WebView, WebViewBuilder};
+
+const URL: &str = "https://www.httpbin.org/cookies";
+
+fn build(builder: WebViewBuilder, window: &Window) -> wry::Result<WebView> {
+  #[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+  ))]
+  {
+    builder.build(window)
+  }
+  #[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+  )))]
+  {
+    use tao::platform::unix::WindowExtUnix;
+    use wry::WebViewBuilderExtUnix;
+    let vbox = window.default_vbox().unwrap();
+    builder.build_gtk(vbox)
+  }
+}
+
+fn main() -> wry::Result<()> {
+  let event_loop = EventLoop::new();
+  let incognito_window = WindowBuilder::new()
+    .with_title("Incognito")
+    .build(&event_loop)
+    .unwrap();
+  let persistent_window = WindowBuilder::new()
+    .with_title("Persistent")
+    .build(&event_loop)
+    .unwrap();
+
+  let incognito = build(
+    WebViewBuilder::new().with_incognito(true).with_url(URL),
+    &incognito_window,
+  )?;
+  incognito.set_cookie(
+    &cookie::Cookie::build(("incognito", "1"))
+      .domain("www.httpbin.org")
+      .path("/")
+      .secure(true)
+      .build(),
+  )?;
+  println!("Incognito cookies:");
+  for cookie in incognito.cookies_for_url(URL)? {
+    println!("\t{cookie}");
+  }
+
+  let persistent = build(WebViewBuilder::new().with_url(URL), &persistent_window)?;
+  let leaked = persistent
+    .cookies_for_url(URL)?
+    .into_iter()
+    .any(|cookie| cookie.name() == "incognito");
+  assert!(
+    !leaked,
+    "the incognito cookie leaked into the persistent webview"
+  );
+  println!("The persistent webview doesn't see the incognito cookie");
+
+  let mut webviews = Some((incognito, persistent));
+  event_loop.run(move |event, _, control_flow| {
+    *control_flow = ControlFlow::Wait;
+
+    if let Event::WindowEvent {
+      event: WindowEvent::CloseRequested,
+      ..
+    } = event
+    {
+      webviews.take();
+      *control_flow = ControlFlow::Exit;
+    }
+  });
+}
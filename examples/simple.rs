@@ -23,14 +23,21 @@ fn main() -> wry::Result<()> {
   #[cfg(feature = "drag-drop")]
   let builder = builder.with_drag_drop_handler(|e| {
     match e {
-      wry::DragDropEvent::Enter { paths, position } => {
+      wry::DragDropEvent::Enter {
+        paths, position, ..
+      } => {
         println!("DragEnter: {position:?} {paths:?} ")
       }
-      wry::DragDropEvent::Over { position } => println!("DragOver: {position:?} "),
+      wry::DragDropEvent::Over {
+        paths, position, ..
+      } => {
+        println!("DragOver: {position:?} {paths:?} ")
+      }
       wry::DragDropEvent::Drop { paths, position } => {
         println!("DragDrop: {position:?} {paths:?} ")
       }
       wry::DragDropEvent::Leave => println!("DragLeave"),
+      wry::DragDropEvent::Cancelled => println!("DragCancelled"),
       _ => {}
     }
 
@@ -0,0 +1,93 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+fn main() -> wry::Result<()> {
+  imp::main()
+}
+
+#[cfg(not(feature = "protocol"))]
+mod imp {
+  pub fn main() -> wry::Result<()> {
+    eprintln!("this example requires the `protocol` feature");
+    Ok(())
+  }
+}
+
+#[cfg(feature = "protocol")]
+mod imp {
+  use tao::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::WindowBuilder,
+  };
+  use wry::{
+    http::{header::CONTENT_TYPE, Response, StatusCode},
+    WebViewBuilder,
+  };
+
+  const INDEX: &str = r#"<html>
+  <body style="background-color:rgba(87,87,87,0.5);">
+    <h1>Served from a custom protocol</h1>
+  </body>
+</html>"#;
+
+  pub fn main() -> wry::Result<()> {
+    let event_loop = EventLoop::new();
+    let window = WindowBuilder::new()
+      .with_decorations(false)
+      .with_transparent(true)
+      .build(&event_loop)
+      .unwrap();
+
+    let builder = WebViewBuilder::new()
+      // the webview stays transparent until the custom protocol response is painted
+      .with_transparent(true)
+      .with_custom_protocol("wry".into(), move |_webview_id, request| {
+        let response = if request.uri().path() == "/" {
+          Response::builder()
+            .header(CONTENT_TYPE, "text/html")
+            .body(INDEX.as_bytes().to_vec())
+        } else {
+          Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(CONTENT_TYPE, "text/plain")
+            .body(b"Not Found".to_vec())
+        };
+        response.unwrap().map(Into::into)
+      })
+      .with_url("wry://localhost");
+
+    #[cfg(any(
+      target_os = "windows",
+      target_os = "macos",
+      target_os = "ios",
+      target_os = "android"
+    ))]
+    let _webview = builder.build(&window)?;
+    #[cfg(not(any(
+      target_os = "windows",
+      target_os = "macos",
+      target_os = "ios",
+      target_os = "android"
+    )))]
+    let _webview = {
+      use tao::platform::unix::WindowExtUnix;
+      use wry::WebViewBuilderExtUnix;
+      let vbox = window.default_vbox().unwrap();
+      builder.build_gtk(vbox)?
+    };
+
+    event_loop.run(move |event, _, control_flow| {
+      *control_flow = ControlFlow::Wait;
+
+      if let Event::WindowEvent {
+        event: WindowEvent::CloseRequested,
+        ..
+      } = event
+      {
+        *control_flow = ControlFlow::Exit
+      }
+    });
+  }
+}
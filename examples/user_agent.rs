@@ -0,0 +1,81 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use tao::{
+  event::{Event, WindowEvent},
+  event_loop::{ControlFlow, EventLoopBuilder},
+  window::WindowBuilder,
+};
+use wry::WebViewBuilder;
+
+const DESKTOP_USER_AGENT: &str =
+  "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Safari/605.1.15";
+const MOBILE_USER_AGENT: &str =
+  "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+fn main() -> wry::Result<()> {
+  let event_loop = EventLoopBuilder::new().build();
+  let proxy = event_loop.create_proxy();
+  let window = WindowBuilder::new().build(&event_loop).unwrap();
+
+  // The server echoes the user agent it received
+  let builder = WebViewBuilder::new()
+    .with_url("https://httpbin.org/user-agent")
+    .with_user_agent(DESKTOP_USER_AGENT)
+    .with_initialization_script(
+      r#"window.addEventListener('DOMContentLoaded', () => {
+        const button = document.createElement('button');
+        button.textContent = 'Switch user agent';
+        button.onclick = () => window.ipc.postMessage('switch');
+        document.body.prepend(button);
+      });"#,
+    )
+    .with_ipc_handler(move |_| {
+      let _ = proxy.send_event(());
+    });
+
+  #[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+  ))]
+  let webview = builder.build(&window)?;
+  #[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+  )))]
+  let webview = {
+    use tao::platform::unix::WindowExtUnix;
+    use wry::WebViewBuilderExtUnix;
+    let vbox = window.default_vbox().unwrap();
+    builder.build_gtk(vbox)?
+  };
+
+  let mut mobile = false;
+  event_loop.run(move |event, _, control_flow| {
+    *control_flow = ControlFlow::Wait;
+
+    match event {
+      Event::UserEvent(()) => {
+        mobile = !mobile;
+        let user_agent = if mobile {
+          MOBILE_USER_AGENT
+        } else {
+          DESKTOP_USER_AGENT
+        };
+        // The new user agent is sent with the requests of the next navigation
+        webview.set_user_agent(user_agent).unwrap();
+        webview.reload().unwrap();
+      }
+      Event::WindowEvent {
+        event: WindowEvent::CloseRequested,
+        ..
+      } => *control_flow = ControlFlow::Exit,
+      _ => {}
+    }
+  });
+}
@@ -0,0 +1,234 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::script::{json_string, parse_json_string_array};
+
+/// A node of the accessibility tree of a page, passed to the callback of
+/// [`WebView::accessibility_snapshot`](crate::WebView::accessibility_snapshot).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessibilityNode {
+  /// The [ARIA role](https://www.w3.org/TR/wai-aria/#role_definitions) of the node, e.g.
+  /// `button` or `heading`, `document` for the root and `text` for the text of the page.
+  pub role: String,
+  /// The accessible name of the node, e.g. the label of a button or the text of a link.
+  pub name: String,
+  /// The value of the node, e.g. the text of a text field, `true` or `false` for checkboxes,
+  /// empty if the node has no value.
+  pub value: String,
+  /// The children of the node, in the order of the document.
+  pub children: Vec<AccessibilityNode>,
+}
+
+impl AccessibilityNode {
+  /// Serializes the tree to JSON, e.g. to compare it with an expected snapshot.
+  pub fn to_json(&self) -> String {
+    let mut json = String::new();
+    self.write_json(&mut json);
+    json
+  }
+
+  fn write_json(&self, json: &mut String) {
+    json.push_str(r#"{"role":"#);
+    json.push_str(&json_string(&self.role));
+    json.push_str(r#","name":"#);
+    json.push_str(&json_string(&self.name));
+    json.push_str(r#","value":"#);
+    json.push_str(&json_string(&self.value));
+    json.push_str(r#","children":["#);
+    for (i, child) in self.children.iter().enumerate() {
+      if i > 0 {
+        json.push(',');
+      }
+      child.write_json(json);
+    }
+    json.push_str("]}");
+  }
+}
+
+/// Returns the nodes as a flat array of `depth, role, name, value` strings, in the order of the
+/// document.
+pub(crate) const SNAPSHOT: &str = r#"(function () {
+  var implicitRoles = {
+    ARTICLE: 'article', ASIDE: 'complementary', BUTTON: 'button', DIALOG: 'dialog', FOOTER: 'contentinfo',
+    FORM: 'form', H1: 'heading', H2: 'heading', H3: 'heading', H4: 'heading', H5: 'heading', H6: 'heading',
+    HEADER: 'banner', HR: 'separator', LI: 'listitem', MAIN: 'main', METER: 'meter', NAV: 'navigation',
+    OL: 'list', OPTION: 'option', P: 'paragraph', PROGRESS: 'progressbar', TABLE: 'table', TD: 'cell',
+    TEXTAREA: 'textbox', TH: 'columnheader', TR: 'row', UL: 'list'
+  };
+  var inputRoles = {
+    button: 'button', checkbox: 'checkbox', image: 'button', radio: 'radio', range: 'slider',
+    reset: 'button', search: 'searchbox', submit: 'button', number: 'spinbutton'
+  };
+  var nameFromContent = {
+    button: 1, cell: 1, checkbox: 1, columnheader: 1, heading: 1, link: 1, menuitem: 1, option: 1,
+    radio: 1, row: 1, switch: 1, tab: 1, treeitem: 1
+  };
+  var text = function (s) { return (s || '').replace(/\s+/g, ' ').trim(); };
+  var role = function (element) {
+    var explicit = text(element.getAttribute('role')).split(' ')[0];
+    if (explicit) return explicit;
+    var tag = element.tagName;
+    if (tag === 'A' || tag === 'AREA') return element.hasAttribute('href') ? 'link' : '';
+    if (tag === 'IMG') return element.getAttribute('alt') === '' ? '' : 'img';
+    if (tag === 'INPUT') {
+      var type = (element.getAttribute('type') || 'text').toLowerCase();
+      return type === 'hidden' ? '' : inputRoles[type] || 'textbox';
+    }
+    if (tag === 'SELECT') return element.multiple || element.size > 1 ? 'listbox' : 'combobox';
+    if (tag === 'SECTION') return element.hasAttribute('aria-label') || element.hasAttribute('aria-labelledby') ? 'region' : '';
+    return implicitRoles[tag] || '';
+  };
+  var name = function (element, role) {
+    var labelledBy = element.getAttribute('aria-labelledby');
+    if (labelledBy) {
+      var labels = labelledBy.split(/\s+/).map(function (id) {
+        var label = document.getElementById(id);
+        return label ? text(label.textContent) : '';
+      }).filter(Boolean);
+      if (labels.length) return labels.join(' ');
+    }
+    var label = text(element.getAttribute('aria-label'));
+    if (label) return label;
+    if (element.labels && element.labels.length) {
+      return Array.prototype.map.call(element.labels, function (label) { return text(label.textContent); }).join(' ');
+    }
+    if (element.tagName === 'IMG' || element.tagName === 'AREA') return text(element.getAttribute('alt'));
+    if (element.tagName === 'INPUT' && /^(button|submit|reset)$/i.test(element.type)) return text(element.value);
+    if (nameFromContent[role]) {
+      var content = text(element.innerText || element.textContent);
+      if (content) return content;
+    }
+    return text(element.getAttribute('title') || element.getAttribute('placeholder'));
+  };
+  var value = function (element, role) {
+    if (role === 'checkbox' || role === 'radio' || role === 'switch') {
+      var checked = element.getAttribute('aria-checked');
+      return checked || String(!!element.checked);
+    }
+    if (element.hasAttribute('aria-valuetext')) return element.getAttribute('aria-valuetext');
+    if (element.hasAttribute('aria-valuenow')) return element.getAttribute('aria-valuenow');
+    if (element.tagName === 'SELECT') {
+      return Array.prototype.map.call(element.selectedOptions || [], function (option) { return text(option.text); }).join(', ');
+    }
+    if (element.tagName === 'INPUT' && element.type === 'password') return '';
+    if (role === 'textbox' || role === 'searchbox' || role === 'spinbutton' || role === 'slider') return String(element.value || '');
+    if (element.tagName === 'PROGRESS' || element.tagName === 'METER') return String(element.value);
+    return '';
+  };
+  var hidden = function (element) {
+    if (element.hidden || element.getAttribute('aria-hidden') === 'true') return true;
+    var style = window.getComputedStyle(element);
+    return style.display === 'none' || style.visibility === 'hidden';
+  };
+  var nodes = ['0', 'document', text(document.title), ''];
+  var walk = function (parent, depth) {
+    for (var node = parent.firstChild; node; node = node.nextSibling) {
+      if (node.nodeType === Node.TEXT_NODE) {
+        var content = text(node.data);
+        if (content) nodes.push(String(depth), 'text', content, '');
+        continue;
+      }
+      if (node.nodeType !== Node.ELEMENT_NODE || /^(SCRIPT|STYLE|NOSCRIPT|TEMPLATE|HEAD)$/.test(node.tagName) || hidden(node)) continue;
+      var nodeRole = role(node);
+      if (nodeRole === 'presentation' || nodeRole === 'none') nodeRole = '';
+      if (nodeRole) nodes.push(String(depth), nodeRole, name(node, nodeRole), value(node, nodeRole));
+      var childDepth = nodeRole ? depth + 1 : depth;
+      walk(node.shadowRoot || node, childDepth);
+    }
+  };
+  if (document.body) walk(document.body, 1);
+  return nodes;
+})()"#;
+
+/// Builds the tree from the result of [`SNAPSHOT`].
+pub(crate) fn parse(json: &str) -> Option<AccessibilityNode> {
+  let strings = parse_json_string_array(json)?;
+  if strings.len() % 4 != 0 {
+    return None;
+  }
+
+  // The ancestors of the current node, the root first
+  let mut ancestors: Vec<AccessibilityNode> = Vec::new();
+  for record in strings.chunks_exact(4) {
+    let depth: usize = record[0].parse().ok()?;
+    if depth > ancestors.len() || (depth == 0 && !ancestors.is_empty()) {
+      return None;
+    }
+    while ancestors.len() > depth {
+      let node = ancestors.pop()?;
+      ancestors.last_mut()?.children.push(node);
+    }
+    ancestors.push(AccessibilityNode {
+      role: record[1].clone(),
+      name: record[2].clone(),
+      value: record[3].clone(),
+      children: Vec::new(),
+    });
+  }
+  while ancestors.len() > 1 {
+    let node = ancestors.pop()?;
+    ancestors.last_mut()?.children.push(node);
+  }
+  ancestors.pop()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn node(role: &str, name: &str, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+      role: role.into(),
+      name: name.into(),
+      value: String::new(),
+      children,
+    }
+  }
+
+  #[test]
+  fn parse_snapshot() {
+    let tree = parse(
+      r#"["0","document","Wry","","1","heading","Title","","2","text","Title","","1","list","","","2","listitem","","","3","link","Home","","1","checkbox","Agree","true"]"#,
+    )
+    .unwrap();
+    let mut checkbox = node("checkbox", "Agree", Vec::new());
+    checkbox.value = "true".into();
+    assert_eq!(
+      tree,
+      node(
+        "document",
+        "Wry",
+        vec![
+          node("heading", "Title", vec![node("text", "Title", Vec::new())]),
+          node(
+            "list",
+            "",
+            vec![node("listitem", "", vec![node("link", "Home", Vec::new())])]
+          ),
+          checkbox,
+        ]
+      )
+    );
+
+    assert_eq!(
+      parse(r#"["0","document","",""]"#),
+      Some(node("document", "", Vec::new()))
+    );
+    assert_eq!(parse(r#"["0","document","","","2","text","",""]"#), None);
+    assert_eq!(
+      parse(r#"["0","document","","","0","document","",""]"#),
+      None
+    );
+    assert_eq!(parse(r#"{"error":"ReferenceError"}"#), None);
+  }
+
+  #[test]
+  fn snapshot_json() {
+    let tree = node("document", "a \"b\"", vec![node("text", "c", Vec::new())]);
+    assert_eq!(
+      tree.to_json(),
+      r#"{"role":"document","name":"a \"b\"","value":"","children":[{"role":"text","name":"c","value":"","children":[]}]}"#
+    );
+  }
+}
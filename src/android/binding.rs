@@ -16,11 +16,13 @@ pub use jni::{
 pub use ndk;
 
 use super::{
-  ASSET_LOADER_DOMAIN, EVAL_CALLBACKS, IPC, ON_LOAD_HANDLER, REQUEST_HANDLER, TITLE_CHANGE_HANDLER,
-  URL_LOADING_OVERRIDE, WITH_ASSET_LOADER,
+  ASSET_LOADER_DOMAIN, CONTENT_MODE, EVAL_CALLBACKS, IPC, LOADING, LOAD_PROGRESS_HANDLER,
+  ON_LOAD_HANDLER, REQUEST_HANDLER, TITLE_CHANGE_HANDLER, URL_CHANGE_HANDLER, URL_LOADING_OVERRIDE,
+  WITH_ASSET_LOADER,
 };
 
-use crate::PageLoadEvent;
+use crate::{NavigationDecision, PageLoadEvent};
+use std::sync::atomic::Ordering;
 
 #[macro_export]
 macro_rules! android_binding {
@@ -97,6 +99,13 @@ macro_rules! android_binding {
       handleReceivedTitle,
       [JObject, JString],
     );
+    android_fn!(
+      $domain,
+      $package,
+      RustWebChromeClient,
+      handleProgressChanged,
+      [JObject, jint],
+    );
   }};
 }
 
@@ -297,11 +306,28 @@ pub unsafe fn shouldOverride(mut env: JNIEnv, _: JClass, url: JString) -> jboole
         .lock()
         .unwrap()
         .as_ref()
-        // We negate the result of the function because the logic for the android
+        // We return `true` to deny the navigation because the logic for the android
         // client is different from how the navigation_handler is defined.
         //
         // https://developer.android.com/reference/android/webkit/WebViewClient#shouldOverrideUrlLoading(android.webkit.WebView,%20android.webkit.WebResourceRequest)
-        .map(|f| !(f.handler)(url))
+        .map(|f| match (f.handler)(url.clone()) {
+          NavigationDecision::Allow => false,
+          NavigationDecision::Deny => true,
+          NavigationDecision::Redirect(url) => {
+            super::MainPipe::send(super::WebViewMessage::LoadUrl(url, None));
+            true
+          }
+          NavigationDecision::AllowWithContentMode(mode) => {
+            // The user agent of this navigation can't be changed anymore, load it again
+            if std::mem::replace(&mut *CONTENT_MODE.lock().unwrap(), mode) == mode {
+              false
+            } else {
+              super::MainPipe::send(super::WebViewMessage::SetContentMode(mode));
+              super::MainPipe::send(super::WebViewMessage::LoadUrl(url, None));
+              true
+            }
+          }
+        })
         .unwrap_or(false)
     }
     Err(e) => {
@@ -342,7 +368,14 @@ pub unsafe fn ipc(mut env: JNIEnv, _: JClass, url: JString, body: JString) {
       let url = url.to_string_lossy().to_string();
       let body = body.to_string_lossy().to_string();
       if let Some(ipc) = IPC.lock().unwrap().as_ref() {
-        (ipc.handler)(Request::builder().uri(url).body(body).unwrap())
+        let source = crate::IpcSource::new(&url, None);
+        (ipc.handler)(
+          Request::builder()
+            .extension(source)
+            .uri(url)
+            .body(body)
+            .unwrap(),
+        )
       }
     }
     (Err(e), _) | (_, Err(e)) => {
@@ -368,6 +401,13 @@ pub unsafe fn handleReceivedTitle(mut env: JNIEnv, _: JClass, _webview: JObject,
   }
 }
 
+#[allow(non_snake_case)]
+pub unsafe fn handleProgressChanged(_: JNIEnv, _: JClass, _webview: JObject, progress: jint) {
+  if let Some(progress_handler) = LOAD_PROGRESS_HANDLER.lock().unwrap().as_ref() {
+    (progress_handler.handler)(progress as f64 / 100.0)
+  }
+}
+
 #[allow(non_snake_case)]
 pub unsafe fn withAssetLoader(_: JNIEnv, _: JClass) -> jboolean {
   (*WITH_ASSET_LOADER.lock().unwrap().as_ref().unwrap_or(&false)).into()
@@ -386,7 +426,11 @@ pub unsafe fn assetLoaderDomain(env: JNIEnv, _: JClass) -> jstring {
 pub unsafe fn onPageLoading(mut env: JNIEnv, _: JClass, url: JString) {
   match env.get_string(&url) {
     Ok(url) => {
+      LOADING.store(true, Ordering::Relaxed);
       let url = url.to_string_lossy().to_string();
+      if let Some(on_url_change) = URL_CHANGE_HANDLER.lock().unwrap().as_ref() {
+        (on_url_change.handler)(url.clone())
+      }
       if let Some(on_load) = ON_LOAD_HANDLER.lock().unwrap().as_ref() {
         (on_load.handler)(PageLoadEvent::Started, url)
       }
@@ -402,6 +446,7 @@ pub unsafe fn onPageLoading(mut env: JNIEnv, _: JClass, url: JString) {
 pub unsafe fn onPageLoaded(mut env: JNIEnv, _: JClass, url: JString) {
   match env.get_string(&url) {
     Ok(url) => {
+      LOADING.store(false, Ordering::Relaxed);
       let url = url.to_string_lossy().to_string();
       if let Some(on_load) = ON_LOAD_HANDLER.lock().unwrap().as_ref() {
         (on_load.handler)(PageLoadEvent::Finished, url)
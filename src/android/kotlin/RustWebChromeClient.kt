@@ -489,5 +489,13 @@ class RustWebChromeClient(appActivity: WryActivity) : WebChromeClient() {
     handleReceivedTitle(view, title)
   }
 
+  override fun onProgressChanged(
+      view: WebView,
+      newProgress: Int
+  ) {
+    handleProgressChanged(view, newProgress)
+  }
+
   private external fun handleReceivedTitle(webview: WebView, title: String)
+  private external fun handleProgressChanged(webview: WebView, progress: Int)
 }
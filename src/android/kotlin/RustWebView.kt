@@ -67,9 +67,13 @@ class RustWebView(context: Context, val initScripts: Array<String>, val id: Stri
         }
     }
 
-    fun loadHTMLMainThread(html: String) {
+    fun loadHTMLMainThread(html: String, baseUrl: String?) {
         post {
-          super.loadData(html, "text/html", null)
+          if (baseUrl != null) {
+            super.loadDataWithBaseURL(baseUrl, html, "text/html", null, null)
+          } else {
+            super.loadData(html, "text/html", null)
+          }
         }
     }
 
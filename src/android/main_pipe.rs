@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{Error, InitializationScript, RGBA};
+use crate::{content_mode::UserAgent, ContentMode, Error, InitializationScript, RGBA};
 use crossbeam_channel::*;
 use jni::{
   errors::Result as JniResult,
@@ -31,6 +31,7 @@ pub struct MainPipe<'a> {
   pub activity: GlobalRef,
   pub webview: Option<GlobalRef>,
   pub webchrome_client: GlobalRef,
+  pub user_agent: UserAgent,
 }
 
 impl<'a> MainPipe<'a> {
@@ -55,6 +56,7 @@ impl<'a> MainPipe<'a> {
           let CreateWebViewAttributes {
             url,
             html,
+            html_base_url,
             #[cfg(any(debug_assertions, feature = "devtools"))]
             devtools,
             transparent,
@@ -66,6 +68,8 @@ impl<'a> MainPipe<'a> {
             initialization_scripts,
             id,
             javascript_disabled,
+            pinch_zoom,
+            content_mode,
             ..
           } = attrs;
 
@@ -131,6 +135,28 @@ impl<'a> MainPipe<'a> {
             )?;
           }
 
+          // content mode, changes the user agent
+          let base_user_agent = self
+            .env
+            .call_method(
+              &web_settings,
+              "getUserAgentString",
+              "()Ljava/lang/String;",
+              &[],
+            )?
+            .l()?;
+          let base_user_agent = JString::from(base_user_agent);
+          self.user_agent = UserAgent {
+            base: self
+              .env
+              .get_string(&base_user_agent)
+              .map(|v| v.to_string_lossy().to_string())?,
+            mode: content_mode,
+          };
+          if content_mode != ContentMode::Recommended {
+            set_content_mode(&mut self.env, &web_settings, &self.user_agent)?;
+          }
+
           // disable javascript
           if javascript_disabled {
             self.env.call_method(
@@ -141,6 +167,30 @@ impl<'a> MainPipe<'a> {
             )?;
           }
 
+          // pinch zoom
+          match pinch_zoom {
+            Some(false) => {
+              self
+                .env
+                .call_method(&web_settings, "setSupportZoom", "(Z)V", &[false.into()])?;
+            }
+            Some(true) => {
+              self.env.call_method(
+                &web_settings,
+                "setBuiltInZoomControls",
+                "(Z)V",
+                &[true.into()],
+              )?;
+              self.env.call_method(
+                &web_settings,
+                "setDisplayZoomControls",
+                "(Z)V",
+                &[false.into()],
+              )?;
+            }
+            None => {}
+          }
+
           self.env.call_method(
             activity,
             "setWebView",
@@ -155,7 +205,7 @@ impl<'a> MainPipe<'a> {
             }
           } else if let Some(h) = html {
             if let Ok(html) = self.env.new_string(h) {
-              load_html(&mut self.env, &webview, &html)?;
+              load_html(&mut self.env, &webview, &html, html_base_url.as_deref())?;
             }
           }
 
@@ -315,6 +365,54 @@ impl<'a> MainPipe<'a> {
             tx.send(url).unwrap()
           }
         }
+        WebViewMessage::GetTitle(tx) => {
+          if let Some(webview) = &self.webview {
+            let title = self
+              .env
+              .call_method(webview.as_obj(), "getTitle", "()Ljava/lang/String;", &[])
+              .and_then(|v| v.l())
+              .and_then(|s| {
+                let s = JString::from(s);
+                self
+                  .env
+                  .get_string(&s)
+                  .map(|v| v.to_string_lossy().to_string())
+              })
+              .unwrap_or_default();
+
+            tx.send(title).unwrap()
+          }
+        }
+        WebViewMessage::CanGoBack(tx) => {
+          if let Some(webview) = &self.webview {
+            let can_go_back = self
+              .env
+              .call_method(webview.as_obj(), "canGoBack", "()Z", &[])
+              .and_then(|v| v.z())
+              .unwrap_or_default();
+            tx.send(can_go_back).unwrap()
+          }
+        }
+        WebViewMessage::CanGoForward(tx) => {
+          if let Some(webview) = &self.webview {
+            let can_go_forward = self
+              .env
+              .call_method(webview.as_obj(), "canGoForward", "()Z", &[])
+              .and_then(|v| v.z())
+              .unwrap_or_default();
+            tx.send(can_go_forward).unwrap()
+          }
+        }
+        WebViewMessage::GoBack => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "goBack", "()V", &[])?;
+          }
+        }
+        WebViewMessage::GoForward => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "goForward", "()V", &[])?;
+          }
+        }
         WebViewMessage::Jni(f) => {
           if let Some(w) = &self.webview {
             f(&mut self.env, activity, w.as_obj());
@@ -335,10 +433,10 @@ impl<'a> MainPipe<'a> {
               .call_method(webview, "clearAllBrowsingData", "()V", &[])?;
           }
         }
-        WebViewMessage::LoadHtml(html) => {
+        WebViewMessage::LoadHtml(html, base_url) => {
           if let Some(webview) = &self.webview {
             let html = self.env.new_string(html)?;
-            load_html(&mut self.env, webview.as_obj(), &html)?;
+            load_html(&mut self.env, webview.as_obj(), &html, base_url.as_deref())?;
           }
         }
         WebViewMessage::Reload => {
@@ -346,6 +444,47 @@ impl<'a> MainPipe<'a> {
             reload(&mut self.env, webview.as_obj())?;
           }
         }
+        WebViewMessage::StopLoading => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "stopLoading", "()V", &[])?;
+          }
+        }
+        WebViewMessage::SetUserAgent(user_agent) => {
+          if let Some(webview) = &self.webview {
+            let web_settings = self
+              .env
+              .call_method(
+                webview,
+                "getSettings",
+                "()Landroid/webkit/WebSettings;",
+                &[],
+              )?
+              .l()?;
+            self.user_agent.base = user_agent;
+            let user_agent = self.env.new_string(self.user_agent.get())?;
+            self.env.call_method(
+              &web_settings,
+              "setUserAgentString",
+              "(Ljava/lang/String;)V",
+              &[(&user_agent).into()],
+            )?;
+          }
+        }
+        WebViewMessage::SetContentMode(mode) => {
+          if let Some(webview) = &self.webview {
+            let web_settings = self
+              .env
+              .call_method(
+                webview,
+                "getSettings",
+                "()Landroid/webkit/WebSettings;",
+                &[],
+              )?
+              .l()?;
+            self.user_agent.mode = mode;
+            set_content_mode(&mut self.env, &web_settings, &self.user_agent)?;
+          }
+        }
         WebViewMessage::GetCookies(tx, url) => {
           if let Some(webview) = &self.webview {
             let url = self.env.new_string(url)?;
@@ -401,9 +540,9 @@ fn load_url<'a>(
     let obj = env.new_object("java/util/HashMap", "()V", &[])?;
     let headers_map = {
       let headers_map = JMap::from_env(env, &obj)?;
-      for (name, value) in headers.iter() {
+      for (name, value) in crate::navigation::request_headers(&headers) {
         let key = env.new_string(name)?;
-        let value = env.new_string(value.to_str().unwrap_or_default())?;
+        let value = env.new_string(value)?;
         headers_map.put(env, &key, &value)?;
       }
       headers_map
@@ -420,12 +559,21 @@ fn load_url<'a>(
   Ok(())
 }
 
-fn load_html<'a>(env: &mut JNIEnv<'a>, webview: &JObject<'a>, html: &JString<'a>) -> JniResult<()> {
+fn load_html<'a>(
+  env: &mut JNIEnv<'a>,
+  webview: &JObject<'a>,
+  html: &JString<'a>,
+  base_url: Option<&str>,
+) -> JniResult<()> {
+  let base_url = match base_url {
+    Some(base_url) => env.new_string(base_url)?.into(),
+    None => JObject::null(),
+  };
   env.call_method(
     webview,
     "loadHTMLMainThread",
-    "(Ljava/lang/String;)V",
-    &[html.into()],
+    "(Ljava/lang/String;Ljava/lang/String;)V",
+    &[html.into(), (&base_url).into()],
   )?;
   Ok(())
 }
@@ -435,6 +583,35 @@ fn reload<'a>(env: &mut JNIEnv<'a>, webview: &JObject<'a>) -> JniResult<()> {
   Ok(())
 }
 
+fn set_content_mode<'a>(
+  env: &mut JNIEnv<'a>,
+  web_settings: &JObject<'a>,
+  user_agent: &UserAgent,
+) -> JniResult<()> {
+  // Desktop pages are laid out in a wide viewport and zoomed out to fit the screen
+  let desktop = user_agent.mode == ContentMode::Desktop;
+  let user_agent = env.new_string(user_agent.get())?;
+  env.call_method(
+    web_settings,
+    "setUserAgentString",
+    "(Ljava/lang/String;)V",
+    &[(&user_agent).into()],
+  )?;
+  env.call_method(
+    web_settings,
+    "setUseWideViewPort",
+    "(Z)V",
+    &[desktop.into()],
+  )?;
+  env.call_method(
+    web_settings,
+    "setLoadWithOverviewMode",
+    "(Z)V",
+    &[desktop.into()],
+  )?;
+  Ok(())
+}
+
 fn set_background_color<'a>(
   env: &mut JNIEnv<'a>,
   webview: &JObject<'a>,
@@ -451,11 +628,19 @@ pub(crate) enum WebViewMessage {
   SetBackgroundColor(RGBA),
   GetWebViewVersion(Sender<Result<String, Error>>),
   GetUrl(Sender<String>),
+  GetTitle(Sender<String>),
+  CanGoBack(Sender<bool>),
+  CanGoForward(Sender<bool>),
+  GoBack,
+  GoForward,
   GetCookies(Sender<Vec<cookie::Cookie<'static>>>, String),
   Jni(Box<dyn FnOnce(&mut JNIEnv, &JObject, &JObject) + Send>),
   LoadUrl(String, Option<http::HeaderMap>),
-  LoadHtml(String),
+  LoadHtml(String, Option<String>),
   Reload,
+  StopLoading,
+  SetUserAgent(String),
+  SetContentMode(ContentMode),
   ClearAllBrowsingData,
   OnDestroy,
 }
@@ -464,6 +649,7 @@ pub(crate) struct CreateWebViewAttributes {
   pub id: String,
   pub url: Option<String>,
   pub html: Option<String>,
+  pub html_base_url: Option<String>,
   #[cfg(any(debug_assertions, feature = "devtools"))]
   pub devtools: bool,
   pub transparent: bool,
@@ -474,6 +660,8 @@ pub(crate) struct CreateWebViewAttributes {
   pub user_agent: Option<String>,
   pub initialization_scripts: Vec<InitializationScript>,
   pub javascript_disabled: bool,
+  pub pinch_zoom: Option<bool>,
+  pub content_mode: ContentMode,
 }
 
 // SAFETY: only use this when you are sure the span will be dropped on the same thread it was entered
@@ -2,14 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::{PageLoadEvent, WebViewAttributes, RGBA};
-use crate::{custom_protocol_workaround, RequestAsyncResponder, Result};
+use super::{
+  AutoplayPolicy, ContentMode, NavigationDecision, PageLoadEvent, Theme, WebViewAttributes, RGBA,
+};
+use crate::{
+  custom_protocol_workaround, InitializationScript, MemoryUsage, MemoryUsageLevel, RequestAction,
+  RequestAsyncResponder, ResponseBody, Result,
+};
 use base64::{engine::general_purpose, Engine};
 use crossbeam_channel::*;
 use html5ever::{interface::QualName, namespace_url, ns, tendril::TendrilSink, LocalName};
 use http::{
   header::{HeaderValue, CONTENT_SECURITY_POLICY, CONTENT_TYPE},
-  Request, Response as HttpResponse,
+  Request, Response as HttpResponse, StatusCode,
 };
 use jni::{
   errors::Result as JniResult,
@@ -25,7 +30,11 @@ use std::{
   borrow::Cow,
   collections::HashMap,
   os::fd::{AsFd as _, AsRawFd as _},
-  sync::{mpsc::channel, Mutex},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::channel,
+    Mutex,
+  },
   time::Duration,
 };
 
@@ -79,12 +88,17 @@ define_static_handlers! {
   IPC =  UnsafeIpc { handler: Box<dyn Fn(Request<String>)> };
   REQUEST_HANDLER = UnsafeRequestHandler { handler:  Box<dyn Fn(&str, Request<Vec<u8>>, bool) -> Option<HttpResponse<Cow<'static, [u8]>>>> };
   TITLE_CHANGE_HANDLER = UnsafeTitleHandler { handler: Box<dyn Fn(String)> };
-  URL_LOADING_OVERRIDE = UnsafeUrlLoadingOverride { handler: Box<dyn Fn(String) -> bool> };
+  URL_LOADING_OVERRIDE = UnsafeUrlLoadingOverride { handler: Box<dyn Fn(String) -> NavigationDecision> };
   ON_LOAD_HANDLER = UnsafeOnPageLoadHandler { handler: Box<dyn Fn(PageLoadEvent, String)> };
+  URL_CHANGE_HANDLER = UnsafeUrlChangeHandler { handler: Box<dyn Fn(String)> };
+  LOAD_PROGRESS_HANDLER = UnsafeLoadProgressHandler { handler: Box<dyn Fn(f64)> };
 }
 
 pub static WITH_ASSET_LOADER: StaticValue<Option<bool>> = StaticValue(Mutex::new(None));
 pub static ASSET_LOADER_DOMAIN: StaticValue<Option<String>> = StaticValue(Mutex::new(None));
+pub static LOADING: AtomicBool = AtomicBool::new(false);
+pub static CONTENT_MODE: StaticValue<ContentMode> =
+  StaticValue(Mutex::new(ContentMode::Recommended));
 
 pub(crate) static PACKAGE: OnceCell<String> = OnceCell::new();
 
@@ -127,6 +141,7 @@ pub unsafe fn android_setup(
     activity,
     webview: None,
     webchrome_client,
+    user_agent: Default::default(),
   };
 
   looper
@@ -166,17 +181,22 @@ impl InnerWebView {
     let WebViewAttributes {
       url,
       html,
+      html_base_url,
       initialization_scripts,
       ipc_handler,
       #[cfg(any(debug_assertions, feature = "devtools"))]
       devtools,
       custom_protocols,
+      web_resource_request_handler,
       background_color,
       transparent,
       headers,
       autoplay,
+      autoplay_policy,
       user_agent,
       javascript_disabled,
+      pinch_zoom,
+      content_mode,
       ..
     } = attributes;
 
@@ -189,6 +209,11 @@ impl InnerWebView {
 
     let http_or_https = if https_scheme { "https" } else { "http" };
 
+    let initialization_scripts: Vec<_> = initialization_scripts
+      .into_iter()
+      .map(InitializationScript::into_document_start)
+      .collect();
+
     let url = if let Some(mut url) = url {
       if let Some((protocol, _)) = url.split_once("://") {
         if custom_protocols.contains_key(protocol) {
@@ -206,6 +231,7 @@ impl InnerWebView {
       .map(|id| id.to_string())
       .unwrap_or_else(|| COUNTER.next().to_string());
 
+    *CONTENT_MODE.lock().unwrap() = content_mode;
     WITH_ASSET_LOADER.lock().unwrap().replace(with_asset_loader);
     if let Some(domain) = asset_loader_domain {
       ASSET_LOADER_DOMAIN.lock().unwrap().replace(domain);
@@ -234,8 +260,11 @@ impl InnerWebView {
 
             let (tx, rx) = channel();
             let initialization_scripts = initialization_scripts_.clone();
-            let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
-              Box::new(move |mut response| {
+            let responder: Box<dyn FnOnce(HttpResponse<ResponseBody>)> =
+              Box::new(move |response| {
+                // The webview needs the whole body, streamed bodies are read into memory
+                let (parts, body) = response.into_parts();
+                let mut response = HttpResponse::from_parts(parts, body.into_bytes());
                 if !is_document_start_script_enabled {
                   #[cfg(feature = "tracing")]
                   tracing::info!("`addDocumentStartJavaScript` is not supported; injecting initialization scripts via custom protocol handler");
@@ -293,7 +322,17 @@ impl InnerWebView {
             (custom_protocol_handler)(webview_id, request, RequestAsyncResponder { responder });
             return Some(rx.recv_timeout(MAIN_PIPE_TIMEOUT).unwrap());
           }
-          None
+
+          // The webview sends the original request, only the actions apply
+          let web_resource_request_handler = web_resource_request_handler.as_ref()?;
+          match web_resource_request_handler(&mut request) {
+            RequestAction::Continue => None,
+            RequestAction::Block => HttpResponse::builder()
+              .status(StatusCode::FORBIDDEN)
+              .body(Cow::Borrowed(&[][..]))
+              .ok(),
+            RequestAction::Override(response) => Some(response),
+          }
         },
       )
     ));
@@ -309,7 +348,7 @@ impl InnerWebView {
         .replace(UnsafeTitleHandler::new(i));
     }
 
-    if let Some(i) = attributes.navigation_handler {
+    if let Some(i) = attributes.navigation_handler_ext {
       URL_LOADING_OVERRIDE
         .lock()
         .unwrap()
@@ -323,20 +362,38 @@ impl InnerWebView {
         .replace(UnsafeOnPageLoadHandler::new(h));
     }
 
+    if let Some(h) = attributes.on_url_change_handler {
+      URL_CHANGE_HANDLER
+        .lock()
+        .unwrap()
+        .replace(UnsafeUrlChangeHandler::new(h));
+    }
+
+    if let Some(h) = attributes.load_progress_handler {
+      LOAD_PROGRESS_HANDLER
+        .lock()
+        .unwrap()
+        .replace(UnsafeLoadProgressHandler::new(h));
+    }
+
     MainPipe::send(WebViewMessage::CreateWebView(CreateWebViewAttributes {
       id: id.clone(),
       url,
       html,
+      html_base_url,
       #[cfg(any(debug_assertions, feature = "devtools"))]
       devtools,
       background_color,
       transparent,
       headers,
       on_webview_created,
-      autoplay,
+      // Muted media can't be told apart, it needs a user gesture too
+      autoplay: autoplay_policy.map_or(autoplay, |policy| policy == AutoplayPolicy::AllowAll),
       user_agent,
       initialization_scripts,
       javascript_disabled,
+      pinch_zoom,
+      content_mode,
     }));
 
     Ok(Self { id })
@@ -346,6 +403,54 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn print_silent<F>(&self, _settings: crate::PrintSettings, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    completion(Err(crate::Error::PrintFailed(
+      "silent printing is not supported on Android".into(),
+    )));
+    Ok(())
+  }
+
+  pub fn print_to_pdf<F>(&self, _options: crate::PrintToPdfOptions, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    completion(Err(crate::Error::PrintFailed(
+      "printing to PDF is not supported on Android".into(),
+    )));
+    Ok(())
+  }
+
+  pub fn find(
+    &self,
+    query: &str,
+    options: crate::FindOptions,
+    callback: impl Fn(u32) + Send + 'static,
+  ) -> Result<()> {
+    self.eval(
+      &crate::find::find(query, options, true),
+      Some(move |count: String| callback(count.parse().unwrap_or(0))),
+    )
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    self.eval(&crate::find::find_step(1), None::<fn(String)>)
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    self.eval(&crate::find::find_step(-1), None::<fn(String)>)
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    self.eval(crate::find::CLEAR_MATCHES, None::<fn(String)>)
+  }
+
+  pub fn select_all(&self) -> Result<()> {
+    self.eval(crate::selection::SELECT_ALL, None::<fn(String)>)
+  }
+
   pub fn id(&self) -> crate::WebViewId<'_> {
     &self.id
   }
@@ -356,16 +461,64 @@ impl InnerWebView {
     rx.recv_timeout(MAIN_PIPE_TIMEOUT).map_err(Into::into)
   }
 
+  pub fn can_go_back(&self) -> Result<bool> {
+    let (tx, rx) = bounded(1);
+    MainPipe::send(WebViewMessage::CanGoBack(tx));
+    rx.recv_timeout(MAIN_PIPE_TIMEOUT).map_err(Into::into)
+  }
+
+  pub fn can_go_forward(&self) -> Result<bool> {
+    let (tx, rx) = bounded(1);
+    MainPipe::send(WebViewMessage::CanGoForward(tx));
+    rx.recv_timeout(MAIN_PIPE_TIMEOUT).map_err(Into::into)
+  }
+
+  pub fn go_back(&self) -> Result<()> {
+    MainPipe::send(WebViewMessage::GoBack);
+    Ok(())
+  }
+
+  pub fn go_forward(&self) -> Result<()> {
+    MainPipe::send(WebViewMessage::GoForward);
+    Ok(())
+  }
+
+  pub fn history_list(&self) -> Result<crate::History> {
+    let url = self.url()?;
+    let (tx, rx) = bounded(1);
+    MainPipe::send(WebViewMessage::GetTitle(tx));
+    let title = rx.recv_timeout(MAIN_PIPE_TIMEOUT)?;
+
+    Ok(crate::History {
+      current: (!url.is_empty()).then_some(crate::HistoryEntry { url, title }),
+      ..Default::default()
+    })
+  }
+
   pub fn eval(&self, js: &str, callback: Option<impl Fn(String) + Send + 'static>) -> Result<()> {
+    // The WebView fails to evaluate scripts of several megabytes, they are sent in chunks
+    let mut scripts = crate::script::split_large_script(js);
+    let js = scripts.pop().unwrap_or_default();
+    for chunk in scripts {
+      MainPipe::send(WebViewMessage::Eval(chunk.into_owned(), None));
+    }
+
     MainPipe::send(WebViewMessage::Eval(
-      js.into(),
+      js.into_owned(),
       callback.map(|c| Box::new(c) as Box<dyn Fn(String) + Send + 'static>),
     ));
     Ok(())
   }
 
+  pub(crate) fn script_evaluator(&self) -> Box<dyn Fn(&str)> {
+    // Unsupported
+    Box::new(|_| ())
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn open_devtools(&self) {}
+  pub fn open_devtools(&self) -> Result<()> {
+    Ok(())
+  }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
   pub fn close_devtools(&self) {}
@@ -379,11 +532,49 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn zoom_level(&self) -> Result<f64> {
+    Ok(1.0)
+  }
+
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    MainPipe::send(WebViewMessage::SetUserAgent(user_agent.to_string()));
+    Ok(())
+  }
+
+  pub fn add_message_handler(
+    &self,
+    _name: &str,
+    _handler: Box<dyn Fn(Request<String>)>,
+  ) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn remove_message_handler(&self, _name: &str) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn set_accept_language(&self, _languages: &[&str]) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn set_navigation_gestures(&self, _enabled: bool) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
   pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
     MainPipe::send(WebViewMessage::SetBackgroundColor(background_color));
     Ok(())
   }
 
+  pub fn set_theme(&self, _theme: Theme) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
   pub fn load_url(&self, url: &str) -> Result<()> {
     MainPipe::send(WebViewMessage::LoadUrl(url.to_string(), None));
     Ok(())
@@ -394,8 +585,22 @@ impl InnerWebView {
     Ok(())
   }
 
-  pub fn load_html(&self, html: &str) -> Result<()> {
-    MainPipe::send(WebViewMessage::LoadHtml(html.to_string()));
+  pub fn load_url_with_body(
+    &self,
+    _url: &str,
+    _method: http::Method,
+    _body: Vec<u8>,
+    _headers: http::HeaderMap,
+  ) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) -> Result<()> {
+    MainPipe::send(WebViewMessage::LoadHtml(
+      html.to_string(),
+      base_url.map(ToString::to_string),
+    ));
     Ok(())
   }
 
@@ -404,11 +609,42 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn reload_ignoring_cache(&self) -> Result<()> {
+    self.reload()
+  }
+
+  pub fn stop_loading(&self) -> Result<()> {
+    MainPipe::send(WebViewMessage::StopLoading);
+    Ok(())
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     MainPipe::send(WebViewMessage::ClearAllBrowsingData);
     Ok(())
   }
 
+  pub fn capture_png<F>(&self, _rect: Option<crate::Rect>, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    completion(Err(crate::Error::CaptureFailed(
+      "capturing the webview is not supported on Android".into(),
+    )));
+    Ok(())
+  }
+
+  pub fn clear_browsing_data<F>(
+    &self,
+    _data: crate::BrowsingDataFlags,
+    _completion: F,
+  ) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    // Unsupported
+    Ok(())
+  }
+
   pub fn cookies_for_url(&self, url: &str) -> Result<Vec<cookie::Cookie<'static>>> {
     let (tx, rx) = bounded(1);
     MainPipe::send(WebViewMessage::GetCookies(tx, url.to_string()));
@@ -425,6 +661,11 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn delete_cookies_for_url(&self, url: &str, name: &str) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
   pub fn cookies(&self) -> Result<Vec<cookie::Cookie<'static>>> {
     Ok(Vec::new())
   }
@@ -443,6 +684,105 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn is_visible(&self) -> Result<bool> {
+    // Unsupported
+    Ok(true)
+  }
+
+  pub fn set_corner_radius(&self, _radius: f64) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn bring_to_front(&self) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn send_to_back(&self) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn suspend<F>(&self, _completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn resume(&self) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn add_init_script(&self, _id: &str, _script: &str) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn remove_init_script(&self, _id: &str) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn init_scripts(&self) -> Vec<String> {
+    Vec::new()
+  }
+
+  pub fn add_user_stylesheet(
+    &self,
+    _id: &str,
+    _css: &str,
+    _main_frame_only: bool,
+    _level: crate::StylesheetLevel,
+  ) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn remove_user_stylesheet(&self, _id: &str) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn set_proxy(&self, _proxy: Option<crate::ProxyConfig>) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn set_muted(&self, _muted: bool) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn is_muted(&self) -> Result<bool> {
+    // Unsupported
+    Ok(false)
+  }
+
+  pub fn is_loading(&self) -> Result<bool> {
+    Ok(LOADING.load(Ordering::Relaxed))
+  }
+
+  pub fn memory_usage(&self) -> Result<MemoryUsage> {
+    Err(crate::Error::Unsupported(
+      "the Android WebView doesn't expose its renderer process".into(),
+    ))
+  }
+
+  pub fn set_memory_usage_level(&self, _level: MemoryUsageLevel) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("Memory usage levels are unsupported on this platform");
+    Ok(())
+  }
+
+  pub fn memory_usage_level(&self) -> Result<MemoryUsageLevel> {
+    // Unsupported
+    Ok(MemoryUsageLevel::Normal)
+  }
+
   pub fn focus(&self) -> Result<()> {
     // Unsupported
     Ok(())
@@ -452,6 +792,26 @@ impl InnerWebView {
     // Unsupported
     Ok(())
   }
+
+  pub fn blur(&self) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop")]
+  pub fn set_drag_drop_handler(
+    &self,
+    _handler: Box<dyn Fn(crate::DragDropEvent) -> crate::DragOperation>,
+  ) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop-simulation")]
+  pub fn simulate_drag_drop(&self, _event: crate::DragDropEvent) -> crate::DragOperation {
+    // Unsupported
+    crate::DragOperation::Native
+  }
 }
 
 #[derive(Clone, Copy)]
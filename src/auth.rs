@@ -0,0 +1,265 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(not(target_os = "android"))]
+use std::{cell::RefCell, collections::HashMap};
+
+/// The server asking for credentials, see [`AuthChallenge`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthChallengeKind {
+  /// The proxy server the requests go through, e.g. the one of
+  /// [`WebViewBuilder::with_proxy_config`](crate::WebViewBuilder::with_proxy_config).
+  Proxy,
+  /// The server of the requested page.
+  Server,
+}
+
+/// An authentication challenge, passed to the handlers of
+/// [`WebViewBuilder::with_proxy_auth_handler`](crate::WebViewBuilder::with_proxy_auth_handler) and
+/// [`WebViewBuilder::with_authentication_handler`](crate::WebViewBuilder::with_authentication_handler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthChallenge {
+  /// Whether the proxy or the page server asks for credentials.
+  pub kind: AuthChallengeKind,
+  /// The host of the server asking for credentials, e.g. `proxy.example.com`.
+  pub host: String,
+  /// The port of the server asking for credentials.
+  pub port: u16,
+  /// The realm of the challenge, empty if the server didn't send one.
+  pub realm: String,
+  /// How many credentials were rejected before this challenge, handlers can give up after a few
+  /// failures by returning `None`.
+  pub previous_failure_count: u32,
+}
+
+/// The credentials answering an [`AuthChallenge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+  pub username: String,
+  pub password: String,
+}
+
+/// A request of a server for a TLS client certificate, passed to the handler of
+/// [`WebViewBuilder::with_client_certificate_handler`](crate::WebViewBuilder::with_client_certificate_handler).
+#[derive(Debug, Clone)]
+pub struct CertRequest {
+  /// The host of the server asking for a certificate.
+  pub host: String,
+  /// The port of the server asking for a certificate.
+  pub port: u16,
+  /// The distinguished names of the certificate authorities the server accepts, empty if it
+  /// accepts any or the platform doesn't report them.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS**: Always empty, on macOS and iOS [`Self::certificates`] only
+  ///   contains the certificates issued by them.
+  pub allowed_issuers: Vec<String>,
+  /// The certificates of the platform store with a private key the server accepts.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The certificates of the user store.
+  /// - **macOS / iOS**: The identities of the keychain.
+  /// - **Linux**: Always empty, use [`Certificate::from_pem_files`] instead.
+  pub certificates: Vec<Certificate>,
+}
+
+/// A TLS client certificate and its private key, see [`CertRequest::certificates`].
+#[derive(Clone)]
+pub struct Certificate {
+  pub(crate) subject: String,
+  #[cfg(gtk)]
+  pub(crate) inner: gtk::gio::TlsCertificate,
+  #[cfg(target_os = "windows")]
+  pub(crate) inner: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2ClientCertificate,
+  /// The `SecIdentityRef` of the certificate.
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub(crate) inner: objc2::rc::Retained<objc2::runtime::NSObject>,
+}
+
+impl Certificate {
+  /// The subject of the certificate, e.g. `CN=John Doe, O=Tauri`. Its format depends on the
+  /// platform, macOS and iOS only give a summary like `John Doe`.
+  pub fn subject(&self) -> &str {
+    &self.subject
+  }
+
+  /// Loads a certificate and its private key from PEM files, `key_file` can be the same as
+  /// `cert_file` if it contains both.
+  #[cfg(gtk)]
+  pub fn from_pem_files(
+    cert_file: impl AsRef<std::path::Path>,
+    key_file: impl AsRef<std::path::Path>,
+  ) -> crate::Result<Self> {
+    let inner = gtk::gio::TlsCertificate::from_files(cert_file, key_file)?;
+    let subject = tls_certificate_property(&inner, "subject-name");
+    Ok(Self { subject, inner })
+  }
+}
+
+/// Reads a string property of `certificate`, empty if the GLib version doesn't have it, e.g.
+/// `subject-name` which requires GLib 2.70.
+#[cfg(gtk)]
+pub(crate) fn tls_certificate_property(
+  certificate: &gtk::gio::TlsCertificate,
+  property: &str,
+) -> String {
+  use gtk::glib::prelude::*;
+
+  certificate
+    .find_property(property)
+    .and_then(|_| certificate.property::<Option<String>>(property))
+    .unwrap_or_default()
+}
+
+impl std::fmt::Debug for Certificate {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Certificate")
+      .field("subject", &self.subject)
+      .finish()
+  }
+}
+
+/// The certificate of a server failing the TLS verification, passed to the handler of
+/// `WebViewBuilder::with_tls_error_handler` (requires the `tls-error-handler` feature).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+  /// The subject of the certificate, e.g. `CN=localhost`. Its format depends on the platform,
+  /// macOS and iOS only give a summary like `localhost`.
+  pub subject: String,
+  /// The issuer of the certificate, e.g. `CN=localhost`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Always empty.
+  /// - **Linux**: Requires GLib 2.70, empty otherwise.
+  pub issuer: String,
+  /// The PEM encoding of the certificate.
+  pub pem: String,
+}
+
+#[cfg(not(target_os = "android"))]
+type AuthChallengeHandler = Box<dyn Fn(AuthChallenge) -> Option<Credentials>>;
+
+/// Routes the authentication challenges to the handler of their kind, and counts the failed
+/// attempts for the platforms that don't.
+#[cfg(not(target_os = "android"))]
+pub(crate) struct AuthHandler {
+  proxy_handler: Option<AuthChallengeHandler>,
+  server_handler: Option<AuthChallengeHandler>,
+  failures: RefCell<HashMap<(AuthChallengeKind, String, u16, String), u32>>,
+}
+
+#[cfg(not(target_os = "android"))]
+impl AuthHandler {
+  /// `None` if neither handler is set.
+  pub(crate) fn new(
+    proxy_handler: Option<AuthChallengeHandler>,
+    server_handler: Option<AuthChallengeHandler>,
+  ) -> Option<Self> {
+    (proxy_handler.is_some() || server_handler.is_some()).then(|| Self {
+      proxy_handler,
+      server_handler,
+      failures: Default::default(),
+    })
+  }
+
+  /// The handler of the challenges of `kind`, `None` keeps the platform handling.
+  pub(crate) fn handler(&self, kind: AuthChallengeKind) -> Option<&AuthChallengeHandler> {
+    match kind {
+      AuthChallengeKind::Proxy => self.proxy_handler.as_ref(),
+      AuthChallengeKind::Server => self.server_handler.as_ref(),
+    }
+  }
+
+  /// The number of failed attempts before `challenge`. `is_retry` tells whether the previous
+  /// attempt failed, `None` if the platform doesn't say so, in which case any repeated challenge
+  /// of the same server and realm counts as a failure.
+  #[allow(dead_code)]
+  pub(crate) fn previous_failure_count(
+    &self,
+    challenge: &AuthChallenge,
+    is_retry: Option<bool>,
+  ) -> u32 {
+    let key = (
+      challenge.kind,
+      challenge.host.clone(),
+      challenge.port,
+      challenge.realm.clone(),
+    );
+    let mut failures = self.failures.borrow_mut();
+    let is_retry = is_retry.unwrap_or_else(|| failures.contains_key(&key));
+    let count = failures.entry(key).or_default();
+    *count = if is_retry { *count + 1 } else { 0 };
+    *count
+  }
+}
+
+/// Reads the realm of a `WWW-Authenticate` or `Proxy-Authenticate` header value,
+/// e.g. `Basic realm="example"`.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn challenge_realm(challenge: &str) -> String {
+  let Some(start) = challenge.find("realm=") else {
+    return String::new();
+  };
+
+  let realm = &challenge[start + "realm=".len()..];
+  match realm.strip_prefix('"') {
+    Some(quoted) => quoted.split('"').next().unwrap_or_default().to_string(),
+    None => realm
+      .split(|c: char| c == ',' || c.is_whitespace())
+      .next()
+      .unwrap_or_default()
+      .to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn failure_count() {
+    let handler = AuthHandler::new(None, Some(Box::new(|_| None))).unwrap();
+    assert!(handler.handler(AuthChallengeKind::Proxy).is_none());
+    assert!(handler.handler(AuthChallengeKind::Server).is_some());
+
+    let challenge = |host: &str| AuthChallenge {
+      kind: AuthChallengeKind::Server,
+      host: host.into(),
+      port: 443,
+      realm: "tauri".into(),
+      previous_failure_count: 0,
+    };
+
+    let count = |host, is_retry| handler.previous_failure_count(&challenge(host), is_retry);
+
+    assert_eq!(count("tauri.app", None), 0);
+    assert_eq!(count("tauri.app", None), 1);
+    assert_eq!(count("tauri.app", None), 2);
+    assert_eq!(count("github.com", None), 0);
+
+    assert_eq!(count("tauri.app", Some(false)), 0);
+    assert_eq!(count("tauri.app", Some(true)), 1);
+    assert_eq!(count("crates.io", Some(true)), 1);
+
+    assert!(AuthHandler::new(None, None).is_none());
+  }
+
+  #[test]
+  fn realm_of_challenge() {
+    assert_eq!(
+      challenge_realm(r#"Basic realm="Tauri proxy""#),
+      "Tauri proxy"
+    );
+    assert_eq!(
+      challenge_realm(r#"Digest realm="tauri", qop="auth", nonce="abc""#),
+      "tauri"
+    );
+    assert_eq!(challenge_realm("Basic realm=tauri, charset=UTF-8"), "tauri");
+    assert_eq!(challenge_realm("Negotiate"), "");
+  }
+}
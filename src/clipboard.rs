@@ -0,0 +1,183 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{script::json_string, InitializationScript, WebViewAttributes};
+
+/// Which clipboard access pages get, see
+/// [`WebViewBuilder::with_clipboard_policy`](crate::WebViewBuilder::with_clipboard_policy).
+///
+/// The default policy denies both reading and writing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClipboardPolicy {
+  /// Whether pages can read the clipboard, with `navigator.clipboard.read()`, `readText()`
+  /// or `document.execCommand('paste')`.
+  pub read: bool,
+  /// Whether pages can write to the clipboard, with `navigator.clipboard.write()`,
+  /// `writeText()` or `document.execCommand('copy')` and `'cut'`.
+  pub write: bool,
+  /// The origins allowed to access the clipboard, e.g. `https://tauri.app`, all origins when empty.
+  pub allowed_origins: Vec<String>,
+}
+
+impl ClipboardPolicy {
+  /// A policy allowing pages of all origins to read and write the clipboard.
+  pub fn allow_all() -> Self {
+    Self {
+      read: true,
+      write: true,
+      allowed_origins: Vec::new(),
+    }
+  }
+
+  /// A policy allowing pages of all origins to read the clipboard but not to write to it.
+  pub fn read_only() -> Self {
+    Self {
+      read: true,
+      ..Default::default()
+    }
+  }
+
+  /// A policy allowing pages of all origins to write to the clipboard but not to read it.
+  pub fn write_only() -> Self {
+    Self {
+      write: true,
+      ..Default::default()
+    }
+  }
+
+  /// Restricts the policy to the pages of `origins`, e.g. `https://tauri.app`.
+  pub fn with_allowed_origins<I, S>(mut self, origins: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.allowed_origins = origins.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Whether the page at `url` may read the clipboard.
+  #[cfg(any(target_os = "windows", gtk, test))]
+  pub(crate) fn allows_read(&self, url: &str) -> bool {
+    self.read && self.allows_origin(&crate::ipc::origin(url))
+  }
+
+  #[cfg(any(target_os = "windows", gtk, test))]
+  fn allows_origin(&self, origin: &str) -> bool {
+    self.allowed_origins.is_empty()
+      || (origin != "null" && self.origins().any(|allowed| allowed == origin))
+  }
+
+  /// The allowed origins serialized like browsers do, e.g. `https://tauri.app:443/` becomes
+  /// `https://tauri.app`.
+  fn origins(&self) -> impl Iterator<Item = String> + '_ {
+    self
+      .allowed_origins
+      .iter()
+      .map(|origin| crate::ipc::origin(origin))
+  }
+}
+
+/// Enforces the clipboard policy, if any, with a script wrapping the clipboard APIs of each frame.
+///
+/// This is best-effort, the platforms only handle their native clipboard permissions.
+pub(crate) fn attach_clipboard_policy(attrs: &mut WebViewAttributes) {
+  let Some(policy) = &attrs.clipboard_policy else {
+    return;
+  };
+  attrs.initialization_scripts.insert(
+    0,
+    InitializationScript {
+      script: script(policy),
+      for_main_frame_only: false,
+      injection_time: Default::default(),
+    },
+  );
+}
+
+fn script(policy: &ClipboardPolicy) -> String {
+  let origins = policy
+    .origins()
+    .map(|origin| json_string(&origin))
+    .collect::<Vec<_>>()
+    .join(",");
+  format!(
+    r#"(function () {{
+  var origins = [{origins}];
+  var origin = window.location.origin;
+  var allowed = origins.length === 0 || (origin !== 'null' && origins.indexOf(origin) !== -1);
+  var read = allowed && {read};
+  var write = allowed && {write};
+  var deny = function () {{
+    return Promise.reject(new DOMException('Clipboard access denied', 'NotAllowedError'));
+  }};
+  var clipboard = window.Clipboard && Clipboard.prototype;
+  if (clipboard && !read) {{
+    clipboard.read = deny;
+    clipboard.readText = deny;
+  }}
+  if (clipboard && !write) {{
+    clipboard.write = deny;
+    clipboard.writeText = deny;
+  }}
+  var execCommand = Document.prototype.execCommand;
+  Document.prototype.execCommand = function (command) {{
+    var name = String(command).toLowerCase();
+    if ((!read && name === 'paste') || (!write && (name === 'copy' || name === 'cut'))) {{
+      return false;
+    }}
+    return execCommand.apply(this, arguments);
+  }};
+}})();"#,
+    read = policy.read,
+    write = policy.write,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn clipboard_origins() {
+    let policy = ClipboardPolicy::read_only().with_allowed_origins(["https://Tauri.app/"]);
+    assert!(policy.allows_read("https://tauri.app/en/"));
+    assert!(!policy.allows_read("https://evil.example/"));
+    assert!(!policy.allows_read("about:blank"));
+
+    let policy = ClipboardPolicy::read_only().with_allowed_origins(["https://tauri.app:443"]);
+    assert!(policy.allows_read("https://tauri.app/"));
+    assert!(!policy.allows_read("https://tauri.app:8443/"));
+
+    let policy = ClipboardPolicy::read_only().with_allowed_origins(["tauri.app"]);
+    assert!(!policy.allows_read("about:blank"));
+    assert!(!ClipboardPolicy::write_only().allows_read("https://tauri.app/"));
+    assert!(ClipboardPolicy::allow_all().allows_read("about:blank"));
+  }
+
+  #[test]
+  fn clipboard_script() {
+    let mut attrs = WebViewAttributes::default();
+    attach_clipboard_policy(&mut attrs);
+    assert!(attrs.initialization_scripts.is_empty());
+
+    attrs.clipboard_policy = Some(ClipboardPolicy::write_only().with_allowed_origins([
+      "https://Tauri.app/",
+      "http://localhost:80",
+      "app://localhost:1420",
+    ]));
+    attach_clipboard_policy(&mut attrs);
+    let script = &attrs.initialization_scripts[0];
+    assert!(!script.for_main_frame_only);
+    let (_, origins) = script.script.split_once("var origins = ").unwrap();
+    let (origins, _) = origins.split_once(';').unwrap();
+    assert_eq!(
+      crate::script::parse_json_string_array(origins).unwrap(),
+      [
+        "https://tauri.app",
+        "http://localhost",
+        "app://localhost:1420"
+      ]
+    );
+  }
+}
@@ -0,0 +1,96 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(any(not(any(target_os = "macos", target_os = "ios")), test))]
+use std::borrow::Cow;
+
+/// The kind of content a webview asks websites for, see
+/// [`WebViewBuilder::with_content_mode`](crate::WebViewBuilder::with_content_mode).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentMode {
+  /// The content of the platform: mobile content on phones, desktop content elsewhere.
+  #[default]
+  Recommended,
+  /// The content for phones and small screens.
+  Mobile,
+  /// The content for desktop computers.
+  Desktop,
+}
+
+/// The user agent of a webview and its content mode, on the platforms emulating the content
+/// modes with the user agent.
+#[cfg(any(not(any(target_os = "macos", target_os = "ios")), test))]
+#[derive(Debug, Default)]
+pub(crate) struct UserAgent {
+  /// The user agent set by the app, or the default one of the platform.
+  pub base: String,
+  pub mode: ContentMode,
+}
+
+#[cfg(any(not(any(target_os = "macos", target_os = "ios")), test))]
+impl UserAgent {
+  /// The user agent to send, the base one changed for the content mode.
+  pub fn get(&self) -> String {
+    user_agent(&self.base, self.mode).into_owned()
+  }
+}
+
+/// Changes `user_agent` to ask for the content of `mode`.
+///
+/// Websites look for a `Mobile` token, Android devices are also recognized by their platform.
+#[cfg(any(not(any(target_os = "macos", target_os = "ios")), test))]
+pub(crate) fn user_agent(user_agent: &str, mode: ContentMode) -> Cow<'_, str> {
+  let is_mobile_token = |token: &str| token == "Mobile" || token.starts_with("Mobile/");
+  match mode {
+    ContentMode::Recommended => Cow::Borrowed(user_agent),
+    ContentMode::Mobile if user_agent.split(' ').any(is_mobile_token) => Cow::Borrowed(user_agent),
+    ContentMode::Mobile => Cow::Owned(match user_agent.find("Safari/") {
+      Some(i) => format!("{}Mobile {}", &user_agent[..i], &user_agent[i..]),
+      None => format!("{user_agent} Mobile"),
+    }),
+    ContentMode::Desktop => {
+      let mut desktop = user_agent
+        .split(' ')
+        .filter(|token| !is_mobile_token(token))
+        .collect::<Vec<_>>()
+        .join(" ");
+      if let (Some(start), Some(end)) = (desktop.find('('), desktop.find(')')) {
+        if start < end && desktop[start..end].contains("Android") {
+          desktop.replace_range(start + 1..end, "X11; Linux x86_64");
+        }
+      }
+      Cow::Owned(desktop)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn content_mode_user_agent() {
+    let linux = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Safari/605.1.15";
+    assert_eq!(user_agent(linux, ContentMode::Recommended), linux);
+    assert_eq!(user_agent(linux, ContentMode::Desktop), linux);
+    assert_eq!(
+      user_agent(linux, ContentMode::Mobile),
+      "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile Safari/605.1.15"
+    );
+
+    let android = "Mozilla/5.0 (Linux; Android 14; Pixel 8; wv) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/120.0.0.0 Mobile Safari/537.36";
+    assert_eq!(user_agent(android, ContentMode::Mobile), android);
+    assert_eq!(
+      user_agent(android, ContentMode::Desktop),
+      "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/120.0.0.0 Safari/537.36"
+    );
+
+    assert_eq!(user_agent("wry", ContentMode::Mobile), "wry Mobile");
+    let user_agent = UserAgent {
+      base: "wry Mobile/1".into(),
+      mode: ContentMode::Desktop,
+    };
+    assert_eq!(user_agent.get(), "wry");
+  }
+}
@@ -0,0 +1,136 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{fmt, rc::Rc};
+
+use dpi::LogicalPosition;
+
+/// The name of the script message handler receiving what the context menu was opened on.
+#[cfg(any(target_os = "macos", gtk))]
+pub(crate) const MESSAGE_HANDLER_NAME: &str = "wryContextMenu";
+
+/// What a context menu was opened on, passed to the handler of
+/// [`WebViewBuilder::with_context_menu_handler`](crate::WebViewBuilder::with_context_menu_handler).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMenuTarget {
+  /// The URL of the link the menu was opened on.
+  pub link_url: Option<String>,
+  /// The selected text, if any.
+  pub selection_text: Option<String>,
+  /// The kind of media the menu was opened on.
+  pub media_type: Option<ContextMenuMediaType>,
+  /// Where the menu was requested, relative to the top-left corner of the webview,
+  /// e.g. to show a menu of the host instead.
+  pub position: LogicalPosition<f64>,
+}
+
+/// The kind of media element a context menu was opened on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMenuMediaType {
+  Image,
+  Video,
+  Audio,
+}
+
+/// The menu to show for a [`ContextMenuTarget`].
+#[derive(Debug, Clone)]
+pub enum ContextMenuAction {
+  /// Show the default menu of the webview.
+  Default,
+  /// Don't show any menu.
+  Suppress,
+  /// Show these items instead of the default ones.
+  Custom(Vec<ContextMenuItem>),
+}
+
+/// An item of a custom context menu, see [`ContextMenuAction::Custom`].
+#[derive(Clone)]
+pub struct ContextMenuItem {
+  pub(crate) label: String,
+  pub(crate) handler: Rc<dyn Fn()>,
+}
+
+impl ContextMenuItem {
+  /// An item showing `label`, calling `handler` when it is selected.
+  pub fn new(label: impl Into<String>, handler: impl Fn() + 'static) -> Self {
+    Self {
+      label: label.into(),
+      handler: Rc::new(handler),
+    }
+  }
+
+  /// The label of the item.
+  pub fn label(&self) -> &str {
+    &self.label
+  }
+}
+
+impl fmt::Debug for ContextMenuItem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ContextMenuItem")
+      .field("label", &self.label)
+      .finish_non_exhaustive()
+  }
+}
+
+/// The script posting what the context menu is opened on before WebKit opens it,
+/// since WebKit doesn't tell it to the UI process.
+#[cfg(any(target_os = "macos", gtk))]
+pub(crate) fn script() -> String {
+  format!(
+    r#"(function () {{
+  window.addEventListener('contextmenu', function (event) {{
+    var target = event.target;
+    var link = target && target.closest ? target.closest('a[href]') : null;
+    var media = '';
+    if (target instanceof HTMLImageElement) media = 'image';
+    else if (target instanceof HTMLVideoElement) media = 'video';
+    else if (target instanceof HTMLAudioElement) media = 'audio';
+    var message = [link ? link.href : '', media, String(window.getSelection())].join('\n');
+    window.webkit.messageHandlers.{MESSAGE_HANDLER_NAME}.postMessage(message);
+  }}, true);
+}})();"#
+  )
+}
+
+/// The target posted by [`script`], the position is left for the caller to fill in.
+#[cfg(any(target_os = "macos", gtk, test))]
+pub(crate) fn parse_message(message: &str) -> ContextMenuTarget {
+  let mut parts = message.splitn(3, '\n');
+  let mut next = || parts.next().filter(|part| !part.is_empty());
+
+  let link_url = next().map(Into::into);
+  let media_type = next().and_then(|media| match media {
+    "image" => Some(ContextMenuMediaType::Image),
+    "video" => Some(ContextMenuMediaType::Video),
+    "audio" => Some(ContextMenuMediaType::Audio),
+    _ => None,
+  });
+  let selection_text = next().map(Into::into);
+
+  ContextMenuTarget {
+    link_url,
+    selection_text,
+    media_type,
+    position: LogicalPosition::new(0., 0.),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn context_menu_messages() {
+    let target = parse_message("https://tauri.app/\nimage\nsome\nlines");
+    assert_eq!(target.link_url.as_deref(), Some("https://tauri.app/"));
+    assert_eq!(target.media_type, Some(ContextMenuMediaType::Image));
+    assert_eq!(target.selection_text.as_deref(), Some("some\nlines"));
+
+    let target = parse_message("\n\n");
+    assert_eq!(target.link_url, None);
+    assert_eq!(target.media_type, None);
+    assert_eq!(target.selection_text, None);
+  }
+}
@@ -0,0 +1,165 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use crate::{Error, Result};
+
+/// Rejects cookies the platform stores would drop or fail on, consistently on all platforms.
+pub(crate) fn validate(cookie: &cookie::Cookie<'_>) -> Result<()> {
+  let secure = cookie.secure().unwrap_or_default();
+  if cookie.domain().map_or(true, str::is_empty) {
+    Err(Error::InvalidCookie("the cookie has no domain"))
+  } else if cookie.same_site() == Some(cookie::SameSite::None) && !secure {
+    Err(Error::InvalidCookie(
+      "`SameSite=None` cookies must be `Secure`",
+    ))
+  } else if (cookie.name().starts_with("__Secure-") || cookie.name().starts_with("__Host-"))
+    && !secure
+  {
+    Err(Error::InvalidCookie(
+      "`__Secure-` and `__Host-` cookies must be `Secure`",
+    ))
+  } else if cookie.name().starts_with("__Host-") && cookie.path() != Some("/") {
+    Err(Error::InvalidCookie("`__Host-` cookies must have `Path=/`"))
+  } else {
+    Ok(())
+  }
+}
+
+/// Whether `cookie` is sent with requests to `scheme://host/path`, following RFC 6265.
+///
+/// [`cookie::Cookie::domain`] strips the leading `.` of domain cookies, so whether the cookie
+/// is host-only, i.e. set without a `Domain` attribute like `__Host-` cookies, is passed separately.
+#[cfg_attr(not(any(target_os = "macos", target_os = "ios")), allow(dead_code))]
+pub(crate) fn matches_url(
+  cookie: &cookie::Cookie<'_>,
+  host_only: bool,
+  scheme: &str,
+  host: &str,
+  path: &str,
+) -> bool {
+  let host = host.trim_start_matches('[').trim_end_matches(']');
+  let ip = host.parse::<IpAddr>().ok();
+
+  let domain_matches = match cookie.domain() {
+    Some(domain) => {
+      host.eq_ignore_ascii_case(domain)
+        || (!host_only
+          && ip.is_none()
+          && host.len() > domain.len()
+          && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+          && host.as_bytes()[host.len() - domain.len() - 1] == b'.')
+    }
+    None => false,
+  };
+
+  let cookie_path = cookie.path().filter(|p| p.starts_with('/')).unwrap_or("/");
+  let path = if path.is_empty() { "/" } else { path };
+  let path_matches = path == cookie_path
+    || (path.starts_with(cookie_path)
+      && (cookie_path.ends_with('/') || path.as_bytes()[cookie_path.len()] == b'/'));
+
+  // secure cookies are also sent to localhost, which browsers treat as a secure context
+  let is_loopback = host.eq_ignore_ascii_case("localhost") || ip.is_some_and(|ip| ip.is_loopback());
+  let secure_matches =
+    !cookie.secure().unwrap_or_default() || scheme == "https" || (scheme == "http" && is_loopback);
+
+  domain_matches && path_matches && secure_matches
+}
+
+#[cfg(test)]
+mod tests {
+  use cookie::Cookie;
+
+  use super::*;
+
+  #[test]
+  fn cookie_url_matching() {
+    let domain_cookie = Cookie::build(("id", "1"))
+      .domain(".example.com")
+      .path("/docs")
+      .build();
+    assert!(matches_url(
+      &domain_cookie,
+      false,
+      "https",
+      "example.com",
+      "/docs"
+    ));
+    assert!(matches_url(
+      &domain_cookie,
+      false,
+      "http",
+      "a.Example.com",
+      "/docs/web/"
+    ));
+    assert!(!matches_url(
+      &domain_cookie,
+      false,
+      "https",
+      "badexample.com",
+      "/docs"
+    ));
+    assert!(!matches_url(
+      &domain_cookie,
+      false,
+      "https",
+      "example.com",
+      "/docsets"
+    ));
+    assert!(!matches_url(
+      &domain_cookie,
+      false,
+      "https",
+      "example.com",
+      "/"
+    ));
+
+    let host_cookie = Cookie::build(("__Host-id", "1"))
+      .domain("example.com")
+      .path("/")
+      .secure(true)
+      .build();
+    assert!(matches_url(
+      &host_cookie,
+      true,
+      "https",
+      "example.com",
+      "/a/b"
+    ));
+    assert!(!matches_url(
+      &host_cookie,
+      true,
+      "https",
+      "a.example.com",
+      "/"
+    ));
+    assert!(!matches_url(&host_cookie, true, "http", "example.com", "/"));
+
+    let local_cookie = Cookie::build(("id", "1"))
+      .domain("127.0.0.1")
+      .secure(true)
+      .build();
+    assert!(matches_url(&local_cookie, true, "http", "127.0.0.1", ""));
+  }
+
+  #[test]
+  fn cookie_validation() {
+    let cookie = |name| Cookie::build((name, "1")).domain("example.com");
+    assert!(validate(&cookie("id").build()).is_ok());
+    assert!(validate(&Cookie::new("id", "1")).is_err());
+    assert!(validate(&cookie("id").same_site(cookie::SameSite::None).build()).is_err());
+    assert!(validate(
+      &cookie("id")
+        .same_site(cookie::SameSite::None)
+        .secure(true)
+        .build()
+    )
+    .is_ok());
+    assert!(validate(&cookie("__Secure-id").build()).is_err());
+    assert!(validate(&cookie("__Host-id").secure(true).build()).is_err());
+    assert!(validate(&cookie("__Host-id").secure(true).path("/").build()).is_ok());
+  }
+}
@@ -0,0 +1,917 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  any::Any,
+  borrow::Cow,
+  fs::{self, Metadata},
+  io::{self, Cursor, Read, Seek},
+  ops::Range,
+  panic::{self, AssertUnwindSafe},
+  path::Path,
+  sync::{Arc, Mutex},
+  time::UNIX_EPOCH,
+};
+
+#[cfg(feature = "protocol")]
+use std::{fs::File, path::PathBuf};
+
+#[cfg(feature = "protocol")]
+use http::{header::CONTENT_ENCODING, HeaderMap};
+use http::{
+  header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, LOCATION, RANGE},
+  HeaderName, HeaderValue, Request, Response, StatusCode,
+};
+
+use crate::{Error, RequestAsyncResponder, WebViewAttributes, WebViewId};
+
+type Handler = Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)>;
+
+trait SeekRead: Read + Seek {}
+impl<T: Read + Seek> SeekRead for T {}
+
+enum Reader {
+  Read(Box<dyn Read + Send>),
+  Seek(Box<dyn SeekRead + Send>),
+}
+
+/// A custom protocol response body that is read incrementally instead of being kept in memory.
+///
+/// See [`WebViewBuilder::with_custom_protocol_stream`](crate::WebViewBuilder::with_custom_protocol_stream).
+pub struct StreamBody {
+  reader: Reader,
+  len: Option<u64>,
+}
+
+impl StreamBody {
+  /// Creates a body that reads from `reader`, `len` is sent as the `Content-Length` when known.
+  pub fn new<R: Read + Send + 'static>(reader: R, len: Option<u64>) -> Self {
+    Self {
+      reader: Reader::Read(Box::new(reader)),
+      len,
+    }
+  }
+
+  /// Creates a body of `len` bytes that reads from a seekable `reader`, e.g. a [`File`](std::fs::File).
+  ///
+  /// Seekable bodies can answer `Range` requests, which `<video>` and `<audio>` elements need for seeking.
+  pub fn seekable<R: Read + Seek + Send + 'static>(reader: R, len: u64) -> Self {
+    Self {
+      reader: Reader::Seek(Box::new(reader)),
+      len: Some(len),
+    }
+  }
+
+  /// The length of the body in bytes, if known.
+  pub fn len(&self) -> Option<u64> {
+    self.len
+  }
+
+  /// Whether the body is known to be empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == Some(0)
+  }
+
+  /// Whether the body can answer `Range` requests.
+  pub fn is_seekable(&self) -> bool {
+    matches!(self.reader, Reader::Seek(_))
+  }
+
+  /// Limits the body to the bytes in `start..end`.
+  #[cfg(feature = "protocol")]
+  fn slice(self, start: u64, end: u64) -> io::Result<Self> {
+    match self.reader {
+      Reader::Seek(mut reader) => {
+        reader.seek(io::SeekFrom::Start(start))?;
+        Ok(Self::new(reader.take(end - start), Some(end - start)))
+      }
+      Reader::Read(_) => Err(io::ErrorKind::Unsupported.into()),
+    }
+  }
+}
+
+impl Read for StreamBody {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    match &mut self.reader {
+      Reader::Read(reader) => reader.read(buf),
+      Reader::Seek(reader) => reader.read(buf),
+    }
+  }
+}
+
+impl<T: Into<Cow<'static, [u8]>>> From<T> for StreamBody {
+  fn from(bytes: T) -> Self {
+    let bytes = bytes.into();
+    let len = bytes.len() as u64;
+    Self::seekable(Cursor::new(bytes), len)
+  }
+}
+
+impl Default for StreamBody {
+  fn default() -> Self {
+    Self::from(Vec::new())
+  }
+}
+
+/// The body of a custom protocol response as handed to the platform webview.
+pub(crate) enum ResponseBody {
+  Bytes(Cow<'static, [u8]>),
+  Stream(StreamBody),
+}
+
+impl ResponseBody {
+  /// Reads the whole body into memory, for platforms that can't stream it.
+  #[allow(dead_code)]
+  pub(crate) fn into_bytes(self) -> Cow<'static, [u8]> {
+    match self {
+      Self::Bytes(bytes) => bytes,
+      Self::Stream(mut body) => {
+        let mut bytes = Vec::with_capacity(body.len().unwrap_or_default() as usize);
+        if let Err(_e) = body.read_to_end(&mut bytes) {
+          #[cfg(feature = "tracing")]
+          tracing::warn!("Failed to read custom protocol response body: {_e}");
+        }
+        bytes.into()
+      }
+    }
+  }
+}
+
+/// A single range of a `Range: bytes=` request header.
+///
+/// See [`RequestExt::range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+  /// `bytes=<start>-<end>`, the end is inclusive.
+  FromTo(u64, u64),
+  /// `bytes=<start>-`
+  From(u64),
+  /// `bytes=-<len>`, the last `len` bytes.
+  Last(u64),
+}
+
+impl ByteRange {
+  /// Parses the value of a `Range` header.
+  ///
+  /// Only a single range is supported, requests for multiple ranges return
+  /// [`RangeError::MultipleRanges`] and should be answered with
+  /// [`ResponseExt::range_not_satisfiable`].
+  pub fn parse(header: &str) -> Result<Self, RangeError> {
+    let spec = header
+      .trim()
+      .strip_prefix("bytes=")
+      .ok_or(RangeError::Invalid)?;
+    if spec.contains(',') {
+      return Err(RangeError::MultipleRanges);
+    }
+    let (start, end) = spec.trim().split_once('-').ok_or(RangeError::Invalid)?;
+    let (start, end) = (start.trim(), end.trim());
+    let parse = |n: &str| n.parse().map_err(|_| RangeError::Invalid);
+    match (start.is_empty(), end.is_empty()) {
+      (false, false) => Ok(Self::FromTo(parse(start)?, parse(end)?)),
+      (false, true) => Ok(Self::From(parse(start)?)),
+      (true, false) => Ok(Self::Last(parse(end)?)),
+      (true, true) => Err(RangeError::Invalid),
+    }
+  }
+
+  /// Resolves the range against a body of `total_len` bytes,
+  /// or returns `None` if it can't be satisfied.
+  pub fn resolve(self, total_len: u64) -> Option<Range<u64>> {
+    let range = match self {
+      Self::FromTo(start, end) if start <= end => start..end.saturating_add(1).min(total_len),
+      Self::FromTo(..) => return None,
+      Self::From(start) => start..total_len,
+      Self::Last(0) => return None,
+      Self::Last(last) => total_len.saturating_sub(last)..total_len,
+    };
+    (!range.is_empty()).then_some(range)
+  }
+}
+
+/// Why a `Range` header couldn't be parsed into a [`ByteRange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+  /// The header is not a valid `bytes` range, it should be ignored and the full body sent.
+  Invalid,
+  /// The header requests multiple ranges, which is not supported.
+  MultipleRanges,
+}
+
+/// Range helpers for custom protocol [`Request`]s.
+pub trait RequestExt {
+  /// Parses the `Range` header of the request, if any.
+  ///
+  /// Only a single range is supported, see [`ByteRange::parse`].
+  fn range(&self) -> Option<Result<ByteRange, RangeError>>;
+}
+
+impl<T> RequestExt for Request<T> {
+  fn range(&self) -> Option<Result<ByteRange, RangeError>> {
+    let header = self.headers().get(RANGE)?;
+    Some(
+      header
+        .to_str()
+        .map_err(|_| RangeError::Invalid)
+        .and_then(ByteRange::parse),
+    )
+  }
+}
+
+/// Helpers to build custom protocol [`Response`]s.
+pub trait ResponseExt<T> {
+  /// Creates a `200 OK` response with the content of the file at `path`.
+  ///
+  /// The `Content-Type` is guessed from the extension of the file, the `Content-Length` and a
+  /// weak `ETag` are set from its metadata. The body can be a `Vec<u8>`, a `Cow<'static, [u8]>`
+  /// or a [`StreamBody`], so the response works with all the custom protocol handlers.
+  fn from_file(path: impl AsRef<Path>) -> io::Result<Response<T>>
+  where
+    T: From<Vec<u8>>;
+
+  /// Creates a `206 Partial Content` response with `body` holding the bytes in `range` of a
+  /// resource of `total_len` bytes.
+  ///
+  /// The `Content-Range`, `Content-Length` and `Accept-Ranges` headers are set accordingly.
+  fn partial(body: T, range: Range<u64>, total_len: u64) -> Response<T>;
+
+  /// Creates a `416 Range Not Satisfiable` response for a resource of `total_len` bytes.
+  fn range_not_satisfiable(total_len: u64) -> Response<T>
+  where
+    T: Default;
+
+  /// Adds the `Cross-Origin-Opener-Policy: same-origin` and
+  /// `Cross-Origin-Embedder-Policy: require-corp` headers, which make the page
+  /// [cross-origin isolated](https://developer.mozilla.org/en-US/docs/Web/API/Window/crossOriginIsolated)
+  /// so it can use `SharedArrayBuffer`.
+  ///
+  /// The page must be a secure context as well, see
+  /// [`WebViewBuilder::with_custom_protocol`](crate::WebViewBuilder::with_custom_protocol).
+  fn cross_origin_isolated(self) -> Response<T>;
+}
+
+impl<T> ResponseExt<T> for Response<T> {
+  fn from_file(path: impl AsRef<Path>) -> io::Result<Response<T>>
+  where
+    T: From<Vec<u8>>,
+  {
+    let path = path.as_ref();
+    let metadata = fs::metadata(path)?;
+    let body = fs::read(path)?;
+    Ok(file_response(path, &metadata, T::from(body)))
+  }
+
+  fn partial(body: T, range: Range<u64>, total_len: u64) -> Response<T> {
+    let mut response = Response::new(body);
+    *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+    let headers = response.headers_mut();
+    headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    headers.insert(CONTENT_LENGTH, HeaderValue::from(range.end - range.start));
+    // An empty range has no valid `Content-Range`, `resolve` never returns one
+    if let Some(last) = range.end.checked_sub(1) {
+      headers.insert(
+        CONTENT_RANGE,
+        header_value(format!("bytes {}-{last}/{total_len}", range.start)),
+      );
+    }
+    response
+  }
+
+  fn range_not_satisfiable(total_len: u64) -> Response<T>
+  where
+    T: Default,
+  {
+    let mut response = Response::new(T::default());
+    *response.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+    let headers = response.headers_mut();
+    headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    headers.insert(CONTENT_RANGE, header_value(format!("bytes */{total_len}")));
+    response
+  }
+
+  fn cross_origin_isolated(mut self) -> Response<T> {
+    let headers = self.headers_mut();
+    headers.insert(
+      HeaderName::from_static("cross-origin-opener-policy"),
+      HeaderValue::from_static("same-origin"),
+    );
+    headers.insert(
+      HeaderName::from_static("cross-origin-embedder-policy"),
+      HeaderValue::from_static("require-corp"),
+    );
+    self
+  }
+}
+
+fn header_value(value: String) -> HeaderValue {
+  // Only ever called with ASCII digits, punctuation and MIME types
+  HeaderValue::try_from(value).expect("invalid header value")
+}
+
+/// The `Content-Type` of a file, guessed from its extension.
+fn mime_type(path: &Path) -> String {
+  // Module scripts and `WebAssembly.instantiateStreaming` reject the legacy types
+  match path.extension().and_then(|e| e.to_str()) {
+    Some("js" | "mjs") => "text/javascript".to_string(),
+    Some("wasm") => "application/wasm".to_string(),
+    _ => mime_guess::from_path(path)
+      .first_or_octet_stream()
+      .to_string(),
+  }
+}
+
+/// A `200 OK` response for the file at `path` with its `Content-Type`, `Content-Length` and a
+/// weak `ETag`.
+fn file_response<T>(path: &Path, metadata: &Metadata, body: T) -> Response<T> {
+  let modified = metadata
+    .modified()
+    .ok()
+    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+    .unwrap_or_default();
+
+  let mut response = Response::new(body);
+  let headers = response.headers_mut();
+  headers.insert(CONTENT_TYPE, header_value(mime_type(path)));
+  headers.insert(CONTENT_LENGTH, HeaderValue::from(metadata.len()));
+  headers.insert(
+    ETAG,
+    header_value(format!(
+      "W/\"{:x}-{:x}\"",
+      metadata.len(),
+      modified.as_millis()
+    )),
+  );
+  response
+}
+
+/// Answers the `Range` header of a request with a partial response when the body is seekable.
+#[cfg(feature = "protocol")]
+pub(crate) fn apply_range(
+  request_headers: &HeaderMap,
+  response: Response<StreamBody>,
+) -> Response<StreamBody> {
+  let (mut parts, body) = response.into_parts();
+  let total_len = match body.len() {
+    Some(len) if body.is_seekable() => len,
+    _ => return Response::from_parts(parts, body),
+  };
+
+  // Leave responses the handler already made partial, or errors, untouched. The ranges of an
+  // encoded body wouldn't match the decoded body the page sees.
+  if parts.status != StatusCode::OK
+    || parts.headers.contains_key(CONTENT_RANGE)
+    || parts.headers.contains_key(CONTENT_ENCODING)
+  {
+    return Response::from_parts(parts, body);
+  }
+
+  parts
+    .headers
+    .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+  let range = match request_headers.get(RANGE).map(|h| h.to_str()) {
+    Some(Ok(header)) => ByteRange::parse(header),
+    _ => Err(RangeError::Invalid),
+  };
+  let range = match range {
+    Ok(range) => range.resolve(total_len),
+    Err(RangeError::MultipleRanges) => None,
+    Err(RangeError::Invalid) => return Response::from_parts(parts, body),
+  };
+
+  let Some(range) = range else {
+    return with_parts(Response::range_not_satisfiable(total_len), parts);
+  };
+
+  match body.slice(range.start, range.end) {
+    Ok(body) => with_parts(Response::partial(body, range, total_len), parts),
+    Err(_e) => {
+      #[cfg(feature = "tracing")]
+      tracing::warn!("Failed to seek custom protocol response body: {_e}");
+      parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+      parts.headers.remove(CONTENT_LENGTH);
+      Response::from_parts(parts, StreamBody::default())
+    }
+  }
+}
+
+/// Decodes a `gzip`, `deflate` or `br` encoded body, for webviews that hand custom protocol
+/// bodies to the page without decoding them.
+///
+/// Responses with other encodings are left untouched.
+#[cfg(all(feature = "protocol", not(target_os = "windows")))]
+pub(crate) fn decode_content_encoding(response: Response<ResponseBody>) -> Response<ResponseBody> {
+  let encoding = response
+    .headers()
+    .get(CONTENT_ENCODING)
+    .and_then(|h| h.to_str().ok())
+    .map(|h| h.trim().to_ascii_lowercase());
+  if !matches!(
+    encoding.as_deref(),
+    Some("gzip" | "x-gzip" | "deflate" | "br" | "identity")
+  ) {
+    return response;
+  }
+
+  let (mut parts, body) = response.into_parts();
+  parts.headers.remove(CONTENT_ENCODING);
+
+  let body: Box<dyn Read + Send> = match body {
+    ResponseBody::Bytes(bytes) => Box::new(Cursor::new(bytes)),
+    ResponseBody::Stream(body) => Box::new(body),
+  };
+  let body: Box<dyn Read + Send> = match encoding.as_deref() {
+    Some("gzip" | "x-gzip") => Box::new(flate2::read::GzDecoder::new(body)),
+    // HTTP `deflate` is zlib wrapped
+    Some("deflate") => Box::new(flate2::read::ZlibDecoder::new(body)),
+    Some("br") => Box::new(brotli_decompressor::Decompressor::new(body, 4096)),
+    _ => body,
+  };
+
+  // The decoded length is only known once the body is read
+  parts.headers.remove(CONTENT_LENGTH);
+  Response::from_parts(parts, ResponseBody::Stream(StreamBody::new(body, None)))
+}
+
+/// Answers the requests of custom protocol handlers that panic or respond with a malformed
+/// response with a `500 Internal Server Error`, so the panics don't unwind into the webview.
+pub(crate) fn attach_protocol_error_handler(attrs: &mut WebViewAttributes) {
+  attrs.custom_protocols = std::mem::take(&mut attrs.custom_protocols)
+    .into_iter()
+    .map(|(scheme, handler)| {
+      let handler = catch_handler_errors(scheme.clone(), handler);
+      (scheme, handler)
+    })
+    .collect();
+}
+
+fn catch_handler_errors(scheme: String, handler: Handler) -> Handler {
+  Box::new(move |id, request, responder| {
+    let error = {
+      let scheme = scheme.clone();
+      let uri = request.uri().to_string();
+      move |reason: String| Error::CustomProtocol {
+        scheme: scheme.clone(),
+        uri: uri.clone(),
+        reason,
+      }
+    };
+
+    // Shared with the handler to answer the request when it panics before responding. It is
+    // only sent to another thread with the responder, see `RequestAsyncResponder`.
+    #[allow(clippy::arc_with_non_send_sync)]
+    let respond = Arc::new(Mutex::new(Some(responder.responder)));
+    let responder = RequestAsyncResponder {
+      responder: Box::new({
+        let respond = respond.clone();
+        let error = error.clone();
+        move |response| {
+          let response = match check_response(&response) {
+            Ok(()) => response,
+            Err(reason) => error_response(error(reason)),
+          };
+          let respond = respond.lock().unwrap().take();
+          if let Some(respond) = respond {
+            respond(response);
+          }
+        }
+      }),
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| handler(id, request, responder)));
+    if let Err(panic) = result {
+      let respond = respond.lock().unwrap().take();
+      let reason = format!("the handler panicked: {}", panic_message(&*panic));
+      if let Some(respond) = respond {
+        respond(error_response(error(reason)));
+      }
+    }
+  })
+}
+
+/// Why the webview would reject `response`, if it would.
+fn check_response(response: &Response<ResponseBody>) -> Result<(), String> {
+  let status = response.status();
+  if status.is_informational() || status.as_u16() > 599 {
+    return Err(format!("invalid status {status}"));
+  }
+  if status.is_redirection()
+    && status != StatusCode::NOT_MODIFIED
+    && !response.headers().contains_key(LOCATION)
+  {
+    return Err(format!("redirect {status} without a `Location` header"));
+  }
+
+  for (name, value) in response.headers() {
+    if value.to_str().is_err() {
+      return Err(format!("the `{name}` header isn't visible ASCII"));
+    }
+  }
+
+  if let Some(len) = response.headers().get(CONTENT_LENGTH) {
+    let len: u64 = len
+      .to_str()
+      .ok()
+      .and_then(|len| len.trim().parse().ok())
+      .ok_or_else(|| "invalid `Content-Length` header".to_string())?;
+    if let ResponseBody::Bytes(body) = response.body() {
+      if len != body.len() as u64 {
+        return Err(format!(
+          "the `Content-Length` of {len} doesn't match the body of {} bytes",
+          body.len()
+        ));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+  panic
+    .downcast_ref::<&str>()
+    .copied()
+    .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+    .unwrap_or("Box<dyn Any>")
+}
+
+/// Logs `error` and answers with a `500 Internal Server Error`, with a page describing the error
+/// in debug builds.
+fn error_response(error: Error) -> Response<ResponseBody> {
+  #[cfg(feature = "tracing")]
+  tracing::error!("{error}");
+
+  let response = Response::builder().status(StatusCode::INTERNAL_SERVER_ERROR);
+  #[cfg(debug_assertions)]
+  let response = response
+    .header(CONTENT_TYPE, "text/html; charset=utf-8")
+    .body(ResponseBody::Bytes(
+      format!(
+        "<!DOCTYPE html><html><head><title>500 Internal Server Error</title></head><body><h1>500 Internal Server Error</h1><pre>{}</pre></body></html>",
+        crate::navigation::escape_html(&error.to_string())
+      )
+      .into_bytes()
+      .into(),
+    ));
+  #[cfg(not(debug_assertions))]
+  let response = response.body(ResponseBody::Bytes(Cow::Borrowed(&[])));
+  response.unwrap()
+}
+
+/// Keeps the handler's headers on a response built by [`ResponseExt`], without overriding its own.
+#[cfg(feature = "protocol")]
+fn with_parts(
+  mut response: Response<StreamBody>,
+  parts: http::response::Parts,
+) -> Response<StreamBody> {
+  let own = response.headers().clone();
+  for (name, value) in parts.headers.iter() {
+    if name != CONTENT_LENGTH && !own.contains_key(name) {
+      response.headers_mut().append(name, value.clone());
+    }
+  }
+  response
+}
+
+/// Resolves the path of a request URI inside `dir`, `None` if it escapes `dir`.
+#[cfg(feature = "protocol")]
+fn asset_path(dir: &Path, uri_path: &str) -> Option<PathBuf> {
+  let uri_path = percent_encoding::percent_decode_str(uri_path)
+    .decode_utf8()
+    .ok()?;
+
+  let mut path = dir.to_path_buf();
+  for segment in uri_path.split('/') {
+    match segment {
+      "" | "." => {}
+      ".." => return None,
+      // `\` and `:` would let the segment be a parent dir or a drive on Windows
+      s if s.contains(['\\', ':', '\0']) => return None,
+      s => path.push(s),
+    }
+  }
+
+  if path.is_dir() {
+    path.push("index.html");
+  }
+  Some(path)
+}
+
+/// Serves the file of `request` from `dir`.
+///
+/// See [`WebViewBuilder::with_asset_folder`](crate::WebViewBuilder::with_asset_folder).
+#[cfg(feature = "protocol")]
+pub(crate) fn serve_asset<T>(dir: &Path, request: &Request<T>) -> Response<StreamBody> {
+  let status = |status: StatusCode| {
+    Response::builder()
+      .status(status)
+      .body(StreamBody::default())
+      .unwrap()
+  };
+
+  let Some(path) = asset_path(dir, request.uri().path()) else {
+    return status(StatusCode::FORBIDDEN);
+  };
+
+  // Resolve the symlinks so a link inside `dir` can't point outside of it
+  let (Ok(root), Ok(path)) = (fs::canonicalize(dir), fs::canonicalize(&path)) else {
+    return status(StatusCode::NOT_FOUND);
+  };
+  if !path.starts_with(&root) {
+    return status(StatusCode::FORBIDDEN);
+  }
+
+  let Some((file, metadata)) = File::open(&path)
+    .and_then(|file| file.metadata().map(|metadata| (file, metadata)))
+    .ok()
+  else {
+    return status(StatusCode::NOT_FOUND);
+  };
+
+  let body = StreamBody::seekable(file, metadata.len());
+  file_response(&path, &metadata, body)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(feature = "protocol")]
+  fn respond(range: Option<&str>) -> Response<StreamBody> {
+    let mut headers = HeaderMap::new();
+    if let Some(range) = range {
+      headers.insert(RANGE, HeaderValue::from_str(range).unwrap());
+    }
+    apply_range(
+      &headers,
+      Response::new(StreamBody::from(&b"0123456789"[..])),
+    )
+  }
+
+  fn read(response: Response<StreamBody>) -> String {
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).unwrap();
+    body
+  }
+
+  #[test]
+  #[cfg(feature = "protocol")]
+  fn range_requests() {
+    let response = respond(None);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[ACCEPT_RANGES], "bytes");
+    assert_eq!(read(response), "0123456789");
+
+    let response = respond(Some("bytes=2-4"));
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 2-4/10");
+    assert_eq!(response.body().len(), Some(3));
+    assert_eq!(read(response), "234");
+
+    assert_eq!(read(respond(Some("bytes=7-"))), "789");
+    assert_eq!(read(respond(Some("bytes=-2"))), "89");
+    assert_eq!(read(respond(Some("bytes=8-100"))), "89");
+
+    let response = respond(Some("bytes=10-"));
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes */10");
+
+    let response = respond(Some("bytes=0-1, 4-5"));
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+    let response = respond(Some("items=0-1"));
+    assert_eq!(response.status(), StatusCode::OK);
+  }
+
+  fn call_handler(
+    handler: impl Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder) + 'static,
+  ) -> Vec<Response<ResponseBody>> {
+    let handler = catch_handler_errors("app".into(), Box::new(handler));
+    let responses = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let responder = RequestAsyncResponder {
+      responder: Box::new({
+        let responses = responses.clone();
+        move |response| responses.borrow_mut().push(response)
+      }),
+    };
+    let request = Request::get("app://localhost/index.html")
+      .body(Vec::new())
+      .unwrap();
+    handler("webview", request, responder);
+    responses.take()
+  }
+
+  #[test]
+  fn protocol_handler_errors() {
+    let responses = call_handler(|_, _, responder| responder.respond(Response::new(&b"ok"[..])));
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].status(), StatusCode::OK);
+
+    let mut responses = call_handler(|_, _, _| panic!("wry"));
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = responses.remove(0).into_body().into_bytes();
+    if cfg!(debug_assertions) {
+      assert!(String::from_utf8_lossy(&body)
+        .contains("Custom protocol 'app' failed to answer app://localhost/index.html: the handler panicked: wry"));
+    }
+
+    // Responding before panicking answers the request once
+    let responses = call_handler(|_, _, responder| {
+      responder.respond(Response::new(Vec::new()));
+      panic!("wry");
+    });
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].status(), StatusCode::OK);
+
+    let malformed = [
+      Response::builder().status(101).body(Vec::new()),
+      Response::builder().status(302).body(Vec::new()),
+      Response::builder()
+        .header(CONTENT_LENGTH, "10")
+        .body(b"wry".to_vec()),
+      Response::builder()
+        .header(CONTENT_LENGTH, "ten")
+        .body(Vec::new()),
+      Response::builder()
+        .header(
+          CONTENT_TYPE,
+          HeaderValue::from_bytes(b"text/html\xff").unwrap(),
+        )
+        .body(Vec::new()),
+    ];
+    for response in malformed {
+      let response = std::cell::Cell::new(Some(response.unwrap()));
+      let responses = call_handler(move |_, _, responder| {
+        responder.respond(response.take().unwrap());
+      });
+      assert_eq!(responses[0].status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+  }
+
+  #[test]
+  fn parse_range_header() {
+    let request = |range: &str| Request::builder().header(RANGE, range).body(()).unwrap();
+
+    assert_eq!(Request::new(()).range(), None);
+    assert_eq!(
+      request("bytes=0-99").range(),
+      Some(Ok(ByteRange::FromTo(0, 99)))
+    );
+    assert_eq!(
+      request("bytes=100-").range(),
+      Some(Ok(ByteRange::From(100)))
+    );
+    assert_eq!(
+      request("bytes=-100").range(),
+      Some(Ok(ByteRange::Last(100)))
+    );
+    assert_eq!(
+      request("bytes=0-1,5-6").range(),
+      Some(Err(RangeError::MultipleRanges))
+    );
+    assert_eq!(request("bytes=a-b").range(), Some(Err(RangeError::Invalid)));
+
+    let response = Response::partial(vec![0; 10], 10..20, 100);
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 10-19/100");
+    assert_eq!(response.headers()[CONTENT_LENGTH], "10");
+  }
+
+  /// A unique temporary directory, removed with everything in it when dropped.
+  #[cfg(feature = "protocol")]
+  struct TempDir(PathBuf);
+
+  #[cfg(feature = "protocol")]
+  impl TempDir {
+    fn new() -> Self {
+      static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+      let nanos = std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos();
+      let count = COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+      let path = std::env::temp_dir().join(format!("wry-{}-{nanos}-{count}", std::process::id()));
+      std::fs::create_dir(&path).unwrap();
+      Self(path)
+    }
+  }
+
+  #[cfg(feature = "protocol")]
+  impl Drop for TempDir {
+    fn drop(&mut self) {
+      let _ = std::fs::remove_dir_all(&self.0);
+    }
+  }
+
+  #[test]
+  #[cfg(feature = "protocol")]
+  fn asset_folder() {
+    let temp = TempDir::new();
+    let dir = temp.0.join("assets");
+    std::fs::create_dir_all(dir.join("css")).unwrap();
+    std::fs::write(dir.join("index.html"), "<html></html>").unwrap();
+    std::fs::write(dir.join("css/main.css"), "body {}").unwrap();
+
+    let serve = |path: &str| {
+      let request = Request::get(format!("app://localhost{path}"))
+        .body(())
+        .unwrap();
+      serve_asset(&dir, &request)
+    };
+
+    let response = serve("/");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/html");
+    assert_eq!(read(response), "<html></html>");
+
+    let response = serve("/css/main.css");
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/css");
+    assert_eq!(read(response), "body {}");
+
+    assert_eq!(serve("/missing.js").status(), StatusCode::NOT_FOUND);
+    assert_eq!(serve("/css/../../secret").status(), StatusCode::FORBIDDEN);
+    assert_eq!(serve("/%2e%2e/secret").status(), StatusCode::FORBIDDEN);
+    assert_eq!(serve("/..%5csecret").status(), StatusCode::FORBIDDEN);
+
+    #[cfg(unix)]
+    {
+      let outside = temp.0.join("outside");
+      std::fs::write(&outside, "secret").unwrap();
+      std::os::unix::fs::symlink(&outside, dir.join("link")).unwrap();
+      assert_eq!(serve("/link").status(), StatusCode::FORBIDDEN);
+    }
+  }
+
+  #[test]
+  fn file_responses() {
+    assert_eq!(mime_type(Path::new("app.mjs")), "text/javascript");
+    assert_eq!(mime_type(Path::new("app.js")), "text/javascript");
+    assert_eq!(mime_type(Path::new("app.wasm")), "application/wasm");
+    assert_eq!(mime_type(Path::new("index.html")), "text/html");
+    assert_eq!(mime_type(Path::new("data")), "application/octet-stream");
+
+    let path = std::env::temp_dir().join(format!("wry-response-{}.mjs", std::process::id()));
+    std::fs::write(&path, "export {}").unwrap();
+
+    let response = Response::<Cow<'static, [u8]>>::from_file(&path).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/javascript");
+    assert_eq!(response.headers()[CONTENT_LENGTH], "9");
+    let etag = response.headers()[ETAG].to_str().unwrap();
+    assert!(etag.starts_with("W/\"9-"));
+    assert_eq!(&response.body()[..], b"export {}");
+
+    let response = Response::<StreamBody>::from_file(&path)
+      .unwrap()
+      .cross_origin_isolated();
+    let headers = response.headers();
+    assert_eq!(headers["cross-origin-opener-policy"], "same-origin");
+    assert_eq!(headers["cross-origin-embedder-policy"], "require-corp");
+    assert_eq!(read(response), "export {}");
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(Response::<Vec<u8>>::from_file(&path).is_err());
+  }
+
+  #[test]
+  #[cfg(all(feature = "protocol", not(target_os = "windows")))]
+  fn content_encoding() {
+    use std::io::Write;
+
+    let html = b"<html><body>compressed</body></html>";
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(html).unwrap();
+    let gzip = encoder.finish().unwrap();
+
+    let response = Response::builder()
+      .header(CONTENT_TYPE, "text/html")
+      .header(CONTENT_ENCODING, "gzip")
+      .header(CONTENT_LENGTH, gzip.len())
+      .body(ResponseBody::Bytes(gzip.into()))
+      .unwrap();
+    let response = decode_content_encoding(response);
+    assert!(!response.headers().contains_key(CONTENT_ENCODING));
+    assert!(!response.headers().contains_key(CONTENT_LENGTH));
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/html");
+    assert_eq!(&response.into_body().into_bytes()[..], html);
+
+    let response = Response::builder()
+      .header(CONTENT_ENCODING, "zstd")
+      .body(ResponseBody::Bytes(Cow::Borrowed(b"zstd")))
+      .unwrap();
+    let response = decode_content_encoding(response);
+    assert_eq!(response.headers()[CONTENT_ENCODING], "zstd");
+    assert_eq!(&response.into_body().into_bytes()[..], b"zstd");
+
+    // Encoded bodies are never sliced
+    let mut headers = HeaderMap::new();
+    headers.insert(RANGE, HeaderValue::from_static("bytes=0-1"));
+    let response = Response::builder()
+      .header(CONTENT_ENCODING, "gzip")
+      .body(StreamBody::from(&b"0123"[..]))
+      .unwrap();
+    assert_eq!(apply_range(&headers, response).status(), StatusCode::OK);
+  }
+}
@@ -0,0 +1,96 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// The kind of a JavaScript dialog.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogKind {
+  /// `window.alert()`.
+  Alert,
+  /// `window.confirm()`.
+  Confirm,
+  /// `window.prompt()`, with the default text of its input.
+  Prompt { default_text: String },
+  /// The confirmation of leaving a page with a `beforeunload` handler.
+  BeforeUnload,
+}
+
+/// A JavaScript dialog opened by a page, passed to the handler of
+/// [`WebViewBuilder::with_dialog_handler`](crate::WebViewBuilder::with_dialog_handler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogRequest {
+  /// The kind of the dialog.
+  pub kind: DialogKind,
+  /// The message of the dialog, empty for [`DialogKind::BeforeUnload`] on most platforms.
+  pub message: String,
+  /// The URL of the page that opened the dialog.
+  pub url: String,
+}
+
+/// The answer to a [`DialogRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogResponse {
+  /// Show the dialog of the platform.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: WebView2 can't show its dialogs once a handler is set, this is the same as
+  ///   [`DialogResponse::Cancel`].
+  /// - **macOS / iOS**: WKWebView has no dialogs of its own, alerts are dismissed, confirmations
+  ///   and prompts cancelled, and pages can always be left.
+  Default,
+  /// Accept the dialog: `confirm()` returns `true`, `prompt()` its default text, and the page is
+  /// left for [`DialogKind::BeforeUnload`].
+  Accept,
+  /// Accept a prompt with the given text, the same as [`DialogResponse::Accept`] for the other
+  /// dialogs.
+  Text(String),
+  /// Cancel the dialog: `confirm()` returns `false`, `prompt()` returns `null`, and the page
+  /// isn't left for [`DialogKind::BeforeUnload`].
+  Cancel,
+}
+
+impl DialogResponse {
+  /// Whether the dialog is accepted, and the text returned by a prompt.
+  ///
+  /// Must not be called with [`DialogResponse::Default`], which the platforms handle themselves.
+  #[allow(dead_code)]
+  pub(crate) fn outcome(self, kind: &DialogKind) -> (bool, Option<String>) {
+    match (self, kind) {
+      (DialogResponse::Cancel | DialogResponse::Default, _) => (false, None),
+      (DialogResponse::Text(text), DialogKind::Prompt { .. }) => (true, Some(text)),
+      (_, DialogKind::Prompt { default_text }) => (true, Some(default_text.clone())),
+      _ => (true, None),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dialog_outcomes() {
+    let prompt = DialogKind::Prompt {
+      default_text: "wry".into(),
+    };
+    assert_eq!(
+      DialogResponse::Accept.outcome(&prompt),
+      (true, Some("wry".into()))
+    );
+    assert_eq!(
+      DialogResponse::Text("tauri".into()).outcome(&prompt),
+      (true, Some("tauri".into()))
+    );
+    assert_eq!(DialogResponse::Cancel.outcome(&prompt), (false, None));
+    assert_eq!(
+      DialogResponse::Text("tauri".into()).outcome(&DialogKind::Confirm),
+      (true, None)
+    );
+    assert_eq!(
+      DialogResponse::Cancel.outcome(&DialogKind::BeforeUnload),
+      (false, None)
+    );
+  }
+}
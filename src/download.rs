@@ -0,0 +1,245 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::RefCell, fmt, path::PathBuf, rc::Rc};
+
+use crate::WebViewAttributes;
+
+/// A download of the webview, passed to the closures of
+/// [`WebViewBuilder::with_download_handler`](crate::WebViewBuilder::with_download_handler).
+///
+/// Clones refer to the same download, so it can be kept to control the download later.
+#[derive(Clone)]
+pub struct Download {
+  url: String,
+  cancel: Rc<dyn Fn()>,
+  pause: Option<(Rc<dyn Fn()>, Rc<dyn Fn()>)>,
+}
+
+impl Download {
+  #[cfg_attr(target_os = "android", allow(dead_code))]
+  pub(crate) fn new(url: String, cancel: impl Fn() + 'static) -> Self {
+    Self {
+      url,
+      cancel: Rc::new(cancel),
+      pause: None,
+    }
+  }
+
+  /// Makes the download pausable, for the platforms that support it.
+  #[cfg_attr(any(target_os = "android", gtk), allow(dead_code))]
+  pub(crate) fn with_pause(
+    mut self,
+    pause: impl Fn() + 'static,
+    resume: impl Fn() + 'static,
+  ) -> Self {
+    self.pause = Some((Rc::new(pause), Rc::new(resume)));
+    self
+  }
+
+  /// The URL the download was requested from.
+  pub fn url(&self) -> &str {
+    &self.url
+  }
+
+  /// Cancels the download, the completion closure is called without a destination.
+  ///
+  /// Does nothing once the download completed.
+  pub fn cancel(&self) {
+    (self.cancel)()
+  }
+
+  /// Whether the download can be paused with [`Self::pause`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Always `false`.
+  pub fn can_pause(&self) -> bool {
+    self.pause.is_some()
+  }
+
+  /// Pauses the download, [`Self::resume`] continues it. The completion closure isn't called for
+  /// paused downloads.
+  ///
+  /// Does nothing if the download [can't be paused](Self::can_pause) or already completed.
+  pub fn pause(&self) {
+    if let Some((pause, _)) = &self.pause {
+      pause()
+    }
+  }
+
+  /// Resumes a paused download.
+  ///
+  /// The rest of the download is requested with a `Range` request. Servers that don't support
+  /// them send the whole file again on Windows, on macOS the download fails and the completion
+  /// closure is called without a destination.
+  ///
+  /// Does nothing if the download isn't paused.
+  pub fn resume(&self) {
+    if let Some((_, resume)) = &self.pause {
+      resume()
+    }
+  }
+}
+
+impl fmt::Debug for Download {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Download")
+      .field("url", &self.url)
+      .field("can_pause", &self.can_pause())
+      .finish_non_exhaustive()
+  }
+}
+
+/// The progress of a [`Download`], passed to the progress closure of
+/// [`WebViewBuilder::with_download_handler`](crate::WebViewBuilder::with_download_handler).
+#[derive(Debug, Clone)]
+pub struct DownloadProgress {
+  /// The download making progress.
+  pub download: Download,
+  /// How many bytes were received so far.
+  pub received_bytes: u64,
+  /// The size of the download in bytes, `None` if the server didn't send it.
+  pub total_bytes: Option<u64>,
+}
+
+type StartedHandler = dyn FnMut(&Download, PathBuf) -> Option<PathBuf>;
+type ProgressHandler = dyn Fn(&DownloadProgress);
+type CompletedHandler = dyn Fn(&Download, Option<PathBuf>);
+
+/// The closures handling the downloads of a webview, see
+/// [`WebViewBuilder::with_download_handler`](crate::WebViewBuilder::with_download_handler).
+pub struct DownloadHandler {
+  started: RefCell<Box<StartedHandler>>,
+  progress: Option<Box<ProgressHandler>>,
+  completed: Option<Box<CompletedHandler>>,
+}
+
+impl DownloadHandler {
+  /// Creates a handler from closures deciding the destination of the downloads, reporting their
+  /// progress and their completion.
+  pub fn new(
+    started: impl FnMut(&Download, PathBuf) -> Option<PathBuf> + 'static,
+    progress: impl Fn(&DownloadProgress) + 'static,
+    completed: impl Fn(&Download, Option<PathBuf>) + 'static,
+  ) -> Self {
+    Self {
+      started: RefCell::new(Box::new(started)),
+      progress: Some(Box::new(progress)),
+      completed: Some(Box::new(completed)),
+    }
+  }
+
+  /// The destination of a download, `None` to cancel it.
+  #[cfg_attr(target_os = "android", allow(dead_code))]
+  pub(crate) fn started(&self, download: &Download, destination: PathBuf) -> Option<PathBuf> {
+    (self.started.borrow_mut())(download, destination)
+  }
+
+  /// Whether the progress of the downloads needs to be reported at all.
+  #[cfg_attr(target_os = "android", allow(dead_code))]
+  pub(crate) fn has_progress(&self) -> bool {
+    self.progress.is_some()
+  }
+
+  #[cfg_attr(target_os = "android", allow(dead_code))]
+  pub(crate) fn progress(
+    &self,
+    download: &Download,
+    received_bytes: u64,
+    total_bytes: Option<u64>,
+  ) {
+    if let Some(progress) = &self.progress {
+      progress(&DownloadProgress {
+        download: download.clone(),
+        received_bytes,
+        total_bytes,
+      });
+    }
+  }
+
+  /// Reports the end of a download, `destination` is `None` if it failed or was cancelled.
+  #[cfg_attr(target_os = "android", allow(dead_code))]
+  pub(crate) fn completed(&self, download: &Download, destination: Option<PathBuf>) {
+    if let Some(completed) = &self.completed {
+      completed(download, destination);
+    }
+  }
+}
+
+/// Turns the download started and completed handlers into a [`DownloadHandler`],
+/// so the platforms only have to handle [`WebViewAttributes::download_handler`].
+pub(crate) fn attach_download_handler(attrs: &mut WebViewAttributes) {
+  let started = attrs.download_started_handler.take();
+  let completed = attrs.download_completed_handler.take();
+  if attrs.download_handler.is_some() || (started.is_none() && completed.is_none()) {
+    return;
+  }
+
+  let started: Box<StartedHandler> = match started {
+    Some(mut started) => Box::new(move |download, mut destination| {
+      started(download.url().to_string(), &mut destination).then_some(destination)
+    }),
+    None => Box::new(|_, destination| Some(destination)),
+  };
+  let completed = completed.map(|completed| -> Box<CompletedHandler> {
+    Box::new(move |download, destination| {
+      let success = destination.is_some();
+      completed(download.url().to_string(), destination, success)
+    })
+  });
+
+  attrs.download_handler = Some(DownloadHandler {
+    started: RefCell::new(started),
+    progress: None,
+    completed,
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn legacy_download_handlers() {
+    let completed = Rc::new(RefCell::new(Vec::new()));
+    let completed_ = completed.clone();
+    let mut attrs = WebViewAttributes {
+      download_started_handler: Some(Box::new(|url, destination| {
+        destination.set_file_name("renamed.zip");
+        !url.ends_with("denied.zip")
+      })),
+      download_completed_handler: Some(Rc::new(move |url, destination, success| {
+        completed_.borrow_mut().push((url, destination, success));
+      })),
+      ..Default::default()
+    };
+    attach_download_handler(&mut attrs);
+
+    let handler = attrs.download_handler.unwrap();
+    let download = Download::new("https://tauri.app/file.zip".into(), || ());
+    let destination = handler.started(&download, PathBuf::from("/downloads/file.zip"));
+    assert_eq!(destination, Some(PathBuf::from("/downloads/renamed.zip")));
+    let denied = Download::new("https://tauri.app/denied.zip".into(), || ());
+    assert_eq!(
+      handler.started(&denied, PathBuf::from("/downloads/denied.zip")),
+      None
+    );
+
+    handler.completed(&download, destination);
+    handler.completed(&denied, None);
+    assert_eq!(
+      *completed.borrow(),
+      vec![
+        (
+          "https://tauri.app/file.zip".to_string(),
+          Some(PathBuf::from("/downloads/renamed.zip")),
+          true
+        ),
+        ("https://tauri.app/denied.zip".to_string(), None, false),
+      ]
+    );
+    assert!(!handler.has_progress());
+  }
+}
@@ -0,0 +1,141 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{DragDropEvent, DragDropHandlerMode, DragOperation};
+
+type Handler = Rc<dyn Fn(DragDropEvent) -> DragOperation>;
+
+/// The drag drop handler of a webview, which can be replaced after the webview is created.
+///
+/// A drag session keeps using the handler that was set when it entered the webview
+/// until it is dropped or leaves, even if the handler is replaced in between.
+pub(crate) struct DragDropHandler {
+  #[cfg(feature = "drag-drop")]
+  mode: DragDropHandlerMode,
+  current: RefCell<Handler>,
+  session: RefCell<Option<Handler>>,
+}
+
+impl DragDropHandler {
+  pub(crate) fn new(
+    handler: Box<dyn Fn(DragDropEvent) -> DragOperation>,
+    mode: DragDropHandlerMode,
+  ) -> Self {
+    Self {
+      #[cfg(feature = "drag-drop")]
+      mode,
+      current: RefCell::new(mode.wrap(handler).into()),
+      session: RefCell::new(None),
+    }
+  }
+
+  #[cfg(feature = "drag-drop")]
+  pub(crate) fn set(&self, handler: Box<dyn Fn(DragDropEvent) -> DragOperation>) {
+    // The previous handler is dropped here, unless a drag session still holds it
+    *self.current.borrow_mut() = self.mode.wrap(handler).into();
+  }
+
+  pub(crate) fn call(&self, event: DragDropEvent) -> DragOperation {
+    let handler = if let DragDropEvent::Enter { .. } = event {
+      let handler = self.current.borrow().clone();
+      *self.session.borrow_mut() = Some(handler.clone());
+      handler
+    } else {
+      let session = self.session.borrow().clone();
+      session.unwrap_or_else(|| self.current.borrow().clone())
+    };
+
+    let ends_session = matches!(event, DragDropEvent::Drop { .. } | DragDropEvent::Cancelled);
+
+    // No borrow is held while calling the handler so it can replace itself
+    let operation = handler(event);
+
+    if ends_session {
+      self.session.borrow_mut().take();
+    }
+
+    operation
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+
+  use super::*;
+
+  fn enter() -> DragDropEvent {
+    DragDropEvent::Enter {
+      paths: Vec::new(),
+      position: (0, 0),
+      operations: Default::default(),
+      data: Default::default(),
+    }
+  }
+
+  #[cfg(feature = "drag-drop")]
+  fn over() -> DragDropEvent {
+    DragDropEvent::Over {
+      paths: Vec::new(),
+      position: (0, 0),
+      operations: Default::default(),
+    }
+  }
+
+  #[test]
+  #[cfg(feature = "drag-drop")]
+  fn drag_session_keeps_its_handler() {
+    let handler = DragDropHandler::new(
+      Box::new(|_| DragOperation::Copy),
+      DragDropHandlerMode::Intercept,
+    );
+    assert_eq!(handler.call(enter()), DragOperation::Copy);
+
+    // The drag that entered before the handler was replaced keeps using the previous one
+    handler.set(Box::new(|_| DragOperation::Move));
+    assert_eq!(handler.call(over()), DragOperation::Copy);
+    assert_eq!(handler.call(DragDropEvent::Leave), DragOperation::Copy);
+    assert_eq!(handler.call(DragDropEvent::Cancelled), DragOperation::Copy);
+
+    assert_eq!(handler.call(enter()), DragOperation::Move);
+    let drop = DragDropEvent::Drop {
+      paths: Vec::new(),
+      position: (0, 0),
+    };
+    assert_eq!(handler.call(drop), DragOperation::Move);
+  }
+
+  #[test]
+  fn drag_drop_handler_modes() {
+    let calls = Rc::new(Cell::new(0));
+    let handler = |mode| {
+      let calls = calls.clone();
+      DragDropHandler::new(
+        Box::new(move |_| {
+          calls.set(calls.get() + 1);
+          DragOperation::Copy
+        }),
+        mode,
+      )
+    };
+
+    assert_eq!(
+      handler(DragDropHandlerMode::Intercept).call(enter()),
+      DragOperation::Copy
+    );
+    assert_eq!(calls.get(), 1);
+    assert_eq!(
+      handler(DragDropHandlerMode::Observe).call(enter()),
+      DragOperation::Native
+    );
+    assert_eq!(calls.get(), 2);
+    assert_eq!(
+      handler(DragDropHandlerMode::Passthrough).call(enter()),
+      DragOperation::Native
+    );
+    assert_eq!(calls.get(), 2);
+  }
+}
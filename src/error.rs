@@ -17,9 +17,14 @@ pub enum Error {
   #[cfg(gtk)]
   #[error("Couldn't find X11 Display")]
   X11DisplayNotFound,
+  #[cfg(gtk)]
+  #[error("WebKitGTK can only navigate with a url-encoded form POST body")]
+  UnsupportedNavigationBody,
   #[cfg(all(gtk, feature = "x11"))]
   #[error(transparent)]
   XlibError(#[from] x11_dl::error::OpenError),
+  #[error("Invalid cookie: {0}")]
+  InvalidCookie(&'static str),
   #[error("Failed to initialize the script")]
   InitScriptError,
   #[error("Bad RPC request: {0} ((1))")]
@@ -35,9 +40,32 @@ pub enum Error {
   SenderError(#[from] std::sync::mpsc::SendError<String>),
   #[error("Failed to send the message")]
   MessageSender,
+  #[error("The webview was closed before the operation completed")]
+  WebViewClosed,
+  #[error("Failed to capture the webview: {0}")]
+  CaptureFailed(String),
+  #[error("Failed to print the webview: {0}")]
+  PrintFailed(String),
+  #[error("Failed to suspend the webview: {0}")]
+  SuspendFailed(String),
+  #[error("Printer '{0}' not found")]
+  PrinterNotFound(String),
+  #[error("Failed to compile the content rules: {0}")]
+  ContentRulesFailed(String),
+  /// The operation isn't available on this platform or in the current state of the webview.
+  #[error("Unsupported: {0}")]
+  Unsupported(String),
+  #[error("Devtools are not enabled, see `WebViewBuilder::with_devtools`")]
+  DevToolsNotEnabled,
+  #[cfg(feature = "serde")]
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
   #[error("IO error: {0}")]
   Io(#[from] std::io::Error),
   #[cfg(target_os = "windows")]
+  #[error("Incognito mode requires WebView2 Runtime version 101.0.1210.39 or higher")]
+  IncognitoUnsupported,
+  #[cfg(target_os = "windows")]
   #[error("WebView2 error: {0}")]
   WebView2Error(webview2_com::Error),
   #[error(transparent)]
@@ -49,6 +77,10 @@ pub enum Error {
   JniError(#[from] jni::errors::Error),
   #[error("Failed to create proxy endpoint")]
   ProxyEndpointCreationFailed,
+  #[error("The proxy can't be changed at runtime: {0}")]
+  ProxyChangeUnsupported(String),
+  #[error("The proxy configuration is not supported: {0}")]
+  UnsupportedProxy(String),
   #[error(transparent)]
   WindowHandleError(#[from] raw_window_handle::HandleError),
   #[error("the window handle kind is not supported")]
@@ -62,12 +94,26 @@ pub enum Error {
   NotMainThread,
   #[error("Custom protocol task is invalid.")]
   CustomProtocolTaskInvalid,
+  /// A custom protocol handler panicked or answered with a malformed response. The request is
+  /// answered with `500 Internal Server Error` instead and the error is logged.
+  #[error("Custom protocol '{scheme}' failed to answer {uri}: {reason}")]
+  CustomProtocol {
+    scheme: String,
+    uri: String,
+    reason: String,
+  },
   #[error("Failed to register URL scheme: {0}, could be due to invalid URL scheme or the scheme is already registered.")]
   UrlSchemeRegisterError(String),
+  /// The custom protocol was registered twice on the [`WebViewBuilder`](crate::WebViewBuilder),
+  /// or on macOS and iOS, the webview configuration already has a handler for it.
   #[error("Duplicate custom protocol '{0}' registered on the WebViewBuilder")]
   DuplicateCustomProtocol(String),
   #[error("Duplicate custom protocol '{0}' registered on the same web context on Linux")]
   ContextDuplicateCustomProtocol(String),
+  /// The name given to [`WebView::add_message_handler`](crate::WebView::add_message_handler) is
+  /// empty, contains a `:` or is used by wry.
+  #[error("Invalid message handler name '{0}'")]
+  InvalidMessageHandlerName(String),
   #[error(transparent)]
   #[cfg(any(target_os = "macos", target_os = "ios"))]
   UrlParse(#[from] url::ParseError),
@@ -0,0 +1,104 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  script::{chunks, json_string},
+  util::Counter,
+  InitializationScript, Result, WebViewAttributes,
+};
+
+/// The largest payload sent with a single script, in bytes of JSON. Larger payloads are sent in
+/// chunks, the platforms struggle with scripts of many megabytes.
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// Defines `window.wry.listen` for the events of [`WebView::emit`](crate::WebView::emit).
+pub(crate) fn attach_event_listener(attrs: &mut WebViewAttributes) {
+  attrs.initialization_scripts.insert(
+    0,
+    InitializationScript {
+      script: SCRIPT.into(),
+      for_main_frame_only: true,
+      injection_time: Default::default(),
+    },
+  );
+}
+
+/// The scripts dispatching `event` with `payload`, to evaluate in order.
+///
+/// The payload is passed as a JSON string and parsed by the page, so it can't run code.
+pub(crate) fn scripts(event: &str, payload: &serde_json::Value) -> Result<Vec<String>> {
+  static IDS: Counter = Counter::new();
+
+  let json = serde_json::to_string(payload)?;
+  let event = json_string(event);
+  let chunks = chunks(&json, CHUNK_SIZE);
+  let id = if chunks.len() > 1 { IDS.next() } else { 0 };
+  let last = chunks.len() - 1;
+  Ok(
+    chunks
+      .into_iter()
+      .enumerate()
+      .map(|(i, chunk)| {
+        format!(
+          "window.__WRY_EVENT__ && window.__WRY_EVENT__({id}, {event}, {}, {})",
+          json_string(chunk),
+          i == last
+        )
+      })
+      .collect(),
+  )
+}
+
+const SCRIPT: &str = r#"(function () {
+  if (window.wry) return;
+  var target = new EventTarget();
+  var chunks = new Map();
+  Object.defineProperty(window, '__WRY_EVENT__', {
+    value: function (id, event, chunk, last) {
+      var parts = chunks.get(id) || [];
+      parts.push(chunk);
+      if (!last) {
+        chunks.set(id, parts);
+        return;
+      }
+      chunks.delete(id);
+      target.dispatchEvent(new CustomEvent(event, { detail: JSON.parse(parts.join('')) }));
+    }
+  });
+  Object.defineProperty(window, 'wry', {
+    value: Object.freeze({
+      listen: function (event, callback) {
+        var listener = function (e) { callback(e.detail); };
+        target.addEventListener(event, listener);
+        return function () { target.removeEventListener(event, listener); };
+      }
+    })
+  });
+})();"#;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn event_scripts() {
+    let scripts = scripts(
+      "update",
+      &serde_json::json!({ "title": "</script><script>alert(1)</script>\"" }),
+    )
+    .unwrap();
+    assert_eq!(
+      scripts,
+      [
+        r#"window.__WRY_EVENT__ && window.__WRY_EVENT__(0, "update", "{\"title\":\"</script><script>alert(1)</script>\\\"\"}", true)"#
+      ]
+    );
+
+    let payload = serde_json::Value::String("é".repeat(CHUNK_SIZE));
+    let scripts = super::scripts("large", &payload).unwrap();
+    assert_eq!(scripts.len(), 3);
+    assert!(scripts[0].ends_with(", false)"));
+    assert!(scripts[2].ends_with(", true)"));
+  }
+}
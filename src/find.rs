@@ -0,0 +1,162 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(any(
+  target_os = "windows",
+  target_os = "android",
+  target_os = "macos",
+  target_os = "ios",
+  test
+))]
+use crate::script::json_string;
+
+/// The options of [`WebView::find`](crate::WebView::find).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindOptions {
+  /// Whether the case of the letters must match. Default is `false`.
+  pub case_sensitive: bool,
+  /// Whether [`WebView::find_next`](crate::WebView::find_next) and
+  /// [`WebView::find_previous`](crate::WebView::find_previous) continue from the other end of
+  /// the page after the last match. Default is `true`.
+  pub wrap_around: bool,
+  /// Whether to only match whole words. Default is `false`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Matches at the start of words, `wry` matches in `wryly`.
+  /// - **macOS / iOS**: Unsupported.
+  pub whole_word: bool,
+}
+
+impl Default for FindOptions {
+  fn default() -> Self {
+    Self {
+      case_sensitive: false,
+      wrap_around: true,
+      whole_word: false,
+    }
+  }
+}
+
+/// Finds `query` in the text of the page and returns the number of matches.
+///
+/// With `highlight`, the matches are highlighted and the first one selected and scrolled into
+/// view, [`find_step`] then moves between them. Matches spanning several elements aren't found.
+#[cfg(any(
+  target_os = "windows",
+  target_os = "android",
+  target_os = "macos",
+  target_os = "ios",
+  test
+))]
+pub(crate) fn find(query: &str, options: FindOptions, highlight: bool) -> String {
+  format!(
+    r#"(function () {{
+  var query = {query};
+  if (window.__wryFind) window.__wryFind.clear();
+  window.__wryFind = null;
+  if (!query) return 0;
+  var pattern = query.replace(/[.*+?^${{}}()|[\]\\]/g, '\\$&');
+  if ({whole_word}) pattern = '(?<![\\p{{L}}\\p{{N}}_])' + pattern + '(?![\\p{{L}}\\p{{N}}_])';
+  var regex = new RegExp(pattern, {flags});
+  var skipped = {{ SCRIPT: 1, STYLE: 1, NOSCRIPT: 1, TEXTAREA: 1 }};
+  var walker = document.createTreeWalker(document.body || document.documentElement, NodeFilter.SHOW_TEXT, {{
+    acceptNode: function (node) {{
+      return node.parentNode && skipped[node.parentNode.nodeName] ? NodeFilter.FILTER_REJECT : NodeFilter.FILTER_ACCEPT;
+    }}
+  }});
+  var ranges = [];
+  for (var node = walker.nextNode(); node; node = walker.nextNode()) {{
+    var match;
+    regex.lastIndex = 0;
+    while ((match = regex.exec(node.data))) {{
+      if (!match[0]) {{ regex.lastIndex++; continue; }}
+      var range = document.createRange();
+      range.setStart(node, match.index);
+      range.setEnd(node, match.index + match[0].length);
+      ranges.push(range);
+    }}
+  }}
+  if (!{highlight}) return ranges.length;
+  var highlights = window.CSS && CSS.highlights;
+  if (highlights && !document.querySelector('style[data-wry-find]')) {{
+    var style = document.createElement('style');
+    style.setAttribute('data-wry-find', '');
+    style.textContent = '::highlight(wry-find) {{ background-color: #ffff00; color: #000000; }} ::highlight(wry-find-current) {{ background-color: #ff9632; color: #000000; }}';
+    (document.head || document.documentElement).appendChild(style);
+  }}
+  var find = window.__wryFind = {{
+    ranges: ranges,
+    index: -1,
+    step: function (direction) {{
+      if (!find.ranges.length) return 0;
+      var index = find.index + direction;
+      if (index < 0 || index >= find.ranges.length) {{
+        if (!{wrap_around}) return find.ranges.length;
+        index = (index + find.ranges.length) % find.ranges.length;
+      }}
+      find.index = index;
+      var range = find.ranges[index];
+      if (highlights) highlights.set('wry-find-current', new Highlight(range));
+      var selection = window.getSelection();
+      selection.removeAllRanges();
+      selection.addRange(range);
+      var element = range.startContainer.parentElement;
+      if (element) element.scrollIntoView({{ block: 'center', inline: 'nearest' }});
+      return find.ranges.length;
+    }},
+    clear: function () {{
+      if (highlights) {{
+        highlights.delete('wry-find');
+        highlights.delete('wry-find-current');
+      }}
+      window.getSelection().removeAllRanges();
+    }}
+  }};
+  if (highlights) highlights.set('wry-find', new Highlight(...ranges));
+  return find.step(1);
+}})()"#,
+    query = json_string(query),
+    whole_word = options.whole_word,
+    flags = json_string(if options.case_sensitive { "gu" } else { "giu" }),
+    wrap_around = options.wrap_around,
+  )
+}
+
+/// Selects the next match of [`find`] with a `direction` of `1`, or the previous one with `-1`.
+#[cfg(any(target_os = "windows", target_os = "android"))]
+pub(crate) fn find_step(direction: i32) -> String {
+  format!("window.__wryFind ? window.__wryFind.step({direction}) : 0")
+}
+
+/// Removes the highlights of [`find`].
+#[cfg(any(target_os = "windows", target_os = "android"))]
+pub(crate) const CLEAR_MATCHES: &str =
+  "if (window.__wryFind) { window.__wryFind.clear(); window.__wryFind = null; }";
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::script::parse_json_string;
+
+  #[test]
+  fn find_script() {
+    let query = "a.b \"c\" \\\n";
+    let options = FindOptions {
+      case_sensitive: true,
+      whole_word: true,
+      ..Default::default()
+    };
+    let script = find(query, options, true);
+    let (_, script_query) = script.split_once("var query = ").unwrap();
+    assert_eq!(parse_json_string(script_query).unwrap().0, query);
+
+    let flags = |script: &str| {
+      let (_, flags) = script.split_once("new RegExp(pattern, ").unwrap();
+      parse_json_string(flags).unwrap().0
+    };
+    assert_eq!(flags(&script), "gu");
+    assert_eq!(flags(&find("wry", Default::default(), false)), "giu");
+  }
+}
@@ -0,0 +1,168 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// A key press passed to the handler of
+/// [`WebViewBuilder::with_accelerator_passthrough`](crate::WebViewBuilder::with_accelerator_passthrough).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEvent {
+  /// The key, named like the `key` of DOM `KeyboardEvent`s: the lowercase character for
+  /// printable keys, e.g. `s` or `1`, otherwise a name like `Enter`, `Escape`, `F5` or `ArrowUp`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux**: Printable keys are the character typed with Shift applied, e.g. `!` for
+  ///   Shift+1.
+  pub key: String,
+  /// The modifiers held while the key was pressed.
+  pub modifiers: Modifiers,
+}
+
+/// The modifier keys held during a [`KeyEvent`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+  pub shift: bool,
+  pub control: bool,
+  /// Alt on Windows and Linux, Option on macOS.
+  pub alt: bool,
+  /// The Windows key on Windows, Super on Linux, Command on macOS.
+  pub meta: bool,
+}
+
+/// The key of a Win32 virtual key code, `None` for keys we don't name.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn key_from_virtual_key(vk: u32) -> Option<String> {
+  let key = match vk {
+    // A-Z keys - return lowercase letter
+    0x41..=0x5A => ((vk as u8) + 32) as char,
+    0x30..=0x39 => (vk as u8) as char,
+    0x70..=0x7B => return Some(format!("F{}", vk - 0x6F)),
+    0x20 => ' ',
+    0x0D => return Some("Enter".into()),
+    0x1B => return Some("Escape".into()),
+    0x09 => return Some("Tab".into()),
+    0x08 => return Some("Backspace".into()),
+    0x2E => return Some("Delete".into()),
+    0x2D => return Some("Insert".into()),
+    0x24 => return Some("Home".into()),
+    0x23 => return Some("End".into()),
+    0x21 => return Some("PageUp".into()),
+    0x22 => return Some("PageDown".into()),
+    0x25 => return Some("ArrowLeft".into()),
+    0x26 => return Some("ArrowUp".into()),
+    0x27 => return Some("ArrowRight".into()),
+    0x28 => return Some("ArrowDown".into()),
+    0xBF => '/',
+    0xDC => '\\',
+    0xBD => '-',
+    0xBB => '=',
+    0xDB => '[',
+    0xDD => ']',
+    0xBA => ';',
+    0xDE => '\'',
+    0xBC => ',',
+    0xBE => '.',
+    0xC0 => '`',
+    _ => return None,
+  };
+  Some(key.to_string())
+}
+
+/// The key of an `NSEvent`'s `charactersIgnoringModifiers`, `None` for keys we don't name.
+#[cfg(any(target_os = "macos", test))]
+pub(crate) fn key_from_mac_character(c: char) -> Option<String> {
+  let key = match c {
+    '\r' | '\u{3}' => "Enter",
+    '\u{1b}' => "Escape",
+    '\t' | '\u{19}' => "Tab",
+    '\u{7f}' => "Backspace",
+    '\u{f700}' => "ArrowUp",
+    '\u{f701}' => "ArrowDown",
+    '\u{f702}' => "ArrowLeft",
+    '\u{f703}' => "ArrowRight",
+    // NSF1FunctionKey to NSF35FunctionKey
+    '\u{f704}'..='\u{f726}' => return Some(format!("F{}", c as u32 - 0xf703)),
+    '\u{f727}' => "Insert",
+    '\u{f728}' => "Delete",
+    '\u{f729}' => "Home",
+    '\u{f72b}' => "End",
+    '\u{f72c}' => "PageUp",
+    '\u{f72d}' => "PageDown",
+    c if c.is_control() || ('\u{f700}'..='\u{f8ff}').contains(&c) => return None,
+    c => return Some(c.to_lowercase().collect()),
+  };
+  Some(key.into())
+}
+
+/// The key of a GDK keyval, from its name and unicode character, `None` for keys we don't name.
+#[cfg(any(gtk, test))]
+pub(crate) fn key_from_gdk_keyval(name: &str, c: Option<char>) -> Option<String> {
+  let key = match name {
+    "Return" | "KP_Enter" | "ISO_Enter" => "Enter",
+    "Escape" => "Escape",
+    "Tab" | "ISO_Left_Tab" => "Tab",
+    "BackSpace" => "Backspace",
+    "Delete" | "KP_Delete" => "Delete",
+    "Insert" | "KP_Insert" => "Insert",
+    "Home" | "KP_Home" => "Home",
+    "End" | "KP_End" => "End",
+    "Page_Up" | "KP_Page_Up" => "PageUp",
+    "Page_Down" | "KP_Page_Down" => "PageDown",
+    "Left" | "KP_Left" => "ArrowLeft",
+    "Up" | "KP_Up" => "ArrowUp",
+    "Right" | "KP_Right" => "ArrowRight",
+    "Down" | "KP_Down" => "ArrowDown",
+    name if name.len() > 1 && name.starts_with('F') && name[1..].parse::<u8>().is_ok() => name,
+    _ => {
+      return c
+        .filter(|c| !c.is_control())
+        .map(|c| c.to_lowercase().collect())
+    }
+  };
+  Some(key.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn virtual_key_names() {
+    assert_eq!(key_from_virtual_key(0x53).as_deref(), Some("s"));
+    assert_eq!(key_from_virtual_key(0x31).as_deref(), Some("1"));
+    assert_eq!(key_from_virtual_key(0x74).as_deref(), Some("F5"));
+    assert_eq!(key_from_virtual_key(0x0D).as_deref(), Some("Enter"));
+    assert_eq!(key_from_virtual_key(0xBF).as_deref(), Some("/"));
+    // VK_SHIFT
+    assert_eq!(key_from_virtual_key(0x10), None);
+  }
+
+  #[test]
+  fn mac_character_names() {
+    assert_eq!(key_from_mac_character('S').as_deref(), Some("s"));
+    assert_eq!(key_from_mac_character('!').as_deref(), Some("!"));
+    assert_eq!(key_from_mac_character('\r').as_deref(), Some("Enter"));
+    assert_eq!(key_from_mac_character('\u{f708}').as_deref(), Some("F5"));
+    assert_eq!(
+      key_from_mac_character('\u{f72c}').as_deref(),
+      Some("PageUp")
+    );
+    assert_eq!(key_from_mac_character('\u{f72f}'), None);
+  }
+
+  #[test]
+  fn gdk_keyval_names() {
+    assert_eq!(key_from_gdk_keyval("s", Some('s')).as_deref(), Some("s"));
+    assert_eq!(key_from_gdk_keyval("S", Some('S')).as_deref(), Some("s"));
+    assert_eq!(key_from_gdk_keyval("F12", None).as_deref(), Some("F12"));
+    assert_eq!(
+      key_from_gdk_keyval("Return", Some('\r')).as_deref(),
+      Some("Enter")
+    );
+    assert_eq!(
+      key_from_gdk_keyval("ISO_Left_Tab", None).as_deref(),
+      Some("Tab")
+    );
+    assert_eq!(key_from_gdk_keyval("Shift_L", None), None);
+  }
+}
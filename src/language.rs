@@ -0,0 +1,94 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(any(target_os = "macos", target_os = "ios", test))]
+use crate::script::json_string;
+
+/// The languages of an `Accept-Language` header, from the most to the least preferred, without
+/// their quality values.
+#[cfg(any(not(target_os = "android"), test))]
+pub(crate) fn languages(accept_language: &str) -> Vec<&str> {
+  let mut languages = accept_language
+    .split(',')
+    .filter_map(|language| {
+      let mut parts = language.split(';');
+      let tag = parts.next()?.trim();
+      let quality = parts
+        .find_map(|param| param.trim().strip_prefix("q="))
+        .and_then(|q| q.trim().parse::<f32>().ok())
+        .unwrap_or(1.0);
+      (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+    })
+    .collect::<Vec<_>>();
+  // A stable sort keeps the order of the header for the same quality
+  languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+  languages.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// The `Accept-Language` header asking for `languages`, from the most to the least preferred.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios", test))]
+pub(crate) fn header(languages: &[&str]) -> String {
+  languages
+    .iter()
+    .map(|language| language.trim())
+    .filter(|language| !language.is_empty())
+    .enumerate()
+    .map(|(i, language)| match i {
+      0 => language.to_string(),
+      // The quality decreases from 0.9 and stays at 0.1 for the least preferred languages
+      i => format!("{language};q=0.{}", 10usize.saturating_sub(i).max(1)),
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Overrides `navigator.language` and `navigator.languages` with the languages of
+/// `accept_language`.
+#[cfg(any(target_os = "macos", target_os = "ios", test))]
+pub(crate) fn script(accept_language: &str) -> String {
+  let languages = languages(accept_language)
+    .into_iter()
+    .map(json_string)
+    .collect::<Vec<_>>()
+    .join(",");
+  format!(
+    r#"(function () {{
+  var languages = Object.freeze([{languages}]);
+  if (!languages.length) return;
+  Object.defineProperty(Navigator.prototype, 'language', {{ get: function () {{ return languages[0]; }}, configurable: true }});
+  Object.defineProperty(Navigator.prototype, 'languages', {{ get: function () {{ return languages; }}, configurable: true }});
+}})();"#
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn accept_language_languages() {
+    assert_eq!(languages("fr-FR"), ["fr-FR"]);
+    assert_eq!(
+      languages("en;q=0.5, fr-FR,fr;q=0.9 , *;q=0.1,de;q=0"),
+      ["fr-FR", "fr", "en"]
+    );
+    assert!(languages("").is_empty());
+
+    assert_eq!(
+      header(&["fr-CH", " fr", "", "en"]),
+      "fr-CH, fr;q=0.9, en;q=0.8"
+    );
+    let many = ["a"; 12];
+    assert!(header(&many).ends_with("a;q=0.1, a;q=0.1, a;q=0.1"));
+    assert_eq!(languages(&header(&["de", "en", "fr"])), ["de", "en", "fr"]);
+
+    let script = script("de;q=0.8, de-\"CH\"");
+    let (_, languages) = script.split_once("Object.freeze(").unwrap();
+    let (languages, _) = languages.split_once(");").unwrap();
+    assert_eq!(
+      crate::script::parse_json_string_array(languages).unwrap(),
+      ["de-\"CH\"", "de"]
+    );
+  }
+}
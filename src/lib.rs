@@ -303,6 +303,7 @@
 //!   loading assets.
 //! - `drag-drop` (default): Enables [`WebViewBuilder::with_drag_drop_handler`] to control the behavior when there are files
 //!   interacting with the window.
+//! - `drag-drop-simulation`: Enables [`WebView::simulate_drag_drop`] to drive the drag drop handler from tests.
 //! - `devtools`: Enables devtools on release builds. Devtools are always enabled in debug builds.
 //!   On **macOS**, enabling devtools, requires calling private APIs so you should not enable this flag in release
 //!   build if your app needs to publish to App Store.
@@ -312,7 +313,12 @@
 //!   Avoid this in release build if your app needs to publish to App Store.
 //! - `linux-body`: Enables body support of custom protocol request on Linux. Requires
 //!   WebKit2GTK v2.40 or above.
+//! - `tls-error-handler`: Enables `WebViewBuilder::with_tls_error_handler` to accept certificates
+//!   failing the TLS verification, e.g. self-signed certificates of development servers. This
+//!   weakens the security of the webview, avoid it in release builds.
 //! - `tracing`: enables [`tracing`] for `evaluate_script`, `ipc_handler`, and `custom_protocols`.
+//! - `serde`: Enables [`serde`](https://docs.rs/serde) support for the `dpi` types and
+//!   [`WebView::evaluate_script_async`].
 //!
 //! ## Partners
 //!
@@ -347,11 +353,34 @@
 // #[macro_use]
 // extern crate objc;
 
+mod accessibility;
+mod auth;
+mod clipboard;
+mod content_mode;
+mod context_menu;
+mod cookies;
+mod custom_protocol;
 #[cfg(any(target_os = "windows", target_os = "android"))]
 mod custom_protocol_workaround;
+mod dialog;
+mod download;
+#[cfg(any(target_os = "macos", target_os = "windows", gtk))]
+mod drag_drop;
 mod error;
+#[cfg(feature = "serde")]
+mod event;
+mod find;
+mod ipc;
+mod keyboard;
+mod language;
+mod memory;
+mod navigation;
+mod permission;
 mod proxy;
-#[cfg(any(target_os = "macos", target_os = "android", target_os = "ios"))]
+mod script;
+mod scroll;
+mod selection;
+mod service_worker;
 mod util;
 mod web_context;
 
@@ -373,7 +402,7 @@ use android::*;
 pub(crate) mod webkitgtk;
 /// Re-exported [raw-window-handle](https://docs.rs/raw-window-handle/latest/raw_window_handle/) crate.
 pub use raw_window_handle;
-use raw_window_handle::HasWindowHandle;
+use raw_window_handle::{HandleError, HasWindowHandle, RawWindowHandle, WindowHandle};
 #[cfg(gtk)]
 use webkitgtk::*;
 
@@ -405,11 +434,31 @@ use std::{borrow::Cow, collections::HashMap, path::PathBuf, rc::Rc};
 
 use http::{Request, Response};
 
+pub use accessibility::AccessibilityNode;
+pub use auth::{
+  AuthChallenge, AuthChallengeKind, CertRequest, Certificate, CertificateInfo, Credentials,
+};
+pub use clipboard::ClipboardPolicy;
+pub use content_mode::ContentMode;
+pub use context_menu::{
+  ContextMenuAction, ContextMenuItem, ContextMenuMediaType, ContextMenuTarget,
+};
 pub use cookie;
+pub(crate) use custom_protocol::ResponseBody;
+pub use custom_protocol::{ByteRange, RangeError, RequestExt, ResponseExt, StreamBody};
+pub use dialog::{DialogKind, DialogRequest, DialogResponse};
+pub use download::{Download, DownloadHandler, DownloadProgress};
 pub use dpi;
 pub use error::*;
+pub use find::FindOptions;
 pub use http;
-pub use proxy::{ProxyConfig, ProxyEndpoint};
+pub use ipc::{IpcResponder, IpcSource};
+pub use keyboard::{KeyEvent, Modifiers};
+pub use memory::{MemoryUsage, MemoryUsageLevel};
+pub use permission::{PermissionKind, PermissionRequest, PermissionResponse};
+pub use proxy::{ProxyAuth, ProxyConfig, ProxyEndpoint};
+pub use selection::Selection;
+pub use service_worker::ServiceWorkerRegistration;
 pub use web_context::WebContext;
 
 #[cfg(target_os = "ios")]
@@ -434,11 +483,58 @@ impl Default for Rect {
   }
 }
 
+impl Rect {
+  /// The rect in physical pixels at `scale_factor`.
+  ///
+  /// The edges are rounded rather than the position and the size, so that fractional
+  /// coordinates don't drift and adjacent rects neither overlap nor leave a gap.
+  #[cfg(any(target_os = "windows", test))]
+  pub(crate) fn to_physical(
+    self,
+    scale_factor: f64,
+  ) -> (dpi::PhysicalPosition<i32>, dpi::PhysicalSize<i32>) {
+    let position = self.position.to_physical::<f64>(scale_factor);
+    let size = self.size.to_physical::<f64>(scale_factor);
+    let (x, y, width, height) = round_edges(position.x, position.y, size.width, size.height);
+    (
+      dpi::PhysicalPosition::new(x, y),
+      dpi::PhysicalSize::new(width, height),
+    )
+  }
+
+  /// The rect in whole logical pixels at `scale_factor`, rounded like [`Rect::to_physical`].
+  #[cfg(any(gtk, test))]
+  pub(crate) fn to_logical(
+    self,
+    scale_factor: f64,
+  ) -> (dpi::LogicalPosition<i32>, dpi::LogicalSize<i32>) {
+    let position = self.position.to_logical::<f64>(scale_factor);
+    let size = self.size.to_logical::<f64>(scale_factor);
+    let (x, y, width, height) = round_edges(position.x, position.y, size.width, size.height);
+    (
+      dpi::LogicalPosition::new(x, y),
+      dpi::LogicalSize::new(width, height),
+    )
+  }
+}
+
+#[cfg(any(target_os = "windows", gtk, test))]
+fn round_edges(x: f64, y: f64, width: f64, height: f64) -> (i32, i32, i32, i32) {
+  let (left, top) = (x.round(), y.round());
+  let (right, bottom) = ((x + width).round(), (y + height).round());
+  (
+    left as i32,
+    top as i32,
+    (right - left) as i32,
+    (bottom - top) as i32,
+  )
+}
+
 /// Resolves a custom protocol [`Request`] asynchronously.
 ///
 /// See [`WebViewBuilder::with_asynchronous_custom_protocol`] for more information.
 pub struct RequestAsyncResponder {
-  pub(crate) responder: Box<dyn FnOnce(Response<Cow<'static, [u8]>>)>,
+  pub(crate) responder: Box<dyn FnOnce(Response<ResponseBody>)>,
 }
 
 // SAFETY: even though the webview bindings do not indicate the responder is Send,
@@ -450,16 +546,41 @@ impl RequestAsyncResponder {
   /// Resolves the request with the given response.
   pub fn respond<T: Into<Cow<'static, [u8]>>>(self, response: Response<T>) {
     let (parts, body) = response.into_parts();
-    (self.responder)(Response::from_parts(parts, body.into()))
+    self.respond_body(Response::from_parts(
+      parts,
+      ResponseBody::Bytes(body.into()),
+    ))
+  }
+
+  /// Resolves the request with the given response, reading its body incrementally.
+  ///
+  /// The body is read on the thread this is called from, so call it from a background thread
+  /// or async task when the body is slow to read.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: The body is read into memory before it is handed to the webview.
+  pub fn respond_stream(self, response: Response<StreamBody>) {
+    let (parts, body) = response.into_parts();
+    self.respond_body(Response::from_parts(parts, ResponseBody::Stream(body)))
+  }
+
+  fn respond_body(self, response: Response<ResponseBody>) {
+    // WebView2 decodes the `Content-Encoding` of custom protocol responses itself
+    #[cfg(all(feature = "protocol", not(target_os = "windows")))]
+    let response = custom_protocol::decode_content_encoding(response);
+    (self.responder)(response)
   }
 }
 
 /// Response for the new window request handler.
 ///
-/// See [`WebViewBuilder::with_new_window_req_handler`].
+/// See [`WebViewBuilder::with_new_window_request_handler`].
 pub enum NewWindowResponse {
   /// Allow the window to be opened with the default implementation.
   Allow,
+  /// Don't open a window, load the URL in the webview that requested it instead.
+  OpenInSameView,
   /// Allow the window to be opened, with the given platform webview instance.
   ///
   /// ## Platform-specific:
@@ -486,6 +607,40 @@ pub enum NewWindowResponse {
   Deny,
 }
 
+/// The decision of the navigation handler.
+///
+/// See [`WebViewBuilder::with_navigation_handler_ext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationDecision {
+  /// Allow the navigation.
+  Allow,
+  /// Cancel the navigation.
+  Deny,
+  /// Cancel the navigation and navigate to the given url instead.
+  Redirect(String),
+  /// Allow the navigation and load the page with the given content mode, which stays in use for
+  /// the following navigations. See [`WebViewBuilder::with_content_mode`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / Android**: When the content mode changes, the navigation is cancelled and the
+  ///   url loaded again with the new user agent, the body of a form submission is lost.
+  AllowWithContentMode(ContentMode),
+}
+
+/// What to do with a request seen by the web resource request handler.
+///
+/// See [`WebViewBuilder::with_web_resource_request_handler`].
+#[derive(Debug)]
+pub enum RequestAction {
+  /// Send the request, with the changes made by the handler.
+  Continue,
+  /// Don't send the request, the page receives a `403 Forbidden` response.
+  Block,
+  /// Don't send the request and answer it with the given response.
+  Override(Response<Cow<'static, [u8]>>),
+}
+
 /// Information about the webview that initiated a new window request.
 #[derive(Debug)]
 pub struct NewWindowOpener {
@@ -535,6 +690,19 @@ pub struct NewWindowFeatures {
   pub opener: NewWindowOpener,
 }
 
+/// A request to open a new window, see [`WebViewBuilder::with_new_window_request_handler`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct NewWindowRequest {
+  /// The URL to open.
+  pub url: String,
+  /// The features of the requested window.
+  pub features: NewWindowFeatures,
+  /// Whether the request comes from a user gesture, e.g. a click on a `target="_blank"` link,
+  /// rather than from a script on its own.
+  pub user_initiated: bool,
+}
+
 /// An id for a webview
 pub type WebViewId<'a> = &'a str;
 
@@ -548,6 +716,9 @@ pub struct WebViewAttributes<'a> {
   /// Whether the WebView should have a custom user-agent.
   pub user_agent: Option<String>,
 
+  /// The languages asked from websites, see [`WebViewBuilder::with_accept_language`].
+  pub accept_language: Option<String>,
+
   /// Whether the WebView window should be visible.
   pub visible: bool,
 
@@ -587,6 +758,13 @@ pub struct WebViewAttributes<'a> {
   /// **macOS / Linux / Android / iOS**: Unsupported
   pub zoom_hotkeys_enabled: bool,
 
+  /// Whether pinch gestures magnify the page, see [`WebViewBuilder::with_pinch_zoom`]. `None`
+  /// keeps the default of the platform.
+  pub pinch_zoom: Option<bool>,
+
+  /// The kind of content asked from websites, see [`WebViewBuilder::with_content_mode`].
+  pub content_mode: ContentMode,
+
   /// Whether load the provided html string to [`WebView`].
   /// This will be ignored if the `url` is provided.
   ///
@@ -599,6 +777,10 @@ pub struct WebViewAttributes<'a> {
   /// - **Windows:** the string can not be larger than 2 MB (2 * 1024 * 1024 bytes) in total size
   pub html: Option<String>,
 
+  /// The URL the relative URLs of [`WebViewAttributes::html`] resolve against, see
+  /// [`WebViewBuilder::with_html_and_base_url`].
+  pub html_base_url: Option<String>,
+
   /// A list of initialization javascript scripts to run when loading new pages.
   /// When webview load a new page, this initialization code will be executed.
   /// It is guaranteed that code is executed before `window.onload`.
@@ -614,6 +796,15 @@ pub struct WebViewAttributes<'a> {
   /// [onPageStarted]: https://developer.android.com/reference/android/webkit/WebViewClient#onPageStarted(android.webkit.WebView,%20java.lang.String,%20android.graphics.Bitmap)
   pub initialization_scripts: Vec<InitializationScript>,
 
+  /// Whether scripts evaluated with [`WebView::evaluate_script`] before the first page was
+  /// committed wait for it, instead of running in the blank document the webview starts with.
+  /// Their callbacks are called once they ran. Default is `true`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, scripts are evaluated once the webview was created.
+  pub deferred_scripts: bool,
+
   /// A list of custom loading protocols with pairs of scheme uri string and a handling
   /// closure.
   ///
@@ -641,22 +832,39 @@ pub struct WebViewAttributes<'a> {
   pub custom_protocols:
     HashMap<String, Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)>>,
 
+  /// A handler to rewrite, block or answer the http(s) requests of the webview,
+  /// see [`WebViewBuilder::with_web_resource_request_handler`].
+  pub web_resource_request_handler: Option<Box<dyn Fn(&mut Request<Vec<u8>>) -> RequestAction>>,
+
   /// The IPC handler to receive the message from Javascript on webview
   /// using `window.ipc.postMessage("insert_message_here")` to host Rust code.
   pub ipc_handler: Option<Box<dyn Fn(Request<String>)>>,
 
+  /// The handler of the requests made with `window.ipc.invoke(payload)` from Javascript,
+  /// see [`WebViewBuilder::with_ipc_invoke_handler`].
+  pub ipc_invoke_handler: Option<Box<dyn Fn(u64, Request<String>, IpcResponder)>>,
+
+  /// How long `window.ipc.invoke` waits for a response before rejecting, `None` waits forever.
+  ///
+  /// Defaults to 30 seconds.
+  pub ipc_invoke_timeout: Option<std::time::Duration>,
+
   /// A handler closure to process incoming [`DragDropEvent`] of the webview.
   ///
   /// # Blocking OS Default Behavior
-  /// Return `true` in the callback to block the OS' default behavior.
+  /// Return any [`DragOperation`] other than [`DragOperation::Native`] in the callback to block the OS' default behavior.
   ///
   /// Note, that if you do block this behavior, it won't be possible to drop files on `<input type="file">` forms.
   /// Also note, that it's not possible to manually set the value of a `<input type="file">` via JavaScript for security reasons.
   #[cfg(feature = "drag-drop")]
   #[cfg_attr(docsrs, doc(cfg(feature = "drag-drop")))]
-  pub drag_drop_handler: Option<Box<dyn Fn(DragDropEvent) -> bool>>,
+  pub drag_drop_handler: Option<Box<dyn Fn(DragDropEvent) -> DragOperation>>,
   #[cfg(not(feature = "drag-drop"))]
-  drag_drop_handler: Option<Box<dyn Fn(DragDropEvent) -> bool>>,
+  drag_drop_handler: Option<Box<dyn Fn(DragDropEvent) -> DragOperation>>,
+
+  /// How the [`WebViewAttributes::drag_drop_handler`] interacts with the native drag and drop
+  /// handling of the webview. Defaults to [`DragDropHandlerMode::Intercept`].
+  pub drag_drop_handler_mode: DragDropHandlerMode,
 
   /// A navigation handler to decide if incoming url is allowed to navigate.
   ///
@@ -664,6 +872,12 @@ pub struct WebViewAttributes<'a> {
   /// `true` allows to navigate and `false` does not.
   pub navigation_handler: Option<Box<dyn Fn(String) -> bool>>,
 
+  /// A navigation handler that can also redirect the navigation to another url.
+  ///
+  /// Takes precedence over [`WebViewAttributes::navigation_handler`],
+  /// see [`WebViewBuilder::with_navigation_handler_ext`].
+  pub navigation_handler_ext: Option<Box<dyn Fn(String) -> NavigationDecision>>,
+
   /// A download started handler to manage incoming downloads.
   ///
   /// The closure takes two parameters, the first is a `String` representing the url being downloaded from and and the
@@ -682,13 +896,22 @@ pub struct WebViewAttributes<'a> {
   /// succeeded. A value of `None` being passed instead of a `PathBuf` does not necessarily indicate that the download
   /// did not succeed, and may instead indicate some other failure, always check the third parameter if you need to
   /// know if the download succeeded.
-  ///
-  /// ## Platform-specific:
-  ///
-  /// - **macOS**: The second parameter indicating the path the file was saved to, is always empty,
-  ///   due to API limitations.
   pub download_completed_handler: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
 
+  /// The closures handling the downloads, see [`WebViewBuilder::with_download_handler`].
+  ///
+  /// Takes precedence over [`WebViewAttributes::download_started_handler`] and
+  /// [`WebViewAttributes::download_completed_handler`].
+  pub download_handler: Option<DownloadHandler>,
+
+  /// A handler closure answering the JavaScript dialogs, see
+  /// [`WebViewBuilder::with_dialog_handler`].
+  pub dialog_handler: Option<Box<dyn Fn(DialogRequest) -> DialogResponse>>,
+
+  /// A handler closure picking the files of `<input type="file">` elements instead of the native
+  /// file picker, see [`WebViewBuilder::with_file_open_handler`].
+  pub file_open_handler: Option<Box<dyn Fn(FileChooserRequest) -> Option<Vec<PathBuf>>>>,
+
   /// A new window request handler to decide if incoming url is allowed to be opened.
   ///
   /// A new window is requested to be opened by the [window.open] API.
@@ -703,12 +926,33 @@ pub struct WebViewAttributes<'a> {
   pub new_window_req_handler:
     Option<Box<dyn Fn(String, NewWindowFeatures) -> NewWindowResponse + Send + Sync>>,
 
+  /// A handler deciding how new windows are opened, see
+  /// [`WebViewBuilder::with_new_window_request_handler`].
+  ///
+  /// Takes precedence over [`WebViewAttributes::new_window_req_handler`].
+  pub new_window_request_handler:
+    Option<Box<dyn Fn(NewWindowRequest) -> NewWindowResponse + Send + Sync>>,
+
   /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
   ///
   /// macOS doesn't provide such method and is always enabled by default. But your app will still need to add menu
   /// item accelerators to use the clipboard shortcuts.
   pub clipboard: bool,
 
+  /// Which clipboard access pages get, see [`WebViewBuilder::with_clipboard_policy`].
+  ///
+  /// Takes precedence over [`WebViewAttributes::clipboard`].
+  pub clipboard_policy: Option<ClipboardPolicy>,
+
+  /// Whether pages can register service workers, see [`WebViewBuilder::with_service_workers`].
+  ///
+  /// `None` keeps the platform defaults.
+  pub service_workers: Option<bool>,
+
+  /// A handler closure called when a page registered a service worker, see
+  /// [`WebViewBuilder::with_service_worker_handler`].
+  pub service_worker_handler: Option<Box<dyn Fn(ServiceWorkerRegistration)>>,
+
   /// Enable web inspector which is usually called browser devtools.
   ///
   /// Note this only enables devtools to the webview. To open it, you can call
@@ -722,6 +966,12 @@ pub struct WebViewAttributes<'a> {
   /// - iOS: Open Safari > Develop > [Your Device Name] > [Your WebView] to get the devtools window.
   pub devtools: bool,
 
+  /// Open the devtools once the webview is created, see [`WebViewBuilder::with_devtools_open_on_start`].
+  pub devtools_open_on_start: bool,
+
+  /// Expose a remote debugging endpoint on this port, see [`WebViewBuilder::with_remote_debugging`].
+  pub remote_debugging_port: Option<u16>,
+
   /// Whether clicking an inactive window also clicks through to the webview. Default is `false`.
   ///
   /// ## Platform-specific
@@ -736,29 +986,70 @@ pub struct WebViewAttributes<'a> {
   /// - Windows: Setting to `false` does nothing on WebView2 Runtime version before 92.0.902.0,
   ///   see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10902-prerelease
   ///
-  /// - **Android / iOS:** Unsupported.
+  /// - **Android:** Unsupported.
   pub back_forward_navigation_gestures: bool,
 
   /// Set a handler closure to process the change of the webview's document title.
   pub document_title_changed_handler: Option<Box<dyn Fn(String)>>,
 
-  /// Run the WebView with incognito mode. Note that WebContext will be ingored if incognito is
-  /// enabled.
-  ///
-  /// ## Platform-specific:
-  ///
-  /// - **Windows**: Requires WebView2 Runtime version 101.0.1210.39 or higher, does nothing on older versions,
-  ///   see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10121039
-  /// - **Android:** Unsupported yet.
-  /// - **macOS / iOS**: Uses the nonPersistent DataStore.
+  /// Set a handler closure to process the change of the webview's URL, see [`WebViewBuilder::with_on_url_change`].
+  pub on_url_change_handler: Option<Box<dyn Fn(String)>>,
+
+  /// Set a handler closure to follow the progress of page loads, see
+  /// [`WebViewBuilder::with_load_progress_handler`].
+  pub load_progress_handler: Option<Box<dyn Fn(f64)>>,
+
+  /// Set a handler closure to allow the page to enter fullscreen and to be notified when it
+  /// leaves it, see [`WebViewBuilder::with_fullscreen_handler`].
+  pub fullscreen_handler: Option<Box<dyn Fn(bool) -> bool>>,
+
+  /// Run the WebView with incognito mode, see [`WebViewBuilder::with_incognito`].
   pub incognito: bool,
 
   /// Whether all media can be played without user interaction.
   pub autoplay: bool,
 
+  /// Which media can be played without user interaction, see
+  /// [`WebViewBuilder::with_autoplay_policy`]. Takes precedence over [`Self::autoplay`].
+  pub autoplay_policy: Option<AutoplayPolicy>,
+
   /// Set a handler closure to process page load events.
   pub on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent, String)>>,
 
+  /// Set a handler closure to process failed navigations, see [`WebViewBuilder::with_on_navigation_error`].
+  pub on_navigation_error_handler: Option<Box<dyn Fn(NavigationError)>>,
+
+  /// Set a handler closure called once the first page is ready to be shown, see [`WebViewBuilder::with_on_first_paint`].
+  pub on_first_paint_handler: Option<Box<dyn Fn()>>,
+
+  /// Set a handler closure called when the webview gains or loses keyboard focus, see [`WebViewBuilder::with_on_focus_changed`].
+  pub on_focus_changed_handler: Option<Box<dyn Fn(bool)>>,
+
+  /// Set a handler closure giving the host the key presses before the webview, see [`WebViewBuilder::with_accelerator_passthrough`].
+  pub accelerator_passthrough: Option<Box<dyn Fn(&KeyEvent) -> bool>>,
+
+  /// Set a handler closure picking the context menu to show, see [`WebViewBuilder::with_context_menu_handler`].
+  pub context_menu_handler: Option<Box<dyn Fn(ContextMenuTarget) -> ContextMenuAction>>,
+
+  /// Set a handler closure answering the permission requests of pages, see [`WebViewBuilder::with_permission_handler`].
+  pub permission_handler: Option<Box<dyn Fn(PermissionRequest) -> PermissionResponse>>,
+
+  /// Set a handler closure answering the authentication challenges of proxies, see [`WebViewBuilder::with_proxy_auth_handler`].
+  pub proxy_auth_handler: Option<Box<dyn Fn(AuthChallenge) -> Option<Credentials>>>,
+
+  /// Set a handler closure answering the authentication challenges of servers, see [`WebViewBuilder::with_authentication_handler`].
+  pub authentication_handler: Option<Box<dyn Fn(AuthChallenge) -> Option<Credentials>>>,
+
+  /// Set a handler closure picking the TLS client certificates, see [`WebViewBuilder::with_client_certificate_handler`].
+  pub client_certificate_handler: Option<Box<dyn Fn(CertRequest) -> Option<Certificate>>>,
+
+  /// Set a handler closure accepting the certificates failing the TLS verification, see [`WebViewBuilder::with_tls_error_handler`].
+  #[cfg(feature = "tls-error-handler")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "tls-error-handler")))]
+  pub tls_error_handler: Option<Box<dyn Fn(&str, &CertificateInfo) -> bool>>,
+  #[cfg(not(feature = "tls-error-handler"))]
+  tls_error_handler: Option<Box<dyn Fn(&str, &CertificateInfo) -> bool>>,
+
   /// Set a proxy configuration for the webview. Supports HTTP CONNECT and SOCKSv5 proxies
   ///
   /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled.
@@ -802,32 +1093,65 @@ impl Default for WebViewAttributes<'_> {
       id: Default::default(),
       context: None,
       user_agent: None,
+      accept_language: None,
       visible: true,
       transparent: false,
       background_color: None,
       url: None,
       headers: None,
       html: None,
+      html_base_url: None,
       initialization_scripts: Default::default(),
+      deferred_scripts: true,
       custom_protocols: Default::default(),
+      web_resource_request_handler: None,
       ipc_handler: None,
+      ipc_invoke_handler: None,
+      ipc_invoke_timeout: Some(std::time::Duration::from_secs(30)),
       drag_drop_handler: None,
+      drag_drop_handler_mode: Default::default(),
       navigation_handler: None,
+      navigation_handler_ext: None,
       download_started_handler: Some(Box::new(|_, _| true)),
       download_completed_handler: None,
+      download_handler: None,
+      dialog_handler: None,
+      file_open_handler: None,
       new_window_req_handler: None,
+      new_window_request_handler: None,
       clipboard: false,
+      clipboard_policy: None,
+      service_workers: None,
+      service_worker_handler: None,
       #[cfg(debug_assertions)]
       devtools: true,
       #[cfg(not(debug_assertions))]
       devtools: false,
+      devtools_open_on_start: false,
+      remote_debugging_port: None,
       zoom_hotkeys_enabled: false,
+      pinch_zoom: None,
+      content_mode: ContentMode::Recommended,
       accept_first_mouse: false,
       back_forward_navigation_gestures: false,
       document_title_changed_handler: None,
+      on_url_change_handler: None,
+      load_progress_handler: None,
+      fullscreen_handler: None,
       incognito: false,
       autoplay: true,
+      autoplay_policy: None,
       on_page_load_handler: None,
+      on_navigation_error_handler: None,
+      on_first_paint_handler: None,
+      on_focus_changed_handler: None,
+      accelerator_passthrough: None,
+      context_menu_handler: None,
+      permission_handler: None,
+      proxy_auth_handler: None,
+      authentication_handler: None,
+      client_certificate_handler: None,
+      tls_error_handler: None,
       proxy_config: None,
       focused: true,
       bounds: Some(Rect {
@@ -895,10 +1219,11 @@ impl<'a> WebViewBuilder<'a> {
   }
 
   /// Indicates whether horizontal swipe gestures trigger backward and forward page navigation.
+  /// [`WebView::set_navigation_gestures`] changes it later on.
   ///
   /// ## Platform-specific:
   ///
-  /// - **Android / iOS:** Unsupported.
+  /// - **Android:** Unsupported.
   pub fn with_back_forward_navigation_gestures(mut self, gesture: bool) -> Self {
     self.attrs.back_forward_navigation_gestures = gesture;
     self
@@ -909,6 +1234,7 @@ impl<'a> WebViewBuilder<'a> {
   /// ## Platform-specific:
   ///
   /// **Windows 7**: Not supported.
+  /// **Linux**: The container and its toplevel window are given an RGBA visual if the window is not realized yet.
   pub fn with_transparent(mut self, transparent: bool) -> Self {
     self.attrs.transparent = transparent;
     self
@@ -916,11 +1242,11 @@ impl<'a> WebViewBuilder<'a> {
 
   /// Specify the webview background color. This will be ignored if `transparent` is set to `true`.
   ///
-  /// The color uses the RGBA format.
+  /// The color uses the RGBA format, see [`WebView::set_background_color`].
   ///
   /// ## Platfrom-specific:
   ///
-  /// - **macOS**: Not implemented.
+  /// - **macOS**: See [`WebView::set_background_color`].
   /// - **Windows**:
   ///   - on Windows 7, transparency is not supported and the alpha value will be ignored.
   ///   - on Windows higher than 7: translucent colors are not supported so any alpha value other than `0` will be replaced by `255`
@@ -941,10 +1267,40 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Sets which media can be played without user interaction, e.g.
+  /// [`AutoplayPolicy::AllowMutedOnly`] for feeds of videos that start muted.
+  ///
+  /// Takes precedence over [`WebViewBuilder::with_autoplay`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Sets `mediaTypesRequiringUserActionForPlayback`,
+  ///   [`AutoplayPolicy::AllowMutedOnly`] requires user interaction for media with audio only.
+  /// - **Linux**: Sets the autoplay policy of the website policies.
+  /// - **Windows**: Passes `--autoplay-policy` to the browser arguments, which
+  ///   [`WebViewBuilderExtWindows::with_additional_browser_args`] replaces.
+  ///   [`AutoplayPolicy::AllowMutedOnly`] is the default policy of WebView2.
+  /// - **Android**: Can't tell muted media apart, [`AutoplayPolicy::AllowMutedOnly`] is the same
+  ///   as [`AutoplayPolicy::RequireUserGesture`].
+  pub fn with_autoplay_policy(mut self, policy: AutoplayPolicy) -> Self {
+    self.attrs.autoplay_policy = Some(policy);
+    self
+  }
+
   /// Initialize javascript code when loading new pages. When webview load a new page, this
   /// initialization code will be executed. It is guaranteed that code is executed before
   /// `window.onload`.
   ///
+  /// The script is injected into the main frame only, use
+  /// [`with_initialization_script_for_main_only`](Self::with_initialization_script_for_main_only)
+  /// to inject it into subframes too.
+  ///
+  /// The initialization scripts run in the order they were added, whichever of
+  /// `with_initialization_script*` added them, followed by the scripts of
+  /// [`WebView::add_init_script`] in the order they were added. The scripts of
+  /// [`InjectionTime::DocumentEnd`] run after all the scripts of [`InjectionTime::DocumentStart`],
+  /// in the order they were added too.
+  ///
   /// ## Example
   /// ```ignore
   /// let webview = WebViewBuilder::new()
@@ -956,7 +1312,8 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// ## Platform-specific
   ///
-  ///- **Windows:** scripts are always added to subframes.
+  /// - **Windows:** scripts are always added to subframes, see
+  ///   [`with_initialization_script_for_main_only`](Self::with_initialization_script_for_main_only).
   /// - **Android:** When [addDocumentStartJavaScript] is not supported,
   ///   we prepend them to each HTML head (implementation only supported on custom protocol URLs).
   ///   For remote URLs, we use [onPageStarted] which is not guaranteed to run before other scripts.
@@ -981,14 +1338,42 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// ## Platform-specific:
   ///
-  /// - **Windows:** scripts are always added to subframes regardless of the `for_main_frame_only` option.
+  /// - **Windows:** scripts are always added to subframes regardless of the `for_main_frame_only` option,
+  ///   `AddScriptToExecuteOnDocumentCreated` applies to all frames and WebView2 has no API to inject
+  ///   a script into the main frame only. Scripts meant for the main frame only can check
+  ///   `window.top === window` themselves.
   /// - **Android**: When [addDocumentStartJavaScript] is not supported, scripts are always injected into main frame only.
   ///
   /// [addDocumentStartJavaScript]: https://developer.android.com/reference/androidx/webkit/WebViewCompat#addDocumentStartJavaScript(android.webkit.WebView,java.lang.String,java.util.Set%3Cjava.lang.String%3E)
   pub fn with_initialization_script_for_main_only<S: Into<String>>(
+    self,
+    js: S,
+    for_main_frame_only: bool,
+  ) -> Self {
+    self.with_initialization_script_ext(js, for_main_frame_only, InjectionTime::DocumentStart)
+  }
+
+  /// Same as [`with_initialization_script_for_main_only`](Self::with_initialization_script_for_main_only)
+  /// but with option to run the script once the document is parsed.
+  ///
+  /// ## Example
+  /// ```ignore
+  /// let webview = WebViewBuilder::new()
+  ///   .with_initialization_script_ext("document.body.dataset.ready = ''", true, InjectionTime::DocumentEnd)
+  ///   .with_url("https://tauri.app")
+  ///   .build(&window)
+  ///   .unwrap();
+  /// ```
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows / Android**: Scripts are always injected when documents are created,
+  ///   [`InjectionTime::DocumentEnd`] scripts wait for `DOMContentLoaded`.
+  pub fn with_initialization_script_ext<S: Into<String>>(
     mut self,
     js: S,
     for_main_frame_only: bool,
+    injection_time: InjectionTime,
   ) -> Self {
     let script = js.into();
     if !script.is_empty() {
@@ -998,11 +1383,24 @@ impl<'a> WebViewBuilder<'a> {
         .push(InitializationScript {
           script,
           for_main_frame_only,
+          injection_time,
         });
     }
     self
   }
 
+  /// Whether scripts evaluated with [`WebView::evaluate_script`] before the first page was
+  /// committed wait for it. Default is `true`, disable it to run them in the blank document the
+  /// webview starts with.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, scripts are evaluated once the webview was created.
+  pub fn with_deferred_scripts(mut self, deferred: bool) -> Self {
+    self.attrs.deferred_scripts = deferred;
+    self
+  }
+
   /// Register custom loading protocols with pairs of scheme uri string and a handling
   /// closure.
   ///
@@ -1010,6 +1408,11 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// When registering a custom protocol with the same name, only the last regisered one will be used.
   ///
+  /// When the closure panics or returns a response the webview would reject, e.g. with a `1xx`
+  /// status or a `Content-Length` that doesn't match the body, the request is answered with
+  /// `500 Internal Server Error` and an [`Error::CustomProtocol`] is logged. In debug builds the
+  /// response is a page describing the error.
+  ///
   /// # Warning
   ///
   /// Pages loaded from custom protocol will have different Origin on different platforms. And
@@ -1028,6 +1431,30 @@ impl<'a> WebViewBuilder<'a> {
   ///   elsewhere in Android (provided the app has appropriate access), but not from the `assets`
   ///   folder which lives within the apk. For the cases where this can be used, it works the same as in macOS and Linux.
   /// - iOS: To get the path of your assets, you can call [`CFBundle::resources_path`](https://docs.rs/core-foundation/latest/core_foundation/bundle/struct.CFBundle.html#method.resources_path). So url like `wry://assets/index.html` could get the html file in assets directory.
+  ///
+  /// # Secure contexts
+  ///
+  /// APIs like `crypto.subtle`, `navigator.serviceWorker` and `SharedArrayBuffer` are only
+  /// available in secure contexts, `SharedArrayBuffer` needs the headers added by
+  /// [`ResponseExt::cross_origin_isolated`] as well.
+  ///
+  /// - Windows and Android: Pages are secure contexts with [`WebViewBuilderExtWindows::with_https_scheme`] and [`WebViewBuilderExtAndroid::with_https_scheme`].
+  /// - Linux: Pages are secure contexts with [`WebViewBuilderExtUnix::with_https_scheme`].
+  /// - macOS and iOS: Pages are always secure contexts, but WKWebView only supports service
+  ///   workers for `http` and `https` pages.
+  ///
+  /// # Compressed responses
+  ///
+  /// Responses can have a `gzip`, `deflate` or `br` `Content-Encoding`, e.g. for assets that are
+  /// compressed at build time. The body must be encoded as a whole, also when it is streamed with
+  /// [`Self::with_custom_protocol_stream`], and `Range` requests aren't answered for encoded bodies.
+  /// Don't set the `Content-Length` of the decoded body on an encoded response.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The response is handed to WebView2 untouched, which decodes it.
+  /// - **macOS / iOS / Linux / Android**: These webviews don't decode custom protocol responses,
+  ///   wry decodes the body while it is handed to the webview instead.
   #[cfg(feature = "protocol")]
   pub fn with_custom_protocol<F>(mut self, name: String, handler: F) -> Self
   where
@@ -1064,10 +1491,55 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Same as [`Self::with_custom_protocol`] but registers all of `schemes` with a single handler,
+  /// which receives the scheme of the request as its first argument.
+  ///
+  /// Registering a scheme twice, in `schemes` or with another custom protocol method, fails the
+  /// builder with [`Error::DuplicateCustomProtocol`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use wry::WebViewBuilder;
+  /// WebViewBuilder::new().with_custom_protocols(
+  ///   vec!["app".into(), "asset".into(), "stream".into()],
+  ///   |scheme, _webview_id, request| {
+  ///     let body = format!("{scheme}: {}", request.uri().path()).into_bytes();
+  ///     http::Response::builder().body(body.into()).unwrap()
+  ///   },
+  /// );
+  /// ```
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: Schemes are registered on the `WKWebViewConfiguration` when the webview
+  ///   is built, so they can't be added or removed afterwards.
+  /// - **Windows / Android**: Each scheme is served from `http://<scheme>.<path>`, see
+  ///   [`Self::with_custom_protocol`].
+  #[cfg(feature = "protocol")]
+  pub fn with_custom_protocols<F>(mut self, schemes: Vec<String>, handler: F) -> Self
+  where
+    F: Fn(&str, WebViewId, Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> + 'static,
+  {
+    let handler = Rc::new(handler);
+    for scheme in schemes {
+      let handler = handler.clone();
+      let name = scheme.clone();
+      self = self.with_custom_protocol(name, move |id, request| handler(&scheme, id, request));
+    }
+    self
+  }
+
   /// Same as [`Self::with_custom_protocol`] but with an asynchronous responder.
   ///
   /// When registering a custom protocol with the same name, only the last regisered one will be used.
   ///
+  /// Panics of the handler are only caught on the thread it is called on, a responder dropped by
+  /// a panicking thread leaves its request unanswered.
+  ///
+  /// Use [`RequestExt::range`] and [`ResponseExt::partial`] to answer the `Range` requests
+  /// of media elements.
+  ///
   /// # Warning
   ///
   /// Pages loaded from custom protocol will have different Origin on different platforms. And
@@ -1123,9 +1595,136 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set a handler to rewrite, block or answer the http(s) requests of the webview, e.g. to add
+  /// headers or block trackers. Requests to custom protocols are not passed to the handler.
+  ///
+  /// The handler can change the method, URI and headers of the request before returning
+  /// [`RequestAction::Continue`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use wry::{http::header::AUTHORIZATION, RequestAction, WebViewBuilder};
+  /// WebViewBuilder::new().with_web_resource_request_handler(|request| {
+  ///   if request.uri().host() == Some("tracker.example") {
+  ///     return RequestAction::Block;
+  ///   }
+  ///   if request.uri().host() == Some("api.example") {
+  ///     request
+  ///       .headers_mut()
+  ///       .insert(AUTHORIZATION, "Bearer token".parse().unwrap());
+  ///   }
+  ///   RequestAction::Continue
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Unsupported, WebKit doesn't let apps handle the `http` and `https`
+  ///   schemes. Serve the content from a custom protocol instead, or block requests with a
+  ///   content rule list.
+  /// - **Linux**: The handler is only notified of the requests, the request changes and the
+  ///   `Block` and `Override` actions are ignored, WebKitGTK only allows changing requests from
+  ///   a web process extension. The request body is always empty.
+  /// - **Android**: The request changes are ignored and the request body is always empty.
+  pub fn with_web_resource_request_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(&mut Request<Vec<u8>>) -> RequestAction + 'static,
+  {
+    self.attrs.web_resource_request_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Same as [`Self::with_custom_protocol`] but the response body is a [`StreamBody`] that is read
+  /// incrementally, so large files don't have to be loaded into memory.
+  ///
+  /// `Range` requests are answered with `206 Partial Content` when the body is
+  /// [seekable](StreamBody::seekable) and the handler responds with `200 OK`, which lets
+  /// `<video>` and `<audio>` elements seek. Only single ranges are supported, requests for
+  /// multiple ranges are answered with `416 Range Not Satisfiable`.
+  ///
+  /// The body is read on the main thread, use [`Self::with_asynchronous_custom_protocol`] and
+  /// [`RequestAsyncResponder::respond_stream`] to read it from another thread instead.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use wry::{StreamBody, WebViewBuilder};
+  /// WebViewBuilder::new()
+  ///   .with_custom_protocol_stream("media".into(), |_webview_id, request| {
+  ///     let path = request.uri().path().trim_start_matches('/');
+  ///     match std::fs::File::open(path) {
+  ///       Ok(file) => {
+  ///         let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+  ///         http::Response::builder()
+  ///           .header("Content-Type", "video/mp4")
+  ///           .body(StreamBody::seekable(file, len))
+  ///           .unwrap()
+  ///       }
+  ///       Err(_) => http::Response::builder()
+  ///         .status(404)
+  ///         .body(StreamBody::from(Vec::new()))
+  ///         .unwrap(),
+  ///     }
+  ///   });
+  /// ```
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: The body is read into memory before it is handed to the webview.
+  #[cfg(feature = "protocol")]
+  pub fn with_custom_protocol_stream<F>(self, name: String, handler: F) -> Self
+  where
+    F: Fn(WebViewId, Request<Vec<u8>>) -> Response<StreamBody> + 'static,
+  {
+    self.with_asynchronous_custom_protocol(name, move |id, request, responder| {
+      let headers = request.headers().clone();
+      let http_response = handler(id, request);
+      responder.respond_stream(custom_protocol::apply_range(&headers, http_response));
+    })
+  }
+
+  /// Registers a custom protocol named `scheme` that serves the files in `dir`.
+  ///
+  /// The `Content-Type` of a file is guessed from its extension and requests for a directory,
+  /// like `app://localhost/`, serve its `index.html`. Missing files are answered with
+  /// `404 Not Found` and paths that escape `dir`, e.g. with `..` or a symlink, with
+  /// `403 Forbidden`.
+  ///
+  /// The files are streamed with [`Self::with_custom_protocol_stream`], so `Range` requests are
+  /// supported as well.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use wry::WebViewBuilder;
+  /// WebViewBuilder::new()
+  ///   .with_asset_folder("app", "dist".into())
+  ///   .with_url("app://localhost/");
+  /// ```
+  #[cfg(feature = "protocol")]
+  pub fn with_asset_folder(self, scheme: &str, dir: PathBuf) -> Self {
+    self.with_custom_protocol_stream(scheme.to_string(), move |_id, request| {
+      custom_protocol::serve_asset(&dir, &request)
+    })
+  }
+
   /// Set the IPC handler to receive the message from Javascript on webview
   /// using `window.ipc.postMessage("insert_message_here")` to host Rust code.
   ///
+  /// The request URL is the URL of the frame that posted the message, and its extensions hold
+  /// an [`IpcSource`] with the origin of that frame:
+  ///
+  /// ```no_run
+  /// use wry::{IpcSource, WebViewBuilder};
+  /// WebViewBuilder::new().with_ipc_handler(|request| {
+  ///   let source = request.extensions().get::<IpcSource>().unwrap();
+  ///   if source.origin() == "https://tauri.app" && source.is_main_frame() != Some(false) {
+  ///     println!("{}", request.body());
+  ///   }
+  /// });
+  /// ```
+  ///
   /// ## Platform-specific
   ///
   /// - **Linux / Android**: The request URL is not supported on iframes and the main frame URL is used instead.
@@ -1137,20 +1736,75 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set the handler of the requests made with `window.ipc.invoke(payload)` from Javascript,
+  /// which returns a `Promise` resolved with the response.
+  ///
+  /// The handler receives the id of the request, the request with the payload as its body and
+  /// the [`IpcResponder`] to answer it with, immediately or later on the main thread. Requests
+  /// that are not answered reject after [`Self::with_ipc_invoke_timeout`].
+  ///
+  /// Messages sent with `window.ipc.postMessage` still go to [`Self::with_ipc_handler`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use wry::WebViewBuilder;
+  /// WebViewBuilder::new()
+  ///   .with_ipc_invoke_handler(|request_id, request, responder| {
+  ///     responder.respond(request_id, Ok(request.body().to_uppercase()));
+  ///   })
+  ///   // `await window.ipc.invoke("hello")` resolves to "HELLO"
+  ///   .with_url("https://tauri.app");
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Linux / macOS / iOS**: Requests are only answered in the main frame,
+  ///   calls from iframes time out.
+  /// - **Android**: Unsupported.
+  pub fn with_ipc_invoke_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(u64, Request<String>, IpcResponder) + 'static,
+  {
+    self.attrs.ipc_invoke_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set how long `window.ipc.invoke` waits for a response before rejecting its `Promise`,
+  /// `None` waits forever. Defaults to 30 seconds.
+  pub fn with_ipc_invoke_timeout(mut self, timeout: Option<std::time::Duration>) -> Self {
+    self.attrs.ipc_invoke_timeout = timeout;
+    self
+  }
+
   /// Set a handler closure to process incoming [`DragDropEvent`] of the webview.
   ///
   /// # Blocking OS Default Behavior
-  /// Return `true` in the callback to block the OS' default behavior.
+  /// Return `true` or a [`DragOperation`] other than [`DragOperation::Native`] in the callback
+  /// to block the OS' default behavior. Returning `true` is the same as returning [`DragOperation::Copy`]
+  /// and returning `false` is the same as returning [`DragOperation::Native`].
   ///
   /// Note, that if you do block this behavior, it won't be possible to drop files on `<input type="file">` forms.
   /// Also note, that it's not possible to manually set the value of a `<input type="file">` via JavaScript for security reasons.
+  /// Use [`WebViewBuilder::with_drag_drop_handler_mode`] to observe drops without blocking the native handling.
   #[cfg(feature = "drag-drop")]
   #[cfg_attr(docsrs, doc(cfg(feature = "drag-drop")))]
-  pub fn with_drag_drop_handler<F>(mut self, handler: F) -> Self
+  pub fn with_drag_drop_handler<F, R>(mut self, handler: F) -> Self
   where
-    F: Fn(DragDropEvent) -> bool + 'static,
+    F: Fn(DragDropEvent) -> R + 'static,
+    R: Into<DragOperation>,
   {
-    self.attrs.drag_drop_handler = Some(Box::new(handler));
+    self.attrs.drag_drop_handler = Some(Box::new(move |event| handler(event).into()));
+    self
+  }
+
+  /// Set how the drag drop handler interacts with the native drag and drop handling of the webview.
+  ///
+  /// See [`DragDropHandlerMode`] for the available modes. Defaults to [`DragDropHandlerMode::Intercept`].
+  #[cfg(feature = "drag-drop")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "drag-drop")))]
+  pub fn with_drag_drop_handler_mode(mut self, mode: DragDropHandlerMode) -> Self {
+    self.attrs.drag_drop_handler_mode = mode;
     self
   }
 
@@ -1211,6 +1865,20 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Same as [`WebViewBuilder::with_html`] but the relative URLs of the page, like
+  /// `<img src="foo.png">`, resolve against `base_url`, e.g. `app://localhost/`.
+  ///
+  /// See [`WebView::load_html_with_base_url`] for the platform-specific behavior.
+  pub fn with_html_and_base_url(
+    mut self,
+    html: impl Into<String>,
+    base_url: impl Into<String>,
+  ) -> Self {
+    self.attrs.html = Some(html.into());
+    self.attrs.html_base_url = Some(base_url.into());
+    self
+  }
+
   /// Set a custom [user-agent](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent) for the WebView.
   ///
   /// ## Platform-specific
@@ -1222,6 +1890,27 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set the languages asked from websites, in the format of the
+  /// [`Accept-Language`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Accept-Language)
+  /// header, e.g. `fr-CH, fr;q=0.9, en;q=0.8` or a single locale like `de-DE`. Pages also see the
+  /// most preferred language in `navigator.language`. Default is the languages of the system.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Sets the preferred languages of the [`WebContext`], shared by its webviews.
+  /// - **Windows**: Sets the language of the WebView2 environment, which also translates the
+  ///   context menus and dialogs. The webviews sharing a data directory must use the same
+  ///   languages. Ignored with a custom environment.
+  /// - **macOS / iOS**: The header is only sent with the requests loaded by wry, e.g.
+  ///   [`WebViewBuilder::with_url`] and [`WebView::load_url`], following links sends the
+  ///   languages of the system. `navigator.language` and `navigator.languages` are only overridden
+  ///   for scripts of the page, `Intl` and workers keep the languages of the system.
+  /// - **Android**: Unsupported.
+  pub fn with_accept_language(mut self, accept_language: impl Into<String>) -> Self {
+    self.attrs.accept_language = Some(accept_language.into());
+    self
+  }
+
   /// Enable or disable web inspector which is usually called devtools.
   ///
   /// Note this only enables devtools to the webview. To open it, you can call
@@ -1238,6 +1927,37 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Open the devtools once the webview is created, this has no effect if devtools are not
+  /// enabled with [`WebViewBuilder::with_devtools`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS**: Unsupported.
+  pub fn with_devtools_open_on_start(mut self, open: bool) -> Self {
+    self.attrs.devtools_open_on_start = open;
+    self
+  }
+
+  /// Expose a remote debugging endpoint on `127.0.0.1:port`, e.g. to attach a debugger or an
+  /// automation tool to the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Passes `--remote-debugging-port` to the browser process, which speaks the Chrome
+  ///   DevTools Protocol. Webviews sharing a data directory must use the same port, WebView2 can't
+  ///   create environments with different browser arguments for the same data directory.
+  /// - **Linux**: Enables the developer extras and starts the WebKit inspector HTTP server,
+  ///   open `http://127.0.0.1:port` in a browser to inspect the webview. The server is started
+  ///   with the first [`WebContext`] of the process, so the port is ignored if a context was
+  ///   created before, or if the `WEBKIT_INSPECTOR_HTTP_SERVER` environment variable is set.
+  /// - **macOS / iOS**: Unsupported, WebKit has no portable remote debugging endpoint. Enable
+  ///   [`WebViewBuilder::with_devtools`] and use the Safari Web Inspector instead.
+  /// - **Android**: Unsupported, open `chrome://inspect/#devices` in Chrome instead.
+  pub fn with_remote_debugging(mut self, port: u16) -> Self {
+    self.attrs.remote_debugging_port = Some(port);
+    self
+  }
+
   /// Whether page zooming by hotkeys or gestures is enabled
   ///
   /// ## Platform-specific
@@ -1251,20 +1971,93 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
-  /// Set a navigation handler to decide if incoming url is allowed to navigate.
+  /// Whether pinch gestures on trackpads and touch screens, and double taps where the platform
+  /// zooms on them, magnify the page. Disabling it e.g. keeps kiosk apps from being zoomed by
+  /// accident, [`WebView::set_zoom`] keeps working.
   ///
-  /// The closure take a `String` parameter as url and returns a `bool` to determine whether the navigation should happen.
-  /// `true` allows to navigate and `false` does not.
-  pub fn with_navigation_handler(mut self, callback: impl Fn(String) -> bool + 'static) -> Self {
-    self.attrs.navigation_handler = Some(Box::new(callback));
+  /// The default depends on the platform: pinch zoom is enabled on Linux and iOS, and disabled
+  /// on macOS, Android and Windows, where [`WebViewBuilder::with_hotkeys_zoom`] enables it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Sets `allowsMagnification`, which also covers the smart magnification of two
+  ///   finger double taps.
+  /// - **iOS**: Disables the pinch gesture of the scroll view, double taps still zoom if the
+  ///   viewport of the page allows it.
+  /// - **Linux**: Pinch gestures are blocked before WebKitGTK sees them, two finger gestures
+  ///   on touch screens don't scroll either.
+  /// - **Windows**: Sets `IsPinchZoomEnabled`, requires WebView2 Runtime version 91.0.865.0 or
+  ///   higher.
+  /// - **Android**: `false` turns off `supportZoom`, `true` turns on the built-in zoom without
+  ///   the zoom buttons.
+  pub fn with_pinch_zoom(mut self, enabled: bool) -> Self {
+    self.attrs.pinch_zoom = Some(enabled);
     self
   }
 
-  /// Set a download started handler to manage incoming downloads.
+  /// Whether websites are asked for their mobile or desktop content, e.g. to show the desktop
+  /// version of responsive sites on a phone. Default is [`ContentMode::Recommended`].
   ///
-  /// The closure takes two parameters, the first is a `String` representing the url being downloaded from and and the
-  /// second is a mutable `PathBuf` reference that (possibly) represents where the file will be downloaded to. The latter
-  /// parameter can be used to set the download location by assigning a new path to it, the assigned path _must_ be
+  /// The mode can be changed for a navigation with [`NavigationDecision::AllowWithContentMode`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Sets the `preferredContentMode` of the webpage preferences, requires
+  ///   macOS 10.15 or iOS 13.
+  /// - **Linux / Windows**: The user agent, including one set with
+  ///   [`WebViewBuilder::with_user_agent`], gets or loses its `Mobile` token. The layout keeps
+  ///   following the size of the webview.
+  /// - **Android**: The user agent gets or loses its `Mobile` token and Android platform,
+  ///   [`ContentMode::Desktop`] also lays pages out in a wide viewport zoomed out to fit.
+  pub fn with_content_mode(mut self, mode: ContentMode) -> Self {
+    self.attrs.content_mode = mode;
+    self
+  }
+
+  /// Set a navigation handler to decide if incoming url is allowed to navigate.
+  ///
+  /// The closure take a `String` parameter as url and returns a `bool` to determine whether the navigation should happen.
+  /// `true` allows to navigate and `false` does not.
+  pub fn with_navigation_handler(mut self, callback: impl Fn(String) -> bool + 'static) -> Self {
+    self.attrs.navigation_handler = Some(Box::new(callback));
+    self
+  }
+
+  /// Set a navigation handler that decides whether the incoming url is allowed to navigate,
+  /// or redirects the navigation to another url.
+  ///
+  /// The closure takes the url as a `String` parameter and returns a [`NavigationDecision`].
+  /// The redirected navigation goes through the handler again, so return
+  /// [`NavigationDecision::Allow`] for the target url to avoid redirecting in a loop.
+  ///
+  /// This takes precedence over [`WebViewBuilder::with_navigation_handler`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// A redirect cancels the original navigation before it starts and loads the new url,
+  /// the current page stays visible until the new one is committed.
+  ///
+  /// - **macOS / iOS / Linux**: The navigation policy decision is ignored and the new url is loaded
+  ///   in the main frame, even when the original navigation targeted an iframe.
+  ///   No page load event is fired for the original url.
+  /// - **Windows**: `NavigationStarting` is cancelled and the new url is navigated to, WebView2
+  ///   still completes the cancelled navigation, so [`WebViewBuilder::with_on_page_load_handler`]
+  ///   can receive an extra [`PageLoadEvent::Finished`] for the current page.
+  /// - **Android**: The url loading is overridden and the new url is loaded once the main thread
+  ///   is idle, iframe navigations are not passed to the handler.
+  pub fn with_navigation_handler_ext(
+    mut self,
+    callback: impl Fn(String) -> NavigationDecision + 'static,
+  ) -> Self {
+    self.attrs.navigation_handler_ext = Some(Box::new(callback));
+    self
+  }
+
+  /// Set a download started handler to manage incoming downloads.
+  ///
+  /// The closure takes two parameters, the first is a `String` representing the url being downloaded from and and the
+  /// second is a mutable `PathBuf` reference that (possibly) represents where the file will be downloaded to. The latter
+  /// parameter can be used to set the download location by assigning a new path to it, the assigned path _must_ be
   /// absolute. The closure returns a `bool` to allow or deny the download.
   ///
   /// By default a handler that allows all downloads is set to match browser behavior.
@@ -1284,11 +2077,6 @@ impl<'a> WebViewBuilder<'a> {
   /// succeeded. A value of `None` being passed instead of a `PathBuf` does not necessarily indicate that the download
   /// did not succeed, and may instead indicate some other failure, always check the third parameter if you need to
   /// know if the download succeeded.
-  ///
-  /// ## Platform-specific:
-  ///
-  /// - **macOS**: The second parameter indicating the path the file was saved to, is always empty,
-  ///   due to API limitations.
   pub fn with_download_completed_handler(
     mut self,
     download_completed_handler: impl Fn(String, Option<PathBuf>, bool) + 'static,
@@ -1297,6 +2085,55 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set the closures handling the downloads, deciding their destination and reporting their
+  /// progress and completion.
+  ///
+  /// - `started` gets the [`Download`] and a destination in the download directory, it returns
+  ///   the destination to download to, which _must_ be absolute, or `None` to cancel the download.
+  /// - `progress` gets the bytes received so far, it is called as the download makes progress.
+  /// - `completed` gets the destination of the download, or `None` if it failed or was cancelled.
+  ///
+  /// Downloads can be cancelled at any time with [`Download::cancel`], and paused with
+  /// [`Download::pause`] where [`Download::can_pause`].
+  ///
+  /// Takes precedence over [`WebViewBuilder::with_download_started_handler`] and
+  /// [`WebViewBuilder::with_download_completed_handler`].
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::WebViewBuilder;
+  ///
+  /// let builder = WebViewBuilder::new().with_download_handler(
+  ///   |download, destination| {
+  ///     println!("downloading {} to {}", download.url(), destination.display());
+  ///     Some(destination)
+  ///   },
+  ///   |progress| {
+  ///     if let Some(total) = progress.total_bytes {
+  ///       println!("{}/{total} bytes", progress.received_bytes);
+  ///     }
+  ///   },
+  ///   |download, destination| match destination {
+  ///     Some(destination) => println!("{} saved to {}", download.url(), destination.display()),
+  ///     None => println!("{} failed", download.url()),
+  ///   },
+  /// );
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn with_download_handler(
+    mut self,
+    started: impl FnMut(&Download, PathBuf) -> Option<PathBuf> + 'static,
+    progress: impl Fn(&DownloadProgress) + 'static,
+    completed: impl Fn(&Download, Option<PathBuf>) + 'static,
+  ) -> Self {
+    self.attrs.download_handler = Some(DownloadHandler::new(started, progress, completed));
+    self
+  }
+
   /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
   ///
   /// macOS doesn't provide such method and is always enabled by default. But your app will still need to add menu
@@ -1306,11 +2143,152 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set which clipboard access pages get, distinguishing reading from writing,
+  /// optionally for some origins only.
+  ///
+  /// Takes precedence over [`WebViewBuilder::with_clipboard`], without a policy the platform
+  /// defaults apply.
+  ///
+  /// The policy is enforced in each frame by a script wrapping the clipboard APIs, along with the
+  /// clipboard permissions of the platform. The script is best-effort and not a security control,
+  /// pages can still reach the clipboard through other means, e.g. user initiated pastes.
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::{ClipboardPolicy, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_clipboard_policy(
+  ///   ClipboardPolicy::read_only().with_allowed_origins(["https://tauri.app"]),
+  /// );
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Clipboard read permission requests are allowed or denied according to the
+  ///   policy instead of prompting the user.
+  /// - **Linux**: Enables `javascript-can-access-clipboard` if the policy allows reading or writing.
+  ///   Clipboard read permission requests, from WebKitGTK 2.42, are allowed or denied according
+  ///   to the policy instead of prompting the user.
+  /// - **macOS / iOS**: Only enforced by the script, WebKit has no public delegate for clipboard
+  ///   access.
+  /// - **Android**: Only enforced by the script.
+  pub fn with_clipboard_policy(mut self, policy: ClipboardPolicy) -> Self {
+    self.attrs.clipboard_policy = Some(policy);
+    self
+  }
+
+  /// Set whether pages can register service workers.
+  ///
+  /// Service workers need a secure context, enabling them makes the pages of the custom
+  /// protocols secure contexts where the platform allows it. Disabling them removes
+  /// `navigator.serviceWorker` from the pages.
+  ///
+  /// Without calling this method the platform defaults apply.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Enabling them registers the custom protocols as secure schemes, like
+  ///   [`WebViewBuilderExtUnix::with_https_scheme`]. The registrations are kept in the data
+  ///   directory of the [`WebContext`], or in memory for [incognito](Self::with_incognito) webviews.
+  /// - **Windows**: Custom protocols are served from `http://<scheme>.<host>`, which is a secure
+  ///   context for `localhost` hosts like `wry://localhost` or with
+  ///   [`WebViewBuilderExtWindows::with_https_scheme`], and also answer the requests of service
+  ///   workers. The registrations are kept in the user data folder, or in memory for
+  ///   [incognito](Self::with_incognito) webviews.
+  /// - **macOS / iOS**: WKWebView doesn't support service workers for custom protocols, on iOS
+  ///   they're only available to [App-Bound Domains](https://webkit.org/blog/10882/app-bound-domains/).
+  /// - **Android**: The requests of service workers don't reach the custom protocols.
+  pub fn with_service_workers(mut self, enabled: bool) -> Self {
+    self.attrs.service_workers = Some(enabled);
+    self
+  }
+
+  /// Set a handler closure called when a page registered a service worker, e.g. to log it.
+  ///
+  /// The registrations are reported by a script wrapping `navigator.serviceWorker.register` in
+  /// the main frame, through the IPC channel.
+  pub fn with_service_worker_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(ServiceWorkerRegistration) + 'static,
+  {
+    self.attrs.service_worker_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a handler closure answering the JavaScript dialogs of the pages, `alert()`, `confirm()`,
+  /// `prompt()` and the confirmation of leaving a page with a `beforeunload` handler, e.g. to show
+  /// themed dialogs or to suppress them.
+  ///
+  /// The closure blocks the page until it returns, like the dialogs it replaces.
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::{DialogKind, DialogResponse, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_dialog_handler(|request| match request.kind {
+  ///   DialogKind::Alert => {
+  ///     println!("{}", request.message);
+  ///     DialogResponse::Accept
+  ///   }
+  ///   DialogKind::Prompt { .. } => DialogResponse::Text("wry".into()),
+  ///   _ => DialogResponse::Default,
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The default dialogs of WebView2 are disabled, see [`DialogResponse::Default`].
+  /// - **macOS / iOS**: The confirmation of leaving a page is requested with a private API.
+  /// - **Android**: Unsupported.
+  pub fn with_dialog_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(DialogRequest) -> DialogResponse + 'static,
+  {
+    self.attrs.dialog_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a handler closure picking the files of `<input type="file">` elements instead of the
+  /// native file picker, e.g. to apply custom filters or to pick files from a remote source.
+  ///
+  /// The closure returns the absolute paths of the picked files, or `None` to cancel.
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::WebViewBuilder;
+  ///
+  /// let builder = WebViewBuilder::new().with_file_open_handler(|request| {
+  ///   if request.mime_types.iter().any(|mime_type| mime_type.starts_with("image/")) {
+  ///     Some(vec!["/home/user/Pictures/avatar.png".into()])
+  ///   } else {
+  ///     None
+  ///   }
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: The accepted types are read with private APIs, they're empty on the versions
+  ///   that don't have them.
+  /// - **Windows / Android / iOS**: Unsupported, WebView2 doesn't allow to replace its file picker.
+  pub fn with_file_open_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(FileChooserRequest) -> Option<Vec<PathBuf>> + 'static,
+  {
+    self.attrs.file_open_handler = Some(Box::new(handler));
+    self
+  }
+
   /// Set a new window request handler to decide if incoming url is allowed to be opened.
   ///
   /// A new window is requested to be opened by the [window.open] API.
   ///
   /// The closure take the URL to open and the window features object and returns [`NewWindowResponse`] to determine whether the window should open.
+  /// See [`WebViewBuilder::with_new_window_request_handler`] to also know whether the request is
+  /// user initiated.
   ///
   /// ## Platform-specific:
   ///
@@ -1325,6 +2303,47 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set a handler deciding how new windows requested by the page are opened, by the
+  /// [window.open] API or links with `target="_blank"`.
+  ///
+  /// The request has the URL to open, the requested window features and whether a user gesture
+  /// triggered it. The returned [`NewWindowResponse`] denies the window, opens it with the default
+  /// implementation or a webview created by the host, or loads the URL in this webview instead.
+  ///
+  /// Takes precedence over [`WebViewBuilder::with_new_window_req_handler`].
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::{NewWindowResponse, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_new_window_request_handler(|request| {
+  ///   if !request.user_initiated {
+  ///     NewWindowResponse::Deny
+  ///   } else if request.url.starts_with("https://tauri.app") {
+  ///     NewWindowResponse::OpenInSameView
+  ///   } else {
+  ///     NewWindowResponse::Allow
+  ///   }
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: The closure is executed on a separate thread to prevent a deadlock.
+  /// - **macOS**: Whether the request is user initiated is read with a private API, on the
+  ///   versions that don't have it only clicked links are.
+  /// - **Android / iOS**: Unsupported.
+  ///
+  /// [window.open]: https://developer.mozilla.org/en-US/docs/Web/API/Window/open
+  pub fn with_new_window_request_handler(
+    mut self,
+    callback: impl Fn(NewWindowRequest) -> NewWindowResponse + Send + Sync + 'static,
+  ) -> Self {
+    self.attrs.new_window_request_handler = Some(Box::new(callback));
+    self
+  }
+
   /// Sets whether clicking an inactive window also clicks through to the webview. Default is `false`.
   ///
   /// ## Platform-specific
@@ -1344,13 +2363,70 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
-  /// Run the WebView with incognito mode. Note that WebContext will be ingored if incognito is
-  /// enabled.
+  /// Set a handler closure to process the change of the webview's URL, e.g. to update an address bar.
+  ///
+  /// This is also called for same-document navigations such as `history.pushState` and fragment changes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Only called when a page starts loading, same-document navigations are not reported.
+  pub fn with_on_url_change(mut self, callback: impl Fn(String) + 'static) -> Self {
+    self.attrs.on_url_change_handler = Some(Box::new(callback));
+    self
+  }
+
+  /// Set a handler closure to follow the progress of page loads, e.g. to show a progress bar. The
+  /// closure gets the estimated progress, from `0.0` when a navigation starts to `1.0` once the
+  /// page is loaded.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: WebView2 doesn't estimate the progress, the closure gets `0.0` when a
+  ///   navigation starts, `0.5` once the new page is committed and `1.0` when it is loaded.
+  pub fn with_load_progress_handler(mut self, callback: impl Fn(f64) + 'static) -> Self {
+    self.attrs.load_progress_handler = Some(Box::new(callback));
+    self
+  }
+
+  /// Set a handler closure to follow the page entering and leaving fullscreen with the
+  /// [Fullscreen API](https://developer.mozilla.org/en-US/docs/Web/API/Fullscreen_API), e.g. to
+  /// hide the controls of the app.
+  ///
+  /// The closure gets `true` when the page requests fullscreen and returns whether it is allowed,
+  /// then `false` once the page left fullscreen, the returned value being ignored.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 13.3 or iOS 16.4. A denied page starts entering fullscreen
+  ///   and is made to leave it with `document.exitFullscreen()`. On macOS pages can only enter
+  ///   fullscreen with the `fullscreen` feature.
+  /// - **Linux**: Allowed requests make the top level window fullscreen.
+  /// - **Windows**: The closure is called once the page entered fullscreen, a denied page is made to
+  ///   leave it with `document.exitFullscreen()`. WebView2 doesn't resize the webview, the app has
+  ///   to make it cover the screen.
+  /// - **Android**: Unsupported.
+  pub fn with_fullscreen_handler(mut self, callback: impl Fn(bool) -> bool + 'static) -> Self {
+    self.attrs.fullscreen_handler = Some(Box::new(callback));
+    self
+  }
+
+  /// Run the WebView with incognito mode.
+  ///
+  /// An incognito webview keeps its cookies, storage and caches in memory only: nothing is
+  /// written to disk, it doesn't see the data of persistent webviews and its data is gone once it
+  /// is dropped.
   ///
   /// ## Platform-specific:
   ///
-  /// - Windows: Requires WebView2 Runtime version 101.0.1210.39 or higher, does nothing on older versions,
+  /// - **Windows**: Uses InPrivate mode. The incognito webviews of the same data directory share
+  ///   their session, which ends when the last of them is dropped. Requires WebView2 Runtime
+  ///   version 101.0.1210.39 or higher, building returns `Error::IncognitoUnsupported` on older
+  ///   versions,
   ///   see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10121039
+  /// - **macOS / iOS**: Uses a new `nonPersistentDataStore` for each webview, the [`WebContext`]
+  ///   is ignored. Ignored when the webview is created with an existing `WKWebViewConfiguration`.
+  /// - **Linux**: Uses a new ephemeral `WebKitWebContext` for each webview, the [`WebContext`] is
+  ///   ignored.
   /// - **Android:** Unsupported yet.
   pub fn with_incognito(mut self, incognito: bool) -> Self {
     self.attrs.incognito = incognito;
@@ -1366,116 +2442,448 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
-  /// Set a proxy configuration for the webview.
+  /// Set a handler to process navigations that failed, e.g. to show an offline page.
   ///
-  /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled. Supports HTTP CONNECT and SOCKSv5 proxies.
-  /// - **Windows / Linux**: Supports HTTP CONNECT and SOCKSv5 proxies.
-  /// - **Android / iOS:** Not supported.
-  pub fn with_proxy_config(mut self, configuration: ProxyConfig) -> Self {
-    self.attrs.proxy_config = Some(configuration);
+  /// Navigations cancelled by another navigation or by the navigation handler
+  /// are reported too, with the platform's cancellation error code.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: WebView2 doesn't describe the error, [`NavigationError::message`] is
+  ///   derived from the `COREWEBVIEW2_WEB_ERROR_STATUS`.
+  /// - **Android**: Unsupported.
+  pub fn with_on_navigation_error(mut self, handler: impl Fn(NavigationError) + 'static) -> Self {
+    self.attrs.on_navigation_error_handler = Some(Box::new(handler));
     self
   }
 
-  /// Set whether the webview should be focused when created.
+  /// Set a handler called once, when the first page of the webview is ready to be shown.
   ///
-  /// ## Platform-specific:
+  /// Combined with [`WebViewBuilder::with_visible`] or a hidden host window, this avoids
+  /// showing the default white background while the first page loads.
   ///
-  /// - **macOS / Android / iOS:** Unsupported.
-  pub fn with_focused(mut self, focused: bool) -> Self {
-    self.attrs.focused = focused;
+  /// ## Platform-specific
+  ///
+  /// None of the platforms expose the actual first paint, the closest signal is used instead:
+  ///
+  /// - **Windows**: Fired on the first `DOMContentLoaded` event. Requires WebView2 Runtime version 1.0.1150.38 or higher,
+  ///   falls back to the first `NavigationCompleted` event on older runtimes.
+  /// - **Linux**: Fired when the first load is committed, the page may still be blank at that point.
+  /// - **macOS / iOS**: Fired when the first navigation finishes.
+  /// - **Android**: Unsupported.
+  pub fn with_on_first_paint(mut self, handler: impl Fn() + 'static) -> Self {
+    self.attrs.on_first_paint_handler = Some(Box::new(handler));
     self
   }
 
-  /// Specify the webview position relative to its parent if it will be created as a child
-  /// or if created using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
+  /// Set a handler called with `true` when the webview gains keyboard focus and `false` when it
+  /// loses it, e.g. to restore the focus of the host UI.
   ///
-  /// Defaults to `x: 0, y: 0, width: 200, height: 200`.
-  pub fn with_bounds(mut self, bounds: Rect) -> Self {
-    self.attrs.bounds = Some(bounds);
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android**: Unsupported.
+  pub fn with_on_focus_changed(mut self, handler: impl Fn(bool) + 'static) -> Self {
+    self.attrs.on_focus_changed_handler = Some(Box::new(handler));
     self
   }
 
-  /// Set whether background throttling should be disabled.
+  /// Set a handler giving the host the key presses before the webview handles them, e.g. for the
+  /// menu shortcuts the page would otherwise swallow.
   ///
-  /// By default, browsers throttle timers and even unload the whole tab (view) to free resources after roughly 5 minutes when
-  /// a view became minimized or hidden. This will permanently suspend all tasks until the documents visibility state
-  /// changes back from hidden to visible by bringing the view back to the foreground.
+  /// Returning `true` consumes the key press for the host, the page doesn't receive it.
+  /// Returning `false` lets the page handle it as usual.
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / Windows / Android**: Unsupported. Workarounds like a pending WebLock transaction might suffice.
-  /// - **iOS**: Supported since version 17.0+.
-  /// - **macOS**: Supported since version 14.0+.
-  ///
-  /// see https://github.com/tauri-apps/tauri/issues/5250#issuecomment-2569380578
-  pub fn with_background_throttling(mut self, policy: BackgroundThrottlingPolicy) -> Self {
-    self.attrs.background_throttling = Some(policy);
+  /// - **macOS**: Only called for key equivalents, i.e. key presses with the Command or Control
+  ///   modifiers and function keys. Consumed key presses go on to the window and the main menu.
+  /// - **iOS / Android**: Unsupported.
+  pub fn with_accelerator_passthrough(
+    mut self,
+    handler: impl Fn(&KeyEvent) -> bool + 'static,
+  ) -> Self {
+    self.attrs.accelerator_passthrough = Some(Box::new(handler));
     self
   }
 
-  /// Whether JavaScript should be disabled.
-  pub fn with_javascript_disabled(mut self) -> Self {
-    self.attrs.javascript_disabled = true;
+  /// Set a handler picking the menu to show when the user opens a context menu, e.g. with a
+  /// right click, from what it was opened on.
+  ///
+  /// The handler can keep the default menu, suppress it, or replace it with custom items.
+  /// Suppress it and use [`ContextMenuTarget::position`] to show a menu of the host instead.
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::{ContextMenuAction, ContextMenuItem, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_context_menu_handler(|target| {
+  ///   match target.link_url {
+  ///     Some(url) => ContextMenuAction::Custom(vec![ContextMenuItem::new(
+  ///       "Open in browser",
+  ///       move || println!("open {url}"),
+  ///     )]),
+  ///     None => ContextMenuAction::Suppress,
+  ///   }
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: What the menu was opened on is reported by a script listening to the
+  ///   `contextmenu` events of the main frame, menus opened in iframes have no link, media or
+  ///   selection.
+  /// - **Linux**: The selection is reported by the same script, menus opened in iframes have no
+  ///   selection. Media other than images are reported as [`ContextMenuMediaType::Video`].
+  /// - **Windows**: Requires WebView2 Runtime version 1.0.1185.39 or higher, the handler is
+  ///   ignored on older versions.
+  /// - **iOS / Android**: Unsupported.
+  pub fn with_context_menu_handler(
+    mut self,
+    handler: impl Fn(ContextMenuTarget) -> ContextMenuAction + 'static,
+  ) -> Self {
+    self.attrs.context_menu_handler = Some(Box::new(handler));
     self
   }
 
-  /// Consume the builder and create the [`WebView`] from a type that implements [`HasWindowHandle`].
+  /// Set a handler answering the requests of pages for permissions like the camera, the
+  /// microphone or the geolocation, e.g. with `getUserMedia`.
   ///
-  /// # Platform-specific:
+  /// Requests the handler answered with [`PermissionResponse::Allow`] are granted without
+  /// calling it again for the same origin and kind until the webview is dropped. Without a
+  /// handler the platform defaults apply.
   ///
-  /// - **Linux**: Only X11 is supported, if you want to support Wayland too, use [`WebViewBuilderExtUnix::new_gtk`].
+  /// ## Example
   ///
-  ///   Although this methods only needs an X11 window handle, we use webkit2gtk, so you still need to initialize gtk
-  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
-  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
-  /// - **Windows**: The webview will auto-resize when the passed handle is resized.
-  /// - **Linux (X11)**: Unlike macOS and Windows, the webview will not auto-resize and you'll need to call [`WebView::set_bounds`] manually.
+  /// ```no_run
+  /// use wry::{PermissionKind, PermissionResponse, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_permission_handler(|request| {
+  ///   match (request.kind, request.origin.as_str()) {
+  ///     (PermissionKind::Microphone, "https://tauri.app") => PermissionResponse::AllowOnce,
+  ///     _ => PermissionResponse::Deny,
+  ///   }
+  /// });
+  /// ```
   ///
-  /// # Panics:
+  /// ## Platform-specific
   ///
-  /// - Panics if the provided handle was not supported or invalid.
-  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
-  pub fn build<W: HasWindowHandle>(self, window: &'a W) -> Result<WebView> {
-    self.error?;
-
-    InnerWebView::new(window, self.attrs, self.platform_specific).map(|webview| WebView { webview })
+  /// - **macOS / iOS**: Requires macOS 12.0+ / iOS 15.0+ and only called for camera and
+  ///   microphone requests. The app's `Info.plist` must contain the `NSCameraUsageDescription`
+  ///   and `NSMicrophoneUsageDescription` keys, or the app is terminated when a page accesses
+  ///   them, and the system still asks the user once for the app.
+  /// - **Linux**: Clipboard read requests are not reported, use
+  ///   [`WebViewBuilder::with_clipboard_policy`] instead. The origin is the one of the main frame.
+  /// - **Windows**: Clipboard read requests are only reported when neither
+  ///   [`WebViewBuilder::with_clipboard`] nor [`WebViewBuilder::with_clipboard_policy`] is set.
+  ///   [`PermissionResponse::Allow`] is saved in the profile of the webview.
+  /// - **Android**: Unsupported.
+  pub fn with_permission_handler(
+    mut self,
+    handler: impl Fn(PermissionRequest) -> PermissionResponse + 'static,
+  ) -> Self {
+    self.attrs.permission_handler = Some(Box::new(handler));
+    self
   }
 
-  /// Consume the builder and create the [`WebView`] as a child window inside the provided [`HasWindowHandle`].
+  /// Set a handler supplying the credentials asked by the proxy server instead of the platform
+  /// dialog, see [`WebViewBuilder::with_authentication_handler`] for the servers of pages.
+  ///
+  /// Returning `None` cancels the challenge, the request then fails with the `407` response of
+  /// the proxy.
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::{Credentials, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_proxy_auth_handler(|challenge| {
+  ///   (challenge.previous_failure_count < 3).then(|| Credentials {
+  ///     username: "user".into(),
+  ///     password: "password".into(),
+  ///   })
+  /// });
+  /// ```
   ///
   /// ## Platform-specific
   ///
-  /// - **Windows**: This will create the webview as a child window of the `parent` window.
-  /// - **macOS**: This will create the webview as a `NSView` subview of the `parent` window's
-  ///   content view.
-  /// - **Linux**: This will create the webview as a child window of the `parent` window. Only X11
-  ///   is supported. This method won't work on Wayland.
+  /// - **Windows**: Requires WebView2 Runtime version 101.0.1150.38 or higher. WebView2 doesn't
+  ///   tell the challenges of proxies apart, they are reported as [`AuthChallengeKind::Server`]
+  ///   with the host of the requested page, to the handler of
+  ///   [`WebViewBuilder::with_authentication_handler`] if set or else to this one.
+  /// - **Android**: Unsupported.
+  pub fn with_proxy_auth_handler(
+    mut self,
+    handler: impl Fn(AuthChallenge) -> Option<Credentials> + 'static,
+  ) -> Self {
+    self.attrs.proxy_auth_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a handler supplying the credentials asked by the servers of pages with basic, digest
+  /// or NTLM authentication instead of the platform dialog.
   ///
-  ///   Although this methods only needs an X11 window handle, you use webkit2gtk, so you still need to initialize gtk
-  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
-  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
+  /// [`AuthChallenge::previous_failure_count`] tells how many credentials the server rejected,
+  /// returning `None` cancels the challenge and the page then shows the `401` response of the
+  /// server.
   ///
-  ///   If you want to support child webviews on X11 and Wayland at the same time,
-  ///   we recommend using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
-  /// - **Android/iOS:** Unsupported.
+  /// ## Example
   ///
-  /// # Panics:
+  /// ```no_run
+  /// use wry::{Credentials, WebViewBuilder};
+  ///
+  /// let builder = WebViewBuilder::new().with_authentication_handler(|challenge| {
+  ///   (challenge.host == "tauri.app" && challenge.previous_failure_count == 0).then(|| Credentials {
+  ///     username: "user".into(),
+  ///     password: "password".into(),
+  ///   })
+  /// });
+  /// ```
   ///
-  /// - Panics if the provided handle was not support or invalid.
-  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
-  pub fn build_as_child<W: HasWindowHandle>(self, window: &'a W) -> Result<WebView> {
-    self.error?;
-
-    InnerWebView::new_as_child(window, self.attrs, self.platform_specific)
-      .map(|webview| WebView { webview })
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 101.0.1150.38 or higher. WebView2 doesn't
+  ///   report rejected credentials, any repeated challenge of the same server and realm counts
+  ///   as a failure. The challenges of proxies are also reported to this handler, see
+  ///   [`WebViewBuilder::with_proxy_auth_handler`].
+  /// - **Android**: Unsupported.
+  pub fn with_authentication_handler(
+    mut self,
+    handler: impl Fn(AuthChallenge) -> Option<Credentials> + 'static,
+  ) -> Self {
+    self.attrs.authentication_handler = Some(Box::new(handler));
+    self
   }
-}
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-pub(crate) struct PlatformSpecificWebViewAttributes {
-  data_store_identifier: Option<[u8; 16]>,
-  traffic_light_inset: Option<dpi::Position>,
-  allow_link_preview: bool,
+  /// Set a handler picking the TLS client certificate sent to the servers asking for one,
+  /// usually out of [`CertRequest::certificates`]. Returning `None` continues without a
+  /// certificate.
+  ///
+  /// ## Example
+  ///
+  /// ```no_run
+  /// use wry::WebViewBuilder;
+  ///
+  /// let builder = WebViewBuilder::new().with_client_certificate_handler(|request| {
+  ///   request
+  ///     .certificates
+  ///     .into_iter()
+  ///     .find(|certificate| certificate.subject().contains("Tauri"))
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 92.0.902.49 or higher.
+  /// - **Linux**: The platform has no certificate store, use [`Certificate::from_pem_files`].
+  /// - **Android**: Unsupported.
+  pub fn with_client_certificate_handler(
+    mut self,
+    handler: impl Fn(CertRequest) -> Option<Certificate> + 'static,
+  ) -> Self {
+    self.attrs.client_certificate_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a handler deciding whether to accept the certificate of a host failing the TLS
+  /// verification, e.g. a self-signed certificate of a development server. Returning `true`
+  /// accepts the certificate for the host until the webview is dropped, returning `false` keeps
+  /// the platform rejection. Without a handler invalid certificates are always rejected.
+  ///
+  /// **Warning**: Accepting an invalid certificate disables the protection of TLS against
+  /// man-in-the-middle attacks for the host. Only accept certificates you can verify yourself,
+  /// e.g. by comparing [`CertificateInfo::pem`] with a known certificate, and never accept them
+  /// all in a release build.
+  ///
+  /// ## Example
+  ///
+  /// ```ignore
+  /// use wry::WebViewBuilder;
+  ///
+  /// let dev_certificate = std::fs::read_to_string("dev-server.pem").unwrap();
+  /// let builder = WebViewBuilder::new().with_tls_error_handler(move |host, certificate| {
+  ///   host == "localhost" && certificate.pem.trim() == dev_certificate.trim()
+  /// });
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: The accepted certificate is allowed for the host in all the webviews sharing
+  ///   the same [`WebContext`], and the page is loaded again.
+  /// - **Windows**: Requires WebView2 Runtime version 105.0.1343.22 or higher.
+  /// - **Android**: Unsupported.
+  #[cfg(feature = "tls-error-handler")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "tls-error-handler")))]
+  pub fn with_tls_error_handler(
+    mut self,
+    handler: impl Fn(&str, &CertificateInfo) -> bool + 'static,
+  ) -> Self {
+    self.attrs.tls_error_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a proxy configuration for the webview, see [`WebView::set_proxy`] to change it later.
+  ///
+  /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled. Supports HTTP CONNECT and SOCKSv5 proxies.
+  /// - **Windows**: Supports HTTP CONNECT and SOCKSv5 proxies without [`ProxyEndpoint::auth`],
+  ///   the webview creation fails with [`Error::UnsupportedProxy`] otherwise.
+  /// - **Linux**: Supports HTTP CONNECT and SOCKSv5 proxies.
+  /// - **Android / iOS:** Not supported.
+  pub fn with_proxy_config(mut self, configuration: ProxyConfig) -> Self {
+    self.attrs.proxy_config = Some(configuration);
+    self
+  }
+
+  /// Set whether the webview should be focused when created.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / Android / iOS:** Unsupported.
+  pub fn with_focused(mut self, focused: bool) -> Self {
+    self.attrs.focused = focused;
+    self
+  }
+
+  /// Specify the webview position relative to its parent if it will be created as a child
+  /// or if created using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
+  ///
+  /// Defaults to `x: 0, y: 0, width: 200, height: 200`.
+  pub fn with_bounds(mut self, bounds: Rect) -> Self {
+    self.attrs.bounds = Some(bounds);
+    self
+  }
+
+  /// Set whether background throttling should be disabled.
+  ///
+  /// By default, browsers throttle timers and even unload the whole tab (view) to free resources after roughly 5 minutes when
+  /// a view became minimized or hidden. This will permanently suspend all tasks until the documents visibility state
+  /// changes back from hidden to visible by bringing the view back to the foreground.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / Windows / Android**: Unsupported. Workarounds like a pending WebLock transaction might suffice.
+  /// - **iOS**: Supported since version 17.0+.
+  /// - **macOS**: Supported since version 14.0+.
+  ///
+  /// see https://github.com/tauri-apps/tauri/issues/5250#issuecomment-2569380578
+  pub fn with_background_throttling(mut self, policy: BackgroundThrottlingPolicy) -> Self {
+    self.attrs.background_throttling = Some(policy);
+    self
+  }
+
+  /// Whether JavaScript should be disabled.
+  pub fn with_javascript_disabled(mut self) -> Self {
+    self.attrs.javascript_disabled = true;
+    self
+  }
+
+  /// Consume the builder and create the [`WebView`] from a type that implements [`HasWindowHandle`].
+  ///
+  /// # Platform-specific:
+  ///
+  /// - **Linux**: Only X11 is supported, if you want to support Wayland too, use [`WebViewBuilderExtUnix::new_gtk`].
+  ///
+  ///   Although this methods only needs an X11 window handle, we use webkit2gtk, so you still need to initialize gtk
+  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
+  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
+  /// - **Windows**: The webview will auto-resize when the passed handle is resized.
+  /// - **Linux (X11)**: Unlike macOS and Windows, the webview will not auto-resize and you'll need to call [`WebView::set_bounds`] manually.
+  ///
+  /// # Panics:
+  ///
+  /// - Panics if the provided handle was not supported or invalid.
+  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
+  pub fn build<W: HasWindowHandle>(mut self, window: &'a W) -> Result<WebView> {
+    let (message_handlers, ipc_responder) = self.prepare();
+    self.error?;
+    InnerWebView::new(window, self.attrs, self.platform_specific)
+      .map(|webview| WebView::from_inner(webview, ipc_responder, message_handlers))
+  }
+
+  /// Consume the builder and create the [`WebView`] as a child window inside the provided [`HasWindowHandle`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: This will create the webview as a child window of the `parent` window.
+  /// - **macOS**: This will create the webview as a `NSView` subview of the `parent` window's
+  ///   content view.
+  /// - **Linux**: This will create the webview as a child window of the `parent` window. Only X11
+  ///   is supported. This method won't work on Wayland.
+  ///
+  ///   Although this methods only needs an X11 window handle, you use webkit2gtk, so you still need to initialize gtk
+  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
+  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
+  ///
+  ///   If you want to support child webviews on X11 and Wayland at the same time,
+  ///   we recommend using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
+  /// - **Android/iOS:** Unsupported.
+  ///
+  /// # Panics:
+  ///
+  /// - Panics if the provided handle was not support or invalid.
+  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
+  pub fn build_as_child<W: HasWindowHandle>(mut self, window: &'a W) -> Result<WebView> {
+    let (message_handlers, ipc_responder) = self.prepare();
+    self.error?;
+    InnerWebView::new_as_child(window, self.attrs, self.platform_specific)
+      .map(|webview| WebView::from_inner(webview, ipc_responder, message_handlers))
+  }
+
+  /// Consume the builder and create the [`WebView`] as a child of a raw window handle, e.g. one
+  /// owned by a UI toolkit that doesn't implement [`HasWindowHandle`].
+  ///
+  /// This behaves like [`WebViewBuilder::build_as_child`], the handle kind must match the platform
+  /// otherwise [`Error::UnsupportedWindowHandle`] is returned.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Expects a [`RawWindowHandle::Win32`] (`HWND`).
+  /// - **macOS**: Expects a [`RawWindowHandle::AppKit`] (`NSView`).
+  /// - **Linux**: Expects a [`RawWindowHandle::Xlib`] window. To embed the webview into a
+  ///   `GtkContainer`, use [`WebViewBuilderExtUnix::build_gtk`] instead.
+  /// - **Android/iOS:** Unsupported.
+  ///
+  /// # Safety
+  ///
+  /// `parent` must be a valid handle that outlives the webview.
+  pub unsafe fn build_as_child_raw(mut self, parent: RawWindowHandle) -> Result<WebView> {
+    let (message_handlers, ipc_responder) = self.prepare();
+    self.error?;
+    InnerWebView::new_as_child(&RawParent(parent), self.attrs, self.platform_specific)
+      .map(|webview| WebView::from_inner(webview, ipc_responder, message_handlers))
+  }
+
+  /// Wires the handlers of wry into the attributes before the platform webview is created.
+  fn prepare(&mut self) -> (ipc::MessageHandlers, Option<IpcResponder>) {
+    navigation::attach_navigation_handler(&mut self.attrs);
+    navigation::attach_new_window_handler(&mut self.attrs);
+    download::attach_download_handler(&mut self.attrs);
+    clipboard::attach_clipboard_policy(&mut self.attrs);
+    service_worker::attach_service_worker_handler(&mut self.attrs);
+    custom_protocol::attach_protocol_error_handler(&mut self.attrs);
+    #[cfg(feature = "serde")]
+    event::attach_event_listener(&mut self.attrs);
+    let message_handlers = ipc::attach_message_handlers(&mut self.attrs);
+    let ipc_responder = ipc::attach_invoke_handler(&mut self.attrs);
+    (message_handlers, ipc_responder)
+  }
+}
+
+/// A raw window handle passed to [`WebViewBuilder::build_as_child_raw`].
+struct RawParent(RawWindowHandle);
+
+impl HasWindowHandle for RawParent {
+  fn window_handle(&self) -> std::result::Result<WindowHandle<'_>, HandleError> {
+    // SAFETY: the caller of `build_as_child_raw` guarantees the handle is valid
+    Ok(unsafe { WindowHandle::borrow_raw(self.0) })
+  }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub(crate) struct PlatformSpecificWebViewAttributes {
+  data_store_identifier: Option<[u8; 16]>,
+  traffic_light_inset: Option<dpi::Position>,
+  allow_link_preview: bool,
   on_web_content_process_terminate_handler: Option<Box<dyn Fn()>>,
   #[cfg(target_os = "ios")]
   input_accessory_view_builder: Option<Box<InputAccessoryViewBuilder>>,
@@ -1483,6 +2891,7 @@ pub(crate) struct PlatformSpecificWebViewAttributes {
   limit_navigations_to_app_bound_domains: bool,
   #[cfg(target_os = "macos")]
   webview_configuration: Option<Retained<objc2_web_kit::WKWebViewConfiguration>>,
+  user_content_controller: Option<Retained<WKUserContentController>>,
 }
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -1500,6 +2909,7 @@ impl Default for PlatformSpecificWebViewAttributes {
       limit_navigations_to_app_bound_domains: false,
       #[cfg(target_os = "macos")]
       webview_configuration: None,
+      user_content_controller: None,
     }
   }
 }
@@ -1527,6 +2937,12 @@ pub trait WebViewBuilderExtDarwin {
   fn with_allow_link_preview(self, allow_link_preview: bool) -> Self;
   /// Set a handler closure to respond to web content process termination. Available on macOS and iOS only.
   fn with_on_web_content_process_terminate_handler(self, handler: impl Fn() + 'static) -> Self;
+  /// Use `controller` as the user content controller of the webview, so webviews built with the
+  /// same controller share their initialization scripts and script message handlers, see
+  /// [`WebContext::set_user_content_controller`].
+  ///
+  /// Dropping one of the webviews keeps the handlers of the others.
+  fn with_user_content_controller(self, controller: Retained<WKUserContentController>) -> Self;
 }
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -1552,6 +2968,11 @@ impl WebViewBuilderExtDarwin for WebViewBuilder<'_> {
       .on_web_content_process_terminate_handler = Some(Box::new(handler));
     self
   }
+
+  fn with_user_content_controller(mut self, controller: Retained<WKUserContentController>) -> Self {
+    self.platform_specific.user_content_controller = Some(controller);
+    self
+  }
 }
 
 #[cfg(target_os = "macos")]
@@ -1679,7 +3100,8 @@ pub trait WebViewBuilderExtWindows {
   ///
   /// - Webview instances with different browser arguments must also have different [data directories](struct.WebContext.html#method.new).
   /// - By default wry passes `--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection`
-  ///   `--autoplay-policy=no-user-gesture-required` if autoplay is enabled
+  ///   `--autoplay-policy=no-user-gesture-required` if autoplay is enabled, or the policy of
+  ///   [`WebViewBuilder::with_autoplay_policy`]
   ///   and `--proxy-server=<scheme>://<host>:<port>` if a proxy is set.
   ///   so if you use this method, you have to add these arguments yourself if you want to keep the same behavior.
   fn with_additional_browser_args<S: Into<String>>(self, additional_args: S) -> Self;
@@ -1875,6 +3297,7 @@ impl WebViewBuilderExtAndroid for WebViewBuilder<'_> {
 pub(crate) struct PlatformSpecificWebViewAttributes {
   extension_path: Option<PathBuf>,
   related_view: Option<webkit2gtk::WebView>,
+  https_scheme: bool,
 }
 
 #[cfg(any(
@@ -1905,6 +3328,15 @@ pub trait WebViewBuilderExtUnix<'a> {
   /// Creates a new webview sharing the same web process with the provided webview.
   /// Useful if you need to link a webview to another, for instance when using the [`WebViewBuilder::with_new_window_req_handler`].
   fn with_related_view(self, webview: webkit2gtk::WebView) -> Self;
+
+  /// Determines whether the custom protocols are registered as secure schemes, which makes
+  /// their pages secure contexts so they can use APIs like `crypto.subtle` and
+  /// `navigator.serviceWorker`.
+  ///
+  /// Pages of secure schemes block `http` resources as mixed content, e.g. from a local server.
+  ///
+  /// The default value is `false`.
+  fn with_https_scheme(self, enabled: bool) -> Self;
 }
 
 #[cfg(any(
@@ -1915,14 +3347,14 @@ pub trait WebViewBuilderExtUnix<'a> {
   target_os = "openbsd",
 ))]
 impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
-  fn build_gtk<W>(self, widget: &'a W) -> Result<WebView>
+  fn build_gtk<W>(mut self, widget: &'a W) -> Result<WebView>
   where
     W: gtk::prelude::IsA<gtk::Container>,
   {
+    let (message_handlers, ipc_responder) = self.prepare();
     self.error?;
-
     InnerWebView::new_gtk(widget, self.attrs, self.platform_specific)
-      .map(|webview| WebView { webview })
+      .map(|webview| WebView::from_inner(webview, ipc_responder, message_handlers))
   }
 
   fn with_extensions_path(mut self, path: impl Into<PathBuf>) -> Self {
@@ -1934,6 +3366,11 @@ impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
     self.platform_specific.related_view.replace(webview);
     self
   }
+
+  fn with_https_scheme(mut self, enabled: bool) -> Self {
+    self.platform_specific.https_scheme = enabled;
+    self
+  }
 }
 
 /// The fundamental type to present a [`WebView`].
@@ -1943,6 +3380,7 @@ impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
 /// [`WebView`] presents the actual WebView window and let you still able to perform actions on it.
 pub struct WebView {
   webview: InnerWebView,
+  message_handlers: ipc::MessageHandlers,
 }
 
 impl WebView {
@@ -1995,17 +3433,125 @@ impl WebView {
     WebViewBuilder::new_with_attributes(attrs).build_as_child(parent)
   }
 
+  fn from_inner(
+    webview: InnerWebView,
+    ipc_responder: Option<IpcResponder>,
+    message_handlers: ipc::MessageHandlers,
+  ) -> Self {
+    if let Some(responder) = ipc_responder {
+      responder.set_evaluator(webview.script_evaluator());
+    }
+    Self {
+      webview,
+      message_handlers,
+    }
+  }
+
   /// Returns the id of this webview.
   pub fn id(&self) -> WebViewId<'_> {
     self.webview.id()
   }
 
-  /// Get the current url of the webview
+  /// Get the current url of the webview, or an empty string before the first navigation.
+  ///
+  /// The url is read from the webview when this is called, so it reflects navigations done
+  /// since the webview was created. It is the url of the top-level frame and includes
+  /// same-document changes made with `history.pushState`, `history.replaceState` or fragment
+  /// navigations, use [`WebViewBuilder::with_on_page_load_handler`] to track the url of the last
+  /// committed document instead.
+  ///
+  /// While a navigation is in flight, this can already be the url being navigated to.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: The url changes as soon as a cross-document navigation starts.
+  /// - **Linux**: The url changes as soon as a cross-document navigation starts,
+  ///   and again on server redirects.
+  /// - **Windows**: The url changes when a cross-document navigation is committed.
   pub fn url(&self) -> Result<String> {
     self.webview.url()
   }
 
+  /// Adds an initialization script identified by `id`, replacing the script previously added
+  /// with the same `id`.
+  ///
+  /// Like the scripts of [`WebViewBuilder::with_initialization_script`], it is injected into the
+  /// main frame only and runs before `window.onload`. Adding or removing scripts only affects the
+  /// documents loaded afterwards, reload the webview to apply them to the current page.
+  ///
+  /// The script runs after the scripts of the [`WebViewBuilder`] and the scripts added before it.
+  /// Replacing a script makes it run last, like removing it and adding it again.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The script is also injected into subframes, see
+  ///   [`WebViewBuilder::with_initialization_script_for_main_only`].
+  /// - **Android**: Unsupported.
+  pub fn add_init_script(&self, id: &str, script: &str) -> Result<()> {
+    self.webview.add_init_script(id, script)
+  }
+
+  /// Removes the initialization script added with [`WebView::add_init_script`] under `id`,
+  /// does nothing if there is none.
+  ///
+  /// The scripts added with the [`WebViewBuilder`] can't be removed.
+  pub fn remove_init_script(&self, id: &str) -> Result<()> {
+    self.webview.remove_init_script(id)
+  }
+
+  /// The ids of the initialization scripts added with [`WebView::add_init_script`], in the order
+  /// they run.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, always empty.
+  pub fn init_scripts(&self) -> Vec<String> {
+    self.webview.init_scripts()
+  }
+
+  /// Adds a stylesheet to the current document and to the documents loaded afterwards, into all
+  /// frames or the main frame only. Returns the id to remove it with
+  /// [`WebView::remove_user_stylesheet`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS**: The stylesheet is injected as a `<style>` element by an
+  ///   initialization script, so it is visible to the page and [`StylesheetLevel::User`] is not
+  ///   supported. It is only added to the main frame of the current document.
+  /// - **Android**: Unsupported.
+  pub fn add_user_stylesheet(
+    &self,
+    css: &str,
+    main_frame_only: bool,
+    level: StylesheetLevel,
+  ) -> Result<StylesheetId> {
+    let id = StylesheetId::next();
+    self
+      .webview
+      .add_user_stylesheet(&id.to_string(), css, main_frame_only, level)?;
+    Ok(id)
+  }
+
+  /// Removes the stylesheet added with [`WebView::add_user_stylesheet`] from the current document
+  /// and the documents loaded afterwards, does nothing if it was already removed.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS**: It is only removed from the main frame of the current document.
+  pub fn remove_user_stylesheet(&self, id: StylesheetId) -> Result<()> {
+    self.webview.remove_user_stylesheet(&id.to_string())
+  }
+
   /// Evaluate and run javascript code.
+  ///
+  /// Scripts evaluated before the first page was committed, e.g. right after the webview was
+  /// built, run once it was, see [`WebViewBuilder::with_deferred_scripts`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Scripts longer than a megabyte are sent to the page in chunks and
+  ///   run with an indirect `eval`, which fails on pages whose Content Security Policy doesn't
+  ///   allow `'unsafe-eval'`. The same applies to the other `evaluate_script*` methods.
   pub fn evaluate_script(&self, js: &str) -> Result<()> {
     self
       .webview
@@ -2015,9 +3561,15 @@ impl WebView {
   /// Evaluate and run javascript code with callback function. The evaluation result will be
   /// serialized into a JSON string and passed to the callback function.
   ///
-  /// Exception is ignored because of the limitation on windows. You can catch it yourself and return as string as a workaround.
+  /// If the script throws, the callback receives `{"error": "<exception message>"}` instead.
+  ///
+  /// The callback runs on the UI thread, so it must not block.
+  ///
+  /// ## Platform-specific:
   ///
-  /// - ** Android:** Not implemented yet.
+  /// - **Windows**: Exceptions are only reported with WebView2 Runtime 1.0.2210 or newer,
+  ///   older runtimes pass `null`.
+  /// - **Android:** Not implemented yet.
   pub fn evaluate_script_with_callback(
     &self,
     js: &str,
@@ -2026,35 +3578,290 @@ impl WebView {
     self.webview.eval(js, Some(callback))
   }
 
-  /// Launch print modal for the webview content.
-  pub fn print(&self) -> Result<()> {
-    self.webview.print()
-  }
+  /// Evaluate javascript code and resolve to its result, parsed from the JSON passed to
+  /// [`Self::evaluate_script_with_callback`].
+  ///
+  /// `undefined` results resolve to [`serde_json::Value::Null`], and scripts that throw resolve to
+  /// `{"error": "<exception message>"}`. If the webview is dropped before the script completes,
+  /// the future resolves to [`Error::WebViewClosed`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android:** Not implemented yet.
+  #[cfg(feature = "serde")]
+  pub fn evaluate_script_async(
+    &self,
+    js: &str,
+  ) -> impl std::future::Future<Output = Result<serde_json::Value>> {
+    let (sender, receiver) = script::result_channel();
+    let evaluated = self
+      .webview
+      .eval(js, Some(move |result: String| sender.send(result)));
 
-  /// Get a list of cookies for specific url.
-  pub fn cookies_for_url(&self, url: &str) -> Result<Vec<cookie::Cookie<'static>>> {
-    self.webview.cookies_for_url(url)
+    async move {
+      evaluated?;
+      let result = receiver.await.ok_or(Error::WebViewClosed)?;
+      if result.is_empty() {
+        return Ok(serde_json::Value::Null);
+      }
+      Ok(serde_json::from_str(&result)?)
+    }
   }
 
-  /// Get the list of cookies.
+  /// Emit `event` to the page with `payload`, received by the callbacks registered with
+  /// `window.wry.listen(event, callback)`:
   ///
-  /// ## Platform-specific
+  /// ```js
+  /// const unlisten = window.wry.listen('progress', (payload) => console.log(payload.percent))
+  /// ```
   ///
-  /// - **Android**: Unsupported, always returns an empty [`Vec`].
-  pub fn cookies(&self) -> Result<Vec<cookie::Cookie<'static>>> {
-    self.webview.cookies()
+  /// The payload is passed to the page as JSON, large payloads are sent in several scripts.
+  /// Events are only received by the main frame, and are lost if the page isn't loaded yet.
+  #[cfg(feature = "serde")]
+  pub fn emit(&self, event: &str, payload: serde_json::Value) -> Result<()> {
+    for script in event::scripts(event, &payload)? {
+      self
+        .webview
+        .eval(&script, None::<Box<dyn Fn(String) + Send + 'static>>)?;
+    }
+    Ok(())
   }
 
-  /// Set a cookie for the webview.
+  /// Add a handler receiving the messages posted to the `name` channel with
+  /// `window.ipc.postMessageTo(name, message)`, replacing the previous handler of `name`. The
+  /// request is built like the ones of [`WebViewBuilder::with_ipc_handler`], which keeps receiving
+  /// the messages of `window.ipc.postMessage`.
+  ///
+  /// On macOS, iOS and Linux, pages can also post with
+  /// `window.webkit.messageHandlers[name].postMessage(message)`.
+  ///
+  /// Returns [`Error::InvalidMessageHandlerName`] if `name` is empty, contains a `:` or is one of
+  /// the names used by wry: `ipc` and `wryContextMenu`.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # fn add(webview: &wry::WebView) -> wry::Result<()> {
+  /// // `window.ipc.postMessageTo('files', 'open')`
+  /// webview.add_message_handler("files", |request| println!("{}", request.body()))?;
+  /// # Ok(())
+  /// # }
+  /// ```
   ///
   /// ## Platform-specific
   ///
-  /// - **Android**: Not supported.
-  pub fn set_cookie(&self, cookie: &cookie::Cookie<'_>) -> Result<()> {
-    self.webview.set_cookie(cookie)
+  /// - **Windows**: Messages are routed by name through the IPC channel of the webview, messages
+  ///   posted from iframes are not delivered.
+  /// - **Android**: Unsupported, `window.ipc.postMessageTo` is not defined.
+  pub fn add_message_handler<F>(&self, name: &str, handler: F) -> Result<()>
+  where
+    F: Fn(Request<String>) + 'static,
+  {
+    let handler: Rc<dyn Fn(Request<String>)> = Rc::new(handler);
+    self.message_handlers.insert(name, handler.clone())?;
+    self
+      .webview
+      .add_message_handler(name, Box::new(move |request| handler(request)))
   }
 
-  /// Delete a cookie for the webview.
+  /// Remove the handler added with [`WebView::add_message_handler`] for `name`, the messages
+  /// posted to `name` are dropped afterwards.
+  pub fn remove_message_handler(&self, name: &str) -> Result<()> {
+    self.message_handlers.remove(name);
+    self.webview.remove_message_handler(name)
+  }
+
+  /// Launch print modal for the webview content.
+  pub fn print(&self) -> Result<()> {
+    self.webview.print()
+  }
+
+  /// Prints the webview content without showing the print dialog, for kiosk and point of sale
+  /// apps. `completion` is called on the main thread once the job is sent to the printer.
+  ///
+  /// `completion` is called with [`Error::PrinterNotFound`] if the printer doesn't exist.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: `completion` is called once the job is started, the webview must be in a window.
+  /// - **iOS / Android**: Unsupported, `completion` is called with [`Error::PrintFailed`].
+  pub fn print_silent<F>(&self, settings: PrintSettings, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    self.webview.print_silent(settings, completion)
+  }
+
+  /// Prints the webview content to a PDF document without showing a dialog, `completion` is
+  /// called on the main thread with the bytes of the document.
+  ///
+  /// Write the bytes to a file with [`std::fs::write`] to save the document to a path.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 11 or iOS 14, earlier versions return
+  ///   [`Error::PrintFailed`]. The document is a single page with the whole content,
+  ///   `options` are ignored.
+  /// - **Linux**: The header and footer are not supported.
+  /// - **Android**: Unsupported, `completion` is called with [`Error::PrintFailed`].
+  pub fn print_to_pdf<F>(&self, options: PrintToPdfOptions, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    self.webview.print_to_pdf(options, completion)
+  }
+
+  /// Find `query` in the page, highlighting the matches and selecting the first one.
+  /// `callback` is called on the main thread with the number of matches.
+  ///
+  /// An empty `query` clears the matches, like [`Self::clear_matches`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: The page is searched with a script, matches spanning several
+  ///   elements aren't found and frames aren't searched.
+  /// - **macOS / iOS**: Requires macOS 11 or iOS 14, earlier versions only count the matches.
+  ///   The matches are selected one at a time instead of highlighted, and counted with a
+  ///   script.
+  pub fn find(
+    &self,
+    query: &str,
+    options: FindOptions,
+    callback: impl Fn(u32) + Send + 'static,
+  ) -> Result<()> {
+    self.webview.find(query, options, callback)
+  }
+
+  /// Select the next match of [`Self::find`].
+  pub fn find_next(&self) -> Result<()> {
+    self.webview.find_next()
+  }
+
+  /// Select the previous match of [`Self::find`].
+  pub fn find_previous(&self) -> Result<()> {
+    self.webview.find_previous()
+  }
+
+  /// Clear the matches of [`Self::find`].
+  pub fn clear_matches(&self) -> Result<()> {
+    self.webview.clear_matches()
+  }
+
+  /// Get the selection of the page as plain text and HTML, `callback` is called on the main
+  /// thread with it.
+  ///
+  /// The selection of the focused text field is preferred, it has no HTML. An empty
+  /// [`Selection`] is passed if nothing is selected or the page can't run scripts.
+  pub fn selected_text(&self, callback: impl Fn(Selection) + Send + 'static) -> Result<()> {
+    self.webview.eval(
+      selection::SELECTED_TEXT,
+      Some(move |json: String| callback(selection::parse(&json).unwrap_or_default())),
+    )
+  }
+
+  /// Select all the content of the page, or of the focused text field.
+  pub fn select_all(&self) -> Result<()> {
+    self.webview.select_all()
+  }
+
+  /// Take a snapshot of the accessibility tree of the page, e.g. for accessibility regression
+  /// tests. `callback` is called on the main thread with the root of the tree, `None` if the
+  /// page can't run scripts.
+  ///
+  /// The tree is computed from the DOM by a script, the same way on all platforms:
+  ///
+  /// - Roles are the ARIA roles of the elements, explicit or implied by their tag. Elements
+  ///   without a role, like `<div>` and `<span>`, are left out and their children moved to
+  ///   their parent.
+  /// - Names follow a simplified accessible name computation: `aria-labelledby`, `aria-label`,
+  ///   the labels of form fields, `alt`, the content for the roles named after it, then `title`
+  ///   and `placeholder`.
+  /// - Hidden elements are left out. The content of frames isn't included, and shadow roots
+  ///   replace the content of their hosts.
+  ///
+  /// ## Platform-specific
+  ///
+  /// The tree isn't read from NSAccessibility on macOS, ATK on Linux or UI Automation on Windows,
+  /// which expose the page from its web process to assistive technologies rather than to the
+  /// app, and name the roles differently on each platform.
+  pub fn accessibility_snapshot(
+    &self,
+    callback: impl Fn(Option<AccessibilityNode>) + Send + 'static,
+  ) -> Result<()> {
+    self.webview.eval(
+      accessibility::SNAPSHOT,
+      Some(move |json: String| callback(accessibility::parse(&json))),
+    )
+  }
+
+  /// Clear the selection of the page and of the focused text field.
+  pub fn clear_selection(&self) -> Result<()> {
+    self.webview.eval(
+      selection::CLEAR_SELECTION,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  /// Get the scroll position of the page in CSS pixels, `callback` is called on the main thread
+  /// with `(x, y)`, `None` if the page can't run scripts.
+  ///
+  /// This is the position of the top document, `window.scrollX` and `window.scrollY`, the
+  /// elements scrolling on their own and the frames of the page have positions of their own.
+  /// The platforms don't expose the scroll position of the page, it is read by a script on all of
+  /// them.
+  pub fn scroll_position(
+    &self,
+    callback: impl Fn(Option<(f64, f64)>) + Send + 'static,
+  ) -> Result<()> {
+    self.webview.eval(
+      scroll::SCROLL_POSITION,
+      Some(move |json: String| callback(scroll::parse(&json))),
+    )
+  }
+
+  /// Scroll the top document to `x`, `y` in CSS pixels, e.g. to restore a position read with
+  /// [`WebView::scroll_position`]. The page jumps to the position even if it scrolls smoothly
+  /// otherwise, [`f64::INFINITY`] scrolls to the end.
+  pub fn set_scroll_position(&self, x: f64, y: f64) -> Result<()> {
+    self.webview.eval(
+      &scroll::scroll_to(x, y),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  /// Get the cookies that the webview would send with a request to `url`.
+  ///
+  /// Session cookies have an [`Expiration::Session`](cookie::Expiration::Session) expiry.
+  pub fn cookies_for_url(&self, url: &str) -> Result<Vec<cookie::Cookie<'static>>> {
+    self.webview.cookies_for_url(url)
+  }
+
+  /// Get the list of cookies.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, always returns an empty [`Vec`].
+  pub fn cookies(&self) -> Result<Vec<cookie::Cookie<'static>>> {
+    self.webview.cookies()
+  }
+
+  /// Set a cookie for the webview.
+  ///
+  /// The cookie is stored in the cookie store of the webview and sent with the later requests
+  /// matching its domain and path, whether or not the loaded page matches it, so cookies can be
+  /// seeded before navigating. A cookie without an explicit domain can't be stored and returns
+  /// [`Error::InvalidCookie`], as does a `SameSite=None` cookie that isn't `Secure` or a
+  /// `__Secure-` / `__Host-` prefixed cookie that doesn't follow the rules of its prefix.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Not supported.
+  pub fn set_cookie(&self, cookie: &cookie::Cookie<'_>) -> Result<()> {
+    cookies::validate(cookie)?;
+    self.webview.set_cookie(cookie)
+  }
+
+  /// Delete a cookie for the webview.
   ///
   /// ## Platform-specific
   ///
@@ -2063,101 +3870,574 @@ impl WebView {
     self.webview.delete_cookie(cookie)
   }
 
-  /// Open the web inspector which is usually called dev tool.
+  /// Delete the cookies named `name` that the webview would send with a request to `url`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Not supported.
+  pub fn delete_cookies_for_url(&self, url: &str, name: &str) -> Result<()> {
+    self.webview.delete_cookies_for_url(url, name)
+  }
+
+  /// Open the web inspector which is usually called dev tool.
+  ///
+  /// Returns [`Error::DevToolsNotEnabled`] if devtools weren't enabled with
+  /// [`WebViewBuilder::with_devtools`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn open_devtools(&self) -> Result<()> {
+    self.webview.open_devtools()
+  }
+
+  /// Open the web inspector for the element at the given position, in logical pixels relative to
+  /// the webview, e.g. for an "Inspect Element" context menu item.
+  ///
+  /// None of the platforms expose inspecting an element by position, so the web inspector is
+  /// opened, the element is highlighted briefly and logged to the console, from where it can be
+  /// revealed in the elements panel.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn inspect_element_at(&self, x: f64, y: f64) -> Result<()> {
+    self.webview.open_devtools()?;
+    self.webview.eval(
+      &script::inspect_element_at(x, y),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  /// Close the web inspector which is usually called dev tool.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn close_devtools(&self) {
+    self.webview.close_devtools()
+  }
+
+  /// Gets the devtool window's current visibility state.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Looks for the devtools window WebView2 opens for the current URL, so it's also
+  ///   `true` if another webview of the same browser process inspects the same URL.
+  /// - **Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn is_devtools_open(&self) -> bool {
+    self.webview.is_devtools_open()
+  }
+
+  /// Set the webview zoom level, same as [`WebView::set_zoom`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  /// - **macOS**: available on macOS 11+ only.
+  /// - **iOS**: available on iOS 14+ only.
+  pub fn zoom(&self, scale_factor: f64) -> Result<()> {
+    self.webview.zoom(scale_factor)
+  }
+
+  /// Sets the page zoom factor, `1.0` is 100%, independently of
+  /// [`WebViewBuilder::with_hotkeys_zoom`].
+  ///
+  /// Page zoom is what browsers do on `Ctrl +`: the page is laid out again for a smaller or
+  /// larger viewport and `window.devicePixelRatio` changes. It is not the pinch magnification
+  /// of trackpads and touch screens, which scales the rendered page without a new layout and
+  /// isn't affected by this method.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  /// - **macOS**: available on macOS 11+ only, `WKWebView.pageZoom`.
+  /// - **iOS**: available on iOS 14+ only, `WKWebView.pageZoom`.
+  pub fn set_zoom(&self, factor: f64) -> Result<()> {
+    self.webview.zoom(factor)
+  }
+
+  /// The page zoom factor, `1.0` is 100%, see [`WebView::set_zoom`].
+  ///
+  /// This includes the zoom changes made with the hotkeys.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported, always `1.0`.
+  /// - **macOS / iOS**: Always `1.0` before macOS 11 and iOS 14.
+  pub fn zoom_level(&self) -> Result<f64> {
+    self.webview.zoom_level()
+  }
+
+  /// Changes the [user-agent](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent)
+  /// set with [`WebViewBuilder::with_user_agent`].
+  ///
+  /// The new user agent is sent with the requests made after this call, the current page keeps
+  /// reporting the previous one in `navigator.userAgent` until the next navigation, so call
+  /// [`WebView::reload`] to apply it to the current page.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 86.0.616.0 or higher, does nothing on older versions.
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    self.webview.set_user_agent(user_agent)
+  }
+
+  /// Changes the [`Accept-Language`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Accept-Language)
+  /// header of the requests made after this call, `languages` going from the most to the least
+  /// preferred. An empty slice stops overriding the header.
+  ///
+  /// Unlike [`WebViewBuilder::with_accept_language`], `navigator.language` is left unchanged, so
+  /// the content negotiation of servers can be tested apart from the locale of scripts.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The header is set on the requests seen by the web resource requested event,
+  ///   the first call starts watching all the http(s) requests of the webview.
+  /// - **macOS / iOS**: The header is only sent with the requests loaded by wry, e.g.
+  ///   [`WebView::load_url`], following links sends the languages of the system or the ones of
+  ///   [`WebViewBuilder::with_accept_language`].
+  /// - **Linux / Android**: Unsupported, WebKitGTK only allows changing requests from a web
+  ///   process extension.
+  pub fn set_accept_language(&self, languages: &[&str]) -> Result<()> {
+    self.webview.set_accept_language(languages)
+  }
+
+  /// Sets whether horizontal swipe gestures trigger backward and forward page navigation, see
+  /// [`WebViewBuilder::with_back_forward_navigation_gestures`]. The next gesture uses the new
+  /// setting.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 92.0.902.0 or higher, does nothing on older
+  ///   versions. Keyboard shortcuts like `Alt+Left` are governed by
+  ///   `WebViewBuilderExtWindows::with_browser_accelerator_keys` instead.
+  /// - **Android**: Unsupported.
+  pub fn set_navigation_gestures(&self, enabled: bool) -> Result<()> {
+    self.webview.set_navigation_gestures(enabled)
+  }
+
+  /// Changes the proxy set with [`WebViewBuilder::with_proxy_config`], `None` uses the system
+  /// proxy. The new proxy is used for the requests made after this call.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Unsupported, WebView2 only reads the proxy when the webview is created.
+  ///   Returns [`Error::ProxyChangeUnsupported`], create a new webview instead.
+  /// - **macOS / iOS**: Requires macOS 14.0+ / iOS 17.0+ and the `mac-proxy` feature flag,
+  ///   returns [`Error::ProxyChangeUnsupported`] otherwise. Changes the proxy of all the webviews
+  ///   sharing the same data store.
+  /// - **Linux**: Changes the proxy of all the webviews sharing the same [`WebContext`].
+  /// - **Android**: Unsupported.
+  pub fn set_proxy(&self, proxy: Option<ProxyConfig>) -> Result<()> {
+    self.webview.set_proxy(proxy)
+  }
+
+  /// Specify the webview background color.
+  ///
+  /// The color uses the RGBA format, with an alpha value below `255` the host window shows
+  /// through the parts of the page without a background of their own.
+  ///
+  /// ## Platfrom-specific:
+  ///
+  /// - **macOS**: An alpha value below `255` turns off the `drawsBackground` of the webview. The
+  ///   color is used as the `underPageBackgroundColor` on macOS 12+.
+  /// - **Linux**: The window must use an RGBA visual for the alpha value to take effect.
+  /// - **Windows**: Requires WebView2 Runtime version 1.0.774.44 or higher.
+  ///   - On Windows 7, transparency is not supported and the alpha value will be ignored.
+  ///   - On Windows higher than 7: translucent colors are not supported so any alpha value other than `0` will be replaced by `255`
+  pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
+    self.webview.set_background_color(background_color)
+  }
+
+  /// Forces the webview to a light or dark theme, which pages see with the
+  /// `prefers-color-scheme` media query. [`Theme::Auto`], the default, follows the system.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Sets the `appearance` of the webview.
+  /// - **iOS**: Sets the `overrideUserInterfaceStyle` of the webview.
+  /// - **Linux**: Sets `gtk-application-prefer-dark-theme`, which changes the theme of the whole
+  ///   app. A dark GTK theme stays dark with [`Theme::Light`].
+  /// - **Windows**: Sets the preferred color scheme of the profile, shared by the webviews of the
+  ///   same [`WebContext`]. Requires WebView2 Runtime version 101.0.1210.39 or higher, returns an
+  ///   error on older versions.
+  /// - **Android**: Unsupported.
+  pub fn set_theme(&self, theme: Theme) -> Result<()> {
+    self.webview.set_theme(theme)
+  }
+
+  /// Navigate to the specified url
+  pub fn load_url(&self, url: &str) -> Result<()> {
+    self.webview.load_url(url)
+  }
+
+  /// Reloads the current page.
+  pub fn reload(&self) -> crate::Result<()> {
+    self.webview.reload()
+  }
+
+  /// Reloads the current page, revalidating the cached resources with the server.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: WebView2 has no API for this, the page is reloaded with the
+  ///   `Page.reload` DevTools Protocol method. If that isn't enough, e.g. for resources
+  ///   served by a custom protocol, add a cache-busting header to the requests in a
+  ///   resource request handler.
+  /// - **Android**: The page is reloaded without bypassing the cache.
+  pub fn reload_ignoring_cache(&self) -> Result<()> {
+    self.webview.reload_ignoring_cache()
+  }
+
+  /// Stops loading the current page, does nothing if it has finished loading.
+  pub fn stop_loading(&self) -> Result<()> {
+    self.webview.stop_loading()
+  }
+
+  /// Whether there is a history entry to go back to.
+  ///
+  /// This reflects the committed history: while a navigation is in flight, it doesn't account
+  /// for the entry that navigation will add until it commits.
+  pub fn can_go_back(&self) -> Result<bool> {
+    self.webview.can_go_back()
+  }
+
+  /// Whether there is a history entry to go forward to, see [`Self::can_go_back`].
+  pub fn can_go_forward(&self) -> Result<bool> {
+    self.webview.can_go_forward()
+  }
+
+  /// Navigate to the previous history entry, does nothing if there is none.
+  ///
+  /// A navigation in flight is cancelled and the webview goes back from the last committed entry.
+  pub fn go_back(&self) -> Result<()> {
+    self.webview.go_back()
+  }
+
+  /// Navigate to the next history entry, does nothing if there is none.
+  ///
+  /// A navigation in flight is cancelled and the webview goes forward from the last committed
+  /// entry.
+  pub fn go_forward(&self) -> Result<()> {
+    self.webview.go_forward()
+  }
+
+  /// The back and forward history entries of the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: The history entries are not exposed, only the current entry is
+  ///   returned.
+  pub fn history_list(&self) -> Result<History> {
+    self.webview.history_list()
+  }
+
+  /// Navigate to the specified url using the specified headers
+  ///
+  /// Headers that the webview computes itself, `Host`, `Content-Length`, `Connection` and
+  /// `Transfer-Encoding`, are dropped, as are values that aren't visible ASCII.
+  /// Repeated headers are sent as a single comma-separated value.
+  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) -> Result<()> {
+    self.webview.load_url_with_headers(url, headers)
+  }
+
+  /// Navigate to the specified url with the given method, request body and headers,
+  /// e.g. to start a single sign-on flow with a form `POST`.
+  ///
+  /// Headers are sent the same way as [`Self::load_url_with_headers`], set a `Content-Type`
+  /// header that matches the body.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux**: WebKitGTK can't set the method or body of a navigation request. `POST`s with an
+  ///   `application/x-www-form-urlencoded` body are sent by submitting an equivalent HTML form,
+  ///   which drops the other headers. Other methods and bodies return [`Error::UnsupportedNavigationBody`].
+  /// - **Android**: Unsupported.
+  pub fn load_url_with_body(
+    &self,
+    url: &str,
+    method: http::Method,
+    body: Vec<u8>,
+    headers: http::HeaderMap,
+  ) -> Result<()> {
+    self.webview.load_url_with_body(url, method, body, headers)
+  }
+
+  /// Load html content into the webview
+  pub fn load_html(&self, html: &str) -> Result<()> {
+    self.webview.load_html(html, None)
+  }
+
+  /// Load html content into the webview, its relative URLs like `<img src="foo.png">` resolve
+  /// against `base_url`, e.g. `app://localhost/` to load them with a custom protocol.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: WebView2 has no base URL for html strings, a `<base>` element is added to the
+  ///   html instead. The page still has a `null` origin, serve the html from a custom protocol
+  ///   with [`WebView::load_url`] if it needs the origin of `base_url`.
+  pub fn load_html_with_base_url(&self, html: &str, base_url: &str) -> Result<()> {
+    self.webview.load_html(html, Some(base_url))
+  }
+
+  /// Captures the webview contents as a PNG image, `completion` is called on the main thread
+  /// with the image once the platform rendered it.
+  ///
+  /// The visible part of the webview is captured, or the `rect` region of it, relative to the
+  /// top-left corner of the webview. The webview must be visible and have rendered its content,
+  /// a hidden or not yet painted webview produces an empty image or an error.
+  ///
+  /// ```no_run
+  /// # use wry::{dpi::{LogicalPosition, LogicalSize}, Rect, WebView};
+  /// # fn capture(webview: &WebView) -> wry::Result<()> {
+  /// let rect = Rect {
+  ///   position: LogicalPosition::new(0, 0).into(),
+  ///   size: LogicalSize::new(400, 300).into(),
+  /// };
+  /// webview.capture_png(Some(rect), |png| {
+  ///   if let Ok(png) = png {
+  ///     std::fs::write("capture.png", png).unwrap();
+  ///   }
+  /// })
+  /// # }
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: A region that is not fully inside the webview fails with an error.
+  /// - **Android**: Unsupported, `completion` is called with [`Error::CaptureFailed`].
+  pub fn capture_png<F>(&self, rect: Option<Rect>, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    self.webview.capture_png(rect, completion)
+  }
+
+  /// Clear all browsing data
+  pub fn clear_all_browsing_data(&self) -> Result<()> {
+    self.webview.clear_all_browsing_data()
+  }
+
+  /// Clear the browsing data of the given categories, `completion` is called on the main thread
+  /// once the platform finished clearing them.
+  ///
+  /// ```no_run
+  /// # use wry::{BrowsingDataFlags, WebView};
+  /// # fn clear(webview: &WebView) -> wry::Result<()> {
+  /// webview.clear_browsing_data(
+  ///   BrowsingDataFlags::COOKIES | BrowsingDataFlags::CACHE,
+  ///   |result| println!("cleared: {result:?}"),
+  /// )
+  /// # }
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 1.0.1245.22 or higher.
+  /// - **Android**: Unsupported, `completion` is never called.
+  pub fn clear_browsing_data<F>(&self, data: BrowsingDataFlags, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    self.webview.clear_browsing_data(data, completion)
+  }
+
+  /// Get the webview bounds, relative to its parent.
+  ///
+  /// The returned rect can be passed back to [`WebView::set_bounds`] unchanged.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: In physical pixels.
+  /// - **macOS / iOS / Linux**: In logical pixels.
+  pub fn bounds(&self) -> Result<Rect> {
+    self.webview.bounds()
+  }
+
+  /// Set the webview bounds.
+  ///
+  /// This is only effective if the webview was created as a child
+  /// or created using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
+  ///
+  /// Fractional logical coordinates are converted with the current scale factor of the webview,
+  /// rounding the edges to whole pixels so that they don't drift on fractional scale displays.
+  pub fn set_bounds(&self, bounds: Rect) -> Result<()> {
+    self.webview.set_bounds(bounds)
+  }
+
+  /// Shows or hides the webview.
+  ///
+  /// A hidden webview stops rendering but keeps its page running, scripts and timers included,
+  /// subject to the background throttling of the platform, see
+  /// [`WebViewBuilder::with_background_throttling`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn set_visible(&self, visible: bool) -> Result<()> {
+    self.webview.set_visible(visible)
+  }
+
+  /// Whether the webview is visible, see [`WebView::set_visible`].
   ///
   /// ## Platform-specific
   ///
-  /// - **Android / iOS:** Not supported.
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn open_devtools(&self) {
-    self.webview.open_devtools()
+  /// - **Android**: Always `true`.
+  pub fn is_visible(&self) -> Result<bool> {
+    self.webview.is_visible()
   }
 
-  /// Close the web inspector which is usually called dev tool.
+  /// Moves the webview above its siblings, e.g. to show the active one of several child webviews
+  /// stacked in the same window.
   ///
   /// ## Platform-specific
   ///
-  /// - **Windows / Android / iOS:** Not supported.
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn close_devtools(&self) {
-    self.webview.close_devtools()
+  /// - **Windows**: The webview is hosted in a child `HWND`, it is restacked among the sibling
+  ///   windows of its parent, which includes native child windows that aren't webviews.
+  /// - **Linux**: Restacks the `GdkWindow` of the webview among its siblings, or the X11 window of
+  ///   webviews created with [`WebViewBuilder::build_as_child`].
+  /// - **Android**: Unsupported.
+  pub fn bring_to_front(&self) -> Result<()> {
+    self.webview.bring_to_front()
   }
 
-  /// Gets the devtool window's current visibility state.
+  /// Rounds the corners of the webview with the given radius in logical pixels, `0.0` removes
+  /// the rounding.
   ///
   /// ## Platform-specific
   ///
-  /// - **Windows / Android / iOS:** Not supported.
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn is_devtools_open(&self) -> bool {
-    self.webview.is_devtools_open()
+  /// - **Windows**: Clips the `HWND` hosting the webview with a rounded region, the corners are
+  ///   not anti-aliased.
+  /// - **Linux**: Sets the input and drawing shape of the webview, the corners are not
+  ///   anti-aliased.
+  /// - **Android**: Unsupported.
+  pub fn set_corner_radius(&self, radius: f64) -> Result<()> {
+    self.webview.set_corner_radius(radius)
   }
 
-  /// Set the webview zoom level
+  /// Moves the webview below its siblings, see [`WebView::bring_to_front`].
   ///
-  /// ## Platform-specific:
+  /// ## Platform-specific
   ///
-  /// - **Android**: Not supported.
-  /// - **macOS**: available on macOS 11+ only.
-  /// - **iOS**: available on iOS 14+ only.
-  pub fn zoom(&self, scale_factor: f64) -> Result<()> {
-    self.webview.zoom(scale_factor)
+  /// - **Android**: Unsupported.
+  pub fn send_to_back(&self) -> Result<()> {
+    self.webview.send_to_back()
   }
 
-  /// Specify the webview background color.
+  /// Suspends the webview to save CPU, e.g. for a background tab, `completion` is called on the
+  /// main thread once it is suspended.
   ///
-  /// The color uses the RGBA format.
+  /// Call [`WebView::resume`] to undo it.
   ///
-  /// ## Platfrom-specific:
+  /// ```no_run
+  /// # use wry::WebView;
+  /// # fn hide_tab(webview: &WebView) -> wry::Result<()> {
+  /// webview.set_visible(false)?;
+  /// webview.suspend(|result| println!("suspended: {result:?}"))
+  /// # }
+  /// ```
   ///
-  /// - **macOS**: Not implemented.
-  /// - **Windows**:
-  ///   - On Windows 7, transparency is not supported and the alpha value will be ignored.
-  ///   - On Windows higher than 7: translucent colors are not supported so any alpha value other than `0` will be replaced by `255`
-  pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
-    self.webview.set_background_color(background_color)
-  }
-
-  /// Navigate to the specified url
-  pub fn load_url(&self, url: &str) -> Result<()> {
-    self.webview.load_url(url)
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Truly suspends the page, its scripts and timers included. The webview must be
+  ///   hidden with [`WebView::set_visible`], otherwise `completion` gets
+  ///   [`Error::SuspendFailed`]. Interacting with the webview, e.g. evaluating a script,
+  ///   resumes it. Requires WebView2 Runtime version 1.0.705.50 or higher.
+  /// - **macOS / iOS**: Only suspends media playback, scripts and timers keep running. Requires
+  ///   macOS 12.0+ / iOS 15.0+.
+  /// - **Linux**: Only mutes media, scripts and timers keep running. WebKitGTK throttles the
+  ///   timers of hidden webviews by itself. `completion` is called right away.
+  /// - **Android**: Unsupported, `completion` is never called.
+  pub fn suspend<F>(&self, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    self.webview.suspend(completion)
   }
 
-  /// Reloads the current page.
-  pub fn reload(&self) -> crate::Result<()> {
-    self.webview.reload()
+  /// Resumes a webview suspended with [`WebView::suspend`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn resume(&self) -> Result<()> {
+    self.webview.resume()
   }
 
-  /// Navigate to the specified url using the specified headers
-  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) -> Result<()> {
-    self.webview.load_url_with_headers(url, headers)
+  /// Mutes or unmutes the audio of the webview, media keep playing while muted.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Uses the private `_setPageMuted:` selector of `WKWebView`, does nothing
+  ///   if a WebKit version doesn't have it.
+  /// - **Windows**: Requires WebView2 Runtime version 1.0.1072.54 or higher.
+  /// - **Android**: Unsupported.
+  pub fn set_muted(&self, muted: bool) -> Result<()> {
+    self.webview.set_muted(muted)
   }
 
-  /// Load html content into the webview
-  pub fn load_html(&self, html: &str) -> Result<()> {
-    self.webview.load_html(html)
+  /// Whether the audio of the webview is muted, see [`WebView::set_muted`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Always `false` if the private `_mediaMutedState` selector of `WKWebView`
+  ///   is unavailable.
+  /// - **Android**: Unsupported, always `false`.
+  pub fn is_muted(&self) -> Result<bool> {
+    self.webview.is_muted()
   }
 
-  /// Clear all browsing data
-  pub fn clear_all_browsing_data(&self) -> Result<()> {
-    self.webview.clear_all_browsing_data()
+  /// Whether the webview is loading a page, from the start of a navigation until the page is
+  /// loaded or the navigation failed.
+  pub fn is_loading(&self) -> Result<bool> {
+    self.webview.is_loading()
   }
 
-  pub fn bounds(&self) -> Result<Rect> {
-    self.webview.bounds()
+  /// The memory used by the processes rendering the page of the webview.
+  ///
+  /// Returns [`Error::Unsupported`] where the platform doesn't tell which processes these are,
+  /// or before they were started.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: The physical footprint of the web content process, what Activity Monitor
+  ///   shows as its memory. Uses the private `_webProcessIdentifier` selector of `WKWebView`.
+  ///   Usually fails on iOS, which doesn't let apps inspect other processes.
+  /// - **Windows**: The working sets of the renderer processes hosting the frames of the page.
+  ///   Requires WebView2 Runtime version 1.0.2210.55 or higher.
+  /// - **Linux / Android**: Unsupported, WebKitGTK and the Android WebView don't expose their
+  ///   content processes.
+  pub fn memory_usage(&self) -> Result<MemoryUsage> {
+    self.webview.memory_usage()
   }
 
-  /// Set the webview bounds.
+  /// Sets the [memory usage target level][1] of the webview, [`MemoryUsageLevel::Low`] lets
+  /// the platform trim the memory of a webview in the background, e.g. a hidden tab.
   ///
-  /// This is only effective if the webview was created as a child
-  /// or created using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
-  pub fn set_bounds(&self, bounds: Rect) -> Result<()> {
-    self.webview.set_bounds(bounds)
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 114.0.1823.32 or higher. Setting
+  ///   [`MemoryUsageLevel::Low`] on a visible webview has no effect until it is hidden.
+  /// - **macOS / Linux / Android / iOS**: Unsupported, logs a warning and does nothing.
+  ///
+  /// [1]: https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2.memoryusagetargetlevel
+  pub fn set_memory_usage_level(&self, level: MemoryUsageLevel) -> Result<()> {
+    self.webview.set_memory_usage_level(level)
   }
 
-  /// Shows or hides the webview.
-  pub fn set_visible(&self, visible: bool) -> Result<()> {
-    self.webview.set_visible(visible)
+  /// The memory usage target level set with [`WebView::set_memory_usage_level`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 114.0.1823.32 or higher.
+  /// - **macOS / Linux / Android / iOS**: Unsupported, always [`MemoryUsageLevel::Normal`].
+  pub fn memory_usage_level(&self) -> Result<MemoryUsageLevel> {
+    self.webview.memory_usage_level()
   }
 
   /// Try moving focus to the webview.
@@ -2173,9 +4453,75 @@ impl WebView {
   pub fn focus_parent(&self) -> Result<()> {
     self.webview.focus_parent()
   }
+
+  /// Removes keyboard focus from the webview, e.g. after closing an in-page modal.
+  ///
+  /// Unlike [`WebView::focus_parent`], this doesn't give the focus to another view.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Moves the focus to the parent window, like [`WebView::focus_parent`].
+  /// - **Android**: Not implemented.
+  pub fn blur(&self) -> Result<()> {
+    self.webview.blur()
+  }
+
+  /// Replace the handler closure processing incoming [`DragDropEvent`] of the webview.
+  ///
+  /// The previous handler is dropped, but a drag operation that entered the webview
+  /// before this call keeps using it until it is dropped or leaves the webview.
+  /// The [`DragDropHandlerMode`] set on the builder still applies to the new handler.
+  ///
+  /// See [`WebViewBuilder::with_drag_drop_handler`] for the meaning of the returned value.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android / iOS**: Unsupported.
+  #[cfg(feature = "drag-drop")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "drag-drop")))]
+  pub fn set_drag_drop_handler<F, R>(&self, handler: F) -> Result<()>
+  where
+    F: Fn(DragDropEvent) -> R + 'static,
+    R: Into<DragOperation>,
+  {
+    self
+      .webview
+      .set_drag_drop_handler(Box::new(move |event| handler(event).into()))
+  }
+
+  /// Dispatch a synthetic [`DragDropEvent`] to the drag drop handler of the webview,
+  /// returning the [`DragOperation`] the handler picked.
+  ///
+  /// This is meant for automated tests that need to exercise drag and drop without a real OS drag session.
+  /// It only drives the wry side of the plumbing: the [`DragDropHandlerMode`] and the drag session tracking
+  /// of [`WebView::set_drag_drop_handler`] apply, but the event is delivered as is, so no position
+  /// conversion happens and the page doesn't receive any native HTML5 drag events.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android / iOS**: Unsupported, always returns [`DragOperation::Native`].
+  #[cfg(feature = "drag-drop-simulation")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "drag-drop-simulation")))]
+  pub fn simulate_drag_drop(&self, event: DragDropEvent) -> DragOperation {
+    self.webview.simulate_drag_drop(event)
+  }
 }
 
 /// An event describing drag and drop operations on the webview.
+///
+/// # Position
+///
+/// Positions are relative to the webview top-left corner. They are not scaled by the page zoom,
+/// so when the page is zoomed, e.g. by [`WebView::zoom`] or the hotkeys enabled with
+/// [`WebViewBuilder::with_hotkeys_zoom`], divide them by the zoom factor to get the
+/// `clientX`/`clientY` JavaScript would report.
+///
+/// ## Platform-specific
+///
+/// - **macOS / Linux**: Positions are in logical points, which match `clientX`/`clientY`
+///   on Retina and other scaled displays when the page isn't zoomed.
+/// - **Windows**: Positions are in physical pixels, divide them by the scale factor of the window
+///   to get logical points.
 #[non_exhaustive]
 #[derive(Debug, Clone)]
 pub enum DragDropEvent {
@@ -2185,11 +4531,19 @@ pub enum DragDropEvent {
     paths: Vec<PathBuf>,
     /// Position of the drag operation, relative to the webview top-left corner.
     position: (i32, i32),
+    /// The operations allowed by the source of the drag.
+    operations: DragOperations,
+    /// The data being dragged onto the webview.
+    data: DragData,
   },
   /// A drag operation is moving over the window.
   Over {
+    /// List of paths that are being dragged over the webview.
+    paths: Vec<PathBuf>,
     /// Position of the drag operation, relative to the webview top-left corner.
     position: (i32, i32),
+    /// The operations allowed by the source of the drag.
+    operations: DragOperations,
   },
   /// The file(s) have been dropped onto the window.
   Drop {
@@ -2200,30 +4554,279 @@ pub enum DragDropEvent {
   },
   /// The drag operation has been cancelled or left the window.
   Leave,
+  /// The drag operation ended without a drop on the webview, either because it left the webview
+  /// or because the drop was denied. Emitted right after [`DragDropEvent::Leave`],
+  /// so every drag session ends with exactly one [`DragDropEvent::Drop`] or [`DragDropEvent::Cancelled`].
+  Cancelled,
 }
 
-/// Get WebView/Webkit version on current platform.
-#[cfg(feature = "os-webview")]
-#[cfg_attr(docsrs, doc(cfg(feature = "os-webview")))]
-pub fn webview_version() -> Result<String> {
-  platform_webview_version()
+/// The data carried by a drag operation, see [`DragDropEvent::Enter`].
+///
+/// ## Platform-specific
+///
+/// - **macOS**: Formats are Uniform Type Identifiers read from the dragging pasteboard, e.g. `public.utf8-plain-text`.
+/// - **Linux**: Formats are the GTK targets of the received selection, e.g. `text/uri-list`.
+/// - **Windows**: Formats are the names of registered clipboard formats, e.g. `HTML Format`.
+#[derive(Debug, Clone, Default)]
+pub struct DragData {
+  pub(crate) text: Option<String>,
+  pub(crate) urls: Vec<String>,
+  pub(crate) raw: HashMap<String, Vec<u8>>,
+}
+
+impl DragData {
+  /// The plain text being dragged, if any.
+  pub fn text(&self) -> Option<&str> {
+    self.text.as_deref()
+  }
+
+  /// The URLs being dragged.
+  pub fn urls(&self) -> &[String] {
+    &self.urls
+  }
+
+  /// The raw bytes of the given platform specific `format`, if any.
+  pub fn raw(&self, format: &str) -> Option<Vec<u8>> {
+    self.raw.get(format).cloned()
+  }
+
+  /// The platform specific formats available through [`DragData::raw`].
+  pub fn formats(&self) -> impl Iterator<Item = &str> {
+    self.raw.keys().map(String::as_str)
+  }
 }
 
-/// The [memory usage target level][1]. There are two levels 'Low' and 'Normal' and the default
-/// level is 'Normal'. When the application is going inactive, setting the level to 'Low' can
-/// significantly reduce the application's memory consumption.
+/// The operation a drag drop handler performs for a [`DragDropEvent`].
 ///
-/// [1]: https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2memoryusagetargetlevel
-#[cfg(target_os = "windows")]
+/// Handlers returning a `bool` are still supported: `true` maps to [`DragOperation::Copy`]
+/// and `false` maps to [`DragOperation::Native`].
 #[non_exhaustive]
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum MemoryUsageLevel {
-  /// The 'Normal' memory usage. Applications should set this level when they are becoming active.
+pub enum DragOperation {
+  /// Don't handle the drag and let the OS' default behavior run.
   #[default]
-  Normal,
-  /// The 'Low' memory usage. Applications can reduce memory comsumption by setting this level when
-  /// they are becoming inactive.
-  Low,
+  Native,
+  /// Block the OS' default behavior and reject the drag.
+  None,
+  /// Block the OS' default behavior and copy the dragged data.
+  Copy,
+  /// Block the OS' default behavior and move the dragged data.
+  Move,
+  /// Block the OS' default behavior and link the dragged data.
+  Link,
+}
+
+impl From<bool> for DragOperation {
+  fn from(handled: bool) -> Self {
+    if handled {
+      Self::Copy
+    } else {
+      Self::Native
+    }
+  }
+}
+
+/// The set of [`DragOperation`]s the source of a drag allows.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DragOperations {
+  copy: bool,
+  move_: bool,
+  link: bool,
+}
+
+impl DragOperations {
+  #[allow(dead_code)]
+  pub(crate) fn new(copy: bool, move_: bool, link: bool) -> Self {
+    Self { copy, move_, link }
+  }
+
+  /// Whether the source of the drag allows `operation`.
+  ///
+  /// Always `false` for [`DragOperation::Native`] and [`DragOperation::None`].
+  pub fn contains(&self, operation: DragOperation) -> bool {
+    match operation {
+      DragOperation::Copy => self.copy,
+      DragOperation::Move => self.move_,
+      DragOperation::Link => self.link,
+      DragOperation::Native | DragOperation::None => false,
+    }
+  }
+}
+
+/// Describes how the drag drop handler set by [`WebViewBuilder::with_drag_drop_handler`]
+/// interacts with the native drag and drop handling of the webview.
+///
+/// Native handling is what makes the page receive HTML5 `dragover` and `drop` events,
+/// and what allows files to be dropped on `<input type="file">` elements. It's not possible
+/// to set the value of a `<input type="file">` via JavaScript for security reasons,
+/// so use [`DragDropHandlerMode::Observe`] if you need both the dropped paths and working file inputs.
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragDropHandlerMode {
+  /// The handler decides whether the OS' default behavior runs:
+  /// returning [`DragOperation::Native`] lets the webview handle the drag natively
+  /// and any other operation blocks it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: The page always receives the native `drop` event, the returned operation
+  ///   only applies while hovering.
+  /// - **Windows**: The drag is always forwarded to the page, the returned operation only affects
+  ///   the cursor effect.
+  #[default]
+  Intercept,
+  /// The handler is called for every event but its return value is ignored
+  /// and the drag is always handled natively by the webview.
+  Observe,
+  /// The handler is never called and the drag is always handled natively by the webview.
+  Passthrough,
+}
+
+impl DragDropHandlerMode {
+  /// Wraps `handler` so that backends can keep treating the returned operation
+  /// as the one to perform regardless of the mode.
+  #[cfg(any(target_os = "macos", target_os = "windows", gtk))]
+  pub(crate) fn wrap(
+    self,
+    handler: Box<dyn Fn(DragDropEvent) -> DragOperation>,
+  ) -> Box<dyn Fn(DragDropEvent) -> DragOperation> {
+    match self {
+      Self::Intercept => handler,
+      Self::Observe => Box::new(move |event| {
+        handler(event);
+        DragOperation::Native
+      }),
+      Self::Passthrough => Box::new(|_| DragOperation::Native),
+    }
+  }
+}
+
+/// A set of browsing data categories to clear with [`WebView::clear_browsing_data`],
+/// combined with `|`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BrowsingDataFlags(u32);
+
+impl BrowsingDataFlags {
+  /// Cookies.
+  pub const COOKIES: Self = Self(1 << 0);
+  /// `localStorage` and `sessionStorage`.
+  pub const LOCAL_STORAGE: Self = Self(1 << 1);
+  /// IndexedDB databases.
+  pub const INDEXED_DB: Self = Self(1 << 2);
+  /// The disk and memory HTTP caches.
+  pub const CACHE: Self = Self(1 << 3);
+  /// Service worker registrations.
+  pub const SERVICE_WORKERS: Self = Self(1 << 4);
+  /// WebSQL databases.
+  pub const WEB_SQL: Self = Self(1 << 5);
+  /// All the categories above.
+  pub const ALL: Self = Self((1 << 6) - 1);
+
+  /// No category.
+  pub const fn empty() -> Self {
+    Self(0)
+  }
+
+  /// Whether no category is set.
+  pub const fn is_empty(&self) -> bool {
+    self.0 == 0
+  }
+
+  /// Whether all the categories of `other` are set.
+  pub const fn contains(&self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl std::ops::BitOr for BrowsingDataFlags {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl std::ops::BitOrAssign for BrowsingDataFlags {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0;
+  }
+}
+
+/// The print job of [`WebView::print_silent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintSettings {
+  /// The name of the printer, the default printer if `None`.
+  pub printer: Option<String>,
+  /// The number of copies, defaults to `1`.
+  pub copies: u32,
+  /// The first and last pages to print, starting from `1`, all the pages if `None`.
+  pub page_range: Option<(u32, u32)>,
+}
+
+impl Default for PrintSettings {
+  fn default() -> Self {
+    Self {
+      printer: None,
+      copies: 1,
+      page_range: None,
+    }
+  }
+}
+
+/// The page layout of [`WebView::print_to_pdf`], all lengths are in inches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrintToPdfOptions {
+  /// The width of the page in portrait orientation, defaults to `8.5`.
+  pub page_width: f64,
+  /// The height of the page in portrait orientation, defaults to `11.0`.
+  pub page_height: f64,
+  /// The margins of the page, default to `0.4` on each side.
+  pub margins: PdfMargins,
+  /// Whether to lay the page out in landscape orientation, defaults to `false`.
+  pub landscape: bool,
+  /// Whether to print a header and footer with the title, URL and page numbers,
+  /// defaults to `false`.
+  pub header_and_footer: bool,
+  /// Whether to print the background colors and images, defaults to `false`.
+  pub print_background: bool,
+}
+
+impl Default for PrintToPdfOptions {
+  fn default() -> Self {
+    Self {
+      page_width: 8.5,
+      page_height: 11.0,
+      margins: PdfMargins {
+        top: 0.4,
+        right: 0.4,
+        bottom: 0.4,
+        left: 0.4,
+      },
+      landscape: false,
+      header_and_footer: false,
+      print_background: false,
+    }
+  }
+}
+
+/// The page margins of [`PrintToPdfOptions`], in inches.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PdfMargins {
+  /// The top margin.
+  pub top: f64,
+  /// The right margin.
+  pub right: f64,
+  /// The bottom margin.
+  pub bottom: f64,
+  /// The left margin.
+  pub left: f64,
+}
+
+/// Get WebView/Webkit version on current platform.
+#[cfg(feature = "os-webview")]
+#[cfg_attr(docsrs, doc(cfg(feature = "os-webview")))]
+pub fn webview_version() -> Result<String> {
+  platform_webview_version()
 }
 
 /// Additional methods on `WebView` that are specific to Windows.
@@ -2242,13 +4845,13 @@ pub trait WebViewExtWindows {
   /// Webview instance.
   fn webview(&self) -> ICoreWebView2;
 
-  /// Changes the webview2 theme.
+  /// Changes the webview2 theme, the same as [`WebView::set_theme`].
   ///
   /// Requires WebView2 Runtime version 101.0.1210.39 or higher, returns error on older versions,
   /// see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10121039
   fn set_theme(&self, theme: Theme) -> Result<()>;
 
-  /// Sets the [memory usage target level][1].
+  /// Sets the [memory usage target level][1], the same as [`WebView::set_memory_usage_level`].
   ///
   /// When to best use this mode depends on the app in question. Most commonly it's called when
   /// the app's visiblity state changes.
@@ -2263,6 +4866,13 @@ pub trait WebViewExtWindows {
   fn set_memory_usage_level(&self, level: MemoryUsageLevel) -> Result<()>;
 
   /// Attaches this webview to the given HWND and removes it from the current one.
+  ///
+  /// Webviews created with [`WebViewBuilder::build`] are resized to fill the new parent, child
+  /// webviews keep their logical bounds, so they are rescaled if the new parent is on a monitor
+  /// with a different scale factor.
+  ///
+  /// If WebView2 refuses the new parent window, the webview is moved back to its previous parent
+  /// and an error is returned.
   fn reparent(&self, hwnd: isize) -> Result<()>;
 }
 
@@ -2481,6 +5091,68 @@ pub enum PageLoadEvent {
   Finished,
 }
 
+/// A navigation that failed, see [`WebViewBuilder::with_on_navigation_error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationError {
+  /// The URL that failed to load.
+  pub url: String,
+  /// The platform error code.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: The `NSError` code, usually one of the `NSURLError` codes.
+  /// - **Linux**: The `GError` code, e.g. a `WebKitNetworkError` or `WebKitPolicyError`.
+  /// - **Windows**: The `COREWEBVIEW2_WEB_ERROR_STATUS`.
+  pub code: i64,
+  /// A description of the error.
+  pub message: String,
+  /// Whether the navigation failed before the new page was committed,
+  /// in which case the previous page is still displayed.
+  pub provisional: bool,
+}
+
+/// An entry of the navigation history of a webview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+  /// The URL of the entry.
+  pub url: String,
+  /// The title of the page, empty if it has none.
+  pub title: String,
+}
+
+/// The navigation history of a webview, see [`WebView::history_list`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+  /// The entries before the current one, from the oldest to the most recent.
+  pub back: Vec<HistoryEntry>,
+  /// The current entry, `None` before the first navigation.
+  pub current: Option<HistoryEntry>,
+  /// The entries after the current one, from the nearest to the farthest.
+  pub forward: Vec<HistoryEntry>,
+}
+
+/// A page asking to pick files for an `<input type="file">`, see
+/// [`WebViewBuilder::with_file_open_handler`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileChooserRequest {
+  /// The MIME types of the `accept` attribute, e.g. `image/png` or `image/*`.
+  pub mime_types: Vec<String>,
+  /// The file extensions of the `accept` attribute, without the leading dot.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Always empty, WebKitGTK only exposes the MIME types.
+  pub extensions: Vec<String>,
+  /// Whether several files can be picked, the `multiple` attribute.
+  pub multiple: bool,
+  /// Whether directories can be picked, the `webkitdirectory` attribute.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Always `false`.
+  pub directories: bool,
+}
+
 /// Background throttling policy
 #[derive(Debug, Clone)]
 pub enum BackgroundThrottlingPolicy {
@@ -2492,6 +5164,18 @@ pub enum BackgroundThrottlingPolicy {
   Throttle,
 }
 
+/// Which media can be played without user interaction, see
+/// [`WebViewBuilder::with_autoplay_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoplayPolicy {
+  /// All media can be played without user interaction.
+  AllowAll,
+  /// Media can only be played after the user interacted with the page.
+  RequireUserGesture,
+  /// Muted media can be played without user interaction, media with audio can't.
+  AllowMutedOnly,
+}
+
 /// An initialization script
 #[derive(Debug, Clone)]
 pub struct InitializationScript {
@@ -2509,12 +5193,70 @@ pub struct InitializationScript {
   ///
   /// [addDocumentStartJavaScript]: https://developer.android.com/reference/androidx/webkit/WebViewCompat#addDocumentStartJavaScript(android.webkit.WebView,java.lang.String,java.util.Set%3Cjava.lang.String%3E)
   pub for_main_frame_only: bool,
+  /// When the script runs, see [`InjectionTime`].
+  pub injection_time: InjectionTime,
+}
+
+/// When an [`InitializationScript`] runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InjectionTime {
+  /// Before the document is parsed, no element exists yet.
+  #[default]
+  DocumentStart,
+  /// Once the document is parsed, before its subresources like images are loaded.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: The script is wrapped in a function called on `DOMContentLoaded`,
+  ///   so its top-level `var`, `let`, `const` and function declarations are not globals.
+  DocumentEnd,
+}
+
+/// The id of a stylesheet added with [`WebView::add_user_stylesheet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StylesheetId(u64);
+
+impl StylesheetId {
+  fn next() -> Self {
+    static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    Self(NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+  }
+}
+
+impl std::fmt::Display for StylesheetId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.0.fmt(f)
+  }
+}
+
+/// The cascade origin of a stylesheet added with [`WebView::add_user_stylesheet`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StylesheetLevel {
+  /// Like a stylesheet of the user, overridden by the styles of the page.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS**: Unsupported, the stylesheet is injected like an
+  ///   [`StylesheetLevel::Author`] stylesheet.
+  #[default]
+  User,
+  /// Like a stylesheet of the page, appended after the stylesheets of the document so it
+  /// overrides the page styles with the same specificity.
+  Author,
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  #[cfg(not(target_os = "android"))]
+  fn build_as_child_raw_unsupported_handle() {
+    let handle = RawWindowHandle::Web(raw_window_handle::WebWindowHandle::new(1));
+    let result = unsafe { WebViewBuilder::new().build_as_child_raw(handle) };
+    assert!(matches!(result, Err(Error::UnsupportedWindowHandle)));
+  }
+
   #[test]
   #[cfg_attr(miri, ignore)]
   fn should_get_webview_version() {
@@ -2522,4 +5264,79 @@ mod tests {
       panic!("{}", error);
     }
   }
+
+  #[test]
+  fn initialization_scripts_main_frame_only() {
+    let builder = WebViewBuilder::new()
+      .with_initialization_script("window.main = true")
+      .with_initialization_script_for_main_only("window.all = true", false)
+      .with_initialization_script("");
+    let scripts = &builder.attrs.initialization_scripts;
+    assert_eq!(scripts.len(), 2);
+    assert!(scripts[0].for_main_frame_only);
+    assert!(!scripts[1].for_main_frame_only);
+    assert_eq!(scripts[0].injection_time, InjectionTime::DocumentStart);
+  }
+
+  #[test]
+  fn initialization_scripts_order() {
+    let scripts = [
+      "window.order = (window.order || []).concat(1)",
+      "window.order = (window.order || []).concat(2)",
+      "window.order = (window.order || []).concat(3)",
+    ];
+    let mut builder = WebViewBuilder::new()
+      .with_initialization_script(scripts[0])
+      .with_initialization_script_for_main_only(scripts[1], false)
+      .with_initialization_script_ext(scripts[2], true, InjectionTime::DocumentStart)
+      .with_clipboard_policy(ClipboardPolicy::read_only())
+      .with_service_workers(false);
+    builder.prepare();
+
+    // The scripts of wry run first, then the scripts of the app in the order they were added
+    let added: Vec<_> = builder
+      .attrs
+      .initialization_scripts
+      .iter()
+      .map(|script| script.script.as_str())
+      .collect();
+    let wry_scripts = added.len() - scripts.len();
+    assert!(wry_scripts > 0);
+    assert_eq!(added[wry_scripts..], scripts);
+  }
+
+  #[test]
+  fn rect_fractional_scale_factor() {
+    let rect = |x: f64, width: f64| Rect {
+      position: dpi::LogicalPosition::new(x, 20.25).into(),
+      size: dpi::LogicalSize::new(width, 50.5).into(),
+    };
+
+    let (position, size) = rect(10.5, 100.5).to_physical(1.5);
+    assert_eq!(position, dpi::PhysicalPosition::new(16, 30));
+    assert_eq!(size, dpi::PhysicalSize::new(151, 76));
+
+    // Adjacent rects share their edge instead of drifting apart.
+    let (left, left_size) = rect(0.0, 33.5).to_physical(1.5);
+    let (right, right_size) = rect(33.5, 33.5).to_physical(1.5);
+    assert_eq!(left.x + left_size.width, right.x);
+    assert_eq!(right.x + right_size.width, (67.0_f64 * 1.5).round() as i32);
+
+    // Physical rects round-trip.
+    let physical = Rect {
+      position: dpi::PhysicalPosition::new(15, 30).into(),
+      size: dpi::PhysicalSize::new(151, 76).into(),
+    };
+    assert_eq!(
+      physical.to_physical(1.5),
+      (
+        dpi::PhysicalPosition::new(15, 30),
+        dpi::PhysicalSize::new(151, 76)
+      )
+    );
+
+    let (position, size) = rect(10.5, 100.5).to_logical(1.5);
+    assert_eq!(position, dpi::LogicalPosition::new(11, 20));
+    assert_eq!(size, dpi::LogicalSize::new(100, 51));
+  }
 }
@@ -0,0 +1,32 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// The memory used by the content processes of a webview, returned by
+/// [`WebView::memory_usage`](crate::WebView::memory_usage).
+///
+/// Processes shared with other webviews are counted in full for each of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+  /// The memory currently used, in bytes.
+  pub resident: u64,
+  /// The most memory used since the processes started, in bytes.
+  pub peak: u64,
+}
+
+/// The [memory usage target level][1] of a webview, see
+/// [`WebView::set_memory_usage_level`](crate::WebView::set_memory_usage_level). When the
+/// application is going inactive, setting the level to `Low` can significantly reduce its memory
+/// consumption.
+///
+/// [1]: https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2memoryusagetargetlevel
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryUsageLevel {
+  /// The 'Normal' memory usage. Applications should set this level when they are becoming active.
+  #[default]
+  Normal,
+  /// The 'Low' memory usage. Applications can reduce memory comsumption by setting this level when
+  /// they are becoming inactive.
+  Low,
+}
@@ -0,0 +1,168 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use http::{
+  header::{CONNECTION, CONTENT_LENGTH, HOST, TRANSFER_ENCODING},
+  HeaderMap, HeaderName,
+};
+
+use crate::{NavigationDecision, NewWindowRequest, WebViewAttributes};
+
+/// Headers the platform webviews compute themselves when navigating,
+/// some of them ignore the ones we send and others fail the navigation.
+const RESTRICTED_HEADERS: [HeaderName; 4] = [HOST, CONTENT_LENGTH, CONNECTION, TRANSFER_ENCODING];
+
+/// The headers to send with a navigation request, with the same result on all platforms.
+///
+/// Restricted headers and values that aren't visible ASCII are dropped,
+/// and repeated headers are joined into a single comma-separated value.
+pub(crate) fn request_headers(headers: &HeaderMap) -> Vec<(&str, String)> {
+  headers
+    .keys()
+    .filter(|name| !RESTRICTED_HEADERS.contains(*name))
+    .filter_map(|name| {
+      let values = headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+      (!values.is_empty()).then(|| (name.as_str(), values.join(", ")))
+    })
+    .collect()
+}
+
+/// Turns the `bool` navigation handler into a [`NavigationDecision`] one,
+/// so the platforms only have to handle [`WebViewAttributes::navigation_handler_ext`].
+pub(crate) fn attach_navigation_handler(attrs: &mut WebViewAttributes) {
+  let Some(navigation_handler) = attrs.navigation_handler.take() else {
+    return;
+  };
+  if attrs.navigation_handler_ext.is_none() {
+    attrs.navigation_handler_ext = Some(Box::new(move |url| {
+      if navigation_handler(url) {
+        NavigationDecision::Allow
+      } else {
+        NavigationDecision::Deny
+      }
+    }));
+  }
+}
+
+/// Turns the new window handler taking the URL and features into a [`NewWindowRequest`] one,
+/// so the platforms only have to handle [`WebViewAttributes::new_window_request_handler`].
+pub(crate) fn attach_new_window_handler(attrs: &mut WebViewAttributes) {
+  let Some(new_window_req_handler) = attrs.new_window_req_handler.take() else {
+    return;
+  };
+  if attrs.new_window_request_handler.is_none() {
+    attrs.new_window_request_handler = Some(Box::new(move |request: NewWindowRequest| {
+      new_window_req_handler(request.url, request.features)
+    }));
+  }
+}
+
+/// An HTML page that submits `body`, an `application/x-www-form-urlencoded` form, to `url`.
+///
+/// Used where the webview can't navigate with a request body.
+#[cfg(gtk)]
+pub(crate) fn form_post_html(url: &str, body: &[u8]) -> String {
+  let decode = |s: &str| {
+    let s = s.replace('+', " ");
+    percent_encoding::percent_decode_str(&s)
+      .decode_utf8_lossy()
+      .into_owned()
+  };
+
+  let mut inputs = String::new();
+  for pair in String::from_utf8_lossy(body).split('&') {
+    if pair.is_empty() {
+      continue;
+    }
+    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+    inputs.push_str(&format!(
+      r#"<input type="hidden" name="{}" value="{}">"#,
+      escape_html(&decode(name)),
+      escape_html(&decode(value)),
+    ));
+  }
+
+  format!(
+    r#"<!DOCTYPE html><html><body><form id="wry-form-post" method="post" action="{}">{inputs}</form><script>document.getElementById("wry-form-post").submit();</script></body></html>"#,
+    escape_html(url)
+  )
+}
+
+#[cfg(any(gtk, debug_assertions))]
+pub(crate) fn escape_html(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('"', "&quot;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+  use http::header::{ACCEPT, AUTHORIZATION};
+
+  use super::*;
+
+  #[test]
+  fn navigation_request_headers() {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, "Bearer token".parse().unwrap());
+    headers.insert(HOST, "example.com".parse().unwrap());
+    headers.insert(CONTENT_LENGTH, "42".parse().unwrap());
+    headers.append(ACCEPT, "text/html".parse().unwrap());
+    headers.append(ACCEPT, "application/json".parse().unwrap());
+
+    let mut request_headers = request_headers(&headers);
+    request_headers.sort();
+    assert_eq!(
+      request_headers,
+      vec![
+        ("accept", "text/html, application/json".to_string()),
+        ("authorization", "Bearer token".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn navigation_handler_decisions() {
+    let mut attrs = WebViewAttributes {
+      navigation_handler: Some(Box::new(|url| url.starts_with("https://"))),
+      ..Default::default()
+    };
+    attach_navigation_handler(&mut attrs);
+    let handler = attrs.navigation_handler_ext.take().unwrap();
+    assert_eq!(
+      handler("https://tauri.app".into()),
+      NavigationDecision::Allow
+    );
+    assert_eq!(handler("http://tauri.app".into()), NavigationDecision::Deny);
+
+    attrs.navigation_handler = Some(Box::new(|_| false));
+    attrs.navigation_handler_ext = Some(Box::new(|_| {
+      NavigationDecision::Redirect("app://new".into())
+    }));
+    attach_navigation_handler(&mut attrs);
+    assert!(attrs.navigation_handler.is_none());
+    assert_eq!(
+      (attrs.navigation_handler_ext.unwrap())("app://old".into()),
+      NavigationDecision::Redirect("app://new".into())
+    );
+  }
+
+  #[test]
+  #[cfg(gtk)]
+  fn form_post_page() {
+    let html = form_post_html(
+      "https://sso.example/login?a=1&b=2",
+      b"user=a+b&token=%3C%22%3E&flag",
+    );
+    assert!(html.contains(r#"action="https://sso.example/login?a=1&amp;b=2""#));
+    assert!(html.contains(r#"name="user" value="a b""#));
+    assert!(html.contains(r#"name="token" value="&lt;&quot;&gt;""#));
+    assert!(html.contains(r#"name="flag" value="""#));
+  }
+}
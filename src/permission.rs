@@ -0,0 +1,117 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(any(target_os = "macos", target_os = "ios", gtk, test))]
+use std::{cell::RefCell, collections::HashSet};
+
+/// A permission a page requests, see [`PermissionRequest`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PermissionKind {
+  Camera,
+  Microphone,
+  /// The camera and the microphone at once, e.g. `getUserMedia({ audio: true, video: true })`.
+  CameraAndMicrophone,
+  Geolocation,
+  Notifications,
+  ClipboardRead,
+}
+
+/// A permission request of a page, passed to the handler of
+/// [`WebViewBuilder::with_permission_handler`](crate::WebViewBuilder::with_permission_handler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionRequest {
+  /// The requested permission.
+  pub kind: PermissionKind,
+  /// The origin of the page requesting it, e.g. `https://tauri.app`.
+  pub origin: String,
+}
+
+/// The answer to a [`PermissionRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionResponse {
+  /// Grant the permission, later requests of the same origin for it are granted without asking.
+  Allow,
+  /// Grant the permission for this request only.
+  AllowOnce,
+  /// Deny the permission.
+  Deny,
+}
+
+/// Remembers the permissions granted with [`PermissionResponse::Allow`] for the platforms that
+/// ask for each request.
+#[cfg(any(target_os = "macos", target_os = "ios", gtk, test))]
+pub(crate) struct PermissionHandler {
+  handler: Box<dyn Fn(PermissionRequest) -> PermissionResponse>,
+  allowed: RefCell<HashSet<(PermissionKind, String)>>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", gtk, test))]
+impl PermissionHandler {
+  pub(crate) fn new(handler: Box<dyn Fn(PermissionRequest) -> PermissionResponse>) -> Self {
+    Self {
+      handler,
+      allowed: Default::default(),
+    }
+  }
+
+  /// Whether to grant the permission of `request`.
+  pub(crate) fn is_allowed(&self, request: PermissionRequest) -> bool {
+    let key = (request.kind, request.origin.clone());
+    if self.allowed.borrow().contains(&key) {
+      return true;
+    }
+
+    match (self.handler)(request) {
+      PermissionResponse::Allow => {
+        self.allowed.borrow_mut().insert(key);
+        true
+      }
+      PermissionResponse::AllowOnce => true,
+      PermissionResponse::Deny => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use super::*;
+
+  #[test]
+  fn remembered_permissions() {
+    let asked = Rc::new(Cell::new(0));
+    let asked_ = asked.clone();
+    let handler = PermissionHandler::new(Box::new(move |request| {
+      asked_.set(asked_.get() + 1);
+      match request.kind {
+        PermissionKind::Camera => PermissionResponse::Allow,
+        PermissionKind::Microphone => PermissionResponse::AllowOnce,
+        _ => PermissionResponse::Deny,
+      }
+    }));
+    let request = |kind| PermissionRequest {
+      kind,
+      origin: "https://tauri.app".into(),
+    };
+
+    assert!(handler.is_allowed(request(PermissionKind::Camera)));
+    assert!(handler.is_allowed(request(PermissionKind::Camera)));
+    assert_eq!(asked.get(), 1);
+
+    assert!(handler.is_allowed(request(PermissionKind::Microphone)));
+    assert!(handler.is_allowed(request(PermissionKind::Microphone)));
+    assert_eq!(asked.get(), 3);
+
+    assert!(!handler.is_allowed(request(PermissionKind::Geolocation)));
+    assert_eq!(asked.get(), 4);
+
+    assert!(handler.is_allowed(PermissionRequest {
+      kind: PermissionKind::Camera,
+      origin: "https://evil.example".into(),
+    }));
+    assert_eq!(asked.get(), 5);
+  }
+}
@@ -1,9 +1,47 @@
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct ProxyEndpoint {
   /// Proxy server host (e.g. 192.168.0.100, localhost, example.com, etc.)
   pub host: String,
   /// Proxy server port (e.g. 1080, 3128, etc.)
   pub port: String,
+  /// Credentials used to authenticate with the proxy server, `None` if it doesn't require any.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Unsupported, returns [`Error::UnsupportedProxy`](crate::Error::UnsupportedProxy).
+  pub auth: Option<ProxyAuth>,
+}
+
+impl ProxyEndpoint {
+  /// An endpoint of the proxy server at `host` and `port` that doesn't require authentication.
+  pub fn new(host: impl Into<String>, port: impl Into<String>) -> Self {
+    Self {
+      host: host.into(),
+      port: port.into(),
+      auth: None,
+    }
+  }
+
+  /// Authenticates with the proxy server with `username` and `password`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Unsupported, returns [`Error::UnsupportedProxy`](crate::Error::UnsupportedProxy).
+  pub fn with_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+    self.auth = Some(ProxyAuth {
+      username: username.into(),
+      password: password.into(),
+    });
+    self
+  }
+}
+
+/// Username and password authentication for a proxy server.
+#[derive(Debug, Clone)]
+pub struct ProxyAuth {
+  pub username: String,
+  pub password: String,
 }
 
 #[derive(Debug, Clone)]
@@ -13,3 +51,21 @@ pub enum ProxyConfig {
   /// Connect to proxy server via SOCKSv5
   Socks5(ProxyEndpoint),
 }
+
+impl ProxyConfig {
+  /// The endpoint of the proxy server.
+  pub fn endpoint(&self) -> &ProxyEndpoint {
+    match self {
+      Self::Http(endpoint) | Self::Socks5(endpoint) => endpoint,
+    }
+  }
+
+  /// The URI scheme of the proxy server, `http` or `socks5`.
+  #[cfg(any(gtk, target_os = "windows"))]
+  pub(crate) fn scheme(&self) -> &'static str {
+    match self {
+      Self::Http(_) => "http",
+      Self::Socks5(_) => "socks5",
+    }
+  }
+}
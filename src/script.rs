@@ -0,0 +1,573 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  cell::RefCell,
+  fmt::Write,
+  sync::{Arc, Mutex},
+};
+
+use crate::WebViewAttributes;
+#[cfg(any(target_os = "windows", target_os = "android", test))]
+use crate::{InitializationScript, InjectionTime};
+#[cfg(feature = "serde")]
+use std::{
+  future::Future,
+  pin::Pin,
+  task::{Context, Poll, Waker},
+};
+
+/// The JSON passed to an evaluation callback when the script throws, `{"error": "<message>"}`.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn error_json(message: &str) -> String {
+  format!(r#"{{"error":{}}}"#, json_string(message))
+}
+
+/// Quotes and escapes `s` as a JSON string.
+pub(crate) fn json_string(s: &str) -> String {
+  let mut json = String::with_capacity(s.len() + 2);
+  json.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      '\r' => json.push_str("\\r"),
+      '\t' => json.push_str("\\t"),
+      c if (c as u32) < 0x20 => {
+        let _ = write!(json, "\\u{:04x}", c as u32);
+      }
+      c => json.push(c),
+    }
+  }
+  json.push('"');
+  json
+}
+
+/// Splits `s` in chunks of at most `size` bytes, at character boundaries.
+#[cfg(any(target_os = "windows", target_os = "android", feature = "serde", test))]
+pub(crate) fn chunks(s: &str, size: usize) -> Vec<&str> {
+  let mut chunks = Vec::new();
+  let mut rest = s;
+  while rest.len() > size {
+    let mut end = size;
+    while !rest.is_char_boundary(end) {
+      end -= 1;
+    }
+    let (chunk, after) = rest.split_at(end);
+    chunks.push(chunk);
+    rest = after;
+  }
+  chunks.push(rest);
+  chunks
+}
+
+/// The longest script evaluated at once, in bytes, on the platforms that fail to evaluate
+/// scripts of several megabytes.
+#[cfg(any(target_os = "windows", target_os = "android", test))]
+const MAX_SCRIPT_LEN: usize = 1024 * 1024;
+
+/// Splits scripts longer than [`MAX_SCRIPT_LEN`] in scripts storing the chunks of their source in
+/// the page, the last one evaluating it and returning its result. Shorter scripts are returned
+/// as is.
+///
+/// The source is evaluated with an indirect `eval`, which the Content Security Policy of the page
+/// may forbid.
+#[cfg(any(target_os = "windows", target_os = "android", test))]
+pub(crate) fn split_large_script(js: &str) -> Vec<std::borrow::Cow<'_, str>> {
+  static IDS: crate::util::Counter = crate::util::Counter::new();
+
+  if js.len() <= MAX_SCRIPT_LEN {
+    return vec![js.into()];
+  }
+
+  let id = IDS.next();
+  let chunks = chunks(js, MAX_SCRIPT_LEN / 2);
+  let last = chunks.len() - 1;
+  chunks
+    .into_iter()
+    .enumerate()
+    .map(|(i, chunk)| {
+      let chunk = json_string(chunk);
+      if i < last {
+        format!(
+          "(function (s) {{ var scripts = window.__WRY_SCRIPTS__ || (window.__WRY_SCRIPTS__ = {{}}); (scripts[{id}] || (scripts[{id}] = [])).push(s); }})({chunk});"
+        )
+      } else {
+        format!(
+          "(function (s) {{ var scripts = window.__WRY_SCRIPTS__ || {{}}; var parts = scripts[{id}] || []; delete scripts[{id}]; parts.push(s); return (0, eval)(parts.join('')); }})({chunk})"
+        )
+      }
+      .into()
+    })
+    .collect()
+}
+
+/// Parses the JSON string at the start of `json`, returning it with the rest of `json`.
+pub(crate) fn parse_json_string(json: &str) -> Option<(String, &str)> {
+  let json = json.strip_prefix('"')?;
+  let mut s = String::new();
+  let mut chars = json.char_indices();
+  while let Some((i, c)) = chars.next() {
+    match c {
+      '"' => return Some((s, &json[i + 1..])),
+      '\\' => s.push(match chars.next()?.1 {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'u' => {
+          let mut code = hex_code(&mut chars)?;
+          if (0xD800..0xDC00).contains(&code) {
+            // A surrogate pair, the low surrogate follows as another escape
+            if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' {
+              return None;
+            }
+            let low = hex_code(&mut chars)?
+              .checked_sub(0xDC00)
+              .filter(|low| *low < 0x400)?;
+            code = 0x10000 + ((code - 0xD800) << 10) + low;
+          }
+          char::from_u32(code)?
+        }
+        c => c,
+      }),
+      c => s.push(c),
+    }
+  }
+  None
+}
+
+/// Parses a JSON array of strings.
+pub(crate) fn parse_json_string_array(json: &str) -> Option<Vec<String>> {
+  let mut rest = json.trim().strip_prefix('[')?.trim_start();
+  let mut strings = Vec::new();
+  if let Some(end) = rest.strip_prefix(']') {
+    return end.trim().is_empty().then_some(strings);
+  }
+  loop {
+    let (s, after) = parse_json_string(rest)?;
+    strings.push(s);
+    let after = after.trim_start();
+    if let Some(end) = after.strip_prefix(']') {
+      return end.trim().is_empty().then_some(strings);
+    }
+    rest = after.strip_prefix(',')?.trim_start();
+  }
+}
+
+/// Reads the 4 hexadecimal digits of a `\u` escape.
+fn hex_code(chars: &mut impl Iterator<Item = (usize, char)>) -> Option<u32> {
+  (0..4).try_fold(0, |code, _| Some(code * 16 + chars.next()?.1.to_digit(16)?))
+}
+
+/// Injects `css` as a `<style>` element tagged with `id`, for the platforms without user stylesheets.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios", test))]
+pub(crate) fn stylesheet(id: &str, css: &str, main_frame_only: bool) -> String {
+  format!(
+    r#"(function () {{
+  if ({main_frame_only} && window !== window.top) return;
+  var style = document.createElement('style');
+  style.setAttribute('data-wry-stylesheet', {id});
+  style.textContent = {css};
+  var append = function () {{ (document.head || document.documentElement).appendChild(style); }};
+  if (document.documentElement) append();
+  else document.addEventListener('DOMContentLoaded', append, {{ once: true }});
+}})();"#,
+    id = json_string(id),
+    css = json_string(css),
+  )
+}
+
+/// Removes the `<style>` element injected by [`stylesheet`].
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios", test))]
+pub(crate) fn remove_stylesheet(id: &str) -> String {
+  format!(
+    "document.querySelectorAll('style[data-wry-stylesheet=' + JSON.stringify({}) + ']').forEach(function (style) {{ style.remove(); }});",
+    json_string(id)
+  )
+}
+
+/// Highlights the element at `(x, y)` and logs it to the console, so it can be revealed in the
+/// web inspector.
+#[cfg(any(debug_assertions, feature = "devtools"))]
+pub(crate) fn inspect_element_at(x: f64, y: f64) -> String {
+  format!(
+    r#"(function () {{
+  var element = document.elementFromPoint({x}, {y});
+  if (!element) return;
+  var rect = element.getBoundingClientRect();
+  var highlight = document.createElement('div');
+  highlight.style.cssText = 'position:fixed;pointer-events:none;z-index:2147483647;background:rgba(111,168,220,0.66);'
+    + 'left:' + rect.left + 'px;top:' + rect.top + 'px;width:' + rect.width + 'px;height:' + rect.height + 'px';
+  document.documentElement.appendChild(highlight);
+  setTimeout(function () {{ highlight.remove(); }}, 2000);
+  console.log(element);
+}})();"#
+  )
+}
+
+#[cfg(any(target_os = "windows", target_os = "android", test))]
+impl InitializationScript {
+  /// The script to inject when documents are created, for the platforms only able to do so.
+  ///
+  /// [`InjectionTime::DocumentEnd`] scripts are wrapped to wait for `DOMContentLoaded`.
+  pub(crate) fn into_document_start(self) -> Self {
+    if self.injection_time == InjectionTime::DocumentStart {
+      return self;
+    }
+
+    Self {
+      script: format!(
+        r#"(function () {{
+  var run = function () {{
+{}
+  }};
+  if (document.readyState === 'loading') {{
+    document.addEventListener('DOMContentLoaded', run, {{ once: true }});
+  }} else {{
+    run();
+  }}
+}})();"#,
+        self.script
+      ),
+      injection_time: InjectionTime::DocumentStart,
+      ..self
+    }
+  }
+}
+
+/// A script evaluated before the first page was committed, with its callback.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) type PendingScript = (String, Option<Box<dyn FnOnce(String) + Send>>);
+
+/// The scripts evaluated before the first page was committed, `None` once it was and the
+/// scripts run right away.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) type PendingScripts = Arc<Mutex<Option<Vec<PendingScript>>>>;
+
+/// The pending scripts of a new webview. Scripts are only deferred with
+/// [`WebViewAttributes::deferred_scripts`] and a page to load, they would wait forever otherwise.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn pending_scripts(attrs: &WebViewAttributes) -> PendingScripts {
+  let deferred = attrs.deferred_scripts && (attrs.url.is_some() || attrs.html.is_some());
+  Arc::new(Mutex::new(deferred.then(Vec::new)))
+}
+
+/// Defers `js` if the first page wasn't committed yet, returning its callback otherwise.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn defer_script<F: FnOnce(String) + Send + 'static>(
+  pending_scripts: &PendingScripts,
+  js: &str,
+  callback: Option<F>,
+) -> std::result::Result<(), Option<F>> {
+  match &mut *pending_scripts.lock().unwrap() {
+    Some(scripts) => {
+      scripts.push((
+        js.into(),
+        callback.map(|callback| Box::new(callback) as Box<dyn FnOnce(String) + Send>),
+      ));
+      Ok(())
+    }
+    None => Err(callback),
+  }
+}
+
+/// The initialization scripts added with [`WebView::add_init_script`](crate::WebView::add_init_script),
+/// in the order they were added, with the platform handle to remove them.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) struct InitScripts<T>(RefCell<Vec<(String, T)>>);
+
+#[cfg_attr(target_os = "android", allow(dead_code))]
+impl<T> InitScripts<T> {
+  pub(crate) fn new() -> Self {
+    Self(RefCell::new(Vec::new()))
+  }
+
+  /// Adds the script `id` last, returning the handle of the script it replaces.
+  pub(crate) fn insert(&self, id: &str, script: T) -> Option<T> {
+    let replaced = self.remove(id);
+    self.0.borrow_mut().push((id.to_string(), script));
+    replaced
+  }
+
+  /// Forgets the script `id`, returning its handle.
+  pub(crate) fn remove(&self, id: &str) -> Option<T> {
+    let mut scripts = self.0.borrow_mut();
+    let index = scripts.iter().position(|(id_, _)| id_ == id)?;
+    Some(scripts.remove(index).1)
+  }
+
+  pub(crate) fn ids(&self) -> Vec<String> {
+    self.0.borrow().iter().map(|(id, _)| id.clone()).collect()
+  }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Default)]
+struct ResultState {
+  result: Option<String>,
+  closed: bool,
+  waker: Option<Waker>,
+}
+
+/// Sends the result of an evaluation to a [`ResultReceiver`].
+///
+/// Dropping it without sending, e.g. because the webview was dropped
+/// before the script completed, resolves the receiver with `None`.
+#[cfg(feature = "serde")]
+pub(crate) struct ResultSender(Arc<Mutex<ResultState>>);
+
+/// A future that resolves to the result of an evaluation.
+#[cfg(feature = "serde")]
+pub(crate) struct ResultReceiver(Arc<Mutex<ResultState>>);
+
+#[cfg(feature = "serde")]
+pub(crate) fn result_channel() -> (ResultSender, ResultReceiver) {
+  let state = Arc::new(Mutex::new(ResultState::default()));
+  (ResultSender(state.clone()), ResultReceiver(state))
+}
+
+#[cfg(feature = "serde")]
+impl ResultSender {
+  pub(crate) fn send(&self, result: String) {
+    let mut state = self.0.lock().unwrap();
+    if state.result.is_none() {
+      state.result = Some(result);
+      if let Some(waker) = state.waker.take() {
+        waker.wake();
+      }
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl Drop for ResultSender {
+  fn drop(&mut self) {
+    let mut state = self.0.lock().unwrap();
+    state.closed = true;
+    if let Some(waker) = state.waker.take() {
+      waker.wake();
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl Future for ResultReceiver {
+  type Output = Option<String>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let mut state = self.0.lock().unwrap();
+    if let Some(result) = state.result.take() {
+      Poll::Ready(Some(result))
+    } else if state.closed {
+      Poll::Ready(None)
+    } else {
+      state.waker = Some(cx.waker().clone());
+      Poll::Pending
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn script_error_json() {
+    assert_eq!(
+      error_json("ReferenceError: \"foo\" is not defined\n"),
+      r#"{"error":"ReferenceError: \"foo\" is not defined\n"}"#
+    );
+    assert_eq!(json_string("\u{1}\\"), r#""\u0001\\""#);
+  }
+
+  #[test]
+  fn chunks_at_char_boundaries() {
+    assert_eq!(chunks("abc", 2), ["ab", "c"]);
+    assert_eq!(chunks("aé", 2), ["a", "é"]);
+    assert_eq!(chunks("", 2), [""]);
+  }
+
+  #[test]
+  fn split_large_scripts() {
+    assert_eq!(split_large_script("window.a = 1"), ["window.a = 1"]);
+
+    // A 5MB script setting a global
+    let js = format!("window.large = '{}';", "x".repeat(5 * 1024 * 1024));
+    let scripts = split_large_script(&js);
+    assert_eq!(scripts.len(), 11);
+    assert!(scripts.iter().all(|script| script.len() < MAX_SCRIPT_LEN));
+    let (last, stored) = scripts.split_last().unwrap();
+    assert!(last.contains("return (0, eval)(parts.join(''));"));
+    let source: String = stored
+      .iter()
+      .chain([last])
+      .map(|script| {
+        let (_, chunk) = script.rsplit_once(")(").unwrap();
+        let chunk = chunk.trim_end_matches(';').strip_suffix(')').unwrap();
+        parse_json_string(chunk).unwrap().0
+      })
+      .collect();
+    assert_eq!(source, js);
+  }
+
+  #[test]
+  fn parse_json_strings() {
+    assert_eq!(
+      parse_json_string(r#""a\"b\\c\/d\n","#),
+      Some(("a\"b\\c/d\n".to_string(), ","))
+    );
+    assert_eq!(
+      parse_json_string(r#""\u00e9\ud83e\udd80""#),
+      Some(("\u{e9}\u{1f980}".to_string(), ""))
+    );
+    assert_eq!(
+      parse_json_string(&json_string("wry\t\u{1}")).unwrap().0,
+      "wry\t\u{1}"
+    );
+    assert_eq!(parse_json_string(r#""\ud83e""#), None);
+    assert_eq!(parse_json_string(r#""unterminated"#), None);
+    assert_eq!(parse_json_string("null"), None);
+
+    assert_eq!(
+      parse_json_string_array(r#"[ "a", "b\"]" ]"#),
+      Some(vec!["a".to_string(), "b\"]".to_string()])
+    );
+    assert_eq!(parse_json_string_array("[]"), Some(Vec::new()));
+    assert_eq!(parse_json_string_array(r#"["a",]"#), None);
+    assert_eq!(parse_json_string_array(r#"["a", 1]"#), None);
+  }
+
+  #[test]
+  fn deferred_scripts() {
+    let attrs = WebViewAttributes {
+      url: Some("https://tauri.app".into()),
+      ..Default::default()
+    };
+    let pending = pending_scripts(&attrs);
+    let (tx, rx) = std::sync::mpsc::channel();
+    assert!(defer_script(
+      &pending,
+      "1 + 1",
+      Some(move |result| tx.send(result).unwrap())
+    )
+    .is_ok());
+    assert!(defer_script(&pending, "window.a = 1", None::<fn(String)>).is_ok());
+
+    // The first page was committed
+    let scripts = pending.lock().unwrap().take().unwrap();
+    let ran: Vec<_> = scripts
+      .into_iter()
+      .map(|(script, callback)| {
+        if let Some(callback) = callback {
+          callback("2".into());
+        }
+        script
+      })
+      .collect();
+    assert_eq!(ran, ["1 + 1", "window.a = 1"]);
+    assert_eq!(rx.try_recv().as_deref(), Ok("2"));
+    assert!(defer_script(&pending, "window.b = 1", None::<fn(String)>).is_err());
+
+    // Without a page or with the opt-out, scripts run right away
+    assert!(pending_scripts(&WebViewAttributes::default())
+      .lock()
+      .unwrap()
+      .is_none());
+    let attrs = WebViewAttributes {
+      deferred_scripts: false,
+      ..attrs
+    };
+    assert!(pending_scripts(&attrs).lock().unwrap().is_none());
+  }
+
+  #[test]
+  fn init_script_document_end() {
+    let script = InitializationScript {
+      script: "console.log(document.body)".into(),
+      for_main_frame_only: true,
+      injection_time: InjectionTime::DocumentStart,
+    };
+    assert_eq!(script.clone().into_document_start().script, script.script);
+
+    let script = InitializationScript {
+      injection_time: InjectionTime::DocumentEnd,
+      ..script
+    }
+    .into_document_start();
+    assert_eq!(script.injection_time, InjectionTime::DocumentStart);
+    assert!(script.for_main_frame_only);
+  }
+
+  #[test]
+  fn stylesheet_escapes_css() {
+    let id = "7\"]";
+    let css = "body::after { content: \"</style>\\\\\" }\n";
+    let script = stylesheet(id, css, true);
+    let (_, script_id) = script.split_once("'data-wry-stylesheet', ").unwrap();
+    assert_eq!(parse_json_string(script_id).unwrap().0, id);
+    let (_, script_css) = script.split_once("style.textContent = ").unwrap();
+    assert_eq!(parse_json_string(script_css).unwrap().0, css);
+
+    let script = remove_stylesheet(id);
+    let (_, script_id) = script.split_once("JSON.stringify(").unwrap();
+    assert_eq!(parse_json_string(script_id).unwrap().0, id);
+  }
+
+  #[test]
+  fn init_scripts_replace() {
+    let scripts = InitScripts::new();
+    assert_eq!(scripts.insert("a", 1), None);
+    assert_eq!(scripts.insert("b", 2), None);
+    assert_eq!(scripts.insert("a", 3), Some(1));
+    assert_eq!(scripts.ids(), ["b", "a"]);
+    assert_eq!(scripts.remove("b"), Some(2));
+    assert_eq!(scripts.remove("b"), None);
+    assert_eq!(scripts.ids(), ["a"]);
+  }
+
+  #[test]
+  fn init_scripts_order() {
+    // The scripts of `WebView::add_init_script` run in the order they were added
+    let scripts = InitScripts::new();
+    scripts.insert("c", ());
+    scripts.insert("a", ());
+    scripts.insert("b", ());
+    assert_eq!(scripts.ids(), ["c", "a", "b"]);
+
+    // Replacing or removing a script keeps the order of the others
+    scripts.insert("c", ());
+    scripts.remove("a");
+    scripts.insert("d", ());
+    assert_eq!(scripts.ids(), ["b", "c", "d"]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn result_channel_closed() {
+    use std::task::Wake;
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+      fn wake(self: Arc<Self>) {}
+    }
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+
+    let (sender, mut receiver) = result_channel();
+    assert_eq!(Pin::new(&mut receiver).poll(&mut cx), Poll::Pending);
+    sender.send("42".into());
+    drop(sender);
+    assert_eq!(
+      Pin::new(&mut receiver).poll(&mut cx),
+      Poll::Ready(Some("42".into()))
+    );
+
+    let (sender, mut receiver) = result_channel();
+    drop(sender);
+    assert_eq!(Pin::new(&mut receiver).poll(&mut cx), Poll::Ready(None));
+  }
+}
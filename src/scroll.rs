@@ -0,0 +1,49 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::script::parse_json_string_array;
+
+/// Returns `[scrollX, scrollY]` of the top document.
+pub(crate) const SCROLL_POSITION: &str =
+  "(function () { return [String(window.scrollX), String(window.scrollY)]; })()";
+
+/// Scrolls the top document to `x`, `y` right away, even if it scrolls smoothly otherwise.
+pub(crate) fn scroll_to(x: f64, y: f64) -> String {
+  format!(
+    "window.scrollTo({{ left: {}, top: {}, behavior: 'instant' }})",
+    js_number(x),
+    js_number(y)
+  )
+}
+
+/// Formats `n` as a JavaScript number, Rust formats infinities as `inf`.
+fn js_number(n: f64) -> String {
+  match n {
+    f64::INFINITY => "Infinity".into(),
+    f64::NEG_INFINITY => "-Infinity".into(),
+    n => n.to_string(),
+  }
+}
+
+/// Parses the result of [`SCROLL_POSITION`].
+pub(crate) fn parse(json: &str) -> Option<(f64, f64)> {
+  let [x, y] = <[String; 2]>::try_from(parse_json_string_array(json)?).ok()?;
+  Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn scroll_scripts() {
+    assert_eq!(parse(r#"["0","1520.5"]"#), Some((0.0, 1520.5)));
+    assert_eq!(parse(r#"["0"]"#), None);
+    assert_eq!(parse(r#"{"error":"ReferenceError"}"#), None);
+    assert_eq!(
+      scroll_to(10.5, f64::INFINITY),
+      "window.scrollTo({ left: 10.5, top: Infinity, behavior: 'instant' })"
+    );
+  }
+}
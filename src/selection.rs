@@ -0,0 +1,72 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::script::parse_json_string_array;
+
+/// The selection of a page, passed to the callback of
+/// [`WebView::selected_text`](crate::WebView::selected_text).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+  /// The selected text, as returned by `window.getSelection().toString()`.
+  pub text: String,
+  /// The selected content as HTML, empty for selections in text fields.
+  pub html: String,
+}
+
+/// Returns `[text, html]`, preferring the selection of the focused text field.
+pub(crate) const SELECTED_TEXT: &str = r#"(function () {
+  var field = document.activeElement;
+  if (field && (field.tagName === 'TEXTAREA' || field.tagName === 'INPUT')) {
+    try {
+      if (field.selectionStart !== field.selectionEnd) {
+        return [field.value.substring(field.selectionStart, field.selectionEnd), ''];
+      }
+    } catch (e) {}
+  }
+  var selection = window.getSelection();
+  if (!selection || !selection.rangeCount) return ['', ''];
+  var container = document.createElement('div');
+  for (var i = 0; i < selection.rangeCount; i++) {
+    container.appendChild(selection.getRangeAt(i).cloneContents());
+  }
+  return [selection.toString(), container.innerHTML];
+})()"#;
+
+#[cfg(any(target_os = "windows", target_os = "android", target_os = "ios"))]
+pub(crate) const SELECT_ALL: &str = "document.execCommand('selectAll')";
+
+pub(crate) const CLEAR_SELECTION: &str = r#"(function () {
+  var field = document.activeElement;
+  if (field && (field.tagName === 'TEXTAREA' || field.tagName === 'INPUT')) {
+    try {
+      field.setSelectionRange(field.selectionEnd, field.selectionEnd);
+    } catch (e) {}
+  }
+  var selection = window.getSelection();
+  if (selection) selection.removeAllRanges();
+})()"#;
+
+/// Parses the result of [`SELECTED_TEXT`].
+pub(crate) fn parse(json: &str) -> Option<Selection> {
+  let [text, html] = <[String; 2]>::try_from(parse_json_string_array(json)?).ok()?;
+  Some(Selection { text, html })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_selection() {
+    assert_eq!(
+      parse(r#"["wry \"webview\"","<b>wry</b> \"webview\""]"#),
+      Some(Selection {
+        text: "wry \"webview\"".into(),
+        html: "<b>wry</b> \"webview\"".into(),
+      })
+    );
+    assert_eq!(parse(r#"[ "", "" ]"#), Some(Selection::default()));
+    assert_eq!(parse(r#"{"error":"ReferenceError"}"#), None);
+  }
+}
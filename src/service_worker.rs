@@ -0,0 +1,112 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use http::Request;
+
+use crate::{InitializationScript, WebViewAttributes};
+
+/// The prefix of the messages posted when a service worker is registered, followed by
+/// `<scope>\n<script url>`.
+const REGISTERED_PREFIX: &str = "__wry_service_worker__:";
+
+/// A service worker registered by a page, passed to the handler of
+/// [`WebViewBuilder::with_service_worker_handler`](crate::WebViewBuilder::with_service_worker_handler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceWorkerRegistration {
+  /// The scope of the registration, e.g. `https://tauri.app/`.
+  pub scope: String,
+  /// The absolute URL of the service worker script.
+  pub script_url: String,
+}
+
+/// Disables service workers or reports their registrations with a script wrapping
+/// `navigator.serviceWorker.register`.
+pub(crate) fn attach_service_worker_handler(attrs: &mut WebViewAttributes) {
+  if attrs.service_workers == Some(false) {
+    attrs.service_worker_handler = None;
+    attrs.initialization_scripts.insert(
+      0,
+      InitializationScript {
+        script: "delete Navigator.prototype.serviceWorker;".into(),
+        for_main_frame_only: false,
+        injection_time: Default::default(),
+      },
+    );
+    return;
+  }
+
+  let Some(handler) = attrs.service_worker_handler.take() else {
+    return;
+  };
+
+  let ipc_handler = attrs.ipc_handler.take();
+  attrs.ipc_handler = Some(Box::new(
+    move |request: Request<String>| match parse_registration(request.body()) {
+      Some(registration) => handler(registration),
+      None => {
+        if let Some(ipc_handler) = &ipc_handler {
+          ipc_handler(request);
+        }
+      }
+    },
+  ));
+
+  attrs.initialization_scripts.insert(
+    0,
+    InitializationScript {
+      script: script(),
+      for_main_frame_only: true,
+      injection_time: Default::default(),
+    },
+  );
+}
+
+fn parse_registration(message: &str) -> Option<ServiceWorkerRegistration> {
+  let (scope, script_url) = message.strip_prefix(REGISTERED_PREFIX)?.split_once('\n')?;
+  Some(ServiceWorkerRegistration {
+    scope: scope.to_string(),
+    script_url: script_url.to_string(),
+  })
+}
+
+fn script() -> String {
+  format!(
+    r#"(function () {{
+  var container = window.ServiceWorkerContainer && ServiceWorkerContainer.prototype;
+  if (!container) return;
+  var register = container.register;
+  container.register = function (scriptURL) {{
+    var url = new URL(String(scriptURL), window.location.href).href;
+    return register.apply(this, arguments).then(function (registration) {{
+      if (window.ipc) window.ipc.postMessage('{REGISTERED_PREFIX}' + registration.scope + '\n' + url);
+      return registration;
+    }});
+  }};
+}})();"#
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn registration_messages() {
+    assert_eq!(
+      parse_registration("__wry_service_worker__:app://localhost/\napp://localhost/sw.js"),
+      Some(ServiceWorkerRegistration {
+        scope: "app://localhost/".into(),
+        script_url: "app://localhost/sw.js".into(),
+      })
+    );
+    assert_eq!(
+      parse_registration("app://localhost/\napp://localhost/sw.js"),
+      None
+    );
+    assert_eq!(
+      parse_registration("__wry_service_worker__:app://localhost/"),
+      None
+    );
+  }
+}
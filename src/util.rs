@@ -11,3 +11,69 @@ impl Counter {
     self.0.fetch_add(1, Ordering::Relaxed)
   }
 }
+
+/// A unique path in the temporary directory, for documents the webviews only write to files.
+#[cfg(any(gtk, target_os = "windows"))]
+pub fn temp_file_path(extension: &str) -> std::path::PathBuf {
+  static COUNTER: Counter = Counter::new();
+  std::env::temp_dir().join(format!(
+    "wry-{}-{}.{extension}",
+    std::process::id(),
+    COUNTER.next()
+  ))
+}
+
+/// Inserts a `<base>` element at the start of the `<head>` of `html`, or after its doctype if it
+/// has no head, so its relative URLs resolve against `base_url`.
+#[cfg(any(target_os = "windows", test))]
+pub fn html_with_base_url(html: &str, base_url: &str) -> String {
+  let lowercase = html.to_ascii_lowercase();
+  let end_of_tag = |start: usize| lowercase[start..].find('>').map(|end| start + end + 1);
+  let position = lowercase
+    .match_indices("<head")
+    .find(|&(start, tag)| {
+      lowercase[start + tag.len()..].starts_with(|c: char| c == '>' || c.is_ascii_whitespace())
+    })
+    .and_then(|(start, _)| end_of_tag(start))
+    .or_else(|| lowercase.find("<!doctype").and_then(end_of_tag))
+    .unwrap_or(0);
+
+  let mut html = html.to_string();
+  html.insert_str(
+    position,
+    &format!(r#"<base href="{}">"#, base_url.replace('"', "&quot;")),
+  );
+  html
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn base_url_element() {
+    let base = r#"<base href="app://localhost/">"#;
+    assert_eq!(
+      html_with_base_url(
+        "<html><head><title>Wry</title></head></html>",
+        "app://localhost/"
+      ),
+      format!("<html><head>{base}<title>Wry</title></head></html>")
+    );
+    assert_eq!(
+      html_with_base_url(
+        "<!DOCTYPE html><HEAD lang=\"en\"><header></header>",
+        "app://localhost/"
+      ),
+      format!("<!DOCTYPE html><HEAD lang=\"en\">{base}<header></header>")
+    );
+    assert_eq!(
+      html_with_base_url("<!doctype html><header></header>", "app://localhost/"),
+      format!("<!doctype html>{base}<header></header>")
+    );
+    assert_eq!(
+      html_with_base_url("<img src=\"foo.png\">", "app://localhost/"),
+      format!("{base}<img src=\"foo.png\">")
+    );
+  }
+}
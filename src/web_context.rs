@@ -36,6 +36,14 @@ impl WebContext {
   /// `data_directory`:
   /// * Whether the WebView window should have a custom user data path. This is useful in Windows
   ///   when a bundled application can't have the webview data inside `Program Files`.
+  ///   Webviews of contexts with different data directories don't share cookies, storage or caches.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: WebKit picks where the data is stored, each data directory maps to its own
+  ///   persistent `WKWebsiteDataStore` instead. Requires macOS 14 or iOS 17, older systems log a
+  ///   warning and use the default data store. A data store identifier set with
+  ///   `WebViewBuilderExtDarwin::with_data_store_identifier` takes precedence.
   pub fn new(data_directory: Option<PathBuf>) -> Self {
     Self {
       os: WebContextImpl::new(data_directory.as_deref()),
@@ -85,6 +93,54 @@ impl WebContext {
   pub fn set_allows_automation(&mut self, flag: bool) {
     self.os.set_allows_automation(flag);
   }
+
+  /// Sets whether the webviews created with this context afterwards get their own web content
+  /// processes. Default is `false`, the webviews of a context share a process pool, which saves
+  /// memory when many of them are open.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Before macOS 12 and iOS 15 webviews of different process pools never share
+  ///   a web content process. Later versions ignore process pools and share processes as WebKit
+  ///   sees fit, use contexts with different data directories to keep webviews apart there.
+  ///   Ignored for webviews built with `WebViewBuilderExtMacos::with_webview_configuration`.
+  /// - **Linux / Windows / Android**: Unsupported.
+  pub fn set_isolated_processes(&mut self, isolated: bool) {
+    self.os.set_isolated_processes(isolated);
+  }
+
+  /// Compiles [content blocking rules] and applies them to the webviews created with this context
+  /// afterwards, e.g. to block ads or trackers.
+  ///
+  /// `json` is a WebKit content blocker JSON array. Adding rules with an `identifier` that was
+  /// already added replaces them.
+  ///
+  /// Returns an error if the rules fail to compile.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Must be called on the main thread.
+  /// - **Linux / Windows / Android**: Unsupported, logs a warning and does nothing.
+  ///
+  /// [content blocking rules]: https://developer.apple.com/documentation/safariservices/creating-a-content-blocker
+  pub fn add_content_rules(&mut self, identifier: &str, json: &str) -> crate::Result<()> {
+    self.os.add_content_rules(identifier, json)
+  }
+
+  /// Shares `controller` between the webviews created with this context afterwards, so their
+  /// initialization scripts and script message handlers are added to it once instead of for each
+  /// webview. IPC messages are still passed to the handler of the webview that posted them.
+  ///
+  /// The scripts added to one of the webviews, e.g. with
+  /// [`WebView::add_init_script`](crate::WebView::add_init_script), run in all of them. A
+  /// controller set with `WebViewBuilderExtDarwin::with_user_content_controller` takes precedence.
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub fn set_user_content_controller(
+    &mut self,
+    controller: Option<objc2::rc::Retained<objc2_web_kit::WKUserContentController>>,
+  ) {
+    self.os.user_content_controller = controller;
+  }
 }
 
 impl Default for WebContext {
@@ -93,15 +149,95 @@ impl Default for WebContext {
   }
 }
 
+/// A stable identifier for the `WKWebsiteDataStore` of a data directory, the 128-bit FNV-1a hash
+/// of its path formatted as a version 8 UUID, so the same directory reopens the same store.
+#[cfg(any(target_os = "macos", target_os = "ios", test))]
+pub(crate) fn data_store_identifier(data_directory: &Path) -> [u8; 16] {
+  const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+  const PRIME: u128 = 0x0000000001000000000000000000013B;
+
+  let mut hash = OFFSET_BASIS;
+  for byte in data_directory.to_string_lossy().bytes() {
+    hash ^= byte as u128;
+    hash = hash.wrapping_mul(PRIME);
+  }
+
+  let mut identifier = hash.to_be_bytes();
+  identifier[6] = (identifier[6] & 0x0f) | 0x80;
+  identifier[8] = (identifier[8] & 0x3f) | 0x80;
+  identifier
+}
+
 #[cfg(not(gtk))]
-#[derive(Debug)]
-pub(crate) struct WebContextImpl;
+#[derive(Debug, Default)]
+pub(crate) struct WebContextImpl {
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub(crate) content_rule_lists: Vec<(
+    String,
+    objc2::rc::Retained<objc2_web_kit::WKContentRuleList>,
+  )>,
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub(crate) user_content_controller:
+    Option<objc2::rc::Retained<objc2_web_kit::WKUserContentController>>,
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub(crate) process_pool: Option<objc2::rc::Retained<objc2_web_kit::WKProcessPool>>,
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub(crate) isolated_processes: bool,
+}
 
 #[cfg(not(gtk))]
 impl WebContextImpl {
   fn new(_: Option<&Path>) -> Self {
-    Self
+    Self::default()
   }
 
   fn set_allows_automation(&mut self, _flag: bool) {}
+
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  fn set_isolated_processes(&mut self, isolated: bool) {
+    self.isolated_processes = isolated;
+    if isolated {
+      self.process_pool = None;
+    }
+  }
+
+  #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+  fn set_isolated_processes(&mut self, _isolated: bool) {}
+
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  fn add_content_rules(&mut self, identifier: &str, json: &str) -> crate::Result<()> {
+    let rule_list = crate::wkwebview::InnerWebView::compile_content_rule_list(identifier, json)?;
+    match self
+      .content_rule_lists
+      .iter_mut()
+      .find(|(id, _)| id == identifier)
+    {
+      Some((_, existing)) => *existing = rule_list,
+      None => self
+        .content_rule_lists
+        .push((identifier.to_string(), rule_list)),
+    }
+    Ok(())
+  }
+
+  #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+  fn add_content_rules(&mut self, _identifier: &str, _json: &str) -> crate::Result<()> {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("Content rules '{_identifier}' ignored, content blocking is unsupported");
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn data_store_identifiers() {
+    let a = data_store_identifier(Path::new("/tmp/profile-a"));
+    assert_eq!(a, data_store_identifier(Path::new("/tmp/profile-a")));
+    assert_ne!(a, data_store_identifier(Path::new("/tmp/profile-b")));
+    assert_eq!(a[6] >> 4, 8);
+    assert_eq!(a[8] >> 6, 0b10);
+  }
 }
@@ -3,15 +3,20 @@
 // SPDX-License-Identifier: MIT
 
 use std::{
-  cell::{Cell, UnsafeCell},
+  cell::{Cell, RefCell, UnsafeCell},
   path::PathBuf,
   rc::Rc,
 };
 
-use gtk::{glib::GString, prelude::*};
+use gtk::{
+  gdk::{DragAction, DragContext},
+  glib::GString,
+  prelude::*,
+  SelectionData,
+};
 use webkit2gtk::WebView;
 
-use crate::DragDropEvent;
+use crate::{DragData, DragDropEvent, DragOperation, DragOperations};
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
 enum DragControllerState {
@@ -24,16 +29,19 @@ struct DragDropController {
   paths: UnsafeCell<Option<Vec<PathBuf>>>,
   state: Cell<DragControllerState>,
   position: Cell<(i32, i32)>,
-  handler: Box<dyn Fn(DragDropEvent) -> bool>,
+  // The data received for the drag entering the webview, until `Enter` is emitted
+  pending: RefCell<Option<(Vec<PathBuf>, DragData)>>,
+  handler: Box<dyn Fn(DragDropEvent) -> DragOperation>,
 }
 
 impl DragDropController {
-  fn new(handler: Box<dyn Fn(DragDropEvent) -> bool>) -> Self {
+  fn new(handler: Box<dyn Fn(DragDropEvent) -> DragOperation>) -> Self {
     Self {
       handler,
       paths: UnsafeCell::new(None),
       state: Cell::new(DragControllerState::Left),
       position: Cell::new((0, 0)),
+      pending: RefCell::new(None),
     }
   }
 
@@ -45,6 +53,10 @@ impl DragDropController {
     unsafe { &mut *self.paths.get() }.take()
   }
 
+  fn cloned_paths(&self) -> Vec<PathBuf> {
+    unsafe { &*self.paths.get() }.clone().unwrap_or_default()
+  }
+
   fn store_position(&self, position: (i32, i32)) {
     self.position.replace(position);
   }
@@ -65,35 +77,71 @@ impl DragDropController {
     self.state.get()
   }
 
-  fn call(&self, event: DragDropEvent) -> bool {
+  fn call(&self, event: DragDropEvent) -> DragOperation {
     (self.handler)(event)
   }
 }
 
-pub(crate) fn connect_drag_event(webview: &WebView, handler: Box<dyn Fn(DragDropEvent) -> bool>) {
+pub(crate) fn connect_drag_event(
+  webview: &WebView,
+  handler: Box<dyn Fn(DragDropEvent) -> DragOperation>,
+) {
   let controller = Rc::new(DragDropController::new(handler));
 
+  // WebKit accepts the text and uri targets already, add them in case its list changes
+  if let Some(targets) = webview.drag_dest_get_target_list() {
+    targets.add_text_targets(0);
+    targets.add_uri_targets(0);
+  }
+
   {
     let controller = controller.clone();
-    webview.connect_drag_data_received(move |_, _, _, _, data, info, _| {
-      if info == 2 {
-        let uris = data.uris();
-        let paths = uris.iter().map(path_buf_from_uri).collect::<Vec<_>>();
-        controller.enter();
-        controller.call(DragDropEvent::Enter {
-          paths: paths.clone(),
-          position: controller.position.get(),
+    webview.connect_drag_data_received(move |_, ctx, _, _, data, _, _| {
+      if controller.state() == DragControllerState::Entered {
+        return;
+      }
+
+      // WebKit asks for each of its targets, `Enter` is emitted with all of them once the
+      // pending transfers were handled
+      let mut pending = controller.pending.borrow_mut();
+      let schedule_enter = pending.is_none();
+      let (paths, drag_data) = pending.get_or_insert_with(Default::default);
+      add_selection_data(paths, drag_data, data);
+      drop(pending);
+
+      if schedule_enter {
+        let controller = controller.clone();
+        let ctx = ctx.clone();
+        gtk::glib::idle_add_local_once(move || {
+          let Some((paths, data)) = controller.pending.take() else {
+            return;
+          };
+          controller.enter();
+          controller.call(DragDropEvent::Enter {
+            paths: paths.clone(),
+            position: controller.position.get(),
+            operations: source_operations(&ctx),
+            data,
+          });
+          controller.store_paths(paths);
         });
-        controller.store_paths(paths);
       }
     });
   }
 
   {
     let controller = controller.clone();
-    webview.connect_drag_motion(move |_, _, x, y, _| {
+    webview.connect_drag_motion(move |_, ctx, x, y, time| {
       if controller.state() == DragControllerState::Entered {
-        controller.call(DragDropEvent::Over { position: (x, y) });
+        let operation = controller.call(DragDropEvent::Over {
+          paths: controller.cloned_paths(),
+          position: (x, y),
+          operations: source_operations(ctx),
+        });
+        if let Some(action) = drag_action(operation) {
+          ctx.drag_status(action, time);
+          return true;
+        }
       } else {
         controller.store_position((x, y));
       }
@@ -134,6 +182,7 @@ pub(crate) fn connect_drag_event(webview: &WebView, handler: Box<dyn Fn(DragDrop
   }
 
   webview.connect_drag_leave(move |_w, _, _| {
+    controller.pending.take();
     if controller.state() != DragControllerState::Left {
       controller.leaving();
       let controller = controller.clone();
@@ -141,12 +190,60 @@ pub(crate) fn connect_drag_event(webview: &WebView, handler: Box<dyn Fn(DragDrop
         if controller.state() == DragControllerState::Leaving {
           controller.leave();
           controller.call(DragDropEvent::Leave);
+          controller.call(DragDropEvent::Cancelled);
         }
       });
     }
   });
 }
 
+/// Adds the files, URLs and text of a received target to the data of the drag.
+fn add_selection_data(paths: &mut Vec<PathBuf>, drag_data: &mut DragData, data: &SelectionData) {
+  let target = data.target().name();
+  let bytes = data.data();
+  let uris = data.uris();
+  if !uris.is_empty() {
+    paths.extend(
+      uris
+        .iter()
+        .filter(|uri| uri.starts_with("file://"))
+        .map(path_buf_from_uri),
+    );
+    drag_data
+      .urls
+      .extend(uris.iter().map(|uri| uri.to_string()));
+  } else if target == "_NETSCAPE_URL" {
+    // The URL followed by the title on the next line
+    let url = String::from_utf8_lossy(&bytes);
+    if let Some(url) = url.lines().next().filter(|url| !url.is_empty()) {
+      drag_data.urls.push(url.to_string());
+    }
+  } else if let Some(text) = data.text() {
+    drag_data.text.get_or_insert_with(|| text.to_string());
+  }
+  drag_data.raw.insert(target.to_string(), bytes);
+}
+
+fn source_operations(ctx: &DragContext) -> DragOperations {
+  let actions = ctx.actions();
+  DragOperations::new(
+    actions.contains(DragAction::COPY),
+    actions.contains(DragAction::MOVE),
+    actions.contains(DragAction::LINK),
+  )
+}
+
+/// Maps the operation returned by the handler, `None` means the drag should be handled natively.
+fn drag_action(operation: DragOperation) -> Option<DragAction> {
+  match operation {
+    DragOperation::Native => None,
+    DragOperation::None => Some(DragAction::empty()),
+    DragOperation::Copy => Some(DragAction::COPY),
+    DragOperation::Move => Some(DragAction::MOVE),
+    DragOperation::Link => Some(DragAction::LINK),
+  }
+}
+
 fn path_buf_from_uri(gstr: &GString) -> PathBuf {
   let path = gstr.as_str();
   let path = path.strip_prefix("file://").unwrap_or(path);
@@ -2,9 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-#[cfg(feature = "x11")]
-use dpi::LogicalPosition;
-use dpi::LogicalSize;
+use dpi::{LogicalPosition, LogicalSize};
 use ffi::CookieManageExt;
 #[cfg(feature = "x11")]
 use gdkx11::{
@@ -16,10 +14,12 @@ use gtk::glib::{self, translate::FromGlibPtrFull};
 use gtk::{
   gdk::{self},
   gio::Cancellable,
+  glib::translate::{ToGlibPtr, ToGlibPtrMut},
   prelude::*,
 };
 use http::Request;
 use javascriptcore::ValueExt;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use raw_window_handle::HasWindowHandle;
 #[cfg(feature = "x11")]
 use raw_window_handle::RawWindowHandle;
@@ -28,6 +28,7 @@ use std::ffi::c_ulong;
 #[cfg(any(debug_assertions, feature = "devtools"))]
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{
+  cell::{Cell, RefCell},
   collections::HashMap,
   rc::Rc,
   sync::{Arc, Mutex},
@@ -35,16 +36,21 @@ use std::{
 #[cfg(any(debug_assertions, feature = "devtools"))]
 use webkit2gtk::WebInspectorExt;
 use webkit2gtk::{
-  AutoplayPolicy, CookieManagerExt, InputMethodContextExt, LoadEvent, NavigationPolicyDecision,
-  NavigationPolicyDecisionExt, NetworkProxyMode, NetworkProxySettings, PolicyDecisionType,
-  PrintOperationExt, SettingsExt, URIRequest, URIRequestExt, UserContentInjectedFrames,
-  UserContentManager, UserContentManagerExt, UserScript, UserScriptInjectionTime,
-  WebContextExt as Webkit2gtkWeContextExt, WebView, WebViewExt, WebsiteDataManagerExt,
-  WebsiteDataManagerExtManual, WebsitePolicies,
+  AuthenticationRequestExt, AuthenticationScheme, AutoplayPolicy, BackForwardListExt,
+  BackForwardListItem, BackForwardListItemExt, ContextMenuExt, CookieManagerExt, Credential,
+  CredentialPersistence, FileChooserRequestExt, FindControllerExt, GeolocationPermissionRequest,
+  HitTestResultExt, InputMethodContextExt, LoadEvent, NavigationPolicyDecision,
+  NavigationPolicyDecisionExt, NetworkProxyMode, NetworkProxySettings,
+  NotificationPermissionRequest, PermissionRequestExt, PolicyDecisionType, PrintOperationExt,
+  ScriptDialogType, SecurityManagerExt, SettingsExt, SnapshotOptions, SnapshotRegion, URIRequest,
+  URIRequestExt, UserContentInjectedFrames, UserContentManager, UserContentManagerExt,
+  UserMediaPermissionRequest, UserMediaPermissionRequestExt, UserScript, UserScriptInjectionTime,
+  UserStyleLevel, UserStyleSheet, WebContextExt as Webkit2gtkWeContextExt, WebView, WebViewExt,
+  WebsiteDataManagerExt, WebsiteDataManagerExtManual, WebsitePolicies,
 };
 use webkit2gtk_sys::{
-  webkit_get_major_version, webkit_get_micro_version, webkit_get_minor_version,
-  webkit_policy_decision_ignore, webkit_policy_decision_use,
+  webkit_authentication_request_authenticate, webkit_get_major_version, webkit_get_micro_version,
+  webkit_get_minor_version, webkit_policy_decision_ignore, webkit_policy_decision_use,
 };
 #[cfg(feature = "x11")]
 use x11_dl::xlib::*;
@@ -52,13 +58,27 @@ use x11_dl::xlib::*;
 pub use web_context::WebContextImpl;
 
 use crate::{
-  proxy::ProxyConfig, web_context::WebContext, Error, NewWindowFeatures, NewWindowOpener,
-  NewWindowResponse, PageLoadEvent, Rect, Result, WebViewAttributes, RGBA,
+  auth::AuthHandler,
+  content_mode::UserAgent,
+  context_menu,
+  drag_drop::DragDropHandler,
+  permission::PermissionHandler,
+  proxy::ProxyConfig,
+  script::{InitScripts, PendingScripts},
+  web_context::WebContext,
+  AuthChallenge, AuthChallengeKind, BrowsingDataFlags, CertRequest, CertificateInfo, ContentMode,
+  ContextMenuAction, ContextMenuMediaType, DialogKind, DialogRequest, DialogResponse,
+  DragOperation, Error, FileChooserRequest, FindOptions, History, HistoryEntry, InjectionTime,
+  KeyEvent, MemoryUsage, MemoryUsageLevel, Modifiers, NavigationDecision, NavigationError,
+  NewWindowFeatures, NewWindowOpener, NewWindowRequest, NewWindowResponse, PageLoadEvent,
+  PermissionKind, PermissionRequest, PrintSettings, PrintToPdfOptions, Rect, Result,
+  StylesheetLevel, Theme, WebViewAttributes, RGBA,
 };
 
 use self::web_context::WebContextExt;
 
 const WEBVIEW_ID: &str = "webview_id";
+const PINCH_ZOOM_GESTURE: &str = "wry_pinch_zoom_gesture";
 
 mod drag_drop;
 mod synthetic_mouse_events;
@@ -85,9 +105,25 @@ pub(crate) struct InnerWebView {
   id: String,
   pub webview: WebView,
   #[cfg(any(debug_assertions, feature = "devtools"))]
+  devtools: bool,
+  #[cfg(any(debug_assertions, feature = "devtools"))]
   is_inspector_open: Arc<AtomicBool>,
-  pending_scripts: Arc<Mutex<Option<Vec<String>>>>,
+  pending_scripts: PendingScripts,
   is_in_fixed_parent: bool,
+  #[cfg(feature = "drag-drop")]
+  drag_drop_handler: Rc<DragDropHandler>,
+  init_scripts: InitScripts<UserScript>,
+  stylesheets: InitScripts<UserStyleSheet>,
+  // Whether the webview was muted before it was suspended, suspending mutes it
+  muted_before_suspend: Cell<Option<bool>>,
+  // `None` until the size-allocate handler that updates the rounded shape is connected
+  corner_radius: Rc<Cell<Option<f64>>>,
+  // The `counted-matches` handler of the last `find`
+  find_handler: RefCell<Option<gtk::glib::SignalHandlerId>>,
+  // The user agent, changed for the content mode by the navigation handler
+  user_agent: Rc<RefCell<UserAgent>>,
+  // The `script-message-received` handlers of `add_message_handler`, by name
+  message_handlers: RefCell<HashMap<String, gtk::glib::SignalHandlerId>>,
 
   #[cfg(feature = "x11")]
   x11: Option<X11Data>,
@@ -250,6 +286,13 @@ impl InnerWebView {
   where
     W: IsA<gtk::Container>,
   {
+    // WebKit starts the inspector server when the first web context is created
+    if let Some(port) = attributes.remote_debugging_port {
+      if std::env::var_os("WEBKIT_INSPECTOR_HTTP_SERVER").is_none() {
+        std::env::set_var("WEBKIT_INSPECTOR_HTTP_SERVER", format!("127.0.0.1:{port}"));
+      }
+    }
+
     // default_context allows us to create a scoped context on-demand
     let mut default_context;
     let web_context = if attributes.incognito {
@@ -265,17 +308,12 @@ impl InnerWebView {
       }
     };
     if let Some(proxy_setting) = &attributes.proxy_config {
-      let proxy_uri = match proxy_setting {
-        ProxyConfig::Http(endpoint) => format!("http://{}:{}", endpoint.host, endpoint.port),
-        ProxyConfig::Socks5(endpoint) => {
-          format!("socks5://{}:{}", endpoint.host, endpoint.port)
-        }
-      };
-      if let Some(website_data_manager) = web_context.context().website_data_manager() {
-        let mut settings = NetworkProxySettings::new(Some(proxy_uri.as_str()), &[]);
-        website_data_manager
-          .set_network_proxy_settings(NetworkProxyMode::Custom, Some(&mut settings));
-      }
+      set_network_proxy(web_context.context(), Some(proxy_setting));
+    }
+    if let Some(accept_language) = &attributes.accept_language {
+      // Sets both the `Accept-Language` header and `navigator.language`
+      let languages = crate::language::languages(accept_language);
+      web_context.context().set_preferred_languages(&languages);
     }
 
     // Extension loading
@@ -285,30 +323,39 @@ impl InnerWebView {
 
     let webview = Self::create_webview(web_context, &attributes, &pl_attrs);
 
-    // Transparent
-    if attributes.transparent {
-      webview.set_background_color(&gtk::gdk::RGBA::new(0., 0., 0., 0.));
-    } else {
-      // background color
-      if let Some((red, green, blue, alpha)) = attributes.background_color {
-        webview.set_background_color(&gtk::gdk::RGBA::new(
-          red as _, green as _, blue as _, alpha as _,
-        ));
-      }
+    // Transparent / background color, must be set before the first navigation
+    // otherwise the default white background is painted until the page loads
+    if let Some(background_color) = background_color(&attributes) {
+      webview.set_background_color(&background_color);
     }
 
     // Webview Settings
-    Self::set_webview_settings(&webview, &attributes);
+    let user_agent = Self::set_webview_settings(&webview, &attributes);
+    let user_agent = Rc::new(RefCell::new(user_agent));
 
     // Webview handlers
-    Self::attach_handlers(&webview, web_context, &mut attributes);
+    Self::attach_handlers(&webview, web_context, &mut attributes, &user_agent);
 
     // IPC handler
     Self::attach_ipc_handler(webview.clone(), &mut attributes);
 
-    // Drag drop handler
-    if let Some(drag_drop_handler) = attributes.drag_drop_handler.take() {
-      drag_drop::connect_drag_event(&webview, drag_drop_handler);
+    // Context menu handler
+    Self::attach_context_menu_handler(&webview, &mut attributes);
+
+    // Drag drop handler, always connected so it can be replaced later on
+    let drag_drop_handler = Rc::new(DragDropHandler::new(
+      attributes
+        .drag_drop_handler
+        .take()
+        .unwrap_or_else(|| Box::new(|_| DragOperation::Native)),
+      attributes.drag_drop_handler_mode,
+    ));
+    {
+      let drag_drop_handler = drag_drop_handler.clone();
+      drag_drop::connect_drag_event(
+        &webview,
+        Box::new(move |event| drag_drop_handler.call(event)),
+      );
     }
 
     web_context.register_automation(webview.clone());
@@ -327,22 +374,44 @@ impl InnerWebView {
     let w = Self {
       id,
       webview,
-      pending_scripts: Arc::new(Mutex::new(Some(Vec::new()))),
+      pending_scripts: crate::script::pending_scripts(&attributes),
 
       is_in_fixed_parent,
+      #[cfg(feature = "drag-drop")]
+      drag_drop_handler,
+      init_scripts: InitScripts::new(),
+      stylesheets: InitScripts::new(),
+      muted_before_suspend: Cell::new(None),
+      corner_radius: Rc::new(Cell::new(None)),
+      find_handler: RefCell::new(None),
+      user_agent,
+      message_handlers: Default::default(),
       #[cfg(feature = "x11")]
       x11: None,
 
+      #[cfg(any(debug_assertions, feature = "devtools"))]
+      devtools: attributes.devtools,
       #[cfg(any(debug_assertions, feature = "devtools"))]
       is_inspector_open,
     };
 
     // Initialize message handler
-    w.init("Object.defineProperty(window, 'ipc', { value: Object.freeze({ postMessage: function(x) { window.webkit.messageHandlers['ipc'].postMessage(x) } }) })", true)?;
+    w.init(
+      &crate::ipc::script(
+        "window.webkit.messageHandlers['ipc'].postMessage(s)",
+        attributes.ipc_invoke_timeout,
+      ),
+      true,
+      InjectionTime::DocumentStart,
+    )?;
 
     // Initialize scripts
     for init_script in attributes.initialization_scripts {
-      w.init(&init_script.script, init_script.for_main_frame_only)?;
+      w.init(
+        &init_script.script,
+        init_script.for_main_frame_only,
+        init_script.injection_time,
+      )?;
     }
 
     // Run pending webview.eval() scripts once webview loads.
@@ -351,9 +420,8 @@ impl InnerWebView {
       if let LoadEvent::Committed = event {
         let mut pending_scripts_ = pending_scripts.lock().unwrap();
         if let Some(pending_scripts) = pending_scripts_.take() {
-          let cancellable: Option<&Cancellable> = None;
-          for script in pending_scripts {
-            webview.run_javascript(&script, cancellable, |_| ());
+          for (script, callback) in pending_scripts {
+            run_javascript(webview, &script, callback);
           }
         }
       }
@@ -362,13 +430,21 @@ impl InnerWebView {
     // Custom protocols handler
     for (name, handler) in attributes.custom_protocols {
       web_context.register_uri_scheme(&name, handler)?;
+      if pl_attrs.https_scheme || attributes.service_workers == Some(true) {
+        web_context
+          .context()
+          .security_manager()
+          .ok_or(Error::MissingManager)?
+          .register_uri_scheme_as_secure(&name);
+      }
     }
 
     // Navigation
     if let Some(url) = attributes.url {
       web_context.load_uri(w.webview.clone(), url, attributes.headers);
     } else if let Some(html) = attributes.html {
-      w.webview.load_html(&html, None);
+      w.webview
+        .load_html(&html, attributes.html_base_url.as_deref());
     }
 
     if attributes.visible {
@@ -379,6 +455,11 @@ impl InnerWebView {
       w.webview.grab_focus();
     }
 
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    if attributes.devtools && attributes.devtools_open_on_start {
+      w.open_devtools()?;
+    }
+
     Ok(w)
   }
 
@@ -391,10 +472,19 @@ impl InnerWebView {
       .user_content_manager(&UserContentManager::new())
       .is_controlled_by_automation(web_context.allows_automation());
 
-    if attributes.autoplay {
+    let autoplay_policy = attributes.autoplay_policy.or(
+      attributes
+        .autoplay
+        .then_some(crate::AutoplayPolicy::AllowAll),
+    );
+    if let Some(autoplay_policy) = autoplay_policy {
       builder = builder.website_policies(
         &WebsitePolicies::builder()
-          .autoplay(AutoplayPolicy::Allow)
+          .autoplay(match autoplay_policy {
+            crate::AutoplayPolicy::AllowAll => AutoplayPolicy::Allow,
+            crate::AutoplayPolicy::RequireUserGesture => AutoplayPolicy::Deny,
+            crate::AutoplayPolicy::AllowMutedOnly => AutoplayPolicy::AllowWithoutSound,
+          })
           .build(),
       );
     }
@@ -408,7 +498,12 @@ impl InnerWebView {
     builder.build()
   }
 
-  fn set_webview_settings(webview: &WebView, attributes: &WebViewAttributes) {
+  fn set_webview_settings(webview: &WebView, attributes: &WebViewAttributes) -> UserAgent {
+    let mut user_agent = UserAgent {
+      mode: attributes.content_mode,
+      ..Default::default()
+    };
+
     // Disable input preedit,fcitx input editor can anchor at edit cursor position
     if let Some(input_context) = webview.input_method_context() {
       input_context.set_enable_preedit(false);
@@ -427,18 +522,26 @@ impl InnerWebView {
         .set_enable_back_forward_navigation_gestures(attributes.back_forward_navigation_gestures);
 
       // Enable clipboard
-      if attributes.clipboard {
+      let clipboard = match &attributes.clipboard_policy {
+        Some(policy) => policy.read || policy.write,
+        None => attributes.clipboard,
+      };
+      if clipboard {
         settings.set_javascript_can_access_clipboard(true);
       }
 
       // Enable App cache
       settings.set_enable_page_cache(true);
 
-      // Set user agent
+      // Set user agent, changed for the content mode
       settings.set_user_agent(attributes.user_agent.as_deref());
+      user_agent.base = settings.user_agent().unwrap_or_default().into();
+      if attributes.content_mode != ContentMode::Recommended {
+        settings.set_user_agent(Some(&user_agent.get()));
+      }
 
       // Devtools
-      if attributes.devtools {
+      if attributes.devtools || attributes.remote_debugging_port.is_some() {
         settings.set_enable_developer_extras(true);
       }
 
@@ -446,12 +549,26 @@ impl InnerWebView {
         settings.set_enable_javascript(false);
       }
     }
+
+    // Claim pinch gestures before the zoom gesture of WebKitGTK sees them
+    if attributes.pinch_zoom == Some(false) {
+      let gesture = gtk::GestureZoom::new(webview);
+      gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
+      gesture.connect_begin(|gesture, _| {
+        gesture.set_state(gtk::EventSequenceState::Claimed);
+      });
+      // GTK 3 widgets don't keep their gestures alive
+      unsafe { webview.set_data(PINCH_ZOOM_GESTURE, gesture) };
+    }
+
+    user_agent
   }
 
   fn attach_handlers(
     webview: &WebView,
     web_context: &mut WebContext,
     attributes: &mut WebViewAttributes,
+    user_agent: &Rc<RefCell<UserAgent>>,
   ) {
     // window.close()
     webview.connect_close(move |webview| unsafe { webview.destroy() });
@@ -467,6 +584,309 @@ impl InnerWebView {
       });
     }
 
+    // URL changed handler
+    if let Some(on_url_change_handler) = attributes.on_url_change_handler.take() {
+      webview.connect_uri_notify(move |webview| {
+        if let Some(uri) = webview.uri() {
+          on_url_change_handler(uri.to_string())
+        }
+      });
+    }
+
+    // Load progress handler
+    if let Some(load_progress_handler) = attributes.load_progress_handler.take() {
+      webview.connect_estimated_load_progress_notify(move |webview| {
+        load_progress_handler(webview.estimated_load_progress())
+      });
+    }
+
+    // Fullscreen handler, returning `true` from `enter-fullscreen` stops the request
+    if let Some(fullscreen_handler) = attributes.fullscreen_handler.take() {
+      let fullscreen_handler = Rc::new(fullscreen_handler);
+      let fullscreen_handler_ = fullscreen_handler.clone();
+      webview.connect_enter_fullscreen(move |_| !fullscreen_handler_(true));
+      webview.connect_leave_fullscreen(move |_| {
+        fullscreen_handler(false);
+        false
+      });
+    }
+
+    // Focus changed handler
+    if let Some(on_focus_changed_handler) = attributes.on_focus_changed_handler.take() {
+      let on_focus_changed_handler = Rc::new(on_focus_changed_handler);
+      let on_focus_changed_handler_ = on_focus_changed_handler.clone();
+      webview.connect_focus_in_event(move |_, _| {
+        on_focus_changed_handler_(true);
+        gtk::glib::Propagation::Proceed
+      });
+      webview.connect_focus_out_event(move |_, _| {
+        on_focus_changed_handler(false);
+        gtk::glib::Propagation::Proceed
+      });
+    }
+
+    // Permission handler, other requests than the ones of pages keep the default handling
+    if let Some(permission_handler) = attributes.permission_handler.take() {
+      let permission_handler = PermissionHandler::new(permission_handler);
+      webview.connect_permission_request(move |webview, request| {
+        let kind = if let Some(request) = request.downcast_ref::<UserMediaPermissionRequest>() {
+          match (request.is_for_video_device(), request.is_for_audio_device()) {
+            (true, true) => PermissionKind::CameraAndMicrophone,
+            (true, false) => PermissionKind::Camera,
+            (false, true) => PermissionKind::Microphone,
+            (false, false) => return false,
+          }
+        } else if request.is::<GeolocationPermissionRequest>() {
+          PermissionKind::Geolocation
+        } else if request.is::<NotificationPermissionRequest>() {
+          PermissionKind::Notifications
+        } else {
+          return false;
+        };
+
+        let origin = crate::ipc::origin(&webview.uri().unwrap_or_default());
+        if permission_handler.is_allowed(PermissionRequest { kind, origin }) {
+          request.allow();
+        } else {
+          request.deny();
+        }
+        true
+      });
+    }
+
+    // Clipboard policy, reading from pages of disallowed origins is denied instead of prompting
+    if let Some(policy) = attributes.clipboard_policy.take() {
+      webview.connect_permission_request(move |webview, request| {
+        // `WebKitClipboardPermissionRequest` is newer than the bindings, since WebKitGTK 2.42
+        if request.type_().name() != "WebKitClipboardPermissionRequest" {
+          return false;
+        }
+
+        if policy.allows_read(&webview.uri().unwrap_or_default()) {
+          request.allow();
+        } else {
+          request.deny();
+        }
+        true
+      });
+    }
+
+    // File chooser of `<input type="file">`
+    if let Some(file_open_handler) = attributes.file_open_handler.take() {
+      webview.connect_run_file_chooser(move |_, request| {
+        let files = file_open_handler(FileChooserRequest {
+          mime_types: request.mime_types().iter().map(|t| t.to_string()).collect(),
+          extensions: Vec::new(),
+          multiple: request.selects_multiple(),
+          directories: false,
+        });
+        match files {
+          Some(files) => {
+            let files = files
+              .iter()
+              .map(|file| file.to_string_lossy())
+              .collect::<Vec<_>>();
+            request.select_files(&files.iter().map(|file| file.as_ref()).collect::<Vec<_>>());
+          }
+          None => request.cancel(),
+        }
+        true
+      });
+    }
+
+    // JavaScript dialogs
+    if let Some(dialog_handler) = attributes.dialog_handler.take() {
+      webview.connect_script_dialog(move |webview, dialog| {
+        let kind = match dialog.dialog_type() {
+          ScriptDialogType::Alert => DialogKind::Alert,
+          ScriptDialogType::Confirm => DialogKind::Confirm,
+          ScriptDialogType::Prompt => DialogKind::Prompt {
+            default_text: dialog
+              .prompt_get_default_text()
+              .map(|text| text.to_string())
+              .unwrap_or_default(),
+          },
+          ScriptDialogType::BeforeUnloadConfirm => DialogKind::BeforeUnload,
+          _ => return false,
+        };
+        let response = dialog_handler(DialogRequest {
+          kind: kind.clone(),
+          message: dialog.message().map(|m| m.to_string()).unwrap_or_default(),
+          url: webview.uri().map(|uri| uri.to_string()).unwrap_or_default(),
+        });
+        if response == DialogResponse::Default {
+          return false;
+        }
+
+        let (accepted, text) = response.outcome(&kind);
+        match kind {
+          DialogKind::Confirm | DialogKind::BeforeUnload => dialog.confirm_set_confirmed(accepted),
+          DialogKind::Prompt { .. } => {
+            if let Some(text) = text {
+              dialog.prompt_set_text(&text);
+            }
+          }
+          _ => {}
+        }
+        true
+      });
+    }
+
+    // Authentication challenges, the handlers answer them instead of the WebKit dialog
+    if let Some(auth_handler) = AuthHandler::new(
+      attributes.proxy_auth_handler.take(),
+      attributes.authentication_handler.take(),
+    ) {
+      webview.connect_authenticate(move |_, request| {
+        if is_client_certificate_request(request) {
+          return false;
+        }
+
+        let kind = if request.is_for_proxy() {
+          AuthChallengeKind::Proxy
+        } else {
+          AuthChallengeKind::Server
+        };
+        let Some(handler) = auth_handler.handler(kind) else {
+          return false;
+        };
+
+        let mut challenge = AuthChallenge {
+          kind,
+          host: request
+            .host()
+            .map(|host| host.to_string())
+            .unwrap_or_default(),
+          port: request.port() as u16,
+          realm: request
+            .realm()
+            .map(|realm| realm.to_string())
+            .unwrap_or_default(),
+          previous_failure_count: 0,
+        };
+        challenge.previous_failure_count =
+          auth_handler.previous_failure_count(&challenge, Some(request.is_retry()));
+
+        match handler(challenge) {
+          Some(credentials) => {
+            let mut credential = Credential::new(
+              &credentials.username,
+              &credentials.password,
+              CredentialPersistence::ForSession,
+            );
+            unsafe {
+              webkit_authentication_request_authenticate(
+                request.to_glib_none().0,
+                credential.to_glib_none_mut().0,
+              )
+            };
+          }
+          None => request.cancel(),
+        }
+        true
+      });
+    }
+
+    // TLS client certificates, the handler picks them instead of WebKit continuing without one
+    if let Some(client_certificate_handler) = attributes.client_certificate_handler.take() {
+      webview.connect_authenticate(move |_, request| {
+        if request.scheme() != AuthenticationScheme::ClientCertificateRequested {
+          return false;
+        }
+
+        let certificate = client_certificate_handler(CertRequest {
+          host: request
+            .host()
+            .map(|host| host.to_string())
+            .unwrap_or_default(),
+          port: request.port() as u16,
+          allowed_issuers: Vec::new(),
+          certificates: Vec::new(),
+        });
+        match certificate {
+          Some(certificate) => {
+            let mut credential = Credential::for_certificate(
+              Some(&certificate.inner),
+              CredentialPersistence::ForSession,
+            );
+            unsafe {
+              webkit_authentication_request_authenticate(
+                request.to_glib_none().0,
+                credential.to_glib_none_mut().0,
+              )
+            };
+          }
+          // A null credential continues without a certificate
+          None => unsafe {
+            webkit_authentication_request_authenticate(
+              request.to_glib_none().0,
+              std::ptr::null_mut(),
+            )
+          },
+        }
+        true
+      });
+    }
+
+    // Accelerator passthrough, the host gets the key presses before WebKit
+    if let Some(accelerator_passthrough) = attributes.accelerator_passthrough.take() {
+      webview.connect_key_press_event(move |_, event| {
+        let keyval = event.keyval();
+        let Some(key) = keyval
+          .name()
+          .and_then(|name| crate::keyboard::key_from_gdk_keyval(&name, keyval.to_unicode()))
+        else {
+          return gtk::glib::Propagation::Proceed;
+        };
+
+        let state = event.state();
+        let event = KeyEvent {
+          key,
+          modifiers: Modifiers {
+            shift: state.contains(gdk::ModifierType::SHIFT_MASK),
+            control: state.contains(gdk::ModifierType::CONTROL_MASK),
+            alt: state.contains(gdk::ModifierType::MOD1_MASK),
+            meta: state.intersects(gdk::ModifierType::SUPER_MASK | gdk::ModifierType::META_MASK),
+          },
+        };
+        if accelerator_passthrough(&event) {
+          gtk::glib::Propagation::Stop
+        } else {
+          gtk::glib::Propagation::Proceed
+        }
+      });
+    }
+
+    // Web resource request handler, only web process extensions can change the requests
+    if let Some(web_resource_request_handler) = attributes.web_resource_request_handler.take() {
+      webview.connect_resource_load_started(move |_, _, request| {
+        let Some(uri) = request.uri() else {
+          return;
+        };
+        if !uri.starts_with("http://") && !uri.starts_with("https://") {
+          return;
+        }
+
+        let method = request.http_method();
+        let mut http_request = Request::builder()
+          .uri(uri.as_str())
+          .method(method.as_deref().unwrap_or("GET"));
+        if let (Some(headers), Some(map)) = (request.http_headers(), http_request.headers_mut()) {
+          headers.foreach(|name, value| {
+            if let (Ok(name), Ok(value)) = (
+              http::HeaderName::from_bytes(name.as_bytes()),
+              http::HeaderValue::from_bytes(value.as_bytes()),
+            ) {
+              map.append(name, value);
+            }
+          });
+        }
+        if let Ok(mut http_request) = http_request.body(Vec::new()) {
+          let _ = web_resource_request_handler(&mut http_request);
+        }
+      });
+    }
+
     // Page load handler
     if let Some(on_page_load_handler) = attributes.on_page_load_handler.take() {
       webview.connect_load_changed(move |webview, load_event| match load_event {
@@ -480,24 +900,85 @@ impl InnerWebView {
       });
     }
 
+    // First paint handler
+    if let Some(on_first_paint_handler) = attributes.on_first_paint_handler.take() {
+      let handler_id = Rc::new(Cell::new(None));
+      let handler_id_ = handler_id.clone();
+      let id = webview.connect_load_changed(move |webview, load_event| {
+        if load_event == LoadEvent::Committed {
+          on_first_paint_handler();
+          if let Some(id) = handler_id_.take() {
+            webview.disconnect(id);
+          }
+        }
+      });
+      handler_id.set(Some(id));
+    }
+
+    // Navigation error handler
+    if let Some(on_navigation_error_handler) = attributes.on_navigation_error_handler.take() {
+      webview.connect_load_failed(move |_webview, load_event, failing_uri, error| {
+        on_navigation_error_handler(NavigationError {
+          url: failing_uri.to_string(),
+          code: unsafe { (*error.as_ptr()).code }.into(),
+          message: error.message().to_string(),
+          provisional: load_event != LoadEvent::Committed,
+        });
+        // Let WebKit show its error page
+        false
+      });
+    }
+
+    // TLS errors, the accepted certificates are allowed for their host and the page reloaded
+    if let Some(tls_error_handler) = attributes.tls_error_handler.take() {
+      webview.connect_load_failed_with_tls_errors(move |webview, failing_uri, certificate, _| {
+        let Some(host) = failing_uri
+          .parse::<http::Uri>()
+          .ok()
+          .and_then(|uri| uri.host().map(ToString::to_string))
+        else {
+          return false;
+        };
+
+        let info = CertificateInfo {
+          subject: crate::auth::tls_certificate_property(certificate, "subject-name"),
+          issuer: crate::auth::tls_certificate_property(certificate, "issuer-name"),
+          pem: crate::auth::tls_certificate_property(certificate, "certificate-pem"),
+        };
+        if !tls_error_handler(&host, &info) {
+          return false;
+        }
+
+        match webview.context() {
+          Some(context) => {
+            context.allow_tls_certificate_for_host(certificate, &host);
+            webview.load_uri(failing_uri);
+            true
+          }
+          None => false,
+        }
+      });
+    }
+
     // window creation handler
-    if let Some(new_window_req_handler) = attributes.new_window_req_handler.take() {
+    if let Some(new_window_request_handler) = attributes.new_window_request_handler.take() {
       let related_webviews = Rc::new(Mutex::new(HashMap::new()));
       webview.connect_create(move |webview, action| {
         let url = action
           .request()
           .and_then(|request| request.uri())
           .map(|uri| uri.as_str().to_string())?;
-        match new_window_req_handler(
-          url.clone(),
-          NewWindowFeatures {
+        match new_window_request_handler(NewWindowRequest {
+          url: url.clone(),
+          features: NewWindowFeatures {
             size: None,
             position: None,
             opener: NewWindowOpener {
               webview: webview.clone(),
             },
           },
-        ) {
+          user_initiated: action.is_user_gesture(),
+        }) {
           NewWindowResponse::Allow => {
             let related_webviews = related_webviews.clone();
             let toplevel = webview.toplevel().unwrap();
@@ -535,6 +1016,10 @@ impl InnerWebView {
             })
             .ok()
           }
+          NewWindowResponse::OpenInSameView => {
+            webview.load_uri(&url);
+            None
+          }
           NewWindowResponse::Create { webview } => Some(webview.upcast::<gtk::Widget>()),
           NewWindowResponse::Deny => None,
         }
@@ -542,8 +1027,9 @@ impl InnerWebView {
     }
 
     // Navigation handler
-    if let Some(navigation_handler) = attributes.navigation_handler.take() {
-      webview.connect_decide_policy(move |_webview, policy_decision, policy_type| {
+    if let Some(navigation_handler) = attributes.navigation_handler_ext.take() {
+      let user_agent = user_agent.clone();
+      webview.connect_decide_policy(move |webview, policy_decision, policy_type| {
         let handler = match policy_type {
           PolicyDecisionType::NavigationAction => &navigation_handler,
           _ => return false,
@@ -553,13 +1039,26 @@ impl InnerWebView {
           if let Some(nav_action) = policy.navigation_action() {
             if let Some(uri_req) = nav_action.request() {
               if let Some(uri) = uri_req.uri() {
-                let allow = handler(uri.to_string());
+                let decision = handler(uri.to_string());
                 let pointer = policy_decision.as_ptr();
-                unsafe {
-                  if allow {
-                    webkit_policy_decision_use(pointer)
-                  } else {
-                    webkit_policy_decision_ignore(pointer)
+                match decision {
+                  NavigationDecision::Allow => unsafe { webkit_policy_decision_use(pointer) },
+                  NavigationDecision::Deny => unsafe { webkit_policy_decision_ignore(pointer) },
+                  NavigationDecision::Redirect(url) => {
+                    unsafe { webkit_policy_decision_ignore(pointer) };
+                    webview.load_uri(&url);
+                  }
+                  NavigationDecision::AllowWithContentMode(mode) => {
+                    if std::mem::replace(&mut user_agent.borrow_mut().mode, mode) == mode {
+                      unsafe { webkit_policy_decision_use(pointer) };
+                    } else {
+                      // The request already has the old user agent, load it again
+                      unsafe { webkit_policy_decision_ignore(pointer) };
+                      if let Some(settings) = WebViewExt::settings(webview) {
+                        settings.set_user_agent(Some(&user_agent.borrow().get()));
+                      }
+                      webview.load_uri(&uri);
+                    }
                   }
                 }
 
@@ -574,13 +1073,8 @@ impl InnerWebView {
     }
 
     // Download handler
-    if attributes.download_started_handler.is_some()
-      || attributes.download_completed_handler.is_some()
-    {
-      web_context.register_download_handler(
-        attributes.download_started_handler.take(),
-        attributes.download_completed_handler.take(),
-      )
+    if let Some(download_handler) = attributes.download_handler.take() {
+      web_context.register_download_handler(download_handler)
     }
   }
 
@@ -590,6 +1084,10 @@ impl InnerWebView {
   {
     let mut is_in_fixed_parent = false;
 
+    if attributes.transparent {
+      Self::set_rgba_visual(container);
+    }
+
     let container_type = container.type_().name();
     if container_type == "GtkBox" {
       container
@@ -624,6 +1122,26 @@ impl InnerWebView {
     is_in_fixed_parent
   }
 
+  /// Makes the container and its toplevel window paint with an alpha channel,
+  /// so a transparent webview doesn't show their opaque background.
+  fn set_rgba_visual<W>(container: &W)
+  where
+    W: IsA<gtk::Container>,
+  {
+    let container = container.upcast_ref::<gtk::Container>();
+    container.set_app_paintable(true);
+
+    if let Some(toplevel) = container.toplevel() {
+      // the visual can't be changed once the window is realized
+      if !toplevel.is_realized() {
+        if let Some(visual) = toplevel.screen().and_then(|s| s.rgba_visual()) {
+          toplevel.set_visual(Some(&visual));
+        }
+      }
+      toplevel.set_app_paintable(true);
+    }
+  }
+
   fn attach_ipc_handler(webview: WebView, attributes: &mut WebViewAttributes) {
     // Message handler
     let ipc_handler = attributes.ipc_handler.take();
@@ -632,15 +1150,17 @@ impl InnerWebView {
       .expect("WebView does not have UserContentManager");
 
     // Connect before registering as recommended by the docs
-    manager.connect_script_message_received(None, move |_m, msg| {
+    manager.connect_script_message_received(Some("ipc"), move |_m, msg| {
       #[cfg(feature = "tracing")]
       let _span = tracing::info_span!(parent: None, "wry::ipc::handle").entered();
 
       if let Some(js) = msg.js_value() {
         if let Some(ipc_handler) = &ipc_handler {
+          let url = webview.uri().unwrap().to_string();
           ipc_handler(
             Request::builder()
-              .uri(webview.uri().unwrap().to_string())
+              .extension(crate::IpcSource::new(&url, None))
+              .uri(url)
               .body(js.to_string())
               .unwrap(),
           );
@@ -652,6 +1172,72 @@ impl InnerWebView {
     manager.register_script_message_handler("ipc");
   }
 
+  fn attach_context_menu_handler(webview: &WebView, attributes: &mut WebViewAttributes) {
+    let Some(context_menu_handler) = attributes.context_menu_handler.take() else {
+      return;
+    };
+    let Some(manager) = webview.user_content_manager() else {
+      return;
+    };
+
+    // The script posts what the menu is opened on before WebKit opens it
+    let target = Rc::new(RefCell::new(None));
+    let target_ = target.clone();
+    manager.connect_script_message_received(
+      Some(context_menu::MESSAGE_HANDLER_NAME),
+      move |_m, msg| {
+        if let Some(js) = msg.js_value() {
+          target_.replace(Some(context_menu::parse_message(&js.to_string())));
+        }
+      },
+    );
+    manager.register_script_message_handler(context_menu::MESSAGE_HANDLER_NAME);
+    manager.add_script(&UserScript::new(
+      &context_menu::script(),
+      UserContentInjectedFrames::TopFrame,
+      UserScriptInjectionTime::Start,
+      &[],
+      &[],
+    ));
+
+    webview.connect_context_menu(move |_, menu, event, hit_test| {
+      let mut target = target
+        .take()
+        .unwrap_or_else(|| context_menu::parse_message(""));
+      if let Some(link_uri) = hit_test.link_uri() {
+        target.link_url = Some(link_uri.to_string());
+      }
+      if hit_test.context_is_image() {
+        target.media_type = Some(ContextMenuMediaType::Image);
+      } else if hit_test.context_is_media() && target.media_type.is_none() {
+        target.media_type = Some(ContextMenuMediaType::Video);
+      }
+      if let Some((x, y)) = event.coords() {
+        target.position = dpi::LogicalPosition::new(x, y);
+      }
+
+      // Returning true doesn't show the menu
+      match context_menu_handler(target) {
+        ContextMenuAction::Default => false,
+        ContextMenuAction::Suppress => true,
+        ContextMenuAction::Custom(items) => {
+          menu.remove_all();
+          for (index, item) in items.into_iter().enumerate() {
+            let action = gtk::gio::SimpleAction::new(&format!("wry-context-menu-{index}"), None);
+            let handler = item.handler.clone();
+            action.connect_activate(move |_, _| handler());
+            menu.append(&webkit2gtk::ContextMenuItem::from_gaction(
+              &action,
+              item.label(),
+              None,
+            ));
+          }
+          false
+        }
+      }
+    });
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   fn attach_inspector_handlers(webview: &WebView) -> Arc<AtomicBool> {
     let is_inspector_open = Arc::new(AtomicBool::default());
@@ -688,34 +1274,29 @@ impl InnerWebView {
     js: &str,
     callback: Option<impl FnOnce(String) + Send + 'static>,
   ) -> Result<()> {
-    if let Some(pending_scripts) = &mut *self.pending_scripts.lock().unwrap() {
-      pending_scripts.push(js.into());
-    } else {
-      let cancellable: Option<&Cancellable> = None;
-
-      #[cfg(feature = "tracing")]
-      let span = SendEnteredSpan(tracing::debug_span!("wry::eval").entered());
-
-      self.webview.run_javascript(js, cancellable, |result| {
-        #[cfg(feature = "tracing")]
-        drop(span);
-
-        if let Some(callback) = callback {
-          let result = result
-            .map(|r| r.js_value().and_then(|js| js.to_json(0)))
-            .unwrap_or_default()
-            .unwrap_or_default()
-            .to_string();
-
-          callback(result);
-        }
-      });
+    if let Err(callback) = crate::script::defer_script(&self.pending_scripts, js, callback) {
+      run_javascript(&self.webview, js, callback);
     }
 
     Ok(())
   }
 
-  fn init(&self, js: &str, for_main_only: bool) -> Result<()> {
+  /// Evaluates scripts without a callback for as long as the webview is alive.
+  pub(crate) fn script_evaluator(&self) -> Box<dyn Fn(&str)> {
+    let webview = self.webview.downgrade();
+    Box::new(move |js| {
+      if let Some(webview) = webview.upgrade() {
+        webview.run_javascript(js, None::<&Cancellable>, |_| ());
+      }
+    })
+  }
+
+  fn init(
+    &self,
+    js: &str,
+    for_main_only: bool,
+    injection_time: InjectionTime,
+  ) -> Result<UserScript> {
     if let Some(manager) = self.webview.user_content_manager() {
       let script = UserScript::new(
         js,
@@ -724,24 +1305,102 @@ impl InnerWebView {
         } else {
           UserContentInjectedFrames::AllFrames
         },
-        UserScriptInjectionTime::Start,
+        match injection_time {
+          InjectionTime::DocumentStart => UserScriptInjectionTime::Start,
+          InjectionTime::DocumentEnd => UserScriptInjectionTime::End,
+        },
         &[],
         &[],
       );
       manager.add_script(&script);
+      Ok(script)
     } else {
-      return Err(Error::InitScriptError);
+      Err(Error::InitScriptError)
+    }
+  }
+
+  pub fn add_init_script(&self, id: &str, script: &str) -> Result<()> {
+    let script = self.init(script, true, InjectionTime::DocumentStart)?;
+    if let Some(replaced) = self.init_scripts.insert(id, script) {
+      self.remove_user_script(&replaced);
+    }
+    Ok(())
+  }
+
+  pub fn remove_init_script(&self, id: &str) -> Result<()> {
+    if let Some(script) = self.init_scripts.remove(id) {
+      self.remove_user_script(&script);
+    }
+    Ok(())
+  }
+
+  pub fn init_scripts(&self) -> Vec<String> {
+    self.init_scripts.ids()
+  }
+
+  fn remove_user_script(&self, script: &UserScript) {
+    if let Some(manager) = self.webview.user_content_manager() {
+      manager.remove_script(script);
+    }
+  }
+
+  pub fn add_user_stylesheet(
+    &self,
+    id: &str,
+    css: &str,
+    main_frame_only: bool,
+    level: StylesheetLevel,
+  ) -> Result<()> {
+    let manager = self
+      .webview
+      .user_content_manager()
+      .ok_or(Error::InitScriptError)?;
+    let stylesheet = UserStyleSheet::new(
+      css,
+      if main_frame_only {
+        UserContentInjectedFrames::TopFrame
+      } else {
+        UserContentInjectedFrames::AllFrames
+      },
+      match level {
+        StylesheetLevel::User => UserStyleLevel::User,
+        StylesheetLevel::Author => UserStyleLevel::Author,
+      },
+      &[],
+      &[],
+    );
+    manager.add_style_sheet(&stylesheet);
+    self.stylesheets.insert(id, stylesheet);
+    Ok(())
+  }
+
+  pub fn set_proxy(&self, proxy: Option<ProxyConfig>) -> Result<()> {
+    if let Some(context) = self.webview.context() {
+      set_network_proxy(&context, proxy.as_ref());
+    }
+    Ok(())
+  }
+
+  pub fn remove_user_stylesheet(&self, id: &str) -> Result<()> {
+    if let Some(stylesheet) = self.stylesheets.remove(id) {
+      if let Some(manager) = self.webview.user_content_manager() {
+        manager.remove_style_sheet(&stylesheet);
+      }
     }
     Ok(())
   }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn open_devtools(&self) {
+  pub fn open_devtools(&self) -> Result<()> {
+    if !self.devtools {
+      return Err(Error::DevToolsNotEnabled);
+    }
     if let Some(inspector) = self.webview.inspector() {
       inspector.show();
       // `bring-to-front` is not received in this case
       self.is_inspector_open.store(true, Ordering::Relaxed);
     }
+    Ok(())
   }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
@@ -761,10 +1420,91 @@ impl InnerWebView {
     Ok(())
   }
 
-  pub fn set_background_color(&self, (red, green, blue, alpha): RGBA) -> Result<()> {
-    self.webview.set_background_color(&gtk::gdk::RGBA::new(
-      red as _, green as _, blue as _, alpha as _,
-    ));
+  pub fn zoom_level(&self) -> Result<f64> {
+    Ok(self.webview.zoom_level())
+  }
+
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    if let Some(settings) = WebViewExt::settings(&self.webview) {
+      let mut current = self.user_agent.borrow_mut();
+      current.base = user_agent.to_string();
+      settings.set_user_agent(Some(&current.get()));
+    }
+    Ok(())
+  }
+
+  pub fn add_message_handler(
+    &self,
+    name: &str,
+    handler: Box<dyn Fn(Request<String>)>,
+  ) -> Result<()> {
+    self.remove_message_handler(name)?;
+    let Some(manager) = self.webview.user_content_manager() else {
+      return Ok(());
+    };
+
+    // The manager is owned by the webview, a strong reference would keep it alive
+    let webview = self.webview.downgrade();
+    let handler_id = manager.connect_script_message_received(Some(name), move |_m, msg| {
+      let (Some(webview), Some(js)) = (webview.upgrade(), msg.js_value()) else {
+        return;
+      };
+      let url = webview.uri().map(|uri| uri.to_string()).unwrap_or_default();
+      if let Ok(request) = Request::builder()
+        .extension(crate::IpcSource::new(&url, None))
+        .uri(url)
+        .body(js.to_string())
+      {
+        handler(request);
+      }
+    });
+    manager.register_script_message_handler(name);
+    self
+      .message_handlers
+      .borrow_mut()
+      .insert(name.to_string(), handler_id);
+    Ok(())
+  }
+
+  pub fn remove_message_handler(&self, name: &str) -> Result<()> {
+    let handler_id = self.message_handlers.borrow_mut().remove(name);
+    if let (Some(handler_id), Some(manager)) = (handler_id, self.webview.user_content_manager()) {
+      manager.unregister_script_message_handler(name);
+      manager.disconnect(handler_id);
+    }
+    Ok(())
+  }
+
+  pub fn set_accept_language(&self, _languages: &[&str]) -> Result<()> {
+    // The preferred languages of the context would also change `navigator.language`
+    #[cfg(feature = "tracing")]
+    tracing::warn!("Setting the Accept-Language header is unsupported on this platform");
+    Ok(())
+  }
+
+  pub fn set_navigation_gestures(&self, enabled: bool) -> Result<()> {
+    if let Some(settings) = WebViewExt::settings(&self.webview) {
+      settings.set_enable_back_forward_navigation_gestures(enabled);
+    }
+    Ok(())
+  }
+
+  pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
+    self
+      .webview
+      .set_background_color(&gdk_rgba(background_color));
+    Ok(())
+  }
+
+  pub fn set_theme(&self, theme: Theme) -> Result<()> {
+    // WebKitGTK follows the GTK theme for `prefers-color-scheme`
+    if let Some(settings) = WidgetExt::settings(&self.webview) {
+      match theme {
+        Theme::Dark => settings.set_gtk_application_prefer_dark_theme(true),
+        Theme::Light => settings.set_gtk_application_prefer_dark_theme(false),
+        Theme::Auto => settings.reset_property("gtk-application-prefer-dark-theme"),
+      }
+    }
     Ok(())
   }
 
@@ -777,11 +1517,8 @@ impl InnerWebView {
     let req = URIRequest::builder().uri(url).build();
 
     if let Some(ref mut req_headers) = req.http_headers() {
-      for (header, value) in headers.iter() {
-        req_headers.append(
-          header.to_string().as_str(),
-          value.to_str().unwrap_or_default(),
-        );
+      for (header, value) in crate::navigation::request_headers(&headers) {
+        req_headers.append(header, &value);
       }
     }
 
@@ -790,8 +1527,34 @@ impl InnerWebView {
     Ok(())
   }
 
-  pub fn load_html(&self, html: &str) -> Result<()> {
-    self.webview.load_html(html, None);
+  pub fn load_url_with_body(
+    &self,
+    url: &str,
+    method: http::Method,
+    body: Vec<u8>,
+    headers: http::HeaderMap,
+  ) -> Result<()> {
+    if method == http::Method::GET && body.is_empty() {
+      return self.load_url_with_headers(url, headers);
+    }
+
+    // WebKitGTK can't set the method or body of a navigation request,
+    // so form posts are sent by submitting an equivalent form instead.
+    let is_form = headers
+      .get(http::header::CONTENT_TYPE)
+      .and_then(|h| h.to_str().ok())
+      .map_or(true, |h| h.starts_with("application/x-www-form-urlencoded"));
+    if method != http::Method::POST || !is_form {
+      return Err(Error::UnsupportedNavigationBody);
+    }
+
+    let html = crate::navigation::form_post_html(url, &body);
+    self.webview.load_html(&html, None);
+    Ok(())
+  }
+
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) -> Result<()> {
+    self.webview.load_html(html, base_url);
     Ok(())
   }
 
@@ -800,6 +1563,51 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn reload_ignoring_cache(&self) -> Result<()> {
+    self.webview.reload_bypass_cache();
+    Ok(())
+  }
+
+  pub fn stop_loading(&self) -> Result<()> {
+    self.webview.stop_loading();
+    Ok(())
+  }
+
+  pub fn can_go_back(&self) -> Result<bool> {
+    Ok(self.webview.can_go_back())
+  }
+
+  pub fn can_go_forward(&self) -> Result<bool> {
+    Ok(self.webview.can_go_forward())
+  }
+
+  pub fn go_back(&self) -> Result<()> {
+    self.webview.go_back();
+    Ok(())
+  }
+
+  pub fn go_forward(&self) -> Result<()> {
+    self.webview.go_forward();
+    Ok(())
+  }
+
+  pub fn history_list(&self) -> Result<History> {
+    let Some(list) = self.webview.back_forward_list() else {
+      return Ok(History::default());
+    };
+
+    let entry = |item: BackForwardListItem| HistoryEntry {
+      url: item.uri().unwrap_or_default().to_string(),
+      title: item.title().unwrap_or_default().to_string(),
+    };
+    // both lists start with the items nearest to the current one
+    Ok(History {
+      back: list.back_list().into_iter().rev().map(entry).collect(),
+      current: list.current_item().map(entry),
+      forward: list.forward_list().into_iter().map(entry).collect(),
+    })
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     if let Some(context) = self.webview.context() {
       if let Some(data_manger) = context.website_data_manager() {
@@ -815,6 +1623,270 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn capture_png<F>(&self, rect: Option<Rect>, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    let scale_factor = self.webview.scale_factor() as f64;
+    self.webview.snapshot(
+      SnapshotRegion::Visible,
+      SnapshotOptions::NONE,
+      None::<&Cancellable>,
+      move |surface| {
+        let png = surface.map_err(Into::into).and_then(|surface| {
+          let surface = gtk::cairo::ImageSurface::try_from(surface)
+            .map_err(|_| Error::CaptureFailed("the snapshot is not an image surface".into()))?;
+          // The surface has the size of the webview in device pixels
+          let (x, y, width, height) = match rect {
+            Some(rect) => {
+              let (x, y) = rect.position.to_physical::<i32>(scale_factor).into();
+              let (width, height) = rect.size.to_physical::<i32>(scale_factor).into();
+              (x, y, width, height)
+            }
+            None => (0, 0, surface.width(), surface.height()),
+          };
+          gdk::pixbuf_get_from_surface(&surface, x, y, width, height)
+            .ok_or_else(|| Error::CaptureFailed("the region is outside of the webview".into()))?
+            .save_to_bufferv("png", &[])
+            .map_err(Into::into)
+        });
+        completion(png);
+      },
+    );
+
+    Ok(())
+  }
+
+  pub fn print_silent<F>(&self, settings: PrintSettings, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    let print_settings = gtk::PrintSettings::new();
+    if let Some(printer) = &settings.printer {
+      print_settings.set_printer(printer);
+    }
+    print_settings.set_n_copies(settings.copies as i32);
+    if let Some((first, last)) = settings.page_range {
+      print_settings.set_print_pages(gtk::PrintPages::Ranges);
+      print_settings.set_page_ranges(&[gtk::PageRange::new(
+        first.saturating_sub(1) as i32,
+        last.saturating_sub(1) as i32,
+      )]);
+    }
+
+    let print = webkit2gtk::PrintOperation::new(&self.webview);
+    print.set_print_settings(&print_settings);
+
+    let completion = Rc::new(RefCell::new(Some(completion)));
+    let completion_ = completion.clone();
+    print.connect_failed(move |_, error| {
+      let Some(completion) = completion_.take() else {
+        return;
+      };
+      completion(Err(match error.kind::<webkit2gtk::PrintError>() {
+        Some(webkit2gtk::PrintError::PrinterNotFound) => {
+          Error::PrinterNotFound(settings.printer.clone().unwrap_or_default())
+        }
+        _ => error.clone().into(),
+      }));
+    });
+    // Keeps the operation alive until it finishes, `finished` is emitted after `failed` too
+    let operation = RefCell::new(Some(print.clone()));
+    print.connect_finished(move |_| {
+      operation.take();
+      if let Some(completion) = completion.take() {
+        completion(Ok(()));
+      }
+    });
+    print.print();
+
+    Ok(())
+  }
+
+  pub fn print_to_pdf<F>(&self, options: PrintToPdfOptions, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    // WebKitGTK only prints to files, through the "Print to File" GTK printer
+    let path = crate::util::temp_file_path("pdf");
+    let uri = gtk::glib::filename_to_uri(&path, None)?;
+    let print_settings = gtk::PrintSettings::new();
+    print_settings.set_printer("Print to File");
+    print_settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT.as_str(), Some("pdf"));
+    print_settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI.as_str(), Some(uri.as_str()));
+
+    let orientation = if options.landscape {
+      gtk::PageOrientation::Landscape
+    } else {
+      gtk::PageOrientation::Portrait
+    };
+    print_settings.set_orientation(orientation);
+    let page_setup = gtk::PageSetup::new();
+    page_setup.set_paper_size(&gtk::PaperSize::new_custom(
+      "wry",
+      "wry",
+      options.page_width,
+      options.page_height,
+      gtk::Unit::Inch,
+    ));
+    page_setup.set_orientation(orientation);
+    page_setup.set_top_margin(options.margins.top, gtk::Unit::Inch);
+    page_setup.set_right_margin(options.margins.right, gtk::Unit::Inch);
+    page_setup.set_bottom_margin(options.margins.bottom, gtk::Unit::Inch);
+    page_setup.set_left_margin(options.margins.left, gtk::Unit::Inch);
+
+    // Backgrounds are a webview setting, restored once the document is written
+    let settings = WebViewExt::settings(&self.webview);
+    let print_backgrounds = settings.as_ref().map(|settings| {
+      let print_backgrounds = settings.is_print_backgrounds();
+      settings.set_print_backgrounds(options.print_background);
+      print_backgrounds
+    });
+
+    let print = webkit2gtk::PrintOperation::new(&self.webview);
+    print.set_print_settings(&print_settings);
+    print.set_page_setup(&page_setup);
+
+    let completion = Rc::new(RefCell::new(Some(completion)));
+    let completion_ = completion.clone();
+    print.connect_failed(move |_, error| {
+      if let Some(completion) = completion_.take() {
+        completion(Err(error.clone().into()));
+      }
+    });
+    // Keeps the operation alive until it finishes, `finished` is emitted after `failed` too
+    let operation = RefCell::new(Some(print.clone()));
+    print.connect_finished(move |_| {
+      operation.take();
+      if let (Some(settings), Some(print_backgrounds)) = (&settings, print_backgrounds) {
+        settings.set_print_backgrounds(print_backgrounds);
+      }
+      let pdf = std::fs::read(&path).map_err(Into::into);
+      let _ = std::fs::remove_file(&path);
+      if let Some(completion) = completion.take() {
+        completion(pdf);
+      }
+    });
+    print.print();
+
+    Ok(())
+  }
+
+  pub fn find(
+    &self,
+    query: &str,
+    options: FindOptions,
+    callback: impl Fn(u32) + Send + 'static,
+  ) -> Result<()> {
+    let Some(controller) = self.webview.find_controller() else {
+      return Ok(());
+    };
+    if let Some(handler) = self.find_handler.take() {
+      controller.disconnect(handler);
+    }
+    if query.is_empty() {
+      controller.search_finish();
+      callback(0);
+      return Ok(());
+    }
+
+    let mut flags = webkit2gtk::FindOptions::empty();
+    flags.set(
+      webkit2gtk::FindOptions::CASE_INSENSITIVE,
+      !options.case_sensitive,
+    );
+    flags.set(webkit2gtk::FindOptions::WRAP_AROUND, options.wrap_around);
+    flags.set(webkit2gtk::FindOptions::AT_WORD_STARTS, options.whole_word);
+
+    self.find_handler.replace(Some(
+      controller.connect_counted_matches(move |_, count| callback(count)),
+    ));
+    controller.count_matches(query, flags.bits(), u32::MAX);
+    controller.search(query, flags.bits(), u32::MAX);
+    Ok(())
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    if let Some(controller) = self.webview.find_controller() {
+      controller.search_next();
+    }
+    Ok(())
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    if let Some(controller) = self.webview.find_controller() {
+      controller.search_previous();
+    }
+    Ok(())
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    if let Some(controller) = self.webview.find_controller() {
+      if let Some(handler) = self.find_handler.take() {
+        controller.disconnect(handler);
+      }
+      controller.search_finish();
+    }
+    Ok(())
+  }
+
+  pub fn select_all(&self) -> Result<()> {
+    self.webview.execute_editing_command("SelectAll");
+    Ok(())
+  }
+
+  pub fn clear_browsing_data<F>(&self, data: BrowsingDataFlags, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    use webkit2gtk::WebsiteDataTypes;
+
+    let mut types = WebsiteDataTypes::empty();
+    for (flag, type_) in [
+      (BrowsingDataFlags::COOKIES, WebsiteDataTypes::COOKIES),
+      (
+        BrowsingDataFlags::LOCAL_STORAGE,
+        WebsiteDataTypes::LOCAL_STORAGE | WebsiteDataTypes::SESSION_STORAGE,
+      ),
+      (
+        BrowsingDataFlags::INDEXED_DB,
+        WebsiteDataTypes::INDEXEDDB_DATABASES,
+      ),
+      (
+        BrowsingDataFlags::CACHE,
+        WebsiteDataTypes::DISK_CACHE
+          | WebsiteDataTypes::MEMORY_CACHE
+          | WebsiteDataTypes::OFFLINE_APPLICATION_CACHE,
+      ),
+      (
+        BrowsingDataFlags::SERVICE_WORKERS,
+        WebsiteDataTypes::SERVICE_WORKER_REGISTRATIONS,
+      ),
+      (
+        BrowsingDataFlags::WEB_SQL,
+        WebsiteDataTypes::WEBSQL_DATABASES,
+      ),
+    ] {
+      if data.contains(flag) {
+        types |= type_;
+      }
+    }
+
+    let data_manager = self
+      .webview
+      .context()
+      .and_then(|context| context.website_data_manager())
+      .ok_or(Error::MissingManager)?;
+    data_manager.clear(
+      types,
+      gtk::glib::TimeSpan::from_seconds(0),
+      None::<&Cancellable>,
+      move |result| completion(result.map_err(Into::into)),
+    );
+
+    Ok(())
+  }
+
   pub fn bounds(&self) -> Result<Rect> {
     let mut bounds = Rect::default();
 
@@ -838,16 +1910,20 @@ impl InnerWebView {
       return Ok(bounds);
     }
 
-    let (size, _) = self.webview.allocated_size();
-    bounds.size = LogicalSize::new(size.width(), size.height()).into();
+    let allocation = self.webview.allocation();
+    if self.is_in_fixed_parent {
+      bounds.position = LogicalPosition::new(allocation.x(), allocation.y()).into();
+    }
+    bounds.size = LogicalSize::new(allocation.width(), allocation.height()).into();
 
     Ok(bounds)
   }
 
   pub fn set_bounds(&self, bounds: Rect) -> Result<()> {
     let scale_factor = self.webview.scale_factor() as f64;
-    let (width, height) = bounds.size.to_logical::<i32>(scale_factor).into();
-    let (x, y) = bounds.position.to_logical::<i32>(scale_factor).into();
+    let (position, size) = bounds.to_logical(scale_factor);
+    let (x, y) = position.into();
+    let (width, height) = size.into();
 
     #[cfg(feature = "x11")]
     if let Some(x11_data) = &self.x11 {
@@ -910,6 +1986,127 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn is_visible(&self) -> Result<bool> {
+    Ok(self.webview.is_visible())
+  }
+
+  pub fn set_corner_radius(&self, radius: f64) -> Result<()> {
+    let is_connected = self.corner_radius.replace(Some(radius)).is_some();
+
+    // The shape has to follow the size of the webview
+    if !is_connected {
+      let corner_radius = self.corner_radius.clone();
+      self
+        .webview
+        .connect_size_allocate(move |webview, allocation| {
+          if let Some(radius) = corner_radius.get().filter(|radius| *radius > 0.) {
+            set_rounded_shape(webview, (allocation.width(), allocation.height()), radius);
+          }
+        });
+    }
+
+    if radius > 0. {
+      let allocation = self.webview.allocation();
+      set_rounded_shape(
+        &self.webview,
+        (allocation.width(), allocation.height()),
+        radius,
+      );
+    } else {
+      self.webview.shape_combine_region(None);
+    }
+
+    Ok(())
+  }
+
+  pub fn bring_to_front(&self) -> Result<()> {
+    #[cfg(feature = "x11")]
+    if let Some(x11_data) = &self.x11 {
+      if x11_data.is_child {
+        unsafe { (x11_data.xlib.XRaiseWindow)(x11_data.x11_display as _, x11_data.x11_window) };
+      }
+    }
+
+    if let Some(window) = self.webview.window() {
+      window.raise();
+    }
+
+    Ok(())
+  }
+
+  pub fn send_to_back(&self) -> Result<()> {
+    #[cfg(feature = "x11")]
+    if let Some(x11_data) = &self.x11 {
+      if x11_data.is_child {
+        unsafe { (x11_data.xlib.XLowerWindow)(x11_data.x11_display as _, x11_data.x11_window) };
+      }
+    }
+
+    if let Some(window) = self.webview.window() {
+      window.lower();
+    }
+
+    Ok(())
+  }
+
+  pub fn suspend<F>(&self, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    if self.muted_before_suspend.get().is_none() {
+      self.muted_before_suspend.set(Some(self.webview.is_muted()));
+    }
+    self.webview.set_is_muted(true);
+    completion(Ok(()));
+    Ok(())
+  }
+
+  pub fn resume(&self) -> Result<()> {
+    if let Some(muted) = self.muted_before_suspend.take() {
+      self.webview.set_is_muted(muted);
+    }
+    Ok(())
+  }
+
+  pub fn set_muted(&self, muted: bool) -> Result<()> {
+    // A suspended webview stays muted until it is resumed
+    if self.muted_before_suspend.get().is_some() {
+      self.muted_before_suspend.set(Some(muted));
+    } else {
+      self.webview.set_is_muted(muted);
+    }
+    Ok(())
+  }
+
+  pub fn is_muted(&self) -> Result<bool> {
+    Ok(
+      self
+        .muted_before_suspend
+        .get()
+        .unwrap_or_else(|| self.webview.is_muted()),
+    )
+  }
+
+  pub fn is_loading(&self) -> Result<bool> {
+    Ok(self.webview.is_loading())
+  }
+
+  pub fn memory_usage(&self) -> Result<MemoryUsage> {
+    Err(Error::Unsupported(
+      "WebKitGTK doesn't expose the web process of a webview".into(),
+    ))
+  }
+
+  pub fn set_memory_usage_level(&self, _level: MemoryUsageLevel) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("Memory usage levels are unsupported on this platform");
+    Ok(())
+  }
+
+  pub fn memory_usage_level(&self) -> Result<MemoryUsageLevel> {
+    Ok(MemoryUsageLevel::Normal)
+  }
+
   pub fn focus(&self) -> Result<()> {
     self.webview.grab_focus();
     Ok(())
@@ -923,6 +2120,34 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn blur(&self) -> Result<()> {
+    if let Some(window) = self
+      .webview
+      .toplevel()
+      .and_then(|toplevel| toplevel.downcast::<gtk::Window>().ok())
+    {
+      if self.webview.has_focus() {
+        window.set_focus(None::<&gtk::Widget>);
+      }
+    }
+
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop")]
+  pub fn set_drag_drop_handler(
+    &self,
+    handler: Box<dyn Fn(crate::DragDropEvent) -> DragOperation>,
+  ) -> Result<()> {
+    self.drag_drop_handler.set(handler);
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop-simulation")]
+  pub fn simulate_drag_drop(&self, event: crate::DragDropEvent) -> DragOperation {
+    self.drag_drop_handler.call(event)
+  }
+
   fn cookie_from_soup_cookie(mut cookie: soup::Cookie) -> cookie::Cookie<'static> {
     let name = cookie.name().map(|n| n.to_string()).unwrap_or_default();
     let value = cookie.value().map(|n| n.to_string()).unwrap_or_default();
@@ -1099,6 +2324,50 @@ impl InnerWebView {
     }
   }
 
+  pub fn delete_cookies_for_url(&self, url: &str, name: &str) -> Result<()> {
+    let Some(cookies_manager) = self
+      .webview
+      .website_data_manager()
+      .and_then(|manager| manager.cookie_manager())
+    else {
+      return Ok(());
+    };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    cookies_manager.cookies(url, None::<&Cancellable>, move |cookies| {
+      let _ = tx.send(cookies);
+    });
+    let cookies = loop {
+      gtk::main_iteration();
+
+      if let Ok(response) = rx.try_recv() {
+        break response?;
+      }
+    };
+
+    // delete the soup cookies themselves, `cookie::Cookie` loses whether they are host-only
+    for mut cookie in cookies {
+      if cookie.name().as_deref() != Some(name) {
+        continue;
+      }
+
+      let (tx, rx) = std::sync::mpsc::channel();
+      cookies_manager.delete_cookie(&mut cookie, None::<&Cancellable>, move |ret| {
+        let _ = tx.send(ret);
+      });
+      loop {
+        gtk::main_iteration();
+
+        if let Ok(response) = rx.try_recv() {
+          response?;
+          break;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
   pub fn reparent<W>(&self, container: &W) -> Result<()>
   where
     W: gtk::prelude::IsA<gtk::Container>,
@@ -1141,6 +2410,121 @@ pub fn platform_webview_version() -> Result<String> {
   Ok(format!("{major}.{minor}.{patch}"))
 }
 
+/// GDK colors have channels from `0.0` to `1.0`.
+fn run_javascript(
+  webview: &WebView,
+  js: &str,
+  callback: Option<impl FnOnce(String) + Send + 'static>,
+) {
+  let cancellable: Option<&Cancellable> = None;
+
+  #[cfg(feature = "tracing")]
+  let span = SendEnteredSpan(tracing::debug_span!("wry::eval").entered());
+
+  webview.run_javascript(js, cancellable, |result| {
+    #[cfg(feature = "tracing")]
+    drop(span);
+
+    if let Some(callback) = callback {
+      let result = match result {
+        Ok(r) => r
+          .js_value()
+          .and_then(|js| js.to_json(0))
+          .unwrap_or_default()
+          .to_string(),
+        Err(e) => crate::script::error_json(e.message()),
+      };
+
+      callback(result);
+    }
+  });
+}
+
+fn gdk_rgba((red, green, blue, alpha): RGBA) -> gdk::RGBA {
+  gdk::RGBA::new(
+    red as f64 / 255.0,
+    green as f64 / 255.0,
+    blue as f64 / 255.0,
+    alpha as f64 / 255.0,
+  )
+}
+
+fn set_rounded_shape(webview: &WebView, size: (i32, i32), radius: f64) {
+  let rectangles = rounded_rectangles(size, radius.round() as i32)
+    .into_iter()
+    .map(|(x, y, width, height)| gtk::cairo::RectangleInt::new(x, y, width, height))
+    .collect::<Vec<_>>();
+  webview.shape_combine_region(Some(&gtk::cairo::Region::create_rectangles(&rectangles)));
+}
+
+/// Approximates a rounded rectangle with one rectangle per row of the corners,
+/// GTK shapes are made of whole pixels.
+fn rounded_rectangles((width, height): (i32, i32), radius: i32) -> Vec<(i32, i32, i32, i32)> {
+  let radius = radius.min(width / 2).min(height / 2).max(0);
+  let mut rectangles = Vec::with_capacity(radius as usize * 2 + 1);
+  for y in 0..radius {
+    let dy = radius as f64 - y as f64 - 0.5;
+    let inset = (radius as f64 - (radius as f64 * radius as f64 - dy * dy).sqrt()).round() as i32;
+    rectangles.push((inset, y, width - 2 * inset, 1));
+    rectangles.push((inset, height - y - 1, width - 2 * inset, 1));
+  }
+  rectangles.push((0, radius, width, height - 2 * radius));
+  rectangles
+}
+
+/// Whether `request` asks for a TLS client certificate rather than credentials.
+fn is_client_certificate_request(request: &webkit2gtk::AuthenticationRequest) -> bool {
+  matches!(
+    request.scheme(),
+    AuthenticationScheme::ClientCertificateRequested
+      | AuthenticationScheme::ClientCertificatePinRequested
+  )
+}
+
+/// Sets the proxy of all the webviews of `context`, `None` uses the system proxy.
+fn set_network_proxy(context: &webkit2gtk::WebContext, proxy: Option<&ProxyConfig>) {
+  let Some(website_data_manager) = context.website_data_manager() else {
+    return;
+  };
+
+  match proxy {
+    Some(proxy) => {
+      let endpoint = proxy.endpoint();
+      // glib reads the credentials from the user info of the proxy uri
+      let user_info = endpoint
+        .auth
+        .as_ref()
+        .map(|auth| {
+          format!(
+            "{}:{}@",
+            utf8_percent_encode(&auth.username, NON_ALPHANUMERIC),
+            utf8_percent_encode(&auth.password, NON_ALPHANUMERIC)
+          )
+        })
+        .unwrap_or_default();
+      let proxy_uri = format!(
+        "{}://{user_info}{}:{}",
+        proxy.scheme(),
+        endpoint.host,
+        endpoint.port
+      );
+      let mut settings = NetworkProxySettings::new(Some(proxy_uri.as_str()), &[]);
+      website_data_manager
+        .set_network_proxy_settings(NetworkProxyMode::Custom, Some(&mut settings));
+    }
+    None => website_data_manager.set_network_proxy_settings(NetworkProxyMode::Default, None),
+  }
+}
+
+/// The background color configured by [`WebViewAttributes::transparent`] or [`WebViewAttributes::background_color`].
+fn background_color(attributes: &WebViewAttributes) -> Option<gdk::RGBA> {
+  if attributes.transparent {
+    Some(gdk::RGBA::new(0., 0., 0., 0.))
+  } else {
+    attributes.background_color.map(gdk_rgba)
+  }
+}
+
 // SAFETY: only use this when you are sure the span will be dropped on the same thread it was entered
 #[cfg(feature = "tracing")]
 struct SendEnteredSpan(tracing::span::EnteredSpan);
@@ -1241,3 +2625,44 @@ mod ffi {
     ) -> *mut glib::ffi::GList;
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn transparent_background_color() {
+    let attributes = WebViewAttributes {
+      transparent: true,
+      background_color: Some((255, 255, 255, 255)),
+      ..Default::default()
+    };
+    let color = background_color(&attributes).unwrap();
+    assert_eq!(color.alpha(), 0.);
+
+    let attributes = WebViewAttributes {
+      background_color: Some((255, 0, 0, 51)),
+      ..Default::default()
+    };
+    let color = background_color(&attributes).unwrap();
+    assert_eq!((color.red(), color.alpha()), (1., 0.2));
+
+    assert!(background_color(&WebViewAttributes::default()).is_none());
+  }
+
+  #[test]
+  fn rounded_rectangles_corners() {
+    assert_eq!(rounded_rectangles((100, 50), 0), vec![(0, 0, 100, 50)]);
+
+    let rectangles = rounded_rectangles((100, 50), 10);
+    assert_eq!(rectangles.len(), 21);
+    // the first row is inset the most, the middle covers the full width
+    assert!(rectangles[0].0 > rectangles[18].0);
+    assert_eq!(rectangles[0].1, 0);
+    assert_eq!(rectangles[1].1, 49);
+    assert_eq!(rectangles[20], (0, 10, 100, 30));
+
+    // the radius is clamped to half the smallest side
+    assert_eq!(rounded_rectangles((10, 4), 10).last(), Some(&(0, 2, 10, 0)));
+  }
+}
@@ -4,21 +4,24 @@
 
 //! Unix platform extensions for [`WebContext`](super::WebContext).
 
-use crate::{Error, RequestAsyncResponder};
-use gtk::glib::{self, MainContext, ObjectExt};
+use crate::{Download, DownloadHandler, Error, RequestAsyncResponder, ResponseBody};
+use gtk::{
+  gio::{InputStream, MemoryInputStream, ReadInputStream},
+  glib::{self, Cast, MainContext, ObjectExt},
+};
 use http::{header::CONTENT_TYPE, HeaderName, HeaderValue, Request, Response as HttpResponse};
 use soup::{MessageHeaders, MessageHeadersType};
 use std::{
-  borrow::Cow,
-  cell::RefCell,
+  cell::Cell,
   env::current_dir,
   path::{Path, PathBuf},
   rc::Rc,
 };
 use webkit2gtk::{
   ApplicationInfo, AutomationSessionExt, CookiePersistentStorage, DownloadExt, SecurityManagerExt,
-  URIRequest, URIRequestExt, URISchemeRequest, URISchemeRequestExt, URISchemeResponse,
-  URISchemeResponseExt, WebContext, WebContextExt as Webkit2gtkContextExt, WebView, WebViewExt,
+  URIRequest, URIRequestExt, URIResponseExt, URISchemeRequest, URISchemeRequestExt,
+  URISchemeResponse, URISchemeResponseExt, WebContext, WebContextExt as Webkit2gtkContextExt,
+  WebView, WebViewExt,
 };
 
 #[derive(Debug)]
@@ -97,11 +100,19 @@ impl WebContextImpl {
     self.context.set_automation_allowed(flag);
   }
 
+  pub fn set_isolated_processes(&mut self, _isolated: bool) {}
+
   pub fn set_web_extensions_directory(&mut self, path: &Path) {
     self
       .context
       .set_web_extensions_directory(&path.to_string_lossy());
   }
+
+  pub fn add_content_rules(&mut self, _identifier: &str, _json: &str) -> crate::Result<()> {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("Content rules '{_identifier}' ignored, content blocking is unsupported");
+    Ok(())
+  }
 }
 
 /// [`WebContext`](super::WebContext) items that only matter on unix.
@@ -124,11 +135,7 @@ pub trait WebContextExt {
 
   fn register_automation(&mut self, webview: WebView);
 
-  fn register_download_handler(
-    &mut self,
-    download_started_callback: Option<Box<dyn FnMut(String, &mut PathBuf) -> bool>>,
-    download_completed_callback: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
-  );
+  fn register_download_handler(&mut self, download_handler: DownloadHandler);
 }
 
 impl WebContextExt for super::WebContext {
@@ -229,24 +236,35 @@ impl WebContextExt for super::WebContext {
         };
 
         let request_ = MainThreadRequest(request.clone());
-        let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
+        let responder: Box<dyn FnOnce(HttpResponse<ResponseBody>)> =
           Box::new(move |http_response| {
             MainContext::default().invoke(move || {
-              let buffer = http_response.body();
-              let input = gtk::gio::MemoryInputStream::from_bytes(&gtk::glib::Bytes::from(buffer));
-              let content_type = http_response
-                .headers()
+              let (parts, body) = http_response.into_parts();
+              let (input, len): (InputStream, i64) = match body {
+                ResponseBody::Bytes(buffer) => {
+                  let len = buffer.len() as i64;
+                  let input = MemoryInputStream::from_bytes(&glib::Bytes::from(&buffer));
+                  (input.upcast(), len)
+                }
+                // WebKit reads the stream as the page consumes it, -1 means the length is unknown
+                ResponseBody::Stream(body) => {
+                  let len = body.len().map_or(-1, |len| len as i64);
+                  (ReadInputStream::new(body).upcast(), len)
+                }
+              };
+              let content_type = parts
+                .headers
                 .get(CONTENT_TYPE)
                 .and_then(|h| h.to_str().ok());
 
-              let response = URISchemeResponse::new(&input, buffer.len() as i64);
-              response.set_status(http_response.status().as_u16() as u32, None);
+              let response = URISchemeResponse::new(&input, len);
+              response.set_status(parts.status.as_u16() as u32, None);
               if let Some(content_type) = content_type {
                 response.set_content_type(content_type);
               }
 
               let headers = MessageHeaders::new(MessageHeadersType::Response);
-              for (name, value) in http_response.headers().into_iter() {
+              for (name, value) in parts.headers.iter() {
                 headers.append(name.as_str(), value.to_str().unwrap_or(""));
               }
               response.set_http_headers(headers);
@@ -281,11 +299,8 @@ impl WebContextExt for super::WebContext {
       let req = URIRequest::builder().uri(&uri).build();
 
       if let Some(ref mut req_headers) = req.http_headers() {
-        for (header, value) in headers.iter() {
-          req_headers.append(
-            header.to_string().as_str(),
-            value.to_str().unwrap_or_default(),
-          );
+        for (header, value) in crate::navigation::request_headers(&headers) {
+          req_headers.append(header, &value);
         }
       }
 
@@ -317,90 +332,99 @@ impl WebContextExt for super::WebContext {
     }
   }
 
-  fn register_download_handler(
-    &mut self,
-    download_started_handler: Option<Box<dyn FnMut(String, &mut PathBuf) -> bool>>,
-    download_completed_handler: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
-  ) {
+  fn register_download_handler(&mut self, download_handler: DownloadHandler) {
     let context = &self.os.context;
 
-    let download_started_handler = Rc::new(RefCell::new(download_started_handler));
-    let failed = Rc::new(RefCell::new(false));
+    let download_handler = Rc::new(download_handler);
 
     context.connect_download_started(move |_context, download| {
-      let download_started_handler = download_started_handler.clone();
-      download.connect_decide_destination(move |download, suggested_filename| {
-        if let Some(uri) = download.request().and_then(|req| req.uri()) {
-          let uri = uri.to_string();
-
-          if let Some(download_started_handler) = download_started_handler.borrow_mut().as_mut() {
-            let mut download_destination =
-              dirs::download_dir().unwrap_or_else(|| current_dir().unwrap_or_default());
-
-            let (mut suggested_filename, ext) = suggested_filename
-              .split_once('.')
-              .map(|(base, ext)| (base, format!(".{ext}")))
-              .unwrap_or((suggested_filename, "".to_string()));
-
-            // For `data:` downloads, webkitgtk will suggest to use the raw data as the filename if the dev provided no name,
-            // for example `"data:attachment/text,sometext"` will result in `text,sometext` but longer data URLs will
-            // result in a cut-off filename, which makes it hard to predict reliably.
-            // TODO: If this keeps causing problems, just remove it and use whatever file name webkitgtk suggests.
-            if uri.starts_with("data:") {
-              if let Some((_, uri_stripped)) = uri.split_once('/') {
-                if let Some((uri_stripped, _)) = uri_stripped.split_once(',') {
-                  if suggested_filename.starts_with(&format!("{uri_stripped},")) {
-                    suggested_filename = "Unknown";
-                  }
+      let Some(uri) = download.request().and_then(|req| req.uri()) else {
+        return;
+      };
+      let handle = Download::new(uri.to_string(), {
+        let download = download.downgrade();
+        move || {
+          if let Some(download) = download.upgrade() {
+            download.cancel();
+          }
+        }
+      });
+
+      download.connect_decide_destination({
+        let download_handler = download_handler.clone();
+        let handle = handle.clone();
+        move |download, suggested_filename| {
+          let uri = handle.url();
+          let mut download_destination =
+            dirs::download_dir().unwrap_or_else(|| current_dir().unwrap_or_default());
+
+          let (mut suggested_filename, ext) = suggested_filename
+            .split_once('.')
+            .map(|(base, ext)| (base, format!(".{ext}")))
+            .unwrap_or((suggested_filename, "".to_string()));
+
+          // For `data:` downloads, webkitgtk will suggest to use the raw data as the filename if the dev provided no name,
+          // for example `"data:attachment/text,sometext"` will result in `text,sometext` but longer data URLs will
+          // result in a cut-off filename, which makes it hard to predict reliably.
+          // TODO: If this keeps causing problems, just remove it and use whatever file name webkitgtk suggests.
+          if uri.starts_with("data:") {
+            if let Some((_, uri_stripped)) = uri.split_once('/') {
+              if let Some((uri_stripped, _)) = uri_stripped.split_once(',') {
+                if suggested_filename.starts_with(&format!("{uri_stripped},")) {
+                  suggested_filename = "Unknown";
                 }
               }
             }
+          }
 
-            download_destination.push(format!("{suggested_filename}{ext}"));
+          download_destination.push(format!("{suggested_filename}{ext}"));
 
-            // WebView2 does not overwrite files but appends numbers
-            let mut counter = 1;
-            while download_destination.exists() {
-              download_destination.set_file_name(format!("{suggested_filename} ({counter}){ext}"));
-              counter += 1;
-            }
+          // WebView2 does not overwrite files but appends numbers
+          let mut counter = 1;
+          while download_destination.exists() {
+            download_destination.set_file_name(format!("{suggested_filename} ({counter}){ext}"));
+            counter += 1;
+          }
 
-            if download_started_handler(uri, &mut download_destination) {
-              download.set_destination(&download_destination.to_string_lossy());
-            } else {
-              download.cancel();
-            }
+          match download_handler.started(&handle, download_destination) {
+            Some(destination) => download.set_destination(&destination.to_string_lossy()),
+            None => download.cancel(),
           }
+          // TODO: check if we may also need `false`
+          true
         }
-        // TODO: check if we may also need `false`
-        true
       });
 
+      if download_handler.has_progress() {
+        download.connect_received_data({
+          let download_handler = download_handler.clone();
+          let handle = handle.clone();
+          move |download, _length| {
+            let total_bytes = download
+              .response()
+              .map(|response| response.content_length())
+              .filter(|length| *length > 0);
+            download_handler.progress(&handle, download.received_data_length(), total_bytes);
+          }
+        });
+      }
+
+      // `failed` is emitted before `finished`, also for cancelled downloads
+      let failed = Rc::new(Cell::new(false));
       download.connect_failed({
         let failed = failed.clone();
-        move |_, _error| {
-          *failed.borrow_mut() = true;
-        }
+        move |_, _error| failed.set(true)
       });
 
-      if let Some(download_completed_handler) = download_completed_handler.clone() {
-        download.connect_finished({
-          let failed = failed.clone();
-          move |download| {
-            if let Some(uri) = download.request().and_then(|req| req.uri()) {
-              let failed = *failed.borrow();
-              let uri = uri.to_string();
-              download_completed_handler(
-                uri,
-                (!failed)
-                  .then(|| download.destination().map(PathBuf::from))
-                  .flatten(),
-                !failed,
-              )
-            }
-          }
-        });
-      }
+      download.connect_finished({
+        let download_handler = download_handler.clone();
+        move |download| {
+          let destination = (!failed.get())
+            .then(|| download.destination().map(PathBuf::from))
+            .flatten();
+          download_handler.completed(&handle, destination);
+        }
+      });
     });
   }
 }
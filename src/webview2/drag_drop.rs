@@ -4,7 +4,7 @@
 
 // A silly implementation of file drop handling for Windows!
 
-use crate::DragDropEvent;
+use crate::{DragData, DragDropEvent, DragOperation, DragOperations};
 
 // TiddlyDesktop: FFI functions for internal drag detection
 // These are defined in TiddlyDesktop's windows.rs and linked at build time.
@@ -66,15 +66,18 @@ use std::{
 
 use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2CompositionController3;
 use windows::{
-  core::{implement, BOOL},
+  core::{implement, w, BOOL},
   Win32::{
     Foundation::{DRAGDROP_E_INVALIDHWND, HWND, LPARAM, POINT, POINTL},
     Graphics::Gdi::ScreenToClient,
     System::{
-      Com::{IDataObject, DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL},
+      Com::{IDataObject, DATADIR_GET, DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL},
+      DataExchange::{GetClipboardFormatNameW, RegisterClipboardFormatW},
+      Memory::{GlobalLock, GlobalSize, GlobalUnlock},
       Ole::{
-        IDropTarget, IDropTarget_Impl, RegisterDragDrop, RevokeDragDrop, CF_HDROP, DROPEFFECT,
-        DROPEFFECT_COPY, DROPEFFECT_NONE,
+        IDropTarget, IDropTarget_Impl, RegisterDragDrop, ReleaseStgMedium, RevokeDragDrop,
+        CF_HDROP, CF_UNICODETEXT, DROPEFFECT, DROPEFFECT_COPY, DROPEFFECT_LINK, DROPEFFECT_MOVE,
+        DROPEFFECT_NONE,
       },
       SystemServices::MODIFIERKEYS_FLAGS,
     },
@@ -85,6 +88,99 @@ use windows::{
   },
 };
 
+/// Reads the `TYMED_HGLOBAL` data of the given clipboard format.
+unsafe fn hglobal_data(data_obj: &IDataObject, format: u16) -> Option<Vec<u8>> {
+  let data_format = FORMATETC {
+    cfFormat: format,
+    ptd: ptr::null_mut(),
+    dwAspect: DVASPECT_CONTENT.0,
+    lindex: -1,
+    tymed: TYMED_HGLOBAL.0 as u32,
+  };
+
+  let mut medium = data_obj.GetData(&data_format).ok()?;
+  let hglobal = medium.u.hGlobal;
+  let locked = GlobalLock(hglobal) as *const u8;
+  let bytes = if locked.is_null() {
+    None
+  } else {
+    let bytes = std::slice::from_raw_parts(locked, GlobalSize(hglobal)).to_vec();
+    let _ = GlobalUnlock(hglobal);
+    Some(bytes)
+  };
+  ReleaseStgMedium(&mut medium);
+  bytes
+}
+
+/// Decodes a null-terminated UTF-16 string.
+fn utf16_string(bytes: &[u8]) -> String {
+  let wide = bytes
+    .chunks_exact(2)
+    .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+    .take_while(|c| *c != 0)
+    .collect::<Vec<_>>();
+  String::from_utf16_lossy(&wide)
+}
+
+unsafe fn read_drag_data(data_obj: &IDataObject) -> DragData {
+  let mut data = DragData {
+    text: hglobal_data(data_obj, CF_UNICODETEXT.0).map(|bytes| utf16_string(&bytes)),
+    ..Default::default()
+  };
+
+  let url_format = RegisterClipboardFormatW(w!("UniformResourceLocatorW"));
+  if let Some(bytes) = hglobal_data(data_obj, url_format as u16) {
+    data.urls.push(utf16_string(&bytes));
+  }
+
+  // Only registered formats (0xC000 and above) have a name we can expose
+  if let Ok(formats) = data_obj.EnumFormatEtc(DATADIR_GET.0 as u32) {
+    let mut format = [FORMATETC::default()];
+    let mut fetched = 0u32;
+    while formats
+      .Next(&mut format, Some(&mut fetched as *mut _))
+      .is_ok()
+      && fetched == 1
+    {
+      let cf_format = format[0].cfFormat;
+      if cf_format < 0xC000 {
+        continue;
+      }
+      let mut name = [0u16; 256];
+      let len = GetClipboardFormatNameW(cf_format as u32, &mut name);
+      if len <= 0 {
+        continue;
+      }
+      if let Some(bytes) = hglobal_data(data_obj, cf_format) {
+        data
+          .raw
+          .insert(String::from_utf16_lossy(&name[..len as usize]), bytes);
+      }
+    }
+  }
+
+  data
+}
+
+fn source_operations(effect: DROPEFFECT) -> DragOperations {
+  DragOperations::new(
+    effect.contains(DROPEFFECT_COPY),
+    effect.contains(DROPEFFECT_MOVE),
+    effect.contains(DROPEFFECT_LINK),
+  )
+}
+
+/// Maps the operation returned by the listener, `None` means the drag should be handled natively.
+fn drop_effect(operation: DragOperation) -> Option<DROPEFFECT> {
+  match operation {
+    DragOperation::Native => None,
+    DragOperation::None => Some(DROPEFFECT_NONE),
+    DragOperation::Copy => Some(DROPEFFECT_COPY),
+    DragOperation::Move => Some(DROPEFFECT_MOVE),
+    DragOperation::Link => Some(DROPEFFECT_LINK),
+  }
+}
+
 // TiddlyDesktop: DragDropController not used with composition hosting mode
 // but kept for potential fallback to windowed mode
 #[allow(dead_code)]
@@ -96,7 +192,7 @@ pub(crate) struct DragDropController {
 #[allow(dead_code)]
 impl DragDropController {
   #[inline]
-  pub(crate) fn new(hwnd: HWND, handler: Box<dyn Fn(DragDropEvent) -> bool>) -> Self {
+  pub(crate) fn new(hwnd: HWND, handler: Box<dyn Fn(DragDropEvent) -> DragOperation>) -> Self {
     let mut controller = DragDropController::default();
 
     let handler = Rc::new(handler);
@@ -118,7 +214,11 @@ impl DragDropController {
   }
 
   #[inline]
-  fn inject_in_hwnd(&mut self, hwnd: HWND, handler: Rc<dyn Fn(DragDropEvent) -> bool>) -> bool {
+  fn inject_in_hwnd(
+    &mut self,
+    hwnd: HWND,
+    handler: Rc<dyn Fn(DragDropEvent) -> DragOperation>,
+  ) -> bool {
     let drag_drop_target: IDropTarget = DragDropTarget::new(hwnd, handler).into();
     if unsafe { RevokeDragDrop(hwnd) } != Err(DRAGDROP_E_INVALIDHWND.into())
       && unsafe { RegisterDragDrop(hwnd, &drag_drop_target) }.is_ok()
@@ -133,17 +233,19 @@ impl DragDropController {
 #[implement(IDropTarget)]
 pub struct DragDropTarget {
   hwnd: HWND,
-  listener: Rc<dyn Fn(DragDropEvent) -> bool>,
+  listener: Rc<dyn Fn(DragDropEvent) -> DragOperation>,
   cursor_effect: UnsafeCell<DROPEFFECT>,
+  paths: UnsafeCell<Vec<PathBuf>>,
   enter_is_valid: UnsafeCell<bool>, /* If the currently hovered item is not valid there must not be any `HoveredFileCancelled` emitted */
 }
 
 impl DragDropTarget {
-  pub fn new(hwnd: HWND, listener: Rc<dyn Fn(DragDropEvent) -> bool>) -> DragDropTarget {
+  pub fn new(hwnd: HWND, listener: Rc<dyn Fn(DragDropEvent) -> DragOperation>) -> DragDropTarget {
     Self {
       hwnd,
       listener,
       cursor_effect: DROPEFFECT_NONE.into(),
+      paths: Vec::new().into(),
       enter_is_valid: false.into(),
     }
   }
@@ -228,8 +330,9 @@ impl IDropTarget_Impl for DragDropTarget_Impl {
     let mut pt = POINT { x: pt.x, y: pt.y };
     let _ = unsafe { ScreenToClient(self.hwnd, &mut pt) };
 
+    let data_obj = pDataObj.ok()?;
     let mut paths = Vec::new();
-    let hdrop = unsafe { DragDropTarget::iterate_filenames(pDataObj, |path| paths.push(path)) };
+    let hdrop = unsafe { DragDropTarget::iterate_filenames_ref(data_obj, |path| paths.push(path)) };
 
     let enter_is_valid = hdrop.is_some();
 
@@ -237,20 +340,21 @@ impl IDropTarget_Impl for DragDropTarget_Impl {
       return Ok(());
     };
 
+    let data = unsafe { read_drag_data(data_obj) };
+
     unsafe {
       *self.enter_is_valid.get() = enter_is_valid;
+      *self.paths.get() = paths.clone();
     }
 
-    (self.listener)(DragDropEvent::Enter {
+    let operation = (self.listener)(DragDropEvent::Enter {
       paths,
       position: (pt.x as _, pt.y as _),
+      operations: source_operations(unsafe { *pdwEffect }),
+      data,
     });
 
-    let cursor_effect = if enter_is_valid {
-      DROPEFFECT_COPY
-    } else {
-      DROPEFFECT_NONE
-    };
+    let cursor_effect = drop_effect(operation).unwrap_or(DROPEFFECT_COPY);
 
     unsafe {
       *pdwEffect = cursor_effect;
@@ -269,9 +373,14 @@ impl IDropTarget_Impl for DragDropTarget_Impl {
     if unsafe { *self.enter_is_valid.get() } {
       let mut pt = POINT { x: pt.x, y: pt.y };
       let _ = unsafe { ScreenToClient(self.hwnd, &mut pt) };
-      (self.listener)(DragDropEvent::Over {
+      let operation = (self.listener)(DragDropEvent::Over {
+        paths: unsafe { (*self.paths.get()).clone() },
         position: (pt.x as _, pt.y as _),
+        operations: source_operations(unsafe { *pdwEffect }),
       });
+      if let Some(cursor_effect) = drop_effect(operation) {
+        unsafe { *self.cursor_effect.get() = cursor_effect };
+      }
     }
 
     unsafe { *pdwEffect = *self.cursor_effect.get() };
@@ -281,7 +390,9 @@ impl IDropTarget_Impl for DragDropTarget_Impl {
   fn DragLeave(&self) -> windows::core::Result<()> {
     if unsafe { *self.enter_is_valid.get() } {
       (self.listener)(DragDropEvent::Leave);
+      (self.listener)(DragDropEvent::Cancelled);
     }
+    unsafe { (*self.paths.get()).clear() };
     Ok(())
   }
 
@@ -307,6 +418,7 @@ impl IDropTarget_Impl for DragDropTarget_Impl {
         unsafe { DragFinish(hdrop) };
       }
     }
+    unsafe { (*self.paths.get()).clear() };
 
     Ok(())
   }
@@ -318,8 +430,9 @@ impl IDropTarget_Impl for DragDropTarget_Impl {
 pub struct CompositionDragDropTarget {
   hwnd: HWND,
   composition_controller: ICoreWebView2CompositionController3,
-  listener: Rc<dyn Fn(DragDropEvent) -> bool>,
+  listener: Rc<dyn Fn(DragDropEvent) -> DragOperation>,
   cursor_effect: UnsafeCell<DROPEFFECT>,
+  paths: UnsafeCell<Vec<PathBuf>>,
   enter_is_valid: UnsafeCell<bool>,
 }
 
@@ -327,13 +440,14 @@ impl CompositionDragDropTarget {
   pub fn new(
     hwnd: HWND,
     composition_controller: ICoreWebView2CompositionController3,
-    listener: Rc<dyn Fn(DragDropEvent) -> bool>,
+    listener: Rc<dyn Fn(DragDropEvent) -> DragOperation>,
   ) -> Self {
     Self {
       hwnd,
       composition_controller,
       listener,
       cursor_effect: DROPEFFECT_NONE.into(),
+      paths: Vec::new().into(),
       enter_is_valid: false.into(),
     }
   }
@@ -342,7 +456,7 @@ impl CompositionDragDropTarget {
   pub fn register(
     hwnd: HWND,
     composition_controller: ICoreWebView2CompositionController3,
-    handler: Box<dyn Fn(DragDropEvent) -> bool>,
+    handler: Box<dyn Fn(DragDropEvent) -> DragOperation>,
   ) -> windows::core::Result<IDropTarget> {
     let drop_target: IDropTarget = Self::new(hwnd, composition_controller, Rc::new(handler)).into();
     unsafe { RegisterDragDrop(hwnd, &drop_target)? };
@@ -378,15 +492,21 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
 
     unsafe {
       *self.enter_is_valid.get() = enter_is_valid;
+      *self.paths.get() = paths.clone();
     }
 
     // Call listener with Enter event (only if we have valid file paths and not internal tiddler/link drag)
-    if enter_is_valid && !skip_listener {
-      (self.listener)(DragDropEvent::Enter {
+    let operations = source_operations(unsafe { *pdwEffect });
+    let listener_effect = if enter_is_valid && !skip_listener {
+      drop_effect((self.listener)(DragDropEvent::Enter {
         paths,
         position: (point.x as _, point.y as _),
-      });
-    }
+        operations,
+        data: unsafe { read_drag_data(data_obj) },
+      }))
+    } else {
+      None
+    };
 
     // Forward to composition controller for HTML5 drag events
     // Skip ONLY for tiddler drags (fully handled by JS)
@@ -403,11 +523,11 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
       };
 
       // Determine cursor effect:
-      // - For external file drags (enter_is_valid): show COPY
+      // - For external file drags handled by the listener: use the listener's effect
       // - For internal drags (skip_listener): always COPY (JS handles drop acceptance)
       // - For other drags: use composition controller's effect
-      let cursor_effect = if enter_is_valid {
-        DROPEFFECT_COPY
+      let cursor_effect = if let Some(listener_effect) = listener_effect {
+        listener_effect
       } else if skip_listener {
         // For internal drags (tiddler/link), always allow dropping.
         // The JS layer ($droppable) handles whether the drop is actually accepted.
@@ -447,11 +567,15 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
     let skip_forwarding = should_skip_forwarding();
 
     // Call listener with Over event (only for file drags and not internal tiddler/link drag)
-    if unsafe { *self.enter_is_valid.get() } && !skip_listener {
-      (self.listener)(DragDropEvent::Over {
+    let listener_effect = if unsafe { *self.enter_is_valid.get() } && !skip_listener {
+      drop_effect((self.listener)(DragDropEvent::Over {
+        paths: unsafe { (*self.paths.get()).clone() },
         position: (point.x as _, point.y as _),
-      });
-    }
+        operations: source_operations(unsafe { *pdwEffect }),
+      }))
+    } else {
+      None
+    };
 
     // Forward to composition controller (skip ONLY for tiddler drags)
     if !skip_forwarding {
@@ -465,11 +589,11 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
       };
 
       // Determine cursor effect:
-      // - For external file drags (enter_is_valid): use cached COPY
+      // - For external file drags handled by the listener: use the listener's effect
       // - For internal drags (skip_listener): always COPY (JS handles drop acceptance)
       // - For other drags: use composition controller's effect
-      if unsafe { *self.enter_is_valid.get() } {
-        unsafe { *pdwEffect = *self.cursor_effect.get() };
+      if let Some(listener_effect) = listener_effect {
+        unsafe { *pdwEffect = listener_effect };
       } else if skip_listener {
         // For internal drags (tiddler/link), always allow dropping.
         // The JS layer ($droppable) handles whether the drop is actually accepted.
@@ -499,6 +623,7 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
     // Call listener with Leave event (if not internal tiddler/link drag)
     if unsafe { *self.enter_is_valid.get() } && !skip_listener {
       (self.listener)(DragDropEvent::Leave);
+      (self.listener)(DragDropEvent::Cancelled);
     }
 
     // Forward to composition controller (skip ONLY for tiddler drags)
@@ -506,6 +631,8 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
       let _ = unsafe { self.composition_controller.DragLeave() };
     }
 
+    unsafe { (*self.paths.get()).clear() };
+
     // TiddlyDesktop: Clear internal drag state
     unsafe { tiddlydesktop_clear_internal_drag(); }
 
@@ -561,6 +688,8 @@ impl IDropTarget_Impl for CompositionDragDropTarget_Impl {
       unsafe { (*pdwEffect).0 = effect };
     }
 
+    unsafe { (*self.paths.get()).clear() };
+
     // TiddlyDesktop: Clear internal drag state
     unsafe { tiddlydesktop_clear_internal_drag(); }
 
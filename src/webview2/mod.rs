@@ -3,14 +3,21 @@
 // SPDX-License-Identifier: MIT
 
 mod drag_drop;
+mod stream;
 mod util;
 
 use std::{
-  borrow::Cow, cell::RefCell, collections::HashSet, fmt::Write, fs, path::PathBuf, rc::Rc,
+  cell::{Cell, RefCell},
+  collections::HashSet,
+  ffi::c_void,
+  fmt::Write,
+  fs,
+  path::PathBuf,
+  rc::Rc,
   sync::mpsc,
 };
 
-use dpi::{PhysicalPosition, PhysicalSize};
+use dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
 use http::{Request, Response as HttpResponse, StatusCode};
 use once_cell::sync::Lazy;
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
@@ -27,8 +34,17 @@ use windows::{
       Direct3D11::{D3D11CreateDevice, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION},
       DirectComposition::{DCompositionCreateDevice, IDCompositionDevice, IDCompositionTarget, IDCompositionVisual},
       Dxgi::IDXGIDevice,
+      Imaging::{
+        CLSID_WICImagingFactory, GUID_ContainerFormatPng, IWICImagingFactory,
+        WICBitmapEncoderNoCache, WICDecodeMetadataCacheOnDemand, WICRect,
+      },
+    },
+    System::{
+      Com::*,
+      LibraryLoader::GetModuleHandleW,
+      ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+      Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
     },
-    System::{Com::*, LibraryLoader::GetModuleHandleW},
     UI::{
       Input::{
         Ime::{ImmGetContext, ImmReleaseContext, ImmSetCompositionWindow, COMPOSITIONFORM, CFS_POINT},
@@ -47,12 +63,27 @@ use windows::{
   },
 };
 
-use self::drag_drop::{CompositionDragDropTarget, DragDropController};
+use self::{
+  drag_drop::{CompositionDragDropTarget, DragDropController},
+  stream::BodyStream,
+};
 use super::Theme;
 use crate::{
-  custom_protocol_workaround, proxy::ProxyConfig, Error, MemoryUsageLevel, NewWindowFeatures,
-  NewWindowOpener, NewWindowResponse, PageLoadEvent, Rect, RequestAsyncResponder, Result,
-  WebViewAttributes, RGBA,
+  auth::AuthHandler,
+  content_mode::UserAgent,
+  custom_protocol_workaround,
+  drag_drop::DragDropHandler,
+  find,
+  proxy::ProxyConfig,
+  script::{self, InitScripts, PendingScripts},
+  AuthChallenge, AuthChallengeKind, AutoplayPolicy, BrowsingDataFlags, CertRequest, Certificate,
+  CertificateInfo, ContentMode, ContextMenuAction, ContextMenuMediaType, ContextMenuTarget,
+  DialogKind, DialogRequest, Download, DragOperation, Error, FindOptions, History, HistoryEntry,
+  KeyEvent, MemoryUsage, MemoryUsageLevel, Modifiers, NavigationDecision, NavigationError,
+  NewWindowFeatures, NewWindowOpener, NewWindowRequest, NewWindowResponse, PageLoadEvent,
+  PermissionKind, PermissionRequest, PermissionResponse, PrintSettings, PrintToPdfOptions, Rect,
+  RequestAction, RequestAsyncResponder, ResponseBody, Result, StylesheetLevel, WebViewAttributes,
+  RGBA,
 };
 
 type EventRegistrationToken = i64;
@@ -103,6 +134,23 @@ pub(crate) struct InnerWebView {
   // TiddlyDesktop: Composition mode drop target - must be kept alive
   #[allow(dead_code)]
   composition_drop_target: Option<windows::Win32::System::Ole::IDropTarget>,
+  #[cfg(feature = "drag-drop")]
+  drag_drop_handler: Rc<DragDropHandler>,
+  // The scripts evaluated before the first page was committed
+  pending_scripts: PendingScripts,
+  // Whether a navigation started and hasn't completed yet
+  loading: Rc<Cell<bool>>,
+  // The user agent, changed for the content mode by the navigation handler
+  user_agent: Rc<RefCell<UserAgent>>,
+  // The `Accept-Language` header of `set_accept_language`, and whether its handler is attached
+  accept_language: Rc<RefCell<Option<HSTRING>>>,
+  accept_language_handler: Cell<bool>,
+  // The ids WebView2 gave to the scripts added with `add_init_script`
+  init_scripts: InitScripts<String>,
+  // The ids WebView2 gave to the scripts injecting the stylesheets of `add_user_stylesheet`
+  stylesheets: InitScripts<String>,
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  devtools: bool,
 }
 
 impl Drop for InnerWebView {
@@ -114,6 +162,7 @@ impl Drop for InnerWebView {
     }
     // TiddlyDesktop: Detach container input subclass before destroying window
     unsafe { Self::dettach_container_input_subclass(self.hwnd) };
+    let _ = unsafe { RemovePropW(self.hwnd, w!("WRY_CORNER_RADIUS")) };
     if self.is_child {
       let _ = unsafe { DestroyWindow(self.hwnd) };
     }
@@ -160,8 +209,17 @@ impl InnerWebView {
 
     let hwnd = Self::create_container_hwnd(parent, &attributes, is_child)?;
 
-    let drop_handler = attributes.drag_drop_handler.take();
+    // No handler provided - use a handler accepting file drops
+    let drag_drop_handler = Rc::new(DragDropHandler::new(
+      attributes
+        .drag_drop_handler
+        .take()
+        .unwrap_or_else(|| Box::new(|_| DragOperation::Copy)),
+      attributes.drag_drop_handler_mode,
+    ));
     let bounds = attributes.bounds;
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    let devtools = attributes.devtools;
 
     let id = attributes
       .id
@@ -182,6 +240,9 @@ impl InnerWebView {
     // TiddlyDesktop: Use composition hosting for full input/drag-drop control
     let (controller, composition_controller, dcomp_device, dcomp_target, dcomp_visual, env_for_pointer) =
       Self::create_composition_controller(hwnd, &env, attributes.incognito, background_color)?;
+    let pending_scripts = script::pending_scripts(&attributes);
+    let loading = Rc::new(Cell::new(false));
+    let user_agent = Rc::new(RefCell::new(UserAgent::default()));
     let webview = Self::init_webview(
       parent,
       hwnd,
@@ -191,6 +252,9 @@ impl InnerWebView {
       &controller,
       pl_attrs,
       is_child,
+      &pending_scripts,
+      &loading,
+      &user_agent,
     )?;
 
     // TiddlyDesktop: Enable external drops - drag-drop is handled via composition controller forwarding
@@ -207,12 +271,13 @@ impl InnerWebView {
     // This both forwards drag events to WebView2 AND extracts file paths for the handler
     let composition_drop_target = if let Some(ref comp_ctrl) = composition_controller {
       comp_ctrl.cast::<ICoreWebView2CompositionController3>().ok().and_then(|ctrl3| {
-        if let Some(handler) = drop_handler {
-          CompositionDragDropTarget::register(hwnd, ctrl3, handler).ok()
-        } else {
-          // No handler provided - use a no-op handler
-          CompositionDragDropTarget::register(hwnd, ctrl3, Box::new(|_| true)).ok()
-        }
+        let drag_drop_handler = drag_drop_handler.clone();
+        CompositionDragDropTarget::register(
+          hwnd,
+          ctrl3,
+          Box::new(move |event| drag_drop_handler.call(event)),
+        )
+        .ok()
       })
     } else {
       None
@@ -317,6 +382,17 @@ impl InnerWebView {
       env_for_pointer,
       drag_drop_controller,
       composition_drop_target,
+      #[cfg(feature = "drag-drop")]
+      drag_drop_handler,
+      pending_scripts,
+      loading,
+      user_agent,
+      accept_language: Default::default(),
+      accept_language_handler: Cell::new(false),
+      init_scripts: InitScripts::new(),
+      stylesheets: InitScripts::new(),
+      #[cfg(any(debug_assertions, feature = "devtools"))]
+      devtools,
     };
 
     if is_child {
@@ -471,6 +547,17 @@ impl InnerWebView {
     attributes: &WebViewAttributes,
     pl_attrs: super::PlatformSpecificWebViewAttributes,
   ) -> Result<ICoreWebView2Environment> {
+    // Chromium ignores the credentials of `--proxy-server`
+    if attributes
+      .proxy_config
+      .as_ref()
+      .is_some_and(|proxy| proxy.endpoint().auth.is_some())
+    {
+      return Err(Error::UnsupportedProxy(
+        "WebView2 doesn't support proxy authentication".to_string(),
+      ));
+    }
+
     let data_directory = attributes
       .context
       .as_deref()
@@ -478,48 +565,69 @@ impl InnerWebView {
       .map(HSTRING::from);
 
     // additional browser args
-    let additional_browser_args = pl_attrs.additional_browser_args.unwrap_or_else(|| {
+    let mut additional_browser_args = pl_attrs.additional_browser_args.unwrap_or_else(|| {
       // remove "mini menu" - See https://github.com/tauri-apps/wry/issues/535
       // and "smart screen" - See https://github.com/tauri-apps/tauri/issues/1345
       // enable white flicker fix
       let default_args = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
       let mut arguments = String::from(default_args);
 
-      if attributes.autoplay {
-        arguments.push_str(" --autoplay-policy=no-user-gesture-required");
+      let autoplay_policy = attributes
+        .autoplay_policy
+        .or(attributes.autoplay.then_some(AutoplayPolicy::AllowAll));
+      match autoplay_policy {
+        Some(AutoplayPolicy::AllowAll) => {
+          arguments.push_str(" --autoplay-policy=no-user-gesture-required")
+        }
+        Some(AutoplayPolicy::RequireUserGesture) => {
+          arguments.push_str(" --autoplay-policy=user-gesture-required")
+        }
+        // Muted media can play without user activation by default
+        Some(AutoplayPolicy::AllowMutedOnly) | None => {}
       }
 
       if let Some(proxy_setting) = &attributes.proxy_config {
-        match proxy_setting {
-          ProxyConfig::Http(endpoint) => {
-            arguments.push_str(" --proxy-server=http://");
-            arguments.push_str(&endpoint.host);
-            arguments.push(':');
-            arguments.push_str(&endpoint.port);
-          }
-          ProxyConfig::Socks5(endpoint) => {
-            arguments.push_str(" --proxy-server=socks5://");
-            arguments.push_str(&endpoint.host);
-            arguments.push(':');
-            arguments.push_str(&endpoint.port);
-          }
-        };
+        let endpoint = proxy_setting.endpoint();
+        arguments.push_str(" --proxy-server=");
+        arguments.push_str(proxy_setting.scheme());
+        arguments.push_str("://");
+        arguments.push_str(&endpoint.host);
+        arguments.push(':');
+        arguments.push_str(&endpoint.port);
       }
 
       arguments
     });
 
+    if let Some(port) = attributes.remote_debugging_port {
+      additional_browser_args.push_str(&format!(" --remote-debugging-port={port}"));
+    }
+
+    let languages = attributes
+      .accept_language
+      .as_deref()
+      .map(crate::language::languages)
+      .filter(|languages| !languages.is_empty());
+    if let Some(languages) = &languages {
+      additional_browser_args.push_str(" --accept-lang=");
+      additional_browser_args.push_str(&languages.join(","));
+    }
+
     let (tx, rx) = mpsc::channel();
     let options = CoreWebView2EnvironmentOptions::default();
     unsafe {
       options.set_additional_browser_arguments(additional_browser_args);
       options.set_are_browser_extensions_enabled(pl_attrs.browser_extensions_enabled);
 
-      // Get user's system language
-      let lcid = GetUserDefaultUILanguage();
-      let mut lang = [0; MAX_LOCALE_NAME as usize];
-      LCIDToLocaleName(lcid as u32, Some(&mut lang), LOCALE_ALLOW_NEUTRAL_NAMES);
-      options.set_language(String::from_utf16_lossy(&lang));
+      if let Some(languages) = &languages {
+        options.set_language(languages[0].to_string());
+      } else {
+        // Get user's system language
+        let lcid = GetUserDefaultUILanguage();
+        let mut lang = [0; MAX_LOCALE_NAME as usize];
+        LCIDToLocaleName(lcid as u32, Some(&mut lang), LOCALE_ALLOW_NEUTRAL_NAMES);
+        options.set_language(String::from_utf16_lossy(&lang));
+      }
 
       let scroll_bar_style = match pl_attrs.scroll_bar_style {
         ScrollBarStyle::Default => COREWEBVIEW2_SCROLLBAR_STYLE_DEFAULT,
@@ -560,6 +668,10 @@ impl InnerWebView {
     let (tx, rx) = mpsc::channel();
     let env = env.clone();
     let env10 = env.cast::<ICoreWebView2Environment10>();
+    // falling back to a regular controller would persist the browsing data
+    if incognito && env10.is_err() {
+      return Err(Error::IncognitoUnsupported);
+    }
 
     // we don't use CreateCoreWebView2ControllerCompletedHandler::wait_for_async
     // as it uses an mspc::channel under the hood, so we can avoid using two channels
@@ -645,6 +757,10 @@ impl InnerWebView {
     let (tx, rx) = mpsc::channel();
     let env3: ICoreWebView2Environment3 = env.cast()?;
     let env10 = env.cast::<ICoreWebView2Environment10>();
+    // falling back to a regular controller would persist the browsing data
+    if incognito && env10.is_err() {
+      return Err(Error::IncognitoUnsupported);
+    }
 
     let handler = CreateCoreWebView2CompositionControllerCompletedHandler::create(Box::new(
       move |error_code, controller| {
@@ -726,6 +842,9 @@ impl InnerWebView {
     controller: &ICoreWebView2Controller,
     pl_attrs: super::PlatformSpecificWebViewAttributes,
     is_child: bool,
+    pending_scripts: &PendingScripts,
+    loading: &Rc<Cell<bool>>,
+    user_agent: &Rc<RefCell<UserAgent>>,
   ) -> Result<ICoreWebView2> {
     let webview = unsafe { controller.CoreWebView2()? };
 
@@ -761,10 +880,24 @@ impl InnerWebView {
     let mut token = EventRegistrationToken::default();
 
     // Webview Settings
-    unsafe { Self::set_webview_settings(&webview, &attributes, &pl_attrs)? };
+    *user_agent.borrow_mut() =
+      unsafe { Self::set_webview_settings(&webview, &attributes, &pl_attrs)? };
 
     // Webview handlers
-    unsafe { Self::attach_handlers(hwnd, &webview, &mut attributes, &mut token, env)? };
+    unsafe { Self::attach_handlers(hwnd, &webview, &mut attributes, &mut token, env, user_agent)? };
+
+    // Run the scripts evaluated before the first page was committed
+    unsafe { Self::attach_pending_scripts(&webview, pending_scripts.clone(), &mut token)? };
+
+    // Loading state and progress
+    unsafe {
+      Self::attach_load_progress(
+        &webview,
+        loading.clone(),
+        attributes.load_progress_handler.take(),
+        &mut token,
+      )?
+    };
 
     // IPC handler
     unsafe { Self::attach_ipc_handler(&webview, &mut attributes, &mut token)? };
@@ -776,7 +909,8 @@ impl InnerWebView {
       .iter()
       .map(|n| n.0.clone())
       .collect();
-    if !attributes.custom_protocols.is_empty() {
+    if !attributes.custom_protocols.is_empty() || attributes.web_resource_request_handler.is_some()
+    {
       unsafe {
         Self::attach_custom_protocol_handler(
           &webview,
@@ -792,11 +926,228 @@ impl InnerWebView {
 
     // Initialize main and subframe scripts
     for init_script in attributes.initialization_scripts {
+      let init_script = init_script.into_document_start();
       Self::add_script_to_execute_on_document_created(&webview, init_script.script)?;
     }
 
-    // Enable clipboard
-    if attributes.clipboard {
+    // Permission handler, clipboard read requests are left to the clipboard settings if any
+    if let Some(permission_handler) = attributes.permission_handler.take() {
+      let clipboard = attributes.clipboard || attributes.clipboard_policy.is_some();
+      unsafe {
+        webview.add_PermissionRequested(
+          &PermissionRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+
+            let mut kind = COREWEBVIEW2_PERMISSION_KIND::default();
+            args.PermissionKind(&mut kind)?;
+            let kind = match kind {
+              COREWEBVIEW2_PERMISSION_KIND_CAMERA => PermissionKind::Camera,
+              COREWEBVIEW2_PERMISSION_KIND_MICROPHONE => PermissionKind::Microphone,
+              COREWEBVIEW2_PERMISSION_KIND_GEOLOCATION => PermissionKind::Geolocation,
+              COREWEBVIEW2_PERMISSION_KIND_NOTIFICATIONS => PermissionKind::Notifications,
+              COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ if !clipboard => {
+                PermissionKind::ClipboardRead
+              }
+              _ => return Ok(()),
+            };
+
+            let mut uri = PWSTR::null();
+            args.Uri(&mut uri)?;
+            let response = permission_handler(PermissionRequest {
+              kind,
+              origin: crate::ipc::origin(&take_pwstr(uri)),
+            });
+
+            // Only saving allowed permissions keeps the handler asked for the others
+            if let Ok(args) = args.cast::<ICoreWebView2PermissionRequestedEventArgs3>() {
+              args.SetSavesInProfile(response == PermissionResponse::Allow)?;
+            }
+            args.SetState(match response {
+              PermissionResponse::Allow | PermissionResponse::AllowOnce => {
+                COREWEBVIEW2_PERMISSION_STATE_ALLOW
+              }
+              PermissionResponse::Deny => COREWEBVIEW2_PERMISSION_STATE_DENY,
+            })?;
+
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
+    }
+
+    // Authentication challenges, the handlers answer them instead of the WebView2 dialog
+    if let Some(auth_handler) = AuthHandler::new(
+      attributes.proxy_auth_handler.take(),
+      attributes.authentication_handler.take(),
+    ) {
+      let webview = webview.cast::<ICoreWebView2_10>()?;
+      unsafe {
+        webview.add_BasicAuthenticationRequested(
+          &BasicAuthenticationRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+
+            let mut uri = PWSTR::null();
+            args.Uri(&mut uri)?;
+            let uri = take_pwstr(uri).parse::<http::Uri>().unwrap_or_default();
+            let mut challenge = PWSTR::null();
+            args.Challenge(&mut challenge)?;
+
+            let default_port = if uri.scheme_str() == Some("http") {
+              80
+            } else {
+              443
+            };
+            // WebView2 doesn't report whether a proxy sent the challenge
+            let mut challenge = AuthChallenge {
+              kind: AuthChallengeKind::Server,
+              host: uri.host().unwrap_or_default().to_string(),
+              port: uri.port_u16().unwrap_or(default_port),
+              realm: crate::auth::challenge_realm(&take_pwstr(challenge)),
+              previous_failure_count: 0,
+            };
+            challenge.previous_failure_count =
+              auth_handler.previous_failure_count(&challenge, None);
+
+            let Some(handler) = auth_handler
+              .handler(AuthChallengeKind::Server)
+              .or_else(|| auth_handler.handler(AuthChallengeKind::Proxy))
+            else {
+              return Ok(());
+            };
+            match handler(challenge) {
+              Some(credentials) => {
+                let response = args.Response()?;
+                response.SetUserName(&HSTRING::from(credentials.username))?;
+                response.SetPassword(&HSTRING::from(credentials.password))?;
+              }
+              None => args.SetCancel(true)?,
+            }
+
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
+    }
+
+    // TLS client certificates, the handler picks them instead of the WebView2 dialog
+    if let Some(client_certificate_handler) = attributes.client_certificate_handler.take() {
+      let webview = webview.cast::<ICoreWebView2_5>()?;
+      unsafe {
+        webview.add_ClientCertificateRequested(
+          &ClientCertificateRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+
+            let mut host = PWSTR::null();
+            args.Host(&mut host)?;
+            let mut port = 0;
+            args.Port(&mut port)?;
+
+            let issuers = args.AllowedCertificateAuthorities()?;
+            let mut count = 0;
+            issuers.Count(&mut count)?;
+            let mut allowed_issuers = Vec::with_capacity(count as usize);
+            for i in 0..count {
+              let mut issuer = PWSTR::null();
+              issuers.GetValueAtIndex(i, &mut issuer)?;
+              allowed_issuers.push(take_pwstr(issuer));
+            }
+
+            let store_certificates = args.MutuallyTrustedCertificates()?;
+            let mut count = 0;
+            store_certificates.Count(&mut count)?;
+            let mut certificates = Vec::with_capacity(count as usize);
+            for i in 0..count {
+              let inner = store_certificates.GetValueAtIndex(i)?;
+              let mut subject = PWSTR::null();
+              inner.Subject(&mut subject)?;
+              certificates.push(Certificate {
+                subject: take_pwstr(subject),
+                inner,
+              });
+            }
+
+            let certificate = client_certificate_handler(CertRequest {
+              host: take_pwstr(host),
+              port: port as u16,
+              allowed_issuers,
+              certificates,
+            });
+            // Handled without a selected certificate continues without one
+            if let Some(certificate) = certificate {
+              args.SetSelectedCertificate(&certificate.inner)?;
+            }
+            args.SetHandled(true)?;
+
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
+    }
+
+    // TLS errors, the accepted certificates are allowed for their host until the webview is dropped
+    if let Some(tls_error_handler) = attributes.tls_error_handler.take() {
+      let webview = webview.cast::<ICoreWebView2_14>()?;
+      unsafe {
+        webview.add_ServerCertificateErrorDetected(
+          &ServerCertificateErrorDetectedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+
+            let mut uri = PWSTR::null();
+            args.RequestUri(&mut uri)?;
+            let uri = take_pwstr(uri).parse::<http::Uri>().unwrap_or_default();
+
+            let certificate = args.ServerCertificate()?;
+            let mut subject = PWSTR::null();
+            certificate.Subject(&mut subject)?;
+            let mut issuer = PWSTR::null();
+            certificate.Issuer(&mut issuer)?;
+            let mut pem = PWSTR::null();
+            certificate.ToPemEncoding(&mut pem)?;
+            let info = CertificateInfo {
+              subject: take_pwstr(subject),
+              issuer: take_pwstr(issuer),
+              pem: take_pwstr(pem),
+            };
+
+            if tls_error_handler(uri.host().unwrap_or_default(), &info) {
+              args.SetAction(COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_ALWAYS_ALLOW)?;
+            }
+
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
+    }
+
+    // Clipboard policy, pages of disallowed origins are denied instead of prompting the user
+    if let Some(policy) = attributes.clipboard_policy.take() {
+      unsafe {
+        webview.add_PermissionRequested(
+          &PermissionRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+
+            let mut kind = COREWEBVIEW2_PERMISSION_KIND::default();
+            args.PermissionKind(&mut kind)?;
+            if kind == COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ {
+              let mut uri = PWSTR::null();
+              args.Uri(&mut uri)?;
+              args.SetState(if policy.allows_read(&take_pwstr(uri)) {
+                COREWEBVIEW2_PERMISSION_STATE_ALLOW
+              } else {
+                COREWEBVIEW2_PERMISSION_STATE_DENY
+              })?;
+            }
+
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
+    } else if attributes.clipboard {
       unsafe {
         webview.add_PermissionRequested(
           &PermissionRequestedEventHandler::create(Box::new(|_, args| {
@@ -832,7 +1183,10 @@ impl InnerWebView {
         unsafe { webview.Navigate(&url)? };
       }
     } else if let Some(html) = attributes.html {
-      let html = HSTRING::from(html);
+      let html = match &attributes.html_base_url {
+        Some(base_url) => HSTRING::from(crate::util::html_with_base_url(&html, base_url)),
+        None => HSTRING::from(html),
+      };
       unsafe { webview.NavigateToString(&html)? };
     }
 
@@ -847,6 +1201,34 @@ impl InnerWebView {
       if attributes.focused {
         controller.MoveFocus(COREWEBVIEW2_MOVE_FOCUS_REASON_PROGRAMMATIC)?;
       }
+
+      #[cfg(any(debug_assertions, feature = "devtools"))]
+      if attributes.devtools && attributes.devtools_open_on_start {
+        webview.OpenDevToolsWindow()?;
+      }
+    }
+
+    // Focus changed handler
+    if let Some(on_focus_changed_handler) = attributes.on_focus_changed_handler.take() {
+      let on_focus_changed_handler = Rc::new(on_focus_changed_handler);
+      let on_focus_changed_handler_ = on_focus_changed_handler.clone();
+
+      unsafe {
+        controller.add_GotFocus(
+          &FocusChangedEventHandler::create(Box::new(move |_, _| {
+            on_focus_changed_handler_(true);
+            Ok(())
+          })),
+          &mut token,
+        )?;
+        controller.add_LostFocus(
+          &FocusChangedEventHandler::create(Box::new(move |_, _| {
+            on_focus_changed_handler(false);
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
     }
 
     // TiddlyDesktop: AcceleratorKeyPressed handler for multi-modifier shortcuts
@@ -854,6 +1236,7 @@ impl InnerWebView {
     // work correctly because the modifier state isn't properly passed to JavaScript.
     // We intercept these in AcceleratorKeyPressed, read the actual modifier state via
     // GetKeyState, and dispatch synthetic JavaScript keyboard events with correct modifiers.
+    let accelerator_passthrough = attributes.accelerator_passthrough.take();
     unsafe {
       let webview_clone = webview.clone();
       let mut accel_token = EventRegistrationToken::default();
@@ -892,6 +1275,25 @@ impl InnerWebView {
           let meta = (GetKeyState(VK_LWIN) & 0x8000u16 as i16) != 0 ||
                      (GetKeyState(VK_RWIN) & 0x8000u16 as i16) != 0;
 
+          // Give the host the first chance to handle the key press
+          if let Some(accelerator_passthrough) = &accelerator_passthrough {
+            if let Some(key) = crate::keyboard::key_from_virtual_key(vk) {
+              let event = KeyEvent {
+                key,
+                modifiers: Modifiers {
+                  shift,
+                  control: ctrl,
+                  alt,
+                  meta,
+                },
+              };
+              if accelerator_passthrough(&event) {
+                args.SetHandled(true)?;
+                return Ok(());
+              }
+            }
+          }
+
           // Count modifiers - only handle multi-modifier combos
           let modifier_count = ctrl as u8 + alt as u8 + shift as u8 + meta as u8;
           if modifier_count < 2 {
@@ -909,41 +1311,9 @@ impl InnerWebView {
           }
 
           // Convert virtual key to JavaScript key name
-          let key_name = match vk {
-            0x41..=0x5A => {
-              // A-Z keys - return lowercase letter
-              let c = ((vk as u8) + 32) as char;
-              format!("{}", c)
-            }
-            0x30..=0x39 => format!("{}", (vk - 0x30)), // 0-9 keys
-            0x70..=0x7B => format!("F{}", vk - 0x6F),  // F1-F12
-            0x20 => " ".to_string(),
-            0x0D => "Enter".to_string(),
-            0x1B => "Escape".to_string(),
-            0x09 => "Tab".to_string(),
-            0x08 => "Backspace".to_string(),
-            0x2E => "Delete".to_string(),
-            0x2D => "Insert".to_string(),
-            0x24 => "Home".to_string(),
-            0x23 => "End".to_string(),
-            0x21 => "PageUp".to_string(),
-            0x22 => "PageDown".to_string(),
-            0x25 => "ArrowLeft".to_string(),
-            0x26 => "ArrowUp".to_string(),
-            0x27 => "ArrowRight".to_string(),
-            0x28 => "ArrowDown".to_string(),
-            0xBF => "/".to_string(),
-            0xDC => "\\".to_string(),
-            0xBD => "-".to_string(),
-            0xBB => "=".to_string(),
-            0xDB => "[".to_string(),
-            0xDD => "]".to_string(),
-            0xBA => ";".to_string(),
-            0xDE => "'".to_string(),
-            0xBC => ",".to_string(),
-            0xBE => ".".to_string(),
-            0xC0 => "`".to_string(),
-            _ => return Ok(()), // Unknown key, let normal handling proceed
+          let Some(key_name) = crate::keyboard::key_from_virtual_key(vk) else {
+            // Unknown key, let normal handling proceed
+            return Ok(());
           };
 
           // Generate the code value (e.g., "KeyN" for 'n', "Digit1" for '1')
@@ -1009,18 +1379,31 @@ impl InnerWebView {
     webview: &ICoreWebView2,
     attributes: &WebViewAttributes,
     pl_attrs: &super::PlatformSpecificWebViewAttributes,
-  ) -> Result<()> {
+  ) -> Result<UserAgent> {
     let settings = webview.Settings()?;
     settings.SetIsStatusBarEnabled(false)?;
     settings.SetAreDefaultContextMenusEnabled(pl_attrs.default_context_menus)?;
     settings.SetIsZoomControlEnabled(attributes.zoom_hotkeys_enabled)?;
     settings.SetAreDevToolsEnabled(attributes.devtools)?;
     settings.SetIsScriptEnabled(!attributes.javascript_disabled)?;
+    // `ScriptDialogOpening` is only raised without the default dialogs
+    settings.SetAreDefaultScriptDialogsEnabled(attributes.dialog_handler.is_none())?;
 
-    if let Some(user_agent) = &attributes.user_agent {
-      if let Ok(settings2) = settings.cast::<ICoreWebView2Settings2>() {
+    // Set user agent, changed for the content mode
+    let mut user_agent = UserAgent {
+      mode: attributes.content_mode,
+      ..Default::default()
+    };
+    if let Ok(settings2) = settings.cast::<ICoreWebView2Settings2>() {
+      if let Some(user_agent) = &attributes.user_agent {
         settings2.SetUserAgent(&HSTRING::from(user_agent))?;
       }
+      let mut base = PWSTR::null();
+      settings2.UserAgent(&mut base)?;
+      user_agent.base = take_pwstr(base);
+      if attributes.content_mode != ContentMode::Recommended {
+        settings2.SetUserAgent(&HSTRING::from(user_agent.get()))?;
+      }
     }
 
     if !pl_attrs.browser_accelerator_keys {
@@ -1030,7 +1413,11 @@ impl InnerWebView {
     }
 
     if let Ok(settings5) = settings.cast::<ICoreWebView2Settings5>() {
-      settings5.SetIsPinchZoomEnabled(attributes.zoom_hotkeys_enabled)?;
+      settings5.SetIsPinchZoomEnabled(
+        attributes
+          .pinch_zoom
+          .unwrap_or(attributes.zoom_hotkeys_enabled),
+      )?;
     }
 
     if let Ok(settings6) = settings.cast::<ICoreWebView2Settings6>() {
@@ -1041,6 +1428,71 @@ impl InnerWebView {
       settings9.SetIsNonClientRegionSupportEnabled(true)?;
     }
 
+    Ok(user_agent)
+  }
+
+  #[inline]
+  unsafe fn attach_pending_scripts(
+    webview: &ICoreWebView2,
+    pending_scripts: PendingScripts,
+    token: &mut EventRegistrationToken,
+  ) -> Result<()> {
+    webview.add_ContentLoading(
+      &ContentLoadingEventHandler::create(Box::new(move |webview, _| {
+        let Some(webview) = webview else {
+          return Ok(());
+        };
+
+        let scripts = pending_scripts.lock().unwrap().take();
+        for (script, callback) in scripts.into_iter().flatten() {
+          Self::evaluate_script(&webview, &script, callback)?;
+        }
+
+        Ok(())
+      })),
+      token,
+    )?;
+
+    Ok(())
+  }
+
+  /// WebView2 doesn't report the progress of a page load, `handler` gets `0.0` when a navigation
+  /// starts, `0.5` once it is committed and `1.0` when it completes.
+  #[inline]
+  unsafe fn attach_load_progress(
+    webview: &ICoreWebView2,
+    loading: Rc<Cell<bool>>,
+    handler: Option<Box<dyn Fn(f64)>>,
+    token: &mut EventRegistrationToken,
+  ) -> Result<()> {
+    let handler: Rc<dyn Fn(f64)> = Rc::from(handler.unwrap_or_else(|| Box::new(|_| {})));
+
+    let (loading_, handler_) = (loading.clone(), handler.clone());
+    webview.add_NavigationStarting(
+      &NavigationStartingEventHandler::create(Box::new(move |_, _| {
+        loading_.set(true);
+        handler_(0.0);
+        Ok(())
+      })),
+      token,
+    )?;
+    let handler_ = handler.clone();
+    webview.add_ContentLoading(
+      &ContentLoadingEventHandler::create(Box::new(move |_, _| {
+        handler_(0.5);
+        Ok(())
+      })),
+      token,
+    )?;
+    webview.add_NavigationCompleted(
+      &NavigationCompletedEventHandler::create(Box::new(move |_, _| {
+        loading.set(false);
+        handler(1.0);
+        Ok(())
+      })),
+      token,
+    )?;
+
     Ok(())
   }
 
@@ -1051,6 +1503,7 @@ impl InnerWebView {
     attributes: &mut WebViewAttributes,
     token: &mut EventRegistrationToken,
     env: &ICoreWebView2Environment,
+    user_agent: &Rc<RefCell<UserAgent>>,
   ) -> Result<()> {
     // Close container HWND when `window.close` is called in JS
     webview.add_WindowCloseRequested(
@@ -1079,6 +1532,47 @@ impl InnerWebView {
       )?;
     }
 
+    // URL changed handler
+    if let Some(on_url_change_handler) = attributes.on_url_change_handler.take() {
+      webview.add_SourceChanged(
+        &SourceChangedEventHandler::create(Box::new(move |webview, _| {
+          let Some(webview) = webview else {
+            return Ok(());
+          };
+
+          on_url_change_handler(Self::url_from_webview(&webview)?);
+          Ok(())
+        })),
+        token,
+      )?;
+    }
+
+    // Fullscreen handler, the page is already fullscreen when asking the handler
+    if let Some(fullscreen_handler) = attributes.fullscreen_handler.take() {
+      let allowed = Cell::new(false);
+      webview.add_ContainsFullScreenElementChanged(
+        &ContainsFullScreenElementChangedEventHandler::create(Box::new(move |webview, _| {
+          let Some(webview) = webview else {
+            return Ok(());
+          };
+
+          let mut fullscreen = BOOL::default();
+          webview.ContainsFullScreenElement(&mut fullscreen)?;
+          if fullscreen.as_bool() {
+            if fullscreen_handler(true) {
+              allowed.set(true);
+            } else {
+              webview.ExecuteScript(w!("document.exitFullscreen()"), None)?;
+            }
+          } else if allowed.replace(false) {
+            fullscreen_handler(false);
+          }
+          Ok(())
+        })),
+        token,
+      )?;
+    }
+
     // Page load handler
     if let Some(on_page_load_handler) = attributes.on_page_load_handler.take() {
       let on_page_load_handler = Rc::new(on_page_load_handler);
@@ -1110,22 +1604,75 @@ impl InnerWebView {
       )?;
     }
 
-    // Navigation handler
-    if let Some(nav_callback) = attributes.navigation_handler.take() {
-      webview.add_NavigationStarting(
-        &NavigationStartingEventHandler::create(Box::new(move |_, args| {
+    // First paint handler
+    if let Some(on_first_paint_handler) = attributes.on_first_paint_handler.take() {
+      let mut on_first_paint_handler = Some(on_first_paint_handler);
+      if let Ok(webview) = webview.cast::<ICoreWebView2_2>() {
+        webview.add_DOMContentLoaded(
+          &DOMContentLoadedEventHandler::create(Box::new(move |_, _| {
+            if let Some(on_first_paint_handler) = on_first_paint_handler.take() {
+              on_first_paint_handler();
+            }
+            Ok(())
+          })),
+          token,
+        )?;
+      } else {
+        webview.add_NavigationCompleted(
+          &NavigationCompletedEventHandler::create(Box::new(move |_, _| {
+            if let Some(on_first_paint_handler) = on_first_paint_handler.take() {
+              on_first_paint_handler();
+            }
+            Ok(())
+          })),
+          token,
+        )?;
+      }
+    }
+
+    // Navigation error handler
+    if let Some(on_navigation_error_handler) = attributes.on_navigation_error_handler.take() {
+      // ContentLoading fires once a navigation is committed, failures before that are provisional
+      let committed_navigation = Rc::new(Cell::new(None));
+      let committed_navigation_ = committed_navigation.clone();
+
+      webview.add_ContentLoading(
+        &ContentLoadingEventHandler::create(Box::new(move |_, args| {
           let Some(args) = args else {
             return Ok(());
           };
 
-          let uri = {
-            let mut uri = PWSTR::null();
-            args.Uri(&mut uri)?;
-            take_pwstr(uri)
+          let mut id = 0;
+          args.NavigationId(&mut id)?;
+          committed_navigation_.set(Some(id));
+
+          Ok(())
+        })),
+        token,
+      )?;
+      webview.add_NavigationCompleted(
+        &NavigationCompletedEventHandler::create(Box::new(move |webview, args| {
+          let (Some(webview), Some(args)) = (webview, args) else {
+            return Ok(());
           };
 
-          let allow = nav_callback(uri);
-          args.SetCancel(!allow)?;
+          let mut is_success = BOOL::default();
+          args.IsSuccess(&mut is_success)?;
+          if is_success.as_bool() {
+            return Ok(());
+          }
+
+          let mut id = 0;
+          args.NavigationId(&mut id)?;
+          let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+          args.WebErrorStatus(&mut status)?;
+
+          on_navigation_error_handler(NavigationError {
+            url: Self::url_from_webview(&webview)?,
+            code: status.0.into(),
+            message: web_error_status_message(status).into(),
+            provisional: committed_navigation.get() != Some(id),
+          });
 
           Ok(())
         })),
@@ -1133,19 +1680,123 @@ impl InnerWebView {
       )?;
     }
 
-    let new_window_req_handler = attributes
-      .new_window_req_handler
-      .take()
-      .map(std::sync::Arc::new);
-    let env_ = env.clone();
-    // New window handler
-    webview.add_NewWindowRequested(
-      &NewWindowRequestedEventHandler::create(Box::new(move |webview, args| {
-        let Some(args) = args else {
+    // Context menu handler
+    if let Some(context_menu_handler) = attributes.context_menu_handler.take() {
+      if let (Ok(webview), Ok(env)) = (
+        webview.cast::<ICoreWebView2_11>(),
+        env.cast::<ICoreWebView2Environment9>(),
+      ) {
+        webview.add_ContextMenuRequested(
+          &ContextMenuRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+              return Ok(());
+            };
+
+            let mut location = POINT::default();
+            args.Location(&mut location)?;
+            let scale_factor = util::dpi_to_scale_factor(util::hwnd_dpi(hwnd));
+            let position = PhysicalPosition::new(location.x, location.y).to_logical(scale_factor);
+
+            let target = context_menu_target(&args.ContextMenuTarget()?, position)?;
+            match context_menu_handler(target) {
+              ContextMenuAction::Default => {}
+              ContextMenuAction::Suppress => args.SetHandled(true)?,
+              ContextMenuAction::Custom(items) => {
+                let menu_items = args.MenuItems()?;
+                let mut count = 0;
+                menu_items.Count(&mut count)?;
+                for index in (0..count).rev() {
+                  menu_items.RemoveValueAtIndex(index)?;
+                }
+
+                for (index, item) in items.into_iter().enumerate() {
+                  let menu_item = env.CreateContextMenuItem(
+                    &HSTRING::from(item.label()),
+                    None,
+                    COREWEBVIEW2_CONTEXT_MENU_ITEM_KIND_COMMAND,
+                  )?;
+                  let mut token = EventRegistrationToken::default();
+                  menu_item.add_CustomItemSelected(
+                    &CustomItemSelectedEventHandler::create(Box::new(move |_, _| {
+                      (item.handler)();
+                      Ok(())
+                    })),
+                    &mut token,
+                  )?;
+                  menu_items.InsertValueAtIndex(index as u32, &menu_item)?;
+                }
+              }
+            }
+
+            Ok(())
+          })),
+          token,
+        )?;
+      }
+    }
+
+    // Navigation handler
+    if let Some(nav_callback) = attributes.navigation_handler_ext.take() {
+      let user_agent = user_agent.clone();
+      webview.add_NavigationStarting(
+        &NavigationStartingEventHandler::create(Box::new(move |webview, args| {
+          let Some(args) = args else {
+            return Ok(());
+          };
+
+          let uri = {
+            let mut uri = PWSTR::null();
+            args.Uri(&mut uri)?;
+            take_pwstr(uri)
+          };
+
+          match nav_callback(uri) {
+            NavigationDecision::Allow => {}
+            NavigationDecision::Deny => args.SetCancel(true)?,
+            NavigationDecision::Redirect(url) => {
+              args.SetCancel(true)?;
+              if let Some(webview) = webview {
+                let url = HSTRING::from(url);
+                webview.Navigate(&url)?;
+              }
+            }
+            NavigationDecision::AllowWithContentMode(mode) => {
+              let user_agent = {
+                let mut user_agent = user_agent.borrow_mut();
+                user_agent.mode = mode;
+                HSTRING::from(user_agent.get())
+              };
+              // The header changes this navigation, the settings the next ones
+              args
+                .RequestHeaders()?
+                .SetHeader(w!("User-Agent"), &user_agent)?;
+              if let Some(webview) = webview {
+                if let Ok(settings2) = webview.Settings()?.cast::<ICoreWebView2Settings2>() {
+                  settings2.SetUserAgent(&user_agent)?;
+                }
+              }
+            }
+          }
+
+          Ok(())
+        })),
+        token,
+      )?;
+    }
+
+    let new_window_request_handler = attributes
+      .new_window_request_handler
+      .take()
+      .map(std::sync::Arc::new);
+    let env_ = env.clone();
+    // New window handler
+    webview.add_NewWindowRequested(
+      &NewWindowRequestedEventHandler::create(Box::new(move |webview, args| {
+        let Some(args) = args else {
           return Ok(());
         };
 
-        if let Some(new_window_req_handler) = &new_window_req_handler {
+        if let Some(new_window_request_handler) = &new_window_request_handler {
           let webview = webview.unwrap();
           let uri = {
             let mut uri = PWSTR::null();
@@ -1198,16 +1849,33 @@ impl InnerWebView {
               },
             });
 
-          let new_window_req_handler = new_window_req_handler.clone();
+          let mut user_initiated: BOOL = false.into();
+          args.IsUserInitiated(&mut user_initiated)?;
+          let request = NewWindowRequest {
+            url: uri,
+            features,
+            user_initiated: user_initiated.as_bool(),
+          };
+
+          let new_window_request_handler = new_window_request_handler.clone();
           let deferral = args.GetDeferral()?;
           let deferral = UnsafeSend(deferral);
           let args = UnsafeSend(args);
           let hwnd = UnsafeSend(hwnd);
-          std::thread::spawn(move || match new_window_req_handler(uri, features) {
+          let opener = UnsafeSend(webview.clone());
+          let url = request.url.clone();
+          std::thread::spawn(move || match new_window_request_handler(request) {
             NewWindowResponse::Allow => {
               let _ = args.take().SetHandled(false);
               let _ = deferral.take().Complete();
             }
+            NewWindowResponse::OpenInSameView => {
+              Self::dispatch_handler(hwnd.take(), move || {
+                let _ = args.take().SetHandled(true);
+                let _ = deferral.take().Complete();
+                let _ = opener.take().Navigate(&HSTRING::from(url));
+              });
+            }
             NewWindowResponse::Create { webview } => {
               Self::dispatch_handler(hwnd.take(), move || {
                 let args = args.take();
@@ -1230,12 +1898,61 @@ impl InnerWebView {
       token,
     )?;
 
+    // JavaScript dialogs
+    if let Some(dialog_handler) = attributes.dialog_handler.take() {
+      webview.add_ScriptDialogOpening(
+        &ScriptDialogOpeningEventHandler::create(Box::new(move |_, args| {
+          let Some(args) = args else {
+            return Ok(());
+          };
+
+          let mut kind = COREWEBVIEW2_SCRIPT_DIALOG_KIND::default();
+          args.Kind(&mut kind)?;
+          let kind = match kind {
+            COREWEBVIEW2_SCRIPT_DIALOG_KIND_ALERT => DialogKind::Alert,
+            COREWEBVIEW2_SCRIPT_DIALOG_KIND_CONFIRM => DialogKind::Confirm,
+            COREWEBVIEW2_SCRIPT_DIALOG_KIND_PROMPT => {
+              let mut default_text = PWSTR::null();
+              args.DefaultText(&mut default_text)?;
+              DialogKind::Prompt {
+                default_text: take_pwstr(default_text),
+              }
+            }
+            COREWEBVIEW2_SCRIPT_DIALOG_KIND_BEFOREUNLOAD => DialogKind::BeforeUnload,
+            _ => return Ok(()),
+          };
+          let message = {
+            let mut message = PWSTR::null();
+            args.Message(&mut message)?;
+            take_pwstr(message)
+          };
+          let url = {
+            let mut uri = PWSTR::null();
+            args.Uri(&mut uri)?;
+            take_pwstr(uri)
+          };
+
+          let response = dialog_handler(DialogRequest {
+            kind: kind.clone(),
+            message,
+            url,
+          });
+          let (accepted, text) = response.outcome(&kind);
+          if let Some(text) = text {
+            args.SetResultText(&HSTRING::from(text))?;
+          }
+          if accepted {
+            args.Accept()?;
+          }
+          Ok(())
+        })),
+        token,
+      )?;
+    }
+
     // Download handler
-    if attributes.download_started_handler.is_some()
-      || attributes.download_completed_handler.is_some()
-    {
-      let mut download_started_handler = attributes.download_started_handler.take();
-      let download_completed_handler = attributes.download_completed_handler.take();
+    if let Some(download_handler) = attributes.download_handler.take() {
+      let download_handler = Rc::new(download_handler);
 
       let webview4: ICoreWebView2_4 = webview.cast()?;
       webview4.add_DownloadStarting(
@@ -1244,16 +1961,35 @@ impl InnerWebView {
             return Ok(());
           };
 
+          let operation = args.DownloadOperation()?;
           let uri = {
             let mut uri = PWSTR::null();
-            args.DownloadOperation()?.Uri(&mut uri)?;
+            operation.Uri(&mut uri)?;
             take_pwstr(uri)
           };
 
-          if let Some(download_completed_handler) = &download_completed_handler {
-            let download_completed_handler = download_completed_handler.clone();
+          // Released once the download completed, the operation holds the handlers
+          let pending = Rc::new(RefCell::new(Some(operation.clone())));
+          let control = |f: fn(&ICoreWebView2DownloadOperation) -> windows::core::Result<()>| {
+            let pending = pending.clone();
+            move || {
+              if let Some(operation) = &*pending.borrow() {
+                let _ = f(operation);
+              }
+            }
+          };
+          let download = Download::new(uri, control(|operation| unsafe { operation.Cancel() }))
+            .with_pause(
+              control(|operation| unsafe { operation.Pause() }),
+              // Restarts the download if the server doesn't support range requests
+              control(|operation| unsafe { operation.Resume() }),
+            );
+
+          {
+            let download_handler = download_handler.clone();
+            let download = download.clone();
 
-            args.DownloadOperation()?.add_StateChanged(
+            operation.add_StateChanged(
               &StateChangedEventHandler::create(Box::new(move |download_operation, _| {
                 let Some(download_operation) = download_operation else {
                   return Ok(());
@@ -1262,16 +1998,17 @@ impl InnerWebView {
                 let mut state = COREWEBVIEW2_DOWNLOAD_STATE::default();
                 download_operation.State(&mut state)?;
 
-                if state != COREWEBVIEW2_DOWNLOAD_STATE_IN_PROGRESS {
-                  let uri = {
-                    let mut uri = PWSTR::null();
-                    download_operation.Uri(&mut uri)?;
-                    take_pwstr(uri)
-                  };
+                // Paused downloads are interrupted until they are resumed
+                let paused = state == COREWEBVIEW2_DOWNLOAD_STATE_INTERRUPTED && {
+                  let mut reason = COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON::default();
+                  download_operation.InterruptReason(&mut reason)?;
+                  reason == COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_PAUSED
+                };
 
-                  let success = state == COREWEBVIEW2_DOWNLOAD_STATE_COMPLETED;
+                if state != COREWEBVIEW2_DOWNLOAD_STATE_IN_PROGRESS && !paused {
+                  pending.borrow_mut().take();
 
-                  let path = if success {
+                  let path = if state == COREWEBVIEW2_DOWNLOAD_STATE_COMPLETED {
                     let mut path = PWSTR::null();
                     download_operation.ResultFilePath(&mut path)?;
                     Some(PathBuf::from(take_pwstr(path)))
@@ -1279,7 +2016,7 @@ impl InnerWebView {
                     None
                   };
 
-                  download_completed_handler(uri, path, success);
+                  download_handler.completed(&download, path);
                 }
 
                 Ok(())
@@ -1288,22 +2025,48 @@ impl InnerWebView {
             )?;
           }
 
-          if let Some(download_started_handler) = &mut download_started_handler {
-            let mut path = {
-              let mut path = PWSTR::null();
-              args.ResultFilePath(&mut path)?;
-              let path = take_pwstr(path);
-              PathBuf::from(&path)
-            };
+          if download_handler.has_progress() {
+            let download_handler = download_handler.clone();
+            let download = download.clone();
+
+            operation.add_BytesReceivedChanged(
+              &BytesReceivedChangedEventHandler::create(Box::new(move |download_operation, _| {
+                let Some(download_operation) = download_operation else {
+                  return Ok(());
+                };
+
+                let mut received_bytes = 0;
+                download_operation.BytesReceived(&mut received_bytes)?;
+                // 0 when the size is unknown
+                let mut total_bytes = 0;
+                download_operation.TotalBytesToReceive(&mut total_bytes)?;
+
+                download_handler.progress(
+                  &download,
+                  received_bytes as u64,
+                  (total_bytes > 0).then_some(total_bytes as u64),
+                );
+                Ok(())
+              })),
+              &mut EventRegistrationToken::default(),
+            )?;
+          }
+
+          let path = {
+            let mut path = PWSTR::null();
+            args.ResultFilePath(&mut path)?;
+            let path = take_pwstr(path);
+            PathBuf::from(&path)
+          };
 
-            if download_started_handler(uri, &mut path) {
+          match download_handler.started(&download, path) {
+            Some(path) => {
               let simplified = dunce::simplified(&path);
               let path = HSTRING::from(simplified);
               args.SetResultFilePath(&path)?;
               args.SetHandled(true)?;
-            } else {
-              args.SetCancel(true)?;
             }
+            None => args.SetCancel(true)?,
           }
 
           Ok(())
@@ -1323,8 +2086,9 @@ impl InnerWebView {
   ) -> Result<()> {
     Self::add_script_to_execute_on_document_created(
       webview,
-      String::from(
-        r#"Object.defineProperty(window, 'ipc', { value: Object.freeze({ postMessage: s=> window.chrome.webview.postMessage(s) }) });"#,
+      crate::ipc::script(
+        "window.chrome.webview.postMessage(s)",
+        attributes.ipc_invoke_timeout,
       ),
     )?;
 
@@ -1349,7 +2113,15 @@ impl InnerWebView {
 
         #[cfg(feature = "tracing")]
         let _span = tracing::info_span!(parent: None, "wry::ipc::handle").entered();
-        ipc_handler(Request::builder().uri(url).body(js).unwrap());
+        // WebMessageReceived is only raised for the top-level document
+        let source = crate::IpcSource::new(&url, Some(true));
+        ipc_handler(
+          Request::builder()
+            .extension(source)
+            .uri(url)
+            .body(js)
+            .unwrap(),
+        );
 
         Ok(())
       })),
@@ -1369,12 +2141,22 @@ impl InnerWebView {
     attributes: &mut WebViewAttributes,
     token: &mut EventRegistrationToken,
   ) -> Result<()> {
-    for name in attributes.custom_protocols.keys() {
-      // WebView2 supports non-standard protocols only on Windows 10+, so we have to use this workaround
-      // See https://github.com/MicrosoftEdge/WebView2Feedback/issues/73
-      let work_around_uri = custom_protocol_workaround::work_around_uri_prefix(http_or_https, name);
-      let filter = HSTRING::from(format!("{work_around_uri}*"));
+    let mut filters = attributes
+      .custom_protocols
+      .keys()
+      .map(|name| {
+        // WebView2 supports non-standard protocols only on Windows 10+, so we have to use this workaround
+        // See https://github.com/MicrosoftEdge/WebView2Feedback/issues/73
+        let work_around_uri =
+          custom_protocol_workaround::work_around_uri_prefix(http_or_https, name);
+        HSTRING::from(format!("{work_around_uri}*"))
+      })
+      .collect::<Vec<_>>();
+    if attributes.web_resource_request_handler.is_some() {
+      filters.extend([HSTRING::from("http://*"), HSTRING::from("https://*")]);
+    }
 
+    for filter in filters {
       // If WebView2 version is high enough, use the new API to add the filter to allow Shared Workers and
       // iframes to work with custom protocols
       // See https://github.com/MicrosoftEdge/WebView2Feedback/issues/1114
@@ -1392,6 +2174,7 @@ impl InnerWebView {
 
     let env = env.clone();
     let custom_protocols = std::mem::take(&mut attributes.custom_protocols);
+    let web_resource_request_handler = attributes.web_resource_request_handler.take();
     let main_thread_id = std::thread::current().id();
 
     webview.add_WebResourceRequested(
@@ -1420,7 +2203,7 @@ impl InnerWebView {
           .iter()
           .find(|(protocol, _)| custom_protocol_workaround::is_work_around_uri(&uri, http_or_https, protocol))
         {
-          let request = match Self::prepare_request(http_or_https, custom_protocol, &webview_request, &uri)
+          let request = match Self::prepare_request(http_or_https, Some(custom_protocol), &webview_request, &uri)
           {
             Ok(req) => req,
             Err(e) => {
@@ -1435,7 +2218,7 @@ impl InnerWebView {
 
           let async_responder = Box::new(move |sent_response| {
             let handler = move || {
-              match Self::prepare_web_request_response(&env, &sent_response) {
+              match Self::prepare_web_request_response(&env, sent_response) {
                 Ok(response) => {
                   let _ = args.SetResponse(&response);
                 }
@@ -1467,6 +2250,14 @@ impl InnerWebView {
               responder: async_responder,
             },
           );
+        } else if let Some(web_resource_request_handler) = &web_resource_request_handler {
+          Self::intercept_web_resource_request(
+            &env,
+            &args,
+            &webview_request,
+            &uri,
+            web_resource_request_handler,
+          )?;
         }
 
         Ok(())
@@ -1479,10 +2270,72 @@ impl InnerWebView {
     Ok(())
   }
 
+  /// Passes an http(s) request to the web resource request handler and applies its changes.
+  unsafe fn intercept_web_resource_request(
+    env: &ICoreWebView2Environment,
+    args: &ICoreWebView2WebResourceRequestedEventArgs,
+    webview_request: &ICoreWebView2WebResourceRequest,
+    webview_request_uri: &str,
+    handler: &dyn Fn(&mut Request<Vec<u8>>) -> RequestAction,
+  ) -> windows::core::Result<()> {
+    let Ok(mut request) = Self::prepare_request("", None, webview_request, webview_request_uri)
+    else {
+      return Ok(());
+    };
+    // Reading the body moved the stream to its end, rewind it for the network request
+    if let Ok(content) = webview_request.Content() {
+      content.Seek(0, STREAM_SEEK_SET, None)?;
+    }
+    let (method, uri, headers, body) = (
+      request.method().clone(),
+      request.uri().clone(),
+      request.headers().clone(),
+      request.body().clone(),
+    );
+
+    match handler(&mut request) {
+      RequestAction::Continue => {
+        if *request.method() != method {
+          webview_request.SetMethod(&HSTRING::from(request.method().as_str()))?;
+        }
+        if *request.uri() != uri {
+          webview_request.SetUri(&HSTRING::from(request.uri().to_string()))?;
+        }
+
+        let webview_headers = webview_request.Headers()?;
+        for name in headers.keys() {
+          if !request.headers().contains_key(name) {
+            webview_headers.RemoveHeader(&HSTRING::from(name.as_str()))?;
+          }
+        }
+        for (name, value) in crate::navigation::request_headers(request.headers()) {
+          if headers.get_all(name) != request.headers().get_all(name) {
+            webview_headers.SetHeader(&HSTRING::from(name), &HSTRING::from(value))?;
+          }
+        }
+
+        if *request.body() != body {
+          let content = SHCreateMemStream(Some(request.body()));
+          webview_request.SetContent(content.as_ref())?;
+        }
+      }
+      RequestAction::Block => {
+        let response = env.CreateWebResourceResponse(None, 403, w!("Forbidden"), w!(""))?;
+        args.SetResponse(&response)?;
+      }
+      RequestAction::Override(response) => {
+        let response = Self::prepare_web_request_response(env, response.map(ResponseBody::Bytes))?;
+        args.SetResponse(&response)?;
+      }
+    }
+
+    Ok(())
+  }
+
   #[inline]
   unsafe fn prepare_request(
     http_or_https: &'static str,
-    custom_protocol: &str,
+    custom_protocol: Option<&str>,
     webview_request: &ICoreWebView2WebResourceRequest,
     webview_request_uri: &str,
   ) -> Result<http::Request<Vec<u8>>> {
@@ -1533,11 +2386,14 @@ impl InnerWebView {
     }
 
     // Undo the protocol workaround when giving path to resolver
-    let path = custom_protocol_workaround::revert_uri_work_around(
-      webview_request_uri,
-      http_or_https,
-      custom_protocol,
-    );
+    let path = match custom_protocol {
+      Some(custom_protocol) => custom_protocol_workaround::revert_uri_work_around(
+        webview_request_uri,
+        http_or_https,
+        custom_protocol,
+      ),
+      None => webview_request_uri.to_string(),
+    };
 
     let request = request.uri(&path).body(body_sent)?;
 
@@ -1547,10 +2403,8 @@ impl InnerWebView {
   #[inline]
   unsafe fn prepare_web_request_response(
     env: &ICoreWebView2Environment,
-    sent_response: &HttpResponse<Cow<'static, [u8]>>,
+    sent_response: HttpResponse<ResponseBody>,
   ) -> windows::core::Result<ICoreWebView2WebResourceResponse> {
-    let content = sent_response.body();
-
     let status = sent_response.status();
     let status_code = status.as_u16();
     let status = HSTRING::from(status.canonical_reason().unwrap_or("OK"));
@@ -1564,10 +2418,12 @@ impl InnerWebView {
     }
     let headers_map = HSTRING::from(headers_map);
 
-    let mut stream = None;
-    if !content.is_empty() {
-      stream = SHCreateMemStream(Some(content));
-    }
+    let stream = match sent_response.into_body() {
+      ResponseBody::Bytes(content) if !content.is_empty() => SHCreateMemStream(Some(&content)),
+      ResponseBody::Bytes(_) => None,
+      ResponseBody::Stream(body) if body.is_empty() => None,
+      ResponseBody::Stream(body) => Some(BodyStream::into_istream(body)),
+    };
 
     env.CreateWebResourceResponse(stream.as_ref(), status_code as i32, &status, &headers_map)
   }
@@ -2176,7 +3032,12 @@ impl InnerWebView {
       WM_SHOWWINDOW => {
         if dwrefdata != 0 {
           let controller = dwrefdata as *mut ICoreWebView2Controller;
-          let is_shown = wparam.0 != 0;
+          // Showing the parent window keeps a webview hidden with `set_visible(false)` hidden
+          let mut container = HWND::default();
+          let is_hidden = (*controller).ParentWindow(&mut container).is_ok()
+            && container != hwnd
+            && GetWindowLongW(container, GWL_STYLE) as u32 & WS_VISIBLE.0 == 0;
+          let is_shown = wparam.0 != 0 && !is_hidden;
           let _ = (*controller).SetIsVisible(is_shown);
         }
       }
@@ -2238,6 +3099,10 @@ impl InnerWebView {
             bottom: height,
           });
 
+          if is_container_subclass {
+            Self::apply_corner_radius(hwnd);
+          }
+
           let mut hwnd = HWND::default();
           if (*controller).ParentWindow(&mut hwnd).is_ok() {
             let _ = SetWindowPos(
@@ -2338,8 +3203,12 @@ impl InnerWebView {
   }
 
   #[inline]
-  fn add_script_to_execute_on_document_created(webview: &ICoreWebView2, js: String) -> Result<()> {
+  fn add_script_to_execute_on_document_created(
+    webview: &ICoreWebView2,
+    js: String,
+  ) -> Result<String> {
     let webview = webview.clone();
+    let (tx, rx) = mpsc::channel();
     AddScriptToExecuteOnDocumentCreatedCompletedHandler::wait_for_async_operation(
       Box::new(move |handler| unsafe {
         let js = HSTRING::from(js);
@@ -2347,9 +3216,74 @@ impl InnerWebView {
           .AddScriptToExecuteOnDocumentCreated(&js, &handler)
           .map_err(Into::into)
       }),
-      Box::new(|e, _| e),
-    )
-    .map_err(Into::into)
+      Box::new(move |e, id| {
+        e?;
+        let _ = tx.send(id);
+        Ok(())
+      }),
+    )?;
+    Ok(rx.try_recv().unwrap_or_default())
+  }
+
+  pub fn add_init_script(&self, id: &str, script: &str) -> Result<()> {
+    let script_id = Self::add_script_to_execute_on_document_created(&self.webview, script.into())?;
+    if let Some(replaced) = self.init_scripts.insert(id, script_id) {
+      unsafe {
+        self
+          .webview
+          .RemoveScriptToExecuteOnDocumentCreated(&HSTRING::from(replaced))?
+      };
+    }
+    Ok(())
+  }
+
+  pub fn remove_init_script(&self, id: &str) -> Result<()> {
+    if let Some(script_id) = self.init_scripts.remove(id) {
+      unsafe {
+        self
+          .webview
+          .RemoveScriptToExecuteOnDocumentCreated(&HSTRING::from(script_id))?
+      };
+    }
+    Ok(())
+  }
+
+  pub fn init_scripts(&self) -> Vec<String> {
+    self.init_scripts.ids()
+  }
+
+  pub fn add_user_stylesheet(
+    &self,
+    id: &str,
+    css: &str,
+    main_frame_only: bool,
+    _level: StylesheetLevel,
+  ) -> Result<()> {
+    let script = script::stylesheet(id, css, main_frame_only);
+    let script_id = Self::add_script_to_execute_on_document_created(&self.webview, script.clone())?;
+    self.stylesheets.insert(id, script_id);
+    self.eval(&script, None::<Box<dyn FnOnce(String) + Send + 'static>>)
+  }
+
+  pub fn set_proxy(&self, _proxy: Option<ProxyConfig>) -> Result<()> {
+    Err(Error::ProxyChangeUnsupported(
+      "WebView2 reads the proxy from the browser arguments, create a new webview with `WebViewBuilder::with_proxy_config` instead".into(),
+    ))
+  }
+
+  pub fn remove_user_stylesheet(&self, id: &str) -> Result<()> {
+    if let Some(script_id) = self.stylesheets.remove(id) {
+      unsafe {
+        self
+          .webview
+          .RemoveScriptToExecuteOnDocumentCreated(&HSTRING::from(script_id))?
+      };
+      self.eval(
+        &script::remove_stylesheet(id),
+        None::<Box<dyn FnOnce(String) + Send + 'static>>,
+      )?;
+    }
+    Ok(())
   }
 
   #[inline]
@@ -2374,6 +3308,56 @@ impl InnerWebView {
     }
   }
 
+  /// Same as [`Self::execute_script`] but passes `{"error": "<message>"}` to the callback
+  /// when the script throws, if the runtime supports it.
+  fn execute_script_with_result(
+    webview: &ICoreWebView2,
+    js: &str,
+    callback: impl FnOnce(String) + Send + 'static,
+  ) -> windows::core::Result<()> {
+    unsafe {
+      let Ok(webview) = webview.cast::<ICoreWebView2_21>() else {
+        return Self::execute_script(webview, js, callback);
+      };
+
+      #[cfg(feature = "tracing")]
+      let span = tracing::debug_span!("wry::eval").entered();
+      let js = HSTRING::from(js);
+      webview.ExecuteScriptWithResult(
+        &js,
+        &ExecuteScriptWithResultCompletedHandler::create(Box::new(|_, result| {
+          #[cfg(feature = "tracing")]
+          drop(span);
+          let result = result
+            .and_then(|result| script_result_json(&result).ok())
+            .unwrap_or_default();
+          callback(result);
+          Ok(())
+        })),
+      )
+    }
+  }
+
+  /// Evaluates `js` for [`Self::eval`], passing its result to `callback`.
+  fn evaluate_script(
+    webview: &ICoreWebView2,
+    js: &str,
+    callback: Option<impl FnOnce(String) + Send + 'static>,
+  ) -> windows::core::Result<()> {
+    // ExecuteScript fails with scripts of several megabytes, they are sent in chunks
+    let mut scripts = script::split_large_script(js);
+    let js = scripts.pop().unwrap_or_default();
+    for chunk in scripts {
+      Self::execute_script(webview, &chunk, |_| ())?;
+    }
+
+    if let Some(callback) = callback {
+      Self::execute_script_with_result(webview, &js, callback)
+    } else {
+      Self::execute_script(webview, &js, |_| ())
+    }
+  }
+
   #[inline]
   fn url_from_webview(webview: &ICoreWebView2) -> windows::core::Result<String> {
     let mut pwstr = PWSTR::null();
@@ -2412,14 +3396,20 @@ impl InnerWebView {
     js: &str,
     callback: Option<impl FnOnce(String) + Send + 'static>,
   ) -> Result<()> {
-    if let Some(callback) = callback {
-      Self::execute_script(&self.webview, js, callback)?
-    } else {
-      Self::execute_script(&self.webview, js, |_| ())?
+    if let Err(callback) = script::defer_script(&self.pending_scripts, js, callback) {
+      Self::evaluate_script(&self.webview, js, callback)?;
     }
     Ok(())
   }
 
+  /// Evaluates scripts without a callback.
+  pub(crate) fn script_evaluator(&self) -> Box<dyn Fn(&str)> {
+    let webview = self.webview.clone();
+    Box::new(move |js| {
+      let _ = Self::execute_script(&webview, js, |_| ());
+    })
+  }
+
   pub fn url(&self) -> Result<String> {
     Self::url_from_webview(&self.webview).map_err(Into::into)
   }
@@ -2428,55 +3418,195 @@ impl InnerWebView {
     unsafe { self.controller.SetZoomFactor(scale_factor) }.map_err(Into::into)
   }
 
-  pub fn load_url(&self, url: &str) -> Result<()> {
-    let url = HSTRING::from(url);
-    unsafe { self.webview.Navigate(&url) }.map_err(Into::into)
+  pub fn zoom_level(&self) -> Result<f64> {
+    let mut zoom_factor = 1.0;
+    unsafe { self.controller.ZoomFactor(&mut zoom_factor) }?;
+    Ok(zoom_factor)
   }
 
-  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) -> Result<()> {
-    load_url_with_headers(&self.webview, &self.env, url, headers)
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    let mut current = self.user_agent.borrow_mut();
+    current.base = user_agent.to_string();
+    unsafe {
+      if let Ok(settings2) = self.webview.Settings()?.cast::<ICoreWebView2Settings2>() {
+        settings2.SetUserAgent(&HSTRING::from(current.get()))?;
+      }
+    }
+    Ok(())
   }
 
-  pub fn load_html(&self, html: &str) -> Result<()> {
-    let html = HSTRING::from(html);
-    unsafe { self.webview.NavigateToString(&html) }.map_err(Into::into)
+  pub fn add_message_handler(
+    &self,
+    _name: &str,
+    _handler: Box<dyn Fn(Request<String>)>,
+  ) -> Result<()> {
+    // The messages of `window.ipc.postMessageTo` are routed by the IPC handler
+    Ok(())
   }
 
-  pub fn reload(&self) -> Result<()> {
-    unsafe { self.webview.Reload() }.map_err(Into::into)
+  pub fn remove_message_handler(&self, _name: &str) -> Result<()> {
+    Ok(())
   }
 
-  pub fn bounds(&self) -> Result<Rect> {
-    let mut bounds = Rect::default();
-    let mut rect = RECT::default();
-    if self.is_child {
-      unsafe { GetClientRect(self.hwnd, &mut rect)? };
-
-      let position_point = &mut [POINT {
-        x: rect.left,
-        y: rect.top,
-      }];
-      unsafe { MapWindowPoints(Some(self.hwnd), Some(*self.parent.borrow()), position_point) };
-
-      bounds.position = PhysicalPosition::new(position_point[0].x, position_point[0].y).into();
-    } else {
-      unsafe { self.controller.Bounds(&mut rect) }?;
+  pub fn set_accept_language(&self, languages: &[&str]) -> Result<()> {
+    let header = crate::language::header(languages);
+    *self.accept_language.borrow_mut() = (!header.is_empty()).then(|| HSTRING::from(header));
+    if !self.accept_language_handler.replace(true) {
+      unsafe { Self::attach_accept_language_handler(&self.webview, self.accept_language.clone()) }?;
     }
-
-    bounds.size = PhysicalSize::new(rect.right - rect.left, rect.bottom - rect.top).into();
-
-    Ok(bounds)
+    Ok(())
   }
 
-  pub fn set_bounds_inner(
-    &self,
-    size: PhysicalSize<i32>,
-    position: PhysicalPosition<i32>,
+  unsafe fn attach_accept_language_handler(
+    webview: &ICoreWebView2,
+    accept_language: Rc<RefCell<Option<HSTRING>>>,
   ) -> Result<()> {
-    unsafe {
-      self.controller.SetBounds(RECT {
-        top: 0,
-        left: 0,
+    for filter in [w!("http://*"), w!("https://*")] {
+      webview.AddWebResourceRequestedFilter(filter, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL)?;
+    }
+
+    let mut token = EventRegistrationToken::default();
+    webview.add_WebResourceRequested(
+      &WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
+        let Some(args) = args else {
+          return Ok(());
+        };
+        if let Some(accept_language) = &*accept_language.borrow() {
+          args
+            .Request()?
+            .Headers()?
+            .SetHeader(w!("Accept-Language"), accept_language)?;
+        }
+        Ok(())
+      })),
+      &mut token,
+    )?;
+
+    Ok(())
+  }
+
+  pub fn set_navigation_gestures(&self, enabled: bool) -> Result<()> {
+    unsafe {
+      if let Ok(settings6) = self.webview.Settings()?.cast::<ICoreWebView2Settings6>() {
+        settings6.SetIsSwipeNavigationEnabled(enabled)?;
+      }
+    }
+    Ok(())
+  }
+
+  pub fn load_url(&self, url: &str) -> Result<()> {
+    let url = HSTRING::from(url);
+    unsafe { self.webview.Navigate(&url) }.map_err(Into::into)
+  }
+
+  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) -> Result<()> {
+    load_url_with_headers(&self.webview, &self.env, url, headers)
+  }
+
+  pub fn load_url_with_body(
+    &self,
+    url: &str,
+    method: http::Method,
+    body: Vec<u8>,
+    headers: http::HeaderMap,
+  ) -> Result<()> {
+    load_url_with_request(&self.webview, &self.env, url, method, &body, headers)
+  }
+
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) -> Result<()> {
+    // WebView2 has no base URL for html strings
+    let html = match base_url {
+      Some(base_url) => HSTRING::from(crate::util::html_with_base_url(html, base_url)),
+      None => HSTRING::from(html),
+    };
+    unsafe { self.webview.NavigateToString(&html) }.map_err(Into::into)
+  }
+
+  pub fn reload(&self) -> Result<()> {
+    unsafe { self.webview.Reload() }.map_err(Into::into)
+  }
+
+  pub fn reload_ignoring_cache(&self) -> Result<()> {
+    unsafe {
+      self.webview.CallDevToolsProtocolMethod(
+        w!("Page.reload"),
+        w!(r#"{"ignoreCache":true}"#),
+        None,
+      )
+    }
+    .map_err(Into::into)
+  }
+
+  pub fn stop_loading(&self) -> Result<()> {
+    unsafe { self.webview.Stop() }.map_err(Into::into)
+  }
+
+  pub fn can_go_back(&self) -> Result<bool> {
+    let mut can_go_back: BOOL = false.into();
+    unsafe { self.webview.CanGoBack(&mut can_go_back) }?;
+    Ok(can_go_back.as_bool())
+  }
+
+  pub fn can_go_forward(&self) -> Result<bool> {
+    let mut can_go_forward: BOOL = false.into();
+    unsafe { self.webview.CanGoForward(&mut can_go_forward) }?;
+    Ok(can_go_forward.as_bool())
+  }
+
+  pub fn go_back(&self) -> Result<()> {
+    unsafe { self.webview.GoBack() }.map_err(Into::into)
+  }
+
+  pub fn go_forward(&self) -> Result<()> {
+    unsafe { self.webview.GoForward() }.map_err(Into::into)
+  }
+
+  pub fn history_list(&self) -> Result<History> {
+    // WebView2 doesn't expose the back and forward entries
+    let url = Self::url_from_webview(&self.webview)?;
+    let title = {
+      let mut title = PWSTR::null();
+      unsafe { self.webview.DocumentTitle(&mut title) }?;
+      take_pwstr(title)
+    };
+
+    Ok(History {
+      current: (!url.is_empty()).then_some(HistoryEntry { url, title }),
+      ..Default::default()
+    })
+  }
+
+  pub fn bounds(&self) -> Result<Rect> {
+    let mut bounds = Rect::default();
+    let mut rect = RECT::default();
+    if self.is_child {
+      unsafe { GetClientRect(self.hwnd, &mut rect)? };
+
+      let position_point = &mut [POINT {
+        x: rect.left,
+        y: rect.top,
+      }];
+      unsafe { MapWindowPoints(Some(self.hwnd), Some(*self.parent.borrow()), position_point) };
+
+      bounds.position = PhysicalPosition::new(position_point[0].x, position_point[0].y).into();
+    } else {
+      unsafe { self.controller.Bounds(&mut rect) }?;
+    }
+
+    bounds.size = PhysicalSize::new(rect.right - rect.left, rect.bottom - rect.top).into();
+
+    Ok(bounds)
+  }
+
+  pub fn set_bounds_inner(
+    &self,
+    size: PhysicalSize<i32>,
+    position: PhysicalPosition<i32>,
+  ) -> Result<()> {
+    unsafe {
+      self.controller.SetBounds(RECT {
+        top: 0,
+        left: 0,
         right: size.width,
         bottom: size.height,
       })?;
@@ -2498,8 +3628,7 @@ impl InnerWebView {
   pub fn set_bounds(&self, bounds: Rect) -> Result<()> {
     let dpi = unsafe { util::hwnd_dpi(self.hwnd) };
     let scale_factor = util::dpi_to_scale_factor(dpi);
-    let size = bounds.size.to_physical::<i32>(scale_factor);
-    let position = bounds.position.to_physical(scale_factor);
+    let (position, size) = bounds.to_physical(scale_factor);
     self.set_bounds_inner(size, position)?;
     Ok(())
   }
@@ -2525,6 +3654,177 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn is_visible(&self) -> Result<bool> {
+    let mut visible = BOOL::default();
+    unsafe { self.controller.IsVisible(&mut visible) }?;
+    Ok(visible.as_bool())
+  }
+
+  pub fn set_corner_radius(&self, radius: f64) -> Result<()> {
+    unsafe {
+      // Stored on the container so the region can be updated when it is resized
+      SetPropW(
+        self.hwnd,
+        w!("WRY_CORNER_RADIUS"),
+        Some(HANDLE((radius.max(0.) as f32).to_bits() as usize as _)),
+      )?;
+      Self::apply_corner_radius(self.hwnd);
+    }
+    Ok(())
+  }
+
+  unsafe fn apply_corner_radius(hwnd: HWND) {
+    let radius = f32::from_bits(GetPropW(hwnd, w!("WRY_CORNER_RADIUS")).0 as usize as u32) as f64;
+    if radius <= 0. {
+      SetWindowRgn(hwnd, None, true);
+      return;
+    }
+
+    let mut rect = RECT::default();
+    if GetClientRect(hwnd, &mut rect).is_err() {
+      return;
+    }
+
+    let scale_factor = util::dpi_to_scale_factor(util::hwnd_dpi(hwnd));
+    let diameter = (radius * 2. * scale_factor).round() as i32;
+    // The system owns the region once it is set
+    let region = CreateRoundRectRgn(0, 0, rect.right + 1, rect.bottom + 1, diameter, diameter);
+    SetWindowRgn(hwnd, Some(region), true);
+  }
+
+  pub fn bring_to_front(&self) -> Result<()> {
+    self.set_z_order(HWND_TOP)
+  }
+
+  pub fn send_to_back(&self) -> Result<()> {
+    self.set_z_order(HWND_BOTTOM)
+  }
+
+  fn set_z_order(&self, insert_after: HWND) -> Result<()> {
+    unsafe {
+      SetWindowPos(
+        self.hwnd,
+        Some(insert_after),
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+      )?;
+    }
+    Ok(())
+  }
+
+  pub fn suspend<F>(&self, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    unsafe {
+      self
+        .webview
+        .cast::<ICoreWebView2_3>()?
+        .TrySuspend(&TrySuspendCompletedHandler::create(Box::new(
+          move |error_code, is_successful| {
+            completion(error_code.map_err(Into::into).and_then(|_| {
+              if is_successful {
+                Ok(())
+              } else {
+                Err(Error::SuspendFailed("the webview must be hidden".into()))
+              }
+            }));
+            Ok(())
+          },
+        )))?;
+    }
+
+    Ok(())
+  }
+
+  pub fn resume(&self) -> Result<()> {
+    unsafe { self.webview.cast::<ICoreWebView2_3>()?.Resume() }.map_err(Into::into)
+  }
+
+  pub fn set_muted(&self, muted: bool) -> Result<()> {
+    unsafe { self.webview.cast::<ICoreWebView2_8>()?.SetIsMuted(muted) }.map_err(Into::into)
+  }
+
+  pub fn is_muted(&self) -> Result<bool> {
+    let mut muted = BOOL::default();
+    unsafe { self.webview.cast::<ICoreWebView2_8>()?.IsMuted(&mut muted) }?;
+    Ok(muted.as_bool())
+  }
+
+  pub fn is_loading(&self) -> Result<bool> {
+    Ok(self.loading.get())
+  }
+
+  pub fn memory_usage(&self) -> Result<MemoryUsage> {
+    let unsupported =
+      |_| Error::Unsupported("requires WebView2 Runtime version 1.0.2210.55 or higher".into());
+    let env = self
+      .env
+      .cast::<ICoreWebView2Environment13>()
+      .map_err(unsupported)?;
+    let mut frame_id = 0;
+    unsafe {
+      self
+        .webview
+        .cast::<ICoreWebView2_20>()
+        .map_err(unsupported)?
+        .FrameId(&mut frame_id)?;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    unsafe {
+      env.GetProcessExtendedInfos(&GetProcessExtendedInfosCompletedHandler::create(Box::new(
+        move |error_code, infos| {
+          error_code?;
+
+          // the renderer processes hosting a frame of the webview, out-of-process iframes included
+          let mut pids = Vec::new();
+          if let Some(infos) = infos {
+            let mut count = 0;
+            infos.Count(&mut count)?;
+            for idx in 0..count {
+              let info = infos.GetValueAtIndex(idx)?;
+              if renderer_hosts_frame(&info, frame_id)? {
+                let mut pid = 0;
+                info.ProcessInfo()?.ProcessId(&mut pid)?;
+                pids.push(pid as u32);
+              }
+            }
+          }
+
+          tx.send(pids)
+            .map_err(|_| windows::core::Error::from(E_UNEXPECTED))
+        },
+      )))?;
+    }
+    let pids = webview2_com::wait_with_pump(rx)?;
+    if pids.is_empty() {
+      return Err(Error::Unsupported(
+        "the renderer process isn't running".into(),
+      ));
+    }
+
+    let mut usage = MemoryUsage::default();
+    for pid in pids {
+      let mut counters = PROCESS_MEMORY_COUNTERS {
+        cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        ..Default::default()
+      };
+      unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)?;
+        let result = GetProcessMemoryInfo(process, &mut counters, counters.cb);
+        let _ = CloseHandle(process);
+        result?;
+      }
+      usage.resident += counters.WorkingSetSize as u64;
+      usage.peak += counters.PeakWorkingSetSize as u64;
+    }
+    Ok(usage)
+  }
+
   pub fn focus(&self) -> Result<()> {
     unsafe {
       self
@@ -2545,6 +3845,24 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn blur(&self) -> Result<()> {
+    self.focus_parent()
+  }
+
+  #[cfg(feature = "drag-drop")]
+  pub fn set_drag_drop_handler(
+    &self,
+    handler: Box<dyn Fn(crate::DragDropEvent) -> DragOperation>,
+  ) -> Result<()> {
+    self.drag_drop_handler.set(handler);
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop-simulation")]
+  pub fn simulate_drag_drop(&self, event: crate::DragDropEvent) -> DragOperation {
+    self.drag_drop_handler.call(event)
+  }
+
   unsafe fn cookie_from_win32(cookie: ICoreWebView2Cookie) -> Result<cookie::Cookie<'static>> {
     let mut name = PWSTR::null();
     cookie.Name(&mut name)?;
@@ -2720,22 +4038,57 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn delete_cookies_for_url(&self, url: &str, name: &str) -> Result<()> {
+    let webview = self.webview.cast::<ICoreWebView2_2>()?;
+    unsafe {
+      webview
+        .CookieManager()?
+        .DeleteCookies(&HSTRING::from(name), &HSTRING::from(url))?;
+    }
+    Ok(())
+  }
+
   pub fn reparent(&self, parent: isize) -> Result<()> {
     let parent = HWND(parent as _);
+    let old_parent = *self.parent.borrow();
+
+    // Child webviews keep their logical bounds, the new parent may be on a monitor
+    // with a different scale factor
+    let logical_bounds = if self.is_child {
+      let scale_factor = util::dpi_to_scale_factor(unsafe { util::hwnd_dpi(self.hwnd) });
+      let bounds = self.bounds()?;
+      Some(Rect {
+        position: bounds.position.to_logical::<f64>(scale_factor).into(),
+        size: bounds.size.to_logical::<f64>(scale_factor).into(),
+      })
+    } else {
+      None
+    };
 
     unsafe {
       SetParent(self.hwnd, Some(parent))?;
 
+      // WebView2 caches the top-level window of its parent for input and focus,
+      // setting the parent window again makes it pick up the new top-level window
+      if let Err(error) = self.controller.SetParentWindow(self.hwnd) {
+        let _ = SetParent(self.hwnd, Some(old_parent));
+        let _ = self.controller.SetParentWindow(self.hwnd);
+        return Err(error.into());
+      }
+
       if !self.is_child {
-        Self::dettach_parent_subclass(*self.parent.borrow());
+        Self::dettach_parent_subclass(old_parent);
         Self::attach_parent_subclass(parent, &self.controller);
+      }
 
-        *self.parent.borrow_mut() = parent;
-
-        let parent_bounds = Self::parent_bounds(parent)?;
+      *self.parent.borrow_mut() = parent;
 
-        self.set_bounds_inner(parent_bounds, (0, 0).into())?;
+      match logical_bounds {
+        Some(bounds) => self.set_bounds(bounds)?,
+        None => self.set_bounds_inner(Self::parent_bounds(parent)?, (0, 0).into())?,
       }
+
+      self.controller.NotifyParentWindowPositionChanged()?;
     }
 
     Ok(())
@@ -2762,6 +4115,209 @@ impl InnerWebView {
     }
   }
 
+  pub fn print_silent<F>(&self, settings: PrintSettings, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    let webview = self.webview.cast::<ICoreWebView2_16>()?;
+
+    unsafe {
+      let print_settings = self
+        .env
+        .cast::<ICoreWebView2Environment6>()?
+        .CreatePrintSettings()?
+        .cast::<ICoreWebView2PrintSettings2>()?;
+      // An empty name prints to the default printer
+      let printer = settings.printer.unwrap_or_default();
+      print_settings.SetPrinterName(&HSTRING::from(printer.as_str()))?;
+      print_settings.SetCopies(settings.copies as i32)?;
+      if let Some((first, last)) = settings.page_range {
+        print_settings.SetPageRanges(&HSTRING::from(format!("{first}-{last}")))?;
+      }
+
+      webview.Print(
+        &print_settings,
+        &PrintCompletedHandler::create(Box::new(move |error_code, status| {
+          let result = error_code.map_err(Into::into).and_then(|_| match status {
+            COREWEBVIEW2_PRINT_STATUS_SUCCEEDED => Ok(()),
+            COREWEBVIEW2_PRINT_STATUS_PRINTER_UNAVAILABLE => Err(Error::PrinterNotFound(printer)),
+            _ => Err(Error::PrintFailed(
+              "the job was not sent to the printer".into(),
+            )),
+          });
+          completion(result);
+          Ok(())
+        })),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  pub fn print_to_pdf<F>(&self, options: PrintToPdfOptions, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    let webview = self.webview.cast::<ICoreWebView2_7>()?;
+    // WebView2 only prints to files
+    let path = crate::util::temp_file_path("pdf");
+
+    unsafe {
+      let settings = self
+        .env
+        .cast::<ICoreWebView2Environment6>()?
+        .CreatePrintSettings()?;
+      settings.SetOrientation(if options.landscape {
+        COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE
+      } else {
+        COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT
+      })?;
+      settings.SetPageWidth(options.page_width)?;
+      settings.SetPageHeight(options.page_height)?;
+      settings.SetMarginTop(options.margins.top)?;
+      settings.SetMarginRight(options.margins.right)?;
+      settings.SetMarginBottom(options.margins.bottom)?;
+      settings.SetMarginLeft(options.margins.left)?;
+      settings.SetShouldPrintHeaderAndFooter(options.header_and_footer)?;
+      settings.SetShouldPrintBackgrounds(options.print_background)?;
+
+      webview.PrintToPdf(
+        &HSTRING::from(path.as_os_str()),
+        &settings,
+        &PrintToPdfCompletedHandler::create(Box::new(move |error_code, is_successful| {
+          let pdf = error_code.map_err(Into::into).and_then(|_| {
+            if !is_successful {
+              return Err(Error::PrintFailed("the document was not written".into()));
+            }
+            std::fs::read(&path).map_err(Into::into)
+          });
+          let _ = std::fs::remove_file(&path);
+          completion(pdf);
+          Ok(())
+        })),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  pub fn find(
+    &self,
+    query: &str,
+    options: FindOptions,
+    callback: impl Fn(u32) + Send + 'static,
+  ) -> Result<()> {
+    self.eval(
+      &find::find(query, options, true),
+      Some(move |count: String| callback(count.parse().unwrap_or(0))),
+    )
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    self.eval(&find::find_step(1), None::<fn(String)>)
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    self.eval(&find::find_step(-1), None::<fn(String)>)
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    self.eval(find::CLEAR_MATCHES, None::<fn(String)>)
+  }
+
+  pub fn select_all(&self) -> Result<()> {
+    self.eval(crate::selection::SELECT_ALL, None::<fn(String)>)
+  }
+
+  pub fn capture_png<F>(&self, rect: Option<Rect>, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    let scale_factor = util::dpi_to_scale_factor(unsafe { util::hwnd_dpi(self.hwnd) });
+    let clip = rect.map(|rect| {
+      let position = rect.position.to_physical::<i32>(scale_factor);
+      let size = rect.size.to_physical::<i32>(scale_factor);
+      WICRect {
+        X: position.x,
+        Y: position.y,
+        Width: size.width,
+        Height: size.height,
+      }
+    });
+
+    let stream = unsafe { SHCreateMemStream(None) }
+      .ok_or_else(|| Error::CaptureFailed("failed to create the image stream".into()))?;
+    let stream_ = stream.clone();
+    unsafe {
+      self.webview.CapturePreview(
+        COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+        &stream,
+        &CapturePreviewCompletedHandler::create(Box::new(move |error_code| {
+          let png = error_code.and_then(|_| match clip {
+            Some(clip) => crop_png(&stream_, clip),
+            None => read_stream(&stream_),
+          });
+          completion(png.map_err(Into::into));
+          Ok(())
+        })),
+      )
+    }
+    .map_err(Into::into)
+  }
+
+  pub fn clear_browsing_data<F>(&self, data: BrowsingDataFlags, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    let mut kinds = 0;
+    for (flag, kind) in [
+      (
+        BrowsingDataFlags::COOKIES,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_COOKIES,
+      ),
+      (
+        BrowsingDataFlags::LOCAL_STORAGE,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_LOCAL_STORAGE,
+      ),
+      (
+        BrowsingDataFlags::INDEXED_DB,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_INDEXED_DB,
+      ),
+      (
+        BrowsingDataFlags::CACHE,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE,
+      ),
+      (
+        BrowsingDataFlags::SERVICE_WORKERS,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_SERVICE_WORKERS,
+      ),
+      (
+        BrowsingDataFlags::WEB_SQL,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_WEB_SQL,
+      ),
+    ] {
+      if data.contains(flag) {
+        kinds |= kind.0;
+      }
+    }
+
+    unsafe {
+      self
+        .webview
+        .cast::<ICoreWebView2_13>()?
+        .Profile()?
+        .cast::<ICoreWebView2Profile2>()?
+        .ClearBrowsingData(
+          COREWEBVIEW2_BROWSING_DATA_KINDS(kinds),
+          &ClearBrowsingDataCompletedHandler::create(Box::new(move |error_code| {
+            completion(error_code.map_err(Into::into));
+            Ok(())
+          })),
+        )
+        .map_err(Into::into)
+    }
+  }
+
   pub fn set_theme(&self, theme: Theme) -> Result<()> {
     unsafe { set_theme(&self.webview, theme) }
   }
@@ -2781,17 +4337,76 @@ impl InnerWebView {
     unsafe { webview.SetMemoryUsageTargetLevel(level).map_err(Into::into) }
   }
 
+  pub fn memory_usage_level(&self) -> Result<MemoryUsageLevel> {
+    let webview = self.webview.cast::<ICoreWebView2_19>()?;
+    let mut level = COREWEBVIEW2_MEMORY_USAGE_TARGET_LEVEL::default();
+    unsafe { webview.MemoryUsageTargetLevel(&mut level) }?;
+    Ok(if level == COREWEBVIEW2_MEMORY_USAGE_TARGET_LEVEL_LOW {
+      MemoryUsageLevel::Low
+    } else {
+      MemoryUsageLevel::Normal
+    })
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn open_devtools(&self) {
-    let _ = unsafe { self.webview.OpenDevToolsWindow() };
+  pub fn open_devtools(&self) -> Result<()> {
+    if !self.devtools {
+      return Err(Error::DevToolsNotEnabled);
+    }
+    unsafe { self.webview.OpenDevToolsWindow() }.map_err(Into::into)
   }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn close_devtools(&self) {}
+  pub fn close_devtools(&self) {
+    if let Some(hwnd) = self.devtools_window() {
+      let _ = unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) };
+    }
+  }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
   pub fn is_devtools_open(&self) -> bool {
-    false
+    self.devtools_window().is_some()
+  }
+
+  /// WebView2 has no API for its devtools window, a top level window of the browser process titled
+  /// `DevTools - <url without http(s)://>`.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  fn devtools_window(&self) -> Option<HWND> {
+    let mut browser_process_id = 0;
+    unsafe { self.webview.BrowserProcessId(&mut browser_process_id) }.ok()?;
+    let url = self.url().ok()?;
+    let url = url
+      .strip_prefix("https://")
+      .or_else(|| url.strip_prefix("http://"))
+      .unwrap_or(&url);
+    let title = format!("DevTools - {url}");
+
+    let mut devtools_window = None;
+    let mut callback = |hwnd: HWND| unsafe {
+      let mut process_id = 0;
+      GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+      if process_id != browser_process_id || !IsWindowVisible(hwnd).as_bool() {
+        return true;
+      }
+      let mut buffer = vec![0; GetWindowTextLengthW(hwnd) as usize + 1];
+      let len = GetWindowTextW(hwnd, &mut buffer);
+      if String::from_utf16_lossy(&buffer[..len as usize]) == title {
+        devtools_window = Some(hwnd);
+        return false;
+      }
+      true
+    };
+    let mut trait_obj: &mut dyn FnMut(HWND) -> bool = &mut callback;
+    let closure_pointer_pointer: *mut c_void = unsafe { std::mem::transmute(&mut trait_obj) };
+    let lparam = LPARAM(closure_pointer_pointer as _);
+    unsafe extern "system" fn enumerate_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+      let closure = &mut *(lparam.0 as *mut c_void as *mut &mut dyn FnMut(HWND) -> bool);
+      closure(hwnd).into()
+    }
+    // Fails when the callback stops the enumeration
+    let _ = unsafe { EnumWindows(Some(enumerate_callback), lparam) };
+
+    devtools_window
   }
 }
 
@@ -2994,33 +4609,207 @@ pub enum ScrollBarStyle {
   FluentOverlay,
 }
 
+unsafe fn script_result_json(
+  result: &ICoreWebView2ExecuteScriptResult,
+) -> windows::core::Result<String> {
+  let mut succeeded = BOOL::default();
+  result.Succeeded(&mut succeeded)?;
+  if succeeded.as_bool() {
+    let mut json = PWSTR::null();
+    result.ResultAsJson(&mut json)?;
+    Ok(take_pwstr(json))
+  } else {
+    let mut message = PWSTR::null();
+    result.Exception()?.Message(&mut message)?;
+    Ok(crate::script::error_json(&take_pwstr(message)))
+  }
+}
+
+/// Whether `info` is a renderer process running a frame of the webview whose main frame is
+/// `frame_id`.
+unsafe fn renderer_hosts_frame(
+  info: &ICoreWebView2ProcessExtendedInfo,
+  frame_id: u32,
+) -> windows::core::Result<bool> {
+  let mut kind = COREWEBVIEW2_PROCESS_KIND::default();
+  info.ProcessInfo()?.Kind(&mut kind)?;
+  if kind != COREWEBVIEW2_PROCESS_KIND_RENDERER {
+    return Ok(false);
+  }
+
+  let frames = info.AssociatedFrameInfos()?.GetIterator()?;
+  let mut has_current = BOOL::default();
+  frames.HasCurrent(&mut has_current)?;
+  while has_current.as_bool() {
+    let mut frame = frames.GetCurrent()?.cast::<ICoreWebView2FrameInfo2>()?;
+    while let Ok(parent) = frame.ParentFrameInfo() {
+      frame = parent.cast()?;
+    }
+    let mut id = 0;
+    frame.FrameId(&mut id)?;
+    if id == frame_id {
+      return Ok(true);
+    }
+    frames.MoveNext(&mut has_current)?;
+  }
+  Ok(false)
+}
+
+unsafe fn context_menu_target(
+  target: &ICoreWebView2ContextMenuTarget,
+  position: LogicalPosition<f64>,
+) -> windows::core::Result<ContextMenuTarget> {
+  let mut kind = COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND::default();
+  target.Kind(&mut kind)?;
+  let media_type = match kind {
+    COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND_IMAGE => Some(ContextMenuMediaType::Image),
+    COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND_VIDEO => Some(ContextMenuMediaType::Video),
+    COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND_AUDIO => Some(ContextMenuMediaType::Audio),
+    _ => None,
+  };
+
+  let mut has_link_uri = BOOL::default();
+  target.HasLinkUri(&mut has_link_uri)?;
+  let link_url = if has_link_uri.as_bool() {
+    let mut uri = PWSTR::null();
+    target.LinkUri(&mut uri)?;
+    Some(take_pwstr(uri))
+  } else {
+    None
+  };
+
+  let mut has_selection = BOOL::default();
+  target.HasSelection(&mut has_selection)?;
+  let selection_text = if has_selection.as_bool() {
+    let mut text = PWSTR::null();
+    target.SelectionText(&mut text)?;
+    Some(take_pwstr(text))
+  } else {
+    None
+  };
+
+  Ok(ContextMenuTarget {
+    link_url,
+    selection_text,
+    media_type,
+    position,
+  })
+}
+
+fn web_error_status_message(status: COREWEBVIEW2_WEB_ERROR_STATUS) -> &'static str {
+  match status {
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT => {
+      "The certificate common name does not match the host name"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED => "The certificate has expired",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CLIENT_CERTIFICATE_CONTAINS_ERRORS => {
+      "The client certificate contains errors"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED => "The certificate has been revoked",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_IS_INVALID => "The certificate is invalid",
+    COREWEBVIEW2_WEB_ERROR_STATUS_SERVER_UNREACHABLE => "The server is unreachable",
+    COREWEBVIEW2_WEB_ERROR_STATUS_TIMEOUT => "The connection timed out",
+    COREWEBVIEW2_WEB_ERROR_STATUS_ERROR_HTTP_INVALID_SERVER_RESPONSE => {
+      "The server returned an invalid or unrecognized response"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_ABORTED => "The connection was aborted",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_RESET => "The connection was reset",
+    COREWEBVIEW2_WEB_ERROR_STATUS_DISCONNECTED => "The internet connection has been lost",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CANNOT_CONNECT => "Cannot connect to the destination",
+    COREWEBVIEW2_WEB_ERROR_STATUS_HOST_NAME_NOT_RESOLVED => "The host name could not be resolved",
+    COREWEBVIEW2_WEB_ERROR_STATUS_OPERATION_CANCELED => "The operation was canceled",
+    COREWEBVIEW2_WEB_ERROR_STATUS_REDIRECT_FAILED => "The request redirect failed",
+    COREWEBVIEW2_WEB_ERROR_STATUS_UNEXPECTED_ERROR => "An unexpected error occurred",
+    COREWEBVIEW2_WEB_ERROR_STATUS_VALID_AUTHENTICATION_CREDENTIALS_REQUIRED => {
+      "The user is prompted with a login but the request was not authenticated"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_VALID_PROXY_AUTHENTICATION_REQUIRED => {
+      "The proxy requires authentication"
+    }
+    _ => "An unknown error occurred",
+  }
+}
+
+/// Reads a stream written by WebView2 from the start.
+unsafe fn read_stream(stream: &IStream) -> windows::core::Result<Vec<u8>> {
+  let mut stat = STATSTG::default();
+  stream.Stat(&mut stat, STATFLAG_NONAME)?;
+  stream.Seek(0, STREAM_SEEK_SET, None)?;
+
+  let mut bytes = vec![0; stat.cbSize as usize];
+  let mut read = 0;
+  stream
+    .Read(bytes.as_mut_ptr() as _, bytes.len() as u32, Some(&mut read))
+    .ok()?;
+  bytes.truncate(read as usize);
+  Ok(bytes)
+}
+
+/// Crops the PNG image in `stream` to `clip` and encodes the result as PNG.
+unsafe fn crop_png(stream: &IStream, clip: WICRect) -> windows::core::Result<Vec<u8>> {
+  let factory: IWICImagingFactory =
+    CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)?;
+
+  stream.Seek(0, STREAM_SEEK_SET, None)?;
+  let decoder =
+    factory.CreateDecoderFromStream(stream, std::ptr::null(), WICDecodeMetadataCacheOnDemand)?;
+  let clipper = factory.CreateBitmapClipper()?;
+  clipper.Initialize(&decoder.GetFrame(0)?, &clip)?;
+
+  let output = SHCreateMemStream(None).ok_or_else(|| windows::core::Error::from(E_OUTOFMEMORY))?;
+  let encoder = factory.CreateEncoder(&GUID_ContainerFormatPng, std::ptr::null())?;
+  encoder.Initialize(&output, WICBitmapEncoderNoCache)?;
+  let mut frame = None;
+  encoder.CreateNewFrame(&mut frame, std::ptr::null_mut())?;
+  let frame = frame.ok_or_else(|| windows::core::Error::from(E_POINTER))?;
+  frame.Initialize(None)?;
+  frame.WriteSource(&clipper, std::ptr::null())?;
+  frame.Commit()?;
+  encoder.Commit()?;
+
+  read_stream(&output)
+}
+
 #[inline]
 fn load_url_with_headers(
   webview: &ICoreWebView2,
   env: &ICoreWebView2Environment,
   url: &str,
   headers: http::HeaderMap,
+) -> Result<()> {
+  load_url_with_request(webview, env, url, http::Method::GET, &[], headers)
+}
+
+#[inline]
+fn load_url_with_request(
+  webview: &ICoreWebView2,
+  env: &ICoreWebView2Environment,
+  url: &str,
+  method: http::Method,
+  body: &[u8],
+  headers: http::HeaderMap,
 ) -> Result<()> {
   let url = HSTRING::from(url);
 
   let headers_map = {
     let mut headers_map = String::new();
-    for (name, value) in headers.iter() {
-      let header_key = name.to_string();
-      if let Ok(value) = value.to_str() {
-        let _ = writeln!(headers_map, "{}: {}", header_key, value);
-      }
+    for (name, value) in crate::navigation::request_headers(&headers) {
+      let _ = writeln!(headers_map, "{}: {}", name, value);
     }
     HSTRING::from(headers_map)
   };
 
   unsafe {
     let env = env.cast::<ICoreWebView2Environment9>()?;
-    let method = HSTRING::from("GET");
-    if let Ok(request) = env.CreateWebResourceRequest(&url, &method, None, &headers_map) {
-      let webview: ICoreWebView2_10 = webview.cast()?;
-      webview.NavigateWithWebResourceRequest(&request)?;
-    }
+    let method = HSTRING::from(method.as_str());
+    let body = if body.is_empty() {
+      None
+    } else {
+      SHCreateMemStream(Some(body))
+    };
+    let request = env.CreateWebResourceRequest(&url, &method, body.as_ref(), &headers_map)?;
+    let webview: ICoreWebView2_10 = webview.cast()?;
+    webview.NavigateWithWebResourceRequest(&request)?;
   };
 
   Ok(())
@@ -0,0 +1,145 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! An [`IStream`] over a [`StreamBody`] so WebView2 can read custom protocol
+//! responses incrementally.
+
+use std::{
+  ffi::c_void,
+  io::{ErrorKind, Read},
+  sync::Mutex,
+};
+
+use windows::{
+  core::{implement, Result, HRESULT},
+  Win32::{
+    Foundation::{E_NOTIMPL, E_POINTER, STG_E_INVALIDFUNCTION, STG_E_READFAULT, S_FALSE, S_OK},
+    System::Com::{
+      ISequentialStream_Impl, IStream, IStream_Impl, LOCKTYPE, STATFLAG, STATSTG, STGC,
+      STGTY_STREAM, STREAM_SEEK, STREAM_SEEK_CUR,
+    },
+  },
+};
+
+use crate::StreamBody;
+
+/// A forward-only stream, WebView2 only reads custom protocol responses front to back.
+#[implement(IStream)]
+pub struct BodyStream {
+  body: Mutex<StreamBody>,
+  position: Mutex<u64>,
+}
+
+impl BodyStream {
+  pub fn into_istream(body: StreamBody) -> IStream {
+    Self {
+      body: Mutex::new(body),
+      position: Mutex::new(0),
+    }
+    .into()
+  }
+}
+
+impl ISequentialStream_Impl for BodyStream_Impl {
+  fn Read(&self, pv: *mut c_void, cb: u32, pcbread: *mut u32) -> HRESULT {
+    if pv.is_null() {
+      return E_POINTER;
+    }
+
+    let buf = unsafe { std::slice::from_raw_parts_mut(pv as *mut u8, cb as usize) };
+    let mut body = self.body.lock().unwrap();
+
+    // IStream::Read must only return less than requested at the end of the stream
+    let mut read = 0;
+    while read < buf.len() {
+      match body.read(&mut buf[read..]) {
+        Ok(0) => break,
+        Ok(count) => read += count,
+        Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+        Err(_e) => {
+          #[cfg(feature = "tracing")]
+          tracing::warn!("Failed to read custom protocol response body: {_e}");
+          return STG_E_READFAULT;
+        }
+      }
+    }
+
+    *self.position.lock().unwrap() += read as u64;
+    if !pcbread.is_null() {
+      unsafe { *pcbread = read as u32 };
+    }
+
+    if read < buf.len() {
+      S_FALSE
+    } else {
+      S_OK
+    }
+  }
+
+  fn Write(&self, _pv: *const c_void, _cb: u32, _pcbwritten: *mut u32) -> HRESULT {
+    STG_E_INVALIDFUNCTION
+  }
+}
+
+impl IStream_Impl for BodyStream_Impl {
+  fn Seek(&self, dlibmove: i64, dworigin: STREAM_SEEK, plibnewposition: *mut u64) -> Result<()> {
+    // Only querying the current position is supported
+    if dworigin != STREAM_SEEK_CUR || dlibmove != 0 {
+      return Err(STG_E_INVALIDFUNCTION.into());
+    }
+    if !plibnewposition.is_null() {
+      unsafe { *plibnewposition = *self.position.lock().unwrap() };
+    }
+    Ok(())
+  }
+
+  fn SetSize(&self, _libnewsize: u64) -> Result<()> {
+    Err(STG_E_INVALIDFUNCTION.into())
+  }
+
+  fn CopyTo(
+    &self,
+    _pstm: windows_core::Ref<'_, IStream>,
+    _cb: u64,
+    _pcbread: *mut u64,
+    _pcbwritten: *mut u64,
+  ) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn Commit(&self, _grfcommitflags: &STGC) -> Result<()> {
+    Ok(())
+  }
+
+  fn Revert(&self) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn LockRegion(&self, _liboffset: u64, _cb: u64, _dwlocktype: &LOCKTYPE) -> Result<()> {
+    Err(STG_E_INVALIDFUNCTION.into())
+  }
+
+  fn UnlockRegion(&self, _liboffset: u64, _cb: u64, _dwlocktype: u32) -> Result<()> {
+    Err(STG_E_INVALIDFUNCTION.into())
+  }
+
+  fn Stat(&self, pstatstg: *mut STATSTG, _grfstatflag: &STATFLAG) -> Result<()> {
+    if pstatstg.is_null() {
+      return Err(E_POINTER.into());
+    }
+    let len = self.body.lock().unwrap().len().unwrap_or_default();
+    unsafe {
+      *pstatstg = STATSTG {
+        r#type: STGTY_STREAM.0 as u32,
+        cbSize: len,
+        ..Default::default()
+      };
+    }
+    Ok(())
+  }
+
+  fn Clone(&self) -> Result<IStream> {
+    Err(E_NOTIMPL.into())
+  }
+}
@@ -0,0 +1,126 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::ffi::c_void;
+
+use objc2::{msg_send, rc::Retained, runtime::NSObject, ClassType};
+use objc2_foundation::{
+  NSArray, NSData, NSDataBase64EncodingOptions, NSKeyValueCoding, NSMutableDictionary, NSNumber,
+  NSString, NSURLCredential, NSURLCredentialPersistence, NSURLProtectionSpace,
+};
+
+use crate::{Certificate, CertificateInfo};
+
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+  static kSecClass: &'static NSString;
+  static kSecClassIdentity: &'static NSString;
+  static kSecMatchLimit: &'static NSString;
+  static kSecMatchLimitAll: &'static NSString;
+  static kSecReturnRef: &'static NSString;
+  static kSecMatchIssuers: &'static NSString;
+
+  fn SecItemCopyMatching(
+    query: &NSMutableDictionary<NSString, NSObject>,
+    result: *mut *mut c_void,
+  ) -> i32;
+  fn SecIdentityCopyCertificate(identity: *const c_void, certificate: *mut *mut c_void) -> i32;
+  fn SecCertificateCopySubjectSummary(certificate: *const c_void) -> *mut NSString;
+  fn SecCertificateCopyData(certificate: *const c_void) -> *mut NSData;
+  fn SecTrustEvaluateWithError(trust: *const c_void, error: *mut *mut c_void) -> bool;
+  fn SecTrustGetCertificateAtIndex(trust: *const c_void, index: isize) -> *const c_void;
+}
+
+/// The identities of the keychain issued by one of `issuers`, or by any issuer if `None`.
+pub(crate) unsafe fn keychain_identities(issuers: Option<&NSArray<NSData>>) -> Vec<Certificate> {
+  let query = NSMutableDictionary::<NSString, NSObject>::new();
+  query.setValue_forKey(Some(kSecClassIdentity), kSecClass);
+  query.setValue_forKey(Some(kSecMatchLimitAll), kSecMatchLimit);
+  query.setValue_forKey(Some(&NSNumber::new_bool(true)), kSecReturnRef);
+  if let Some(issuers) = issuers.filter(|issuers| !issuers.is_empty()) {
+    query.setValue_forKey(Some(issuers), kSecMatchIssuers);
+  }
+
+  let mut result = std::ptr::null_mut();
+  if SecItemCopyMatching(&query, &mut result) != 0 || result.is_null() {
+    return Vec::new();
+  }
+  let Some(identities) = Retained::from_raw(result as *mut NSArray<NSObject>) else {
+    return Vec::new();
+  };
+
+  identities
+    .iter()
+    .map(|identity| {
+      let mut certificate = std::ptr::null_mut();
+      let subject =
+        if SecIdentityCopyCertificate(Retained::as_ptr(&identity).cast(), &mut certificate) == 0 {
+          let subject = Retained::from_raw(SecCertificateCopySubjectSummary(certificate));
+          // Releases the certificate
+          let _ = Retained::from_raw(certificate as *mut NSObject);
+          subject.map(|subject| subject.to_string())
+        } else {
+          None
+        };
+
+      Certificate {
+        subject: subject.unwrap_or_default(),
+        inner: identity,
+      }
+    })
+    .collect()
+}
+
+/// A credential authenticating with the identity of `certificate`.
+pub(crate) unsafe fn certificate_credential(
+  certificate: &Certificate,
+) -> Retained<NSURLCredential> {
+  let identity = Retained::as_ptr(&certificate.inner) as *const c_void;
+  msg_send![
+    NSURLCredential::class(),
+    credentialWithIdentity: identity,
+    certificates: std::ptr::null::<NSArray>(),
+    persistence: NSURLCredentialPersistence::ForSession,
+  ]
+}
+
+/// The certificate of the server of `protection_space` and the credential accepting it, `None`
+/// if the certificate passes the TLS verification.
+pub(crate) unsafe fn untrusted_server_certificate(
+  protection_space: &NSURLProtectionSpace,
+) -> Option<(CertificateInfo, Retained<NSURLCredential>)> {
+  let trust: *const c_void = msg_send![protection_space, serverTrust];
+  if trust.is_null() || SecTrustEvaluateWithError(trust, std::ptr::null_mut()) {
+    return None;
+  }
+
+  // The leaf certificate comes first
+  let certificate = SecTrustGetCertificateAtIndex(trust, 0);
+  if certificate.is_null() {
+    return None;
+  }
+
+  let subject = Retained::from_raw(SecCertificateCopySubjectSummary(certificate))
+    .map(|subject| subject.to_string())
+    .unwrap_or_default();
+  let pem = Retained::from_raw(SecCertificateCopyData(certificate))
+    .map(|der| {
+      let base64 = der.base64EncodedStringWithOptions(
+        NSDataBase64EncodingOptions::Encoding64CharacterLineLength
+          | NSDataBase64EncodingOptions::EndLineWithLineFeed,
+      );
+      format!("-----BEGIN CERTIFICATE-----\n{base64}\n-----END CERTIFICATE-----\n")
+    })
+    .unwrap_or_default();
+  let credential = msg_send![NSURLCredential::class(), credentialForTrust: trust];
+
+  Some((
+    CertificateInfo {
+      subject,
+      issuer: String::new(),
+      pem,
+    },
+    credential,
+  ))
+}
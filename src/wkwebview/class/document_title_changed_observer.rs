@@ -40,11 +40,8 @@ define_class!(
       if let (Some(key_path), Some(object)) = (key_path, of_object) {
         unsafe {
           if key_path.isEqualToString(ns_string!("title")) {
-            let handler = &self.ivars().handler;
-            // if !handler.is_null() {
-            let title: *const NSString = msg_send![object, title];
-            handler((*title).to_string());
-            // }
+            let title: Option<Retained<NSString>> = msg_send![object, title];
+            (self.ivars().handler)(title.map(|t| t.to_string()).unwrap_or_default());
           }
         }
       }
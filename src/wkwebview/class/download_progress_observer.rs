@@ -0,0 +1,93 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{ffi::c_void, ptr::null_mut};
+
+use objc2::{
+  define_class, msg_send,
+  rc::Retained,
+  runtime::{AnyObject, NSObject},
+  AllocAnyThread, DefinedClass,
+};
+use objc2_foundation::{
+  ns_string, NSDictionary, NSKeyValueChangeKey, NSKeyValueObservingOptions,
+  NSObjectNSKeyValueObserverRegistration, NSObjectProtocol, NSString,
+};
+
+pub struct DownloadProgressObserverIvars {
+  /// The `NSProgress` of a `WKDownload`.
+  pub object: Retained<NSObject>,
+  pub handler: Box<dyn Fn(u64, Option<u64>)>,
+}
+
+define_class!(
+  #[unsafe(super(NSObject))]
+  #[name = "DownloadProgressObserver"]
+  #[ivars = DownloadProgressObserverIvars]
+  pub struct DownloadProgressObserver;
+
+  /// NSKeyValueObserving.
+  impl DownloadProgressObserver {
+    #[unsafe(method(observeValueForKeyPath:ofObject:change:context:))]
+    fn observe_value_for_key_path(
+      &self,
+      key_path: Option<&NSString>,
+      of_object: Option<&AnyObject>,
+      _change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
+      _context: *mut c_void,
+    ) {
+      if let (Some(key_path), Some(object)) = (key_path, of_object) {
+        unsafe {
+          if key_path.isEqualToString(ns_string!("completedUnitCount")) {
+            let completed: i64 = msg_send![object, completedUnitCount];
+            // -1 when the size is unknown
+            let total: i64 = msg_send![object, totalUnitCount];
+            (self.ivars().handler)(completed.max(0) as u64, (total > 0).then_some(total as u64));
+          }
+        }
+      }
+    }
+  }
+
+  unsafe impl NSObjectProtocol for DownloadProgressObserver {}
+);
+
+impl DownloadProgressObserver {
+  pub fn new(
+    progress: Retained<NSObject>,
+    handler: Box<dyn Fn(u64, Option<u64>)>,
+  ) -> Retained<Self> {
+    let observer = Self::alloc().set_ivars(DownloadProgressObserverIvars {
+      object: progress,
+      handler,
+    });
+
+    let observer: Retained<Self> = unsafe { msg_send![super(observer), init] };
+
+    unsafe {
+      observer
+        .ivars()
+        .object
+        .addObserver_forKeyPath_options_context(
+          &observer,
+          ns_string!("completedUnitCount"),
+          NSKeyValueObservingOptions::New,
+          null_mut(),
+        );
+    }
+
+    observer
+  }
+}
+
+impl Drop for DownloadProgressObserver {
+  fn drop(&mut self) {
+    unsafe {
+      self
+        .ivars()
+        .object
+        .removeObserver_forKeyPath(self, ns_string!("completedUnitCount"));
+    }
+  }
+}
@@ -0,0 +1,110 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::Cell, ffi::c_void, ptr::null_mut};
+
+use objc2::{
+  define_class, msg_send,
+  rc::Retained,
+  runtime::{AnyObject, NSObject},
+  AllocAnyThread, DefinedClass,
+};
+use objc2_foundation::{
+  ns_string, NSDictionary, NSKeyValueChangeKey, NSKeyValueObservingOptions,
+  NSObjectNSKeyValueObserverRegistration, NSObjectProtocol, NSString,
+};
+
+#[cfg(target_os = "ios")]
+use crate::wkwebview::ios::WKWebView::WKFullscreenState;
+#[cfg(target_os = "macos")]
+use objc2_web_kit::WKFullscreenState;
+
+use crate::{wkwebview::evaluate_javascript, WryWebView};
+pub struct FullscreenObserverIvars {
+  pub object: Retained<WryWebView>,
+  pub handler: Box<dyn Fn(bool) -> bool>,
+  // Whether the handler allowed the page to enter fullscreen and wasn't told it left yet
+  pub fullscreen: Cell<bool>,
+}
+
+define_class!(
+  #[unsafe(super(NSObject))]
+  #[name = "FullscreenObserver"]
+  #[ivars = FullscreenObserverIvars]
+  pub struct FullscreenObserver;
+
+  /// NSKeyValueObserving.
+  impl FullscreenObserver {
+    #[unsafe(method(observeValueForKeyPath:ofObject:change:context:))]
+    fn observe_value_for_key_path(
+      &self,
+      key_path: Option<&NSString>,
+      of_object: Option<&AnyObject>,
+      _change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
+      _context: *mut c_void,
+    ) {
+      if let (Some(key_path), Some(object)) = (key_path, of_object) {
+        unsafe {
+          if key_path.isEqualToString(ns_string!("fullscreenState")) {
+            let state: WKFullscreenState = msg_send![object, fullscreenState];
+            let ivars = self.ivars();
+            if state == WKFullscreenState::EnteringFullscreen && !ivars.fullscreen.get() {
+              if (ivars.handler)(true) {
+                ivars.fullscreen.set(true);
+              } else {
+                evaluate_javascript(
+                  &ivars.object,
+                  "document.exitFullscreen()",
+                  None::<Box<dyn Fn(String) + Send + 'static>>,
+                );
+              }
+            } else if state == WKFullscreenState::NotInFullscreen && ivars.fullscreen.replace(false)
+            {
+              (ivars.handler)(false);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  unsafe impl NSObjectProtocol for FullscreenObserver {}
+);
+
+impl FullscreenObserver {
+  pub fn new(webview: Retained<WryWebView>, handler: Box<dyn Fn(bool) -> bool>) -> Retained<Self> {
+    let observer = Self::alloc().set_ivars(FullscreenObserverIvars {
+      object: webview,
+      handler,
+      fullscreen: Cell::new(false),
+    });
+
+    let observer: Retained<Self> = unsafe { msg_send![super(observer), init] };
+
+    unsafe {
+      observer
+        .ivars()
+        .object
+        .addObserver_forKeyPath_options_context(
+          &observer,
+          ns_string!("fullscreenState"),
+          NSKeyValueObservingOptions::New,
+          null_mut(),
+        );
+    }
+
+    observer
+  }
+}
+
+impl Drop for FullscreenObserver {
+  fn drop(&mut self) {
+    unsafe {
+      self
+        .ivars()
+        .object
+        .removeObserver_forKeyPath(self, ns_string!("fullscreenState"));
+    }
+  }
+}
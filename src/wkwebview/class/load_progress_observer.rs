@@ -0,0 +1,88 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{ffi::c_void, ptr::null_mut};
+
+use objc2::{
+  define_class, msg_send,
+  rc::Retained,
+  runtime::{AnyObject, NSObject},
+  AllocAnyThread, DefinedClass,
+};
+use objc2_foundation::{
+  ns_string, NSDictionary, NSKeyValueChangeKey, NSKeyValueObservingOptions,
+  NSObjectNSKeyValueObserverRegistration, NSObjectProtocol, NSString,
+};
+
+use crate::WryWebView;
+pub struct LoadProgressObserverIvars {
+  pub object: Retained<WryWebView>,
+  pub handler: Box<dyn Fn(f64)>,
+}
+
+define_class!(
+  #[unsafe(super(NSObject))]
+  #[name = "LoadProgressObserver"]
+  #[ivars = LoadProgressObserverIvars]
+  pub struct LoadProgressObserver;
+
+  /// NSKeyValueObserving.
+  impl LoadProgressObserver {
+    #[unsafe(method(observeValueForKeyPath:ofObject:change:context:))]
+    fn observe_value_for_key_path(
+      &self,
+      key_path: Option<&NSString>,
+      of_object: Option<&AnyObject>,
+      _change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
+      _context: *mut c_void,
+    ) {
+      if let (Some(key_path), Some(object)) = (key_path, of_object) {
+        unsafe {
+          if key_path.isEqualToString(ns_string!("estimatedProgress")) {
+            let progress: f64 = msg_send![object, estimatedProgress];
+            (self.ivars().handler)(progress);
+          }
+        }
+      }
+    }
+  }
+
+  unsafe impl NSObjectProtocol for LoadProgressObserver {}
+);
+
+impl LoadProgressObserver {
+  pub fn new(webview: Retained<WryWebView>, handler: Box<dyn Fn(f64)>) -> Retained<Self> {
+    let observer = Self::alloc().set_ivars(LoadProgressObserverIvars {
+      object: webview,
+      handler,
+    });
+
+    let observer: Retained<Self> = unsafe { msg_send![super(observer), init] };
+
+    unsafe {
+      observer
+        .ivars()
+        .object
+        .addObserver_forKeyPath_options_context(
+          &observer,
+          ns_string!("estimatedProgress"),
+          NSKeyValueObservingOptions::New,
+          null_mut(),
+        );
+    }
+
+    observer
+  }
+}
+
+impl Drop for LoadProgressObserver {
+  fn drop(&mut self) {
+    unsafe {
+      self
+        .ivars()
+        .object
+        .removeObserver_forKeyPath(self, ns_string!("estimatedProgress"));
+    }
+  }
+}
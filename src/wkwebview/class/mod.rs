@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: MIT
 
 pub mod document_title_changed_observer;
+pub mod download_progress_observer;
+pub mod fullscreen_observer;
+pub mod load_progress_observer;
+pub mod url_changed_observer;
 pub mod url_scheme_handler;
 pub mod wry_download_delegate;
 pub mod wry_navigation_delegate;
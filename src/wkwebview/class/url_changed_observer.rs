@@ -0,0 +1,90 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{ffi::c_void, ptr::null_mut};
+
+use objc2::{
+  define_class, msg_send,
+  rc::Retained,
+  runtime::{AnyObject, NSObject},
+  AllocAnyThread, DefinedClass,
+};
+use objc2_foundation::{
+  ns_string, NSDictionary, NSKeyValueChangeKey, NSKeyValueObservingOptions,
+  NSObjectNSKeyValueObserverRegistration, NSObjectProtocol, NSString, NSURL,
+};
+
+use crate::WryWebView;
+pub struct UrlChangedObserverIvars {
+  pub object: Retained<WryWebView>,
+  pub handler: Box<dyn Fn(String)>,
+}
+
+define_class!(
+  #[unsafe(super(NSObject))]
+  #[name = "UrlChangedObserver"]
+  #[ivars = UrlChangedObserverIvars]
+  pub struct UrlChangedObserver;
+
+  /// NSKeyValueObserving.
+  impl UrlChangedObserver {
+    #[unsafe(method(observeValueForKeyPath:ofObject:change:context:))]
+    fn observe_value_for_key_path(
+      &self,
+      key_path: Option<&NSString>,
+      of_object: Option<&AnyObject>,
+      _change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
+      _context: *mut c_void,
+    ) {
+      if let (Some(key_path), Some(object)) = (key_path, of_object) {
+        unsafe {
+          if key_path.isEqualToString(ns_string!("URL")) {
+            let url: Option<Retained<NSURL>> = msg_send![object, URL];
+            if let Some(url) = url.and_then(|url| url.absoluteString()) {
+              (self.ivars().handler)(url.to_string());
+            }
+          }
+        }
+      }
+    }
+  }
+
+  unsafe impl NSObjectProtocol for UrlChangedObserver {}
+);
+
+impl UrlChangedObserver {
+  pub fn new(webview: Retained<WryWebView>, handler: Box<dyn Fn(String)>) -> Retained<Self> {
+    let observer = Self::alloc().set_ivars(UrlChangedObserverIvars {
+      object: webview,
+      handler,
+    });
+
+    let observer: Retained<Self> = unsafe { msg_send![super(observer), init] };
+
+    unsafe {
+      observer
+        .ivars()
+        .object
+        .addObserver_forKeyPath_options_context(
+          &observer,
+          ns_string!("URL"),
+          NSKeyValueObservingOptions::New,
+          null_mut(),
+        );
+    }
+
+    observer
+  }
+}
+
+impl Drop for UrlChangedObserver {
+  fn drop(&mut self) {
+    unsafe {
+      self
+        .ivars()
+        .object
+        .removeObserver_forKeyPath(self, ns_string!("URL"));
+    }
+  }
+}
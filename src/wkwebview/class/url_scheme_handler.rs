@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: MIT
 
 use std::{
-  borrow::Cow,
   ffi::{c_char, c_void, CStr},
+  io::{ErrorKind, Read},
   panic::AssertUnwindSafe,
   ptr::NonNull,
 };
@@ -19,12 +19,15 @@ use objc2::{
   AllocAnyThread, ClassType, Message,
 };
 use objc2_foundation::{
-  NSData, NSHTTPURLResponse, NSMutableDictionary, NSObject, NSObjectProtocol, NSString, NSURL,
-  NSUUID,
+  NSData, NSError, NSHTTPURLResponse, NSMutableDictionary, NSObject, NSObjectProtocol, NSString,
+  NSURL, NSUUID,
 };
 use objc2_web_kit::{WKURLSchemeHandler, WKURLSchemeTask};
 
-use crate::{wkwebview::WEBVIEW_STATE, RequestAsyncResponder, WryWebView};
+use crate::{wkwebview::WEBVIEW_STATE, RequestAsyncResponder, ResponseBody, WryWebView};
+
+/// The size of the chunks a streamed response body is sent to the webview in.
+const STREAM_CHUNK_LEN: usize = 64 * 1024;
 
 pub fn create(name: &str) -> &AnyClass {
   unsafe {
@@ -180,7 +183,7 @@ extern "C" fn start_task(
         Ok(final_request) => {
           let webview = webview.retain();
           let task = task.retain();
-          let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
+          let responder: Box<dyn FnOnce(HttpResponse<ResponseBody>)> =
             Box::new(move |sent_response| {
               // Consolidate checks before calling into `did*` methods.
               let validate = || -> crate::Result<()> {
@@ -205,19 +208,23 @@ extern "C" fn start_task(
                 task_uuid: Retained<NSUUID>,
                 webview_id: &str,
                 url: Retained<NSURL>,
-                sent_response: HttpResponse<Cow<'_, [u8]>>,
+                sent_response: HttpResponse<ResponseBody>,
               ) -> crate::Result<()> {
                 // Validate
                 check_webview_id_valid(webview_id)?;
                 check_task_is_valid(&webview, task_key, task_uuid.clone())?;
 
-                let content = sent_response.body();
+                let (parts, body) = sent_response.into_parts();
+                let content_length = match &body {
+                  ResponseBody::Bytes(content) => Some(content.len() as u64),
+                  ResponseBody::Stream(body) => body.len(),
+                };
                 // default: application/octet-stream, but should be provided by the client
-                let wanted_mime = sent_response.headers().get(CONTENT_TYPE);
+                let wanted_mime = parts.headers.get(CONTENT_TYPE);
                 // default to 200
-                let wanted_status_code = sent_response.status().as_u16() as i32;
+                let wanted_status_code = parts.status.as_u16() as i32;
                 // default to HTTP/1.1
-                let wanted_version = format!("{:#?}", sent_response.version());
+                let wanted_version = format!("{:#?}", parts.version);
 
                 let headers = NSMutableDictionary::new();
                 if let Some(mime) = wanted_mime {
@@ -226,13 +233,15 @@ extern "C" fn start_task(
                     &*NSString::from_str(mime.to_str().unwrap()),
                   );
                 }
-                headers.insert(
-                  &*NSString::from_str(CONTENT_LENGTH.as_str()),
-                  &*NSString::from_str(&content.len().to_string()),
-                );
+                if let Some(content_length) = content_length {
+                  headers.insert(
+                    &*NSString::from_str(CONTENT_LENGTH.as_str()),
+                    &*NSString::from_str(&content_length.to_string()),
+                  );
+                }
 
                 // add headers
-                for (name, value) in sent_response.headers().iter() {
+                for (name, value) in parts.headers.iter() {
                   if let Ok(value) = value.to_str() {
                     headers.insert(
                       &*NSString::from_str(name.as_str()),
@@ -262,23 +271,53 @@ extern "C" fn start_task(
                 .map_err(|_e| crate::Error::CustomProtocolTaskInvalid)?;
 
                 // Send data
-                let data = NSData::alloc();
-                // MIGRATE NOTE: we copied the content to the NSData because content will be freed
-                // when out of scope but NSData will also free the content when it's done and cause doube free.
-                let data = NSData::initWithBytes_length(
-                  data,
-                  content.as_ptr() as *mut c_void,
-                  content.len(),
-                );
-
-                // Check validity again
-                check_webview_id_valid(webview_id)?;
-                check_task_is_valid(&webview, task_key, task_uuid.clone())?;
+                let send_data = |content: &[u8]| -> crate::Result<()> {
+                  let data = NSData::alloc();
+                  // MIGRATE NOTE: we copied the content to the NSData because content will be freed
+                  // when out of scope but NSData will also free the content when it's done and cause doube free.
+                  let data = NSData::initWithBytes_length(
+                    data,
+                    content.as_ptr() as *mut c_void,
+                    content.len(),
+                  );
 
-                objc2::exception::catch(AssertUnwindSafe(|| {
-                  task.didReceiveData(&data);
-                }))
-                .map_err(|_e| crate::Error::CustomProtocolTaskInvalid)?;
+                  // Check validity again
+                  check_webview_id_valid(webview_id)?;
+                  check_task_is_valid(&webview, task_key, task_uuid.clone())?;
+
+                  objc2::exception::catch(AssertUnwindSafe(|| {
+                    task.didReceiveData(&data);
+                  }))
+                  .map_err(|_e| crate::Error::CustomProtocolTaskInvalid)
+                };
+
+                match body {
+                  ResponseBody::Bytes(content) => send_data(&content)?,
+                  // Streamed bodies are sent in chunks so they are never fully held in memory,
+                  // the task is checked before each chunk in case the page stopped loading it.
+                  ResponseBody::Stream(mut body) => {
+                    let mut buffer = vec![0; STREAM_CHUNK_LEN];
+                    loop {
+                      match body.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(count) => send_data(&buffer[..count])?,
+                        Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                        Err(e) => {
+                          check_webview_id_valid(webview_id)?;
+                          check_task_is_valid(&webview, task_key, task_uuid.clone())?;
+
+                          let error = NSError::new(0, &NSString::from_str("wry.custom_protocol"));
+                          objc2::exception::catch(AssertUnwindSafe(|| {
+                            task.didFailWithError(&error);
+                          }))
+                          .map_err(|_e| crate::Error::CustomProtocolTaskInvalid)?;
+                          webview.remove_custom_task_key(task_key);
+                          return Err(e.into());
+                        }
+                      }
+                    }
+                  }
+                }
 
                 check_webview_id_valid(webview_id)?;
                 check_task_is_valid(&webview, task_key, task_uuid)?;
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
 
 use objc2::{define_class, msg_send, rc::Retained, runtime::NSObject, MainThreadOnly};
 use objc2_foundation::{
@@ -10,11 +10,30 @@ use objc2_foundation::{
 };
 use objc2_web_kit::{WKDownload, WKDownloadDelegate};
 
-use crate::wkwebview::download::{download_did_fail, download_did_finish, download_policy};
+use super::download_progress_observer::DownloadProgressObserver;
+use crate::{
+  wkwebview::download::{download_did_fail, download_did_finish, download_policy},
+  Download, DownloadHandler,
+};
+
+/// A download in progress.
+pub struct DownloadState {
+  /// Replaced when the download is resumed.
+  pub wk_download: Retained<WKDownload>,
+  pub download: Download,
+  pub destination: PathBuf,
+  pub progress_observer: Option<Retained<DownloadProgressObserver>>,
+  pub paused: bool,
+  /// The data to resume a paused download from, once WebKit provided it.
+  pub resume_data: Option<Retained<NSData>>,
+}
+
+/// The downloads in progress, by a unique id.
+pub type Downloads = RefCell<HashMap<u32, DownloadState>>;
 
 pub struct WryDownloadDelegateIvars {
-  pub started: Option<RefCell<Box<dyn FnMut(String, &mut PathBuf) -> bool + 'static>>>,
-  pub completed: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
+  pub handler: Rc<DownloadHandler>,
+  pub downloads: Rc<Downloads>,
 }
 
 define_class!(
@@ -51,16 +70,12 @@ define_class!(
 );
 
 impl WryDownloadDelegate {
-  pub fn new(
-    download_started_handler: Option<Box<dyn FnMut(String, &mut PathBuf) -> bool + 'static>>,
-    download_completed_handler: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
-    mtm: MainThreadMarker,
-  ) -> Retained<Self> {
+  pub fn new(download_handler: DownloadHandler, mtm: MainThreadMarker) -> Retained<Self> {
     let delegate = mtm
       .alloc::<WryDownloadDelegate>()
       .set_ivars(WryDownloadDelegateIvars {
-        started: download_started_handler.map(|handler| RefCell::new(handler)),
-        completed: download_completed_handler,
+        handler: Rc::new(download_handler),
+        downloads: Default::default(),
       });
 
     unsafe { msg_send![super(delegate), init] }
@@ -2,13 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::sync::{Arc, Mutex};
+use std::{
+  cell::{Cell, RefCell},
+  ptr::NonNull,
+};
 
 use objc2::{define_class, msg_send, rc::Retained, runtime::NSObject, MainThreadOnly};
-use objc2_foundation::{MainThreadMarker, NSObjectProtocol};
+use objc2_foundation::{
+  MainThreadMarker, NSError, NSObjectProtocol, NSURLAuthenticationChallenge, NSURLCredential,
+  NSURLSessionAuthChallengeDisposition,
+};
 use objc2_web_kit::{
   WKDownload, WKNavigation, WKNavigationAction, WKNavigationActionPolicy, WKNavigationDelegate,
-  WKNavigationResponse, WKNavigationResponsePolicy,
+  WKNavigationResponse, WKNavigationResponsePolicy, WKWebpagePreferences,
 };
 
 #[cfg(target_os = "ios")]
@@ -17,26 +23,37 @@ use crate::wkwebview::ios::WKWebView::WKWebView;
 use objc2_web_kit::WKWebView;
 
 use crate::{
+  auth::AuthHandler,
+  script::PendingScripts,
   url_from_webview,
   wkwebview::{
     download::{navigation_download_action, navigation_download_response},
     navigation::{
-      did_commit_navigation, did_finish_navigation, navigation_policy, navigation_policy_response,
+      content_mode, did_commit_navigation, did_fail_navigation, did_finish_navigation,
+      did_receive_authentication_challenge, navigation_policy, navigation_policy_response,
       web_content_process_did_terminate,
     },
   },
-  PageLoadEvent, WryWebView,
+  AuthChallenge, CertRequest, Certificate, CertificateInfo, ContentMode, Credentials,
+  NavigationDecision, NavigationError, PageLoadEvent, WryWebView,
 };
 
 use super::wry_download_delegate::WryDownloadDelegate;
 
 pub struct WryNavigationDelegateIvars {
-  pub pending_scripts: Arc<Mutex<Option<Vec<String>>>>,
+  pub pending_scripts: PendingScripts,
   pub has_download_handler: bool,
-  pub navigation_policy_function: Box<dyn Fn(String) -> bool>,
+  pub navigation_policy_function: Box<dyn Fn(String) -> NavigationDecision>,
+  // The content mode of `NavigationDecision::AllowWithContentMode`, kept for the next navigations
+  pub content_mode: Cell<Option<ContentMode>>,
   pub download_delegate: Option<Retained<WryDownloadDelegate>>,
   pub on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent)>>,
+  pub on_first_paint_handler: RefCell<Option<Box<dyn Fn()>>>,
+  pub on_navigation_error_handler: Option<Box<dyn Fn(NavigationError)>>,
   pub on_web_content_process_terminate_handler: Option<Box<dyn Fn()>>,
+  pub auth_handler: Option<AuthHandler>,
+  pub client_certificate_handler: Option<Box<dyn Fn(CertRequest) -> Option<Certificate>>>,
+  pub tls_error_handler: Option<Box<dyn Fn(&str, &CertificateInfo) -> bool>>,
 }
 
 define_class!(
@@ -56,7 +73,29 @@ define_class!(
       action: &WKNavigationAction,
       handler: &block2::Block<dyn Fn(WKNavigationActionPolicy)>,
     ) {
-      navigation_policy(self, webview, action, handler);
+      navigation_policy(self, webview, action, &|policy, _| {
+        (*handler).call((policy,))
+      });
+    }
+
+    // Used instead of the method above on macOS 10.15+ and iOS 13+
+    #[unsafe(method(webView:decidePolicyForNavigationAction:preferences:decisionHandler:))]
+    fn navigation_policy_with_preferences(
+      &self,
+      webview: &WKWebView,
+      action: &WKNavigationAction,
+      preferences: &WKWebpagePreferences,
+      handler: &block2::Block<dyn Fn(WKNavigationActionPolicy, NonNull<WKWebpagePreferences>)>,
+    ) {
+      navigation_policy(self, webview, action, &|policy, mode| {
+        if mode.is_some() {
+          self.ivars().content_mode.set(mode);
+        }
+        if let Some(mode) = self.ivars().content_mode.get() {
+          unsafe { preferences.setPreferredContentMode(content_mode(mode)) };
+        }
+        (*handler).call((policy, NonNull::from(preferences)));
+      });
     }
 
     #[unsafe(method(webView:decidePolicyForNavigationResponse:decisionHandler:))]
@@ -74,6 +113,21 @@ define_class!(
       did_finish_navigation(self, webview, navigation);
     }
 
+    #[unsafe(method(webView:didFailProvisionalNavigation:withError:))]
+    fn did_fail_provisional_navigation(
+      &self,
+      webview: &WKWebView,
+      navigation: &WKNavigation,
+      error: &NSError,
+    ) {
+      did_fail_navigation(self, webview, navigation, error, true);
+    }
+
+    #[unsafe(method(webView:didFailNavigation:withError:))]
+    fn did_fail_navigation(&self, webview: &WKWebView, navigation: &WKNavigation, error: &NSError) {
+      did_fail_navigation(self, webview, navigation, error, false);
+    }
+
     #[unsafe(method(webView:didCommitNavigation:))]
     fn did_commit_navigation(&self, webview: &WKWebView, navigation: &WKNavigation) {
       did_commit_navigation(self, webview, navigation);
@@ -99,6 +153,16 @@ define_class!(
       navigation_download_response(self, webview, response, download);
     }
 
+    #[unsafe(method(webView:didReceiveAuthenticationChallenge:completionHandler:))]
+    fn did_receive_authentication_challenge(
+      &self,
+      webview: &WKWebView,
+      challenge: &NSURLAuthenticationChallenge,
+      handler: &block2::Block<dyn Fn(NSURLSessionAuthChallengeDisposition, *mut NSURLCredential)>,
+    ) {
+      did_receive_authentication_challenge(self, webview, challenge, handler);
+    }
+
     #[unsafe(method(webViewWebContentProcessDidTerminate:))]
     fn web_content_process_did_terminate(&self, webview: &WKWebView) {
       web_content_process_did_terminate(self, webview);
@@ -110,18 +174,26 @@ impl WryNavigationDelegate {
   #[allow(clippy::too_many_arguments)]
   pub fn new(
     webview: Retained<WryWebView>,
-    pending_scripts: Arc<Mutex<Option<Vec<String>>>>,
+    pending_scripts: PendingScripts,
     has_download_handler: bool,
-    navigation_handler: Option<Box<dyn Fn(String) -> bool>>,
+    navigation_handler: Option<Box<dyn Fn(String) -> NavigationDecision>>,
     download_delegate: Option<Retained<WryDownloadDelegate>>,
     on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent, String)>>,
+    on_first_paint_handler: Option<Box<dyn Fn()>>,
+    on_navigation_error_handler: Option<Box<dyn Fn(NavigationError)>>,
     on_web_content_process_terminate_handler: Option<Box<dyn Fn()>>,
+    proxy_auth_handler: Option<Box<dyn Fn(AuthChallenge) -> Option<Credentials>>>,
+    authentication_handler: Option<Box<dyn Fn(AuthChallenge) -> Option<Credentials>>>,
+    client_certificate_handler: Option<Box<dyn Fn(CertRequest) -> Option<Certificate>>>,
+    tls_error_handler: Option<Box<dyn Fn(&str, &CertificateInfo) -> bool>>,
     mtm: MainThreadMarker,
   ) -> Retained<Self> {
-    let navigation_policy_function = Box::new(move |url: String| -> bool {
+    let navigation_policy_function = Box::new(move |url: String| -> NavigationDecision {
       navigation_handler
         .as_ref()
-        .map_or(true, |navigation_handler| (navigation_handler)(url))
+        .map_or(NavigationDecision::Allow, |navigation_handler| {
+          (navigation_handler)(url)
+        })
     });
 
     let on_page_load_handler = if let Some(handler) = on_page_load_handler {
@@ -148,10 +220,16 @@ impl WryNavigationDelegate {
       .set_ivars(WryNavigationDelegateIvars {
         pending_scripts,
         navigation_policy_function,
+        content_mode: Cell::new(None),
         has_download_handler,
         download_delegate,
         on_page_load_handler,
+        on_first_paint_handler: RefCell::new(on_first_paint_handler),
+        on_navigation_error_handler,
         on_web_content_process_terminate_handler,
+        auth_handler: AuthHandler::new(proxy_auth_handler, authentication_handler),
+        client_certificate_handler,
+        tls_error_handler,
       });
 
     unsafe { msg_send![super(delegate), init] }
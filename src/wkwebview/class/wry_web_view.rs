@@ -2,21 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+#[cfg(target_os = "macos")]
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+};
 use std::{collections::HashMap, sync::Mutex};
 
 #[cfg(target_os = "macos")]
 use objc2::runtime::ProtocolObject;
 use objc2::{define_class, rc::Retained, runtime::Bool, DeclaredClass};
 #[cfg(target_os = "macos")]
-use objc2_app_kit::{NSDraggingDestination, NSEvent};
+use objc2_app_kit::{NSDraggingDestination, NSEvent, NSMenu, NSMenuItem};
 use objc2_foundation::{NSObjectProtocol, NSUUID};
 
 #[cfg(target_os = "ios")]
 use crate::wkwebview::ios::WKWebView::WKWebView;
 #[cfg(target_os = "macos")]
 use crate::{
-  wkwebview::{drag_drop, synthetic_mouse_events},
-  DragDropEvent,
+  drag_drop::DragDropHandler,
+  wkwebview::{context_menu, drag_drop, synthetic_mouse_events},
 };
 #[cfg(target_os = "ios")]
 use objc2_ui_kit::UIEvent as NSEvent;
@@ -26,9 +31,28 @@ use objc2_web_kit::WKWebView;
 pub struct WryWebViewIvars {
   pub(crate) is_child: bool,
   #[cfg(target_os = "macos")]
-  pub(crate) drag_drop_handler: Box<dyn Fn(DragDropEvent) -> bool>,
+  pub(crate) drag_drop_handler: DragDropHandler,
+  /// Whether a drag entered the webview and wasn't dropped or exited yet.
+  #[cfg(target_os = "macos")]
+  pub(crate) drag_active: Cell<bool>,
+  /// The paths of the active drag, read from the pasteboard once when it enters.
+  #[cfg(target_os = "macos")]
+  pub(crate) drag_paths: RefCell<Vec<std::path::PathBuf>>,
   #[cfg(target_os = "macos")]
   pub(crate) accept_first_mouse: objc2::runtime::Bool,
+  #[cfg(target_os = "macos")]
+  pub(crate) on_focus_changed_handler: Option<Box<dyn Fn(bool)>>,
+  #[cfg(target_os = "macos")]
+  pub(crate) accelerator_passthrough: Option<Box<dyn Fn(&crate::KeyEvent) -> bool>>,
+  #[cfg(target_os = "macos")]
+  pub(crate) context_menu_handler:
+    Option<Box<dyn Fn(crate::ContextMenuTarget) -> crate::ContextMenuAction>>,
+  /// What the next context menu is opened on, posted by the context menu script.
+  #[cfg(target_os = "macos")]
+  pub(crate) context_menu_target: Rc<RefCell<Option<crate::ContextMenuTarget>>>,
+  /// The custom items of the open context menu.
+  #[cfg(target_os = "macos")]
+  pub(crate) context_menu_items: RefCell<Vec<crate::ContextMenuItem>>,
   #[cfg(target_os = "ios")]
   pub(crate) input_accessory_view_builder: Option<Box<crate::InputAccessoryViewBuilder>>,
   pub(crate) custom_protocol_task_ids: Mutex<HashMap<usize, Retained<NSUUID>>>,
@@ -44,6 +68,14 @@ define_class!(
   impl WryWebView {
     #[unsafe(method(performKeyEquivalent:))]
     fn perform_key_equivalent(&self, event: &NSEvent) -> Bool {
+      // Consumed key presses go on to the window and the main menu
+      #[cfg(target_os = "macos")]
+      if let Some(accelerator_passthrough) = &self.ivars().accelerator_passthrough {
+        if key_event(event).is_some_and(|event| accelerator_passthrough(&event)) {
+          return Bool::NO;
+        }
+      }
+
       // This is a temporary workaround for https://github.com/tauri-apps/tauri/issues/9426
       // FIXME: When the webview is a child webview, performKeyEquivalent always return YES
       // and stop propagating the event to the window, hence the menu shortcut won't be
@@ -62,6 +94,26 @@ define_class!(
       self.ivars().accept_first_mouse
     }
 
+    #[cfg(target_os = "macos")]
+    #[unsafe(method(becomeFirstResponder))]
+    fn become_first_responder(&self) -> Bool {
+      let became: Bool = unsafe { objc2::msg_send![super(self), becomeFirstResponder] };
+      if let (true, Some(handler)) = (became.as_bool(), &self.ivars().on_focus_changed_handler) {
+        handler(true);
+      }
+      became
+    }
+
+    #[cfg(target_os = "macos")]
+    #[unsafe(method(resignFirstResponder))]
+    fn resign_first_responder(&self) -> Bool {
+      let resigned: Bool = unsafe { objc2::msg_send![super(self), resignFirstResponder] };
+      if let (true, Some(handler)) = (resigned.as_bool(), &self.ivars().on_focus_changed_handler) {
+        handler(false);
+      }
+      resigned
+    }
+
     #[cfg(target_os = "ios")]
     #[unsafe(method_id(inputAccessoryView))]
     fn input_accessory_view(&self) -> Option<Retained<objc2_ui_kit::UIView>> {
@@ -107,6 +159,20 @@ define_class!(
     }
   }
 
+  // Context menu
+  #[cfg(target_os = "macos")]
+  impl WryWebView {
+    #[unsafe(method(willOpenMenu:withEvent:))]
+    fn will_open_menu(&self, menu: &NSMenu, event: Option<&NSEvent>) {
+      context_menu::will_open_menu(self, menu, event)
+    }
+
+    #[unsafe(method(wryContextMenuItemSelected:))]
+    fn context_menu_item_selected(&self, item: &NSMenuItem) {
+      context_menu::item_selected(self, item)
+    }
+  }
+
   // Synthetic mouse events
   #[cfg(target_os = "macos")]
   impl WryWebView {
@@ -122,6 +188,32 @@ define_class!(
   }
 );
 
+/// The [`crate::KeyEvent`] of a key down `NSEvent`, `None` for keys we don't name.
+#[cfg(target_os = "macos")]
+fn key_event(event: &NSEvent) -> Option<crate::KeyEvent> {
+  use objc2_app_kit::{
+    NSAlternateKeyMask, NSCommandKeyMask, NSControlKeyMask, NSEventType, NSShiftKeyMask,
+  };
+
+  unsafe {
+    if event.r#type() != NSEventType::KeyDown {
+      return None;
+    }
+    let characters = event.charactersIgnoringModifiers()?.to_string();
+    let key = crate::keyboard::key_from_mac_character(characters.chars().next()?)?;
+    let flags = event.modifierFlags();
+    Some(crate::KeyEvent {
+      key,
+      modifiers: crate::Modifiers {
+        shift: flags.contains(NSShiftKeyMask),
+        control: flags.contains(NSControlKeyMask),
+        alt: flags.contains(NSAlternateKeyMask),
+        meta: flags.contains(NSCommandKeyMask),
+      },
+    })
+  }
+}
+
 // Custom Protocol Task Checker
 impl WryWebView {
   pub(crate) fn add_custom_task_key(&self, task_id: usize) -> Retained<NSUUID> {
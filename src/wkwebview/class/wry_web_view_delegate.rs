@@ -2,23 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{ffi::CStr, panic::AssertUnwindSafe};
+use std::{cell::RefCell, ffi::CStr, panic::AssertUnwindSafe, rc::Rc};
 
 use http::Request;
 use objc2::{
   define_class, msg_send,
   rc::Retained,
-  runtime::{NSObject, ProtocolObject},
+  runtime::{AnyObject, NSObject, ProtocolObject},
   DeclaredClass, MainThreadOnly,
 };
-use objc2_foundation::{ns_string, MainThreadMarker, NSObjectProtocol, NSString};
+use objc2_foundation::{MainThreadMarker, NSObjectProtocol, NSString};
 use objc2_web_kit::{WKScriptMessage, WKScriptMessageHandler, WKUserContentController};
 
 pub const IPC_MESSAGE_HANDLER_NAME: &str = "ipc";
 
+thread_local! {
+  // The delegates registered on the user content controllers, shared by the webviews of a
+  // controller since a controller has a single handler per name
+  static DELEGATES: RefCell<Vec<Retained<WryWebViewDelegate>>> = const { RefCell::new(Vec::new()) };
+}
+
 pub struct WryWebViewDelegateIvars {
   pub controller: Retained<WKUserContentController>,
-  pub ipc_handler: Box<dyn Fn(Request<String>)>,
+  pub name: String,
+  // The handlers of the webviews using the controller, by webview
+  ipc_handlers: RefCell<Vec<(*const AnyObject, Rc<dyn Fn(Request<String>)>)>>,
 }
 
 define_class!(
@@ -43,7 +51,22 @@ define_class!(
         #[cfg(feature = "tracing")]
         let _span = tracing::info_span!(parent: None, "wry::ipc::handle").entered();
 
-        let ipc_handler = &this.ivars().ipc_handler;
+        // The webview the message was posted from, nil once it was deallocated
+        let webview: Option<Retained<AnyObject>> = msg_send![msg, webView];
+        let Some(webview) = webview else {
+          return;
+        };
+        let ipc_handler = this
+          .ivars()
+          .ipc_handlers
+          .borrow()
+          .iter()
+          .find(|(webview_, _)| *webview_ == Retained::as_ptr(&webview))
+          .map(|(_, ipc_handler)| ipc_handler.clone());
+        let Some(ipc_handler) = ipc_handler else {
+          return;
+        };
+
         let body = msg.body();
         if let Ok(body) = body.downcast::<NSString>() {
           let js_utf8 = body.UTF8String();
@@ -58,7 +81,12 @@ define_class!(
             CStr::from_ptr(url_utf8).to_str(),
             CStr::from_ptr(js_utf8).to_str(),
           ) {
-            if let Ok(r) = Request::builder().uri(url).body(js.to_string()) {
+            let source = crate::IpcSource::new(url, Some(frame_info.isMainFrame()));
+            if let Ok(r) = Request::builder()
+              .extension(source)
+              .uri(url)
+              .body(js.to_string())
+            {
               ipc_handler(r);
             } else {
               #[cfg(feature = "tracing")]
@@ -76,16 +104,68 @@ define_class!(
 );
 
 impl WryWebViewDelegate {
+  /// Handles the `name` messages that `webview` posts to `controller` with `ipc_handler`, reusing
+  /// the delegate of the other webviews of the controller.
   pub fn new(
     controller: Retained<WKUserContentController>,
+    name: &str,
+    webview: *const AnyObject,
     ipc_handler: Box<dyn Fn(Request<String>)>,
     mtm: MainThreadMarker,
+  ) -> Retained<Self> {
+    let delegate = DELEGATES.with_borrow(|delegates| {
+      delegates
+        .iter()
+        .find(|delegate| {
+          delegate.ivars().name == name && std::ptr::eq(&*delegate.ivars().controller, &*controller)
+        })
+        .cloned()
+    });
+    let delegate = delegate.unwrap_or_else(|| {
+      let delegate = Self::register(controller, name, mtm);
+      DELEGATES.with_borrow_mut(|delegates| delegates.push(delegate.clone()));
+      delegate
+    });
+    delegate
+      .ivars()
+      .ipc_handlers
+      .borrow_mut()
+      .push((webview, Rc::from(ipc_handler)));
+    delegate
+  }
+
+  /// Stops handling the messages of `webview`, the delegate is removed from its controller once
+  /// no webview uses it.
+  pub fn remove(&self, webview: *const AnyObject) {
+    let mut ipc_handlers = self.ivars().ipc_handlers.borrow_mut();
+    ipc_handlers.retain(|(webview_, _)| *webview_ != webview);
+    if !ipc_handlers.is_empty() {
+      return;
+    }
+    drop(ipc_handlers);
+
+    DELEGATES
+      .with_borrow_mut(|delegates| delegates.retain(|delegate| !std::ptr::eq(&**delegate, self)));
+    unsafe {
+      // this will decrease the retain count of the handler and trigger the drop
+      self
+        .ivars()
+        .controller
+        .removeScriptMessageHandlerForName(&NSString::from_str(&self.ivars().name));
+    }
+  }
+
+  fn register(
+    controller: Retained<WKUserContentController>,
+    name: &str,
+    mtm: MainThreadMarker,
   ) -> Retained<Self> {
     let delegate = mtm
       .alloc::<WryWebViewDelegate>()
       .set_ivars(WryWebViewDelegateIvars {
-        ipc_handler,
         controller,
+        name: name.to_string(),
+        ipc_handlers: Default::default(),
       });
 
     let delegate: Retained<Self> = unsafe { msg_send![super(delegate), init] };
@@ -97,7 +177,7 @@ impl WryWebViewDelegate {
         delegate
           .ivars()
           .controller
-          .addScriptMessageHandler_name(proto_delegate, ns_string!(IPC_MESSAGE_HANDLER_NAME));
+          .addScriptMessageHandler_name(proto_delegate, &NSString::from_str(name));
       }));
     }
 
@@ -3,15 +3,19 @@
 // SPDX-License-Identifier: MIT
 
 #[cfg(target_os = "macos")]
-use std::{cell::RefCell, ptr::null_mut, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
+use std::{path::PathBuf, ptr::null_mut};
 
 use block2::Block;
-#[cfg(target_os = "macos")]
-use objc2::DefinedClass;
-use objc2::{define_class, msg_send, rc::Retained, runtime::NSObject, MainThreadOnly};
+use objc2::{
+  define_class, msg_send,
+  rc::Retained,
+  runtime::{Bool, NSObject},
+  DefinedClass, MainThreadOnly,
+};
 #[cfg(target_os = "macos")]
 use objc2_app_kit::{NSModalResponse, NSModalResponseOK, NSOpenPanel, NSWindowDelegate};
-use objc2_foundation::{MainThreadMarker, NSObjectProtocol};
+use objc2_foundation::{MainThreadMarker, NSObjectProtocol, NSString};
 #[cfg(target_os = "macos")]
 use objc2_foundation::{NSArray, NSURL};
 
@@ -21,7 +25,10 @@ use objc2_web_kit::{
   WKFrameInfo, WKMediaCaptureType, WKPermissionDecision, WKSecurityOrigin, WKUIDelegate,
 };
 
-use crate::{NewWindowFeatures, NewWindowResponse, WryWebView};
+use crate::{
+  permission::PermissionHandler, DialogKind, DialogRequest, DialogResponse, FileChooserRequest,
+  NewWindowRequest, NewWindowResponse, PermissionKind, PermissionRequest, WryWebView,
+};
 
 #[cfg(target_os = "macos")]
 struct NewWindow {
@@ -82,10 +89,14 @@ impl WryNSWindowDelegate {
 
 pub struct WryWebViewUIDelegateIvars {
   #[cfg(target_os = "macos")]
-  new_window_req_handler:
-    Option<Box<dyn Fn(String, NewWindowFeatures) -> NewWindowResponse + Send + Sync>>,
+  new_window_request_handler:
+    Option<Box<dyn Fn(NewWindowRequest) -> NewWindowResponse + Send + Sync>>,
   #[cfg(target_os = "macos")]
   new_windows: Rc<RefCell<Vec<NewWindow>>>,
+  #[cfg(target_os = "macos")]
+  file_open_handler: Option<Box<dyn Fn(FileChooserRequest) -> Option<Vec<PathBuf>>>>,
+  dialog_handler: Option<Box<dyn Fn(DialogRequest) -> DialogResponse>>,
+  permission_handler: Option<PermissionHandler>,
 }
 
 define_class!(
@@ -97,7 +108,73 @@ define_class!(
 
   unsafe impl NSObjectProtocol for WryWebViewUIDelegate {}
 
+  /// WKUIDelegatePrivate.
+  impl WryWebViewUIDelegate {
+    #[unsafe(method(_webView:runBeforeUnloadConfirmPanelWithMessage:initiatedByFrame:completionHandler:))]
+    fn run_before_unload_confirm_panel(
+      &self,
+      _webview: &WryWebView,
+      message: &NSString,
+      frame: &WKFrameInfo,
+      completion_handler: &Block<dyn Fn(Bool)>,
+    ) {
+      let (leave, _) = self
+        .dialog_outcome(DialogKind::BeforeUnload, message, frame)
+        .unwrap_or((true, None));
+      (*completion_handler).call((Bool::new(leave),));
+    }
+  }
+
   unsafe impl WKUIDelegate for WryWebViewUIDelegate {
+    #[unsafe(method(webView:runJavaScriptAlertPanelWithMessage:initiatedByFrame:completionHandler:))]
+    fn run_javascript_alert_panel(
+      &self,
+      _webview: &WryWebView,
+      message: &NSString,
+      frame: &WKFrameInfo,
+      completion_handler: &Block<dyn Fn()>,
+    ) {
+      self.dialog_outcome(DialogKind::Alert, message, frame);
+      (*completion_handler).call(());
+    }
+
+    #[unsafe(method(webView:runJavaScriptConfirmPanelWithMessage:initiatedByFrame:completionHandler:))]
+    fn run_javascript_confirm_panel(
+      &self,
+      _webview: &WryWebView,
+      message: &NSString,
+      frame: &WKFrameInfo,
+      completion_handler: &Block<dyn Fn(Bool)>,
+    ) {
+      let (confirmed, _) = self
+        .dialog_outcome(DialogKind::Confirm, message, frame)
+        .unwrap_or_default();
+      (*completion_handler).call((Bool::new(confirmed),));
+    }
+
+    #[unsafe(method(webView:runJavaScriptTextInputPanelWithPrompt:defaultText:initiatedByFrame:completionHandler:))]
+    fn run_javascript_text_input_panel(
+      &self,
+      _webview: &WryWebView,
+      prompt: &NSString,
+      default_text: Option<&NSString>,
+      frame: &WKFrameInfo,
+      completion_handler: &Block<dyn Fn(*mut NSString)>,
+    ) {
+      let kind = DialogKind::Prompt {
+        default_text: default_text
+          .map(|text| text.to_string())
+          .unwrap_or_default(),
+      };
+      match self.dialog_outcome(kind, prompt, frame) {
+        Some((_, Some(text))) => {
+          let text = NSString::from_str(&text);
+          (*completion_handler).call((Retained::as_ptr(&text) as *mut _,));
+        }
+        _ => (*completion_handler).call((null_mut(),)),
+      }
+    }
+
     #[cfg(target_os = "macos")]
     #[unsafe(method(webView:runOpenPanelWithParameters:initiatedByFrame:completionHandler:))]
     fn run_file_upload_panel(
@@ -108,6 +185,30 @@ define_class!(
       handler: &block2::Block<dyn Fn(*const NSArray<NSURL>)>,
     ) {
       unsafe {
+        if let Some(file_open_handler) = &self.ivars().file_open_handler {
+          let files = file_open_handler(FileChooserRequest {
+            mime_types: accepted_types(open_panel_params, objc2::sel!(_acceptedMIMETypes)),
+            extensions: accepted_types(open_panel_params, objc2::sel!(_acceptedFileExtensions)),
+            multiple: open_panel_params.allowsMultipleSelection(),
+            directories: open_panel_params.allowsDirectories(),
+          });
+          match files {
+            Some(files) => {
+              let urls = files
+                .iter()
+                .map(|file| {
+                  let path = NSString::from_str(&file.to_string_lossy());
+                  NSURL::fileURLWithPath(&path)
+                })
+                .collect::<Vec<_>>();
+              let urls = NSArray::from_retained_slice(&urls);
+              (*handler).call((Retained::as_ptr(&urls),));
+            }
+            None => (*handler).call((null_mut(),)),
+          }
+          return;
+        }
+
         if let Some(mtm) = MainThreadMarker::new() {
           let open_panel = NSOpenPanel::openPanel(mtm);
           open_panel.setCanChooseFiles(true);
@@ -130,13 +231,40 @@ define_class!(
     fn request_media_capture_permission(
       &self,
       _webview: &WryWebView,
-      _origin: &WKSecurityOrigin,
+      origin: &WKSecurityOrigin,
       _frame: &WKFrameInfo,
-      _capture_type: WKMediaCaptureType,
+      capture_type: WKMediaCaptureType,
       decision_handler: &Block<dyn Fn(WKPermissionDecision)>,
     ) {
       //https://developer.apple.com/documentation/webkit/wkpermissiondecision?language=objc
-      (*decision_handler).call((WKPermissionDecision::Grant,));
+      let Some(permission_handler) = &self.ivars().permission_handler else {
+        (*decision_handler).call((WKPermissionDecision::Grant,));
+        return;
+      };
+
+      let kind = match capture_type {
+        WKMediaCaptureType::Camera => PermissionKind::Camera,
+        WKMediaCaptureType::Microphone => PermissionKind::Microphone,
+        WKMediaCaptureType::CameraAndMicrophone => PermissionKind::CameraAndMicrophone,
+        _ => {
+          (*decision_handler).call((WKPermissionDecision::Prompt,));
+          return;
+        }
+      };
+
+      let origin = unsafe {
+        let port = match origin.port() {
+          0 => String::new(),
+          port => format!(":{port}"),
+        };
+        crate::ipc::origin(&format!("{}://{}{port}", origin.protocol(), origin.host()))
+      };
+      let decision = if permission_handler.is_allowed(PermissionRequest { kind, origin }) {
+        WKPermissionDecision::Grant
+      } else {
+        WKPermissionDecision::Deny
+      };
+      (*decision_handler).call((decision,));
     }
 
     #[cfg(target_os = "macos")]
@@ -148,7 +276,7 @@ define_class!(
       action: &objc2_web_kit::WKNavigationAction,
       window_features: &objc2_web_kit::WKWindowFeatures,
     ) -> Option<Retained<objc2_web_kit::WKWebView>> {
-      if let Some(new_window_req_handler) = &self.ivars().new_window_req_handler {
+      if let Some(new_window_request_handler) = &self.ivars().new_window_request_handler {
         let request = action.request();
         let url = request.URL().unwrap().absoluteString().unwrap();
 
@@ -156,9 +284,16 @@ define_class!(
         let screen = current_window.screen().unwrap();
         let screen_frame = screen.frame();
 
-        match new_window_req_handler(
-          url.to_string(),
-          NewWindowFeatures {
+        // `_isUserInitiated` is private, clicked links are user initiated in any case
+        let user_initiated = if action.respondsToSelector(objc2::sel!(_isUserInitiated)) {
+          msg_send![action, _isUserInitiated]
+        } else {
+          action.navigationType() == objc2_web_kit::WKNavigationType::LinkActivated
+        };
+
+        match new_window_request_handler(NewWindowRequest {
+          url: url.to_string(),
+          features: crate::NewWindowFeatures {
             size: if let (Some(width), Some(height)) =
               (window_features.width(), window_features.height())
             {
@@ -179,7 +314,8 @@ define_class!(
               target_configuration: configuration.into(),
             },
           },
-        ) {
+          user_initiated,
+        }) {
           NewWindowResponse::Allow => {
             let mtm = MainThreadMarker::new().unwrap();
 
@@ -254,6 +390,10 @@ define_class!(
 
             Some(webview)
           }
+          NewWindowResponse::OpenInSameView => {
+            let _ = webview.loadRequest(&request);
+            None
+          }
           NewWindowResponse::Create { webview } => Some(webview),
           NewWindowResponse::Deny => None,
         }
@@ -264,23 +404,69 @@ define_class!(
   }
 );
 
+/// The accepted MIME types or extensions of the `<input type="file">`, WebKit only exposes them
+/// with private selectors.
+#[cfg(target_os = "macos")]
+unsafe fn accepted_types(
+  params: &WKOpenPanelParameters,
+  selector: objc2::runtime::Sel,
+) -> Vec<String> {
+  if !objc2::runtime::NSObject::respondsToSelector(params, selector) {
+    return Vec::new();
+  }
+  let types: Option<Retained<NSArray<NSString>>> = msg_send![params, performSelector: selector];
+  types
+    .map(|types| types.iter().map(|t| t.to_string()).collect())
+    .unwrap_or_default()
+}
+
 impl WryWebViewUIDelegate {
+  /// Asks the dialog handler whether the dialog is accepted and the text a prompt returns, `None`
+  /// without a handler or for [`DialogResponse::Default`].
+  fn dialog_outcome(
+    &self,
+    kind: DialogKind,
+    message: &NSString,
+    frame: &WKFrameInfo,
+  ) -> Option<(bool, Option<String>)> {
+    let dialog_handler = self.ivars().dialog_handler.as_ref()?;
+    let url = unsafe { frame.request().URL() }
+      .and_then(|url| url.absoluteString())
+      .map(|url| url.to_string())
+      .unwrap_or_default();
+    let response = dialog_handler(DialogRequest {
+      kind: kind.clone(),
+      message: message.to_string(),
+      url,
+    });
+    (response != DialogResponse::Default).then(|| response.outcome(&kind))
+  }
+
   pub fn new(
     mtm: MainThreadMarker,
-    new_window_req_handler: Option<
-      Box<dyn Fn(String, NewWindowFeatures) -> NewWindowResponse + Send + Sync>,
+    new_window_request_handler: Option<
+      Box<dyn Fn(NewWindowRequest) -> NewWindowResponse + Send + Sync>,
     >,
+    file_open_handler: Option<Box<dyn Fn(FileChooserRequest) -> Option<Vec<PathBuf>>>>,
+    dialog_handler: Option<Box<dyn Fn(DialogRequest) -> DialogResponse>>,
+    permission_handler: Option<PermissionHandler>,
   ) -> Retained<Self> {
     #[cfg(target_os = "ios")]
-    let _new_window_req_handler = new_window_req_handler;
+    let _new_window_request_handler = new_window_request_handler;
+    #[cfg(target_os = "ios")]
+    let _file_open_handler = file_open_handler;
 
     let delegate = mtm
       .alloc::<WryWebViewUIDelegate>()
       .set_ivars(WryWebViewUIDelegateIvars {
         #[cfg(target_os = "macos")]
-        new_window_req_handler,
+        new_window_request_handler,
         #[cfg(target_os = "macos")]
         new_windows: Rc::new(RefCell::new(vec![])),
+        #[cfg(target_os = "macos")]
+        file_open_handler,
+        dialog_handler,
+        permission_handler,
       });
     unsafe { msg_send![super(delegate), init] }
   }
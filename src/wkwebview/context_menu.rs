@@ -0,0 +1,66 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use dpi::LogicalPosition;
+use objc2::{runtime::AnyObject, sel, DeclaredClass, MainThreadOnly};
+use objc2_app_kit::{NSEvent, NSMenu, NSMenuItem, NSView};
+use objc2_foundation::{ns_string, NSString};
+
+use crate::{context_menu, ContextMenuAction};
+
+use super::WryWebView;
+
+pub(crate) fn will_open_menu(this: &WryWebView, menu: &NSMenu, event: Option<&NSEvent>) {
+  let Some(handler) = &this.ivars().context_menu_handler else {
+    return;
+  };
+
+  // Posted by the context menu script before WebKit opens the menu
+  let mut target = this
+    .ivars()
+    .context_menu_target
+    .take()
+    .unwrap_or_else(|| context_menu::parse_message(""));
+
+  unsafe {
+    if let Some(event) = event {
+      let view: &NSView = this;
+      let point = view.convertPoint_fromView(event.locationInWindow(), None);
+      target.position = LogicalPosition::new(point.x, point.y);
+    }
+
+    match handler(target) {
+      ContextMenuAction::Default => {}
+      ContextMenuAction::Suppress => menu.removeAllItems(),
+      ContextMenuAction::Custom(items) => {
+        menu.removeAllItems();
+        for (index, item) in items.iter().enumerate() {
+          let menu_item = NSMenuItem::initWithTitle_action_keyEquivalent(
+            this.mtm().alloc(),
+            &NSString::from_str(item.label()),
+            Some(sel!(wryContextMenuItemSelected:)),
+            ns_string!(""),
+          );
+          let target: &AnyObject = this;
+          menu_item.setTarget(Some(target));
+          menu_item.setTag(index as _);
+          menu.addItem(&menu_item);
+        }
+        this.ivars().context_menu_items.replace(items);
+      }
+    }
+  }
+}
+
+pub(crate) fn item_selected(this: &WryWebView, item: &NSMenuItem) {
+  let handler = this
+    .ivars()
+    .context_menu_items
+    .borrow()
+    .get(item.tag() as usize)
+    .map(|item| item.handler.clone());
+  if let Some(handler) = handler {
+    handler();
+  }
+}
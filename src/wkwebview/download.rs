@@ -1,6 +1,16 @@
-use std::{env::current_dir, ptr::null_mut};
+use std::{
+  env::current_dir,
+  ptr::{null_mut, NonNull},
+  rc::{Rc, Weak},
+};
 
-use objc2::{rc::Retained, runtime::ProtocolObject, DeclaredClass};
+use block2::RcBlock;
+use objc2::{
+  msg_send,
+  rc::Retained,
+  runtime::{NSObject, ProtocolObject},
+  DeclaredClass, Message,
+};
 use objc2_foundation::{NSData, NSError, NSString, NSURLResponse, NSURL};
 use objc2_web_kit::{WKDownload, WKNavigationAction, WKNavigationResponse};
 
@@ -10,8 +20,11 @@ use crate::wkwebview::ios::WKWebView::WKWebView;
 use objc2_web_kit::WKWebView;
 
 use super::class::{
-  wry_download_delegate::WryDownloadDelegate, wry_navigation_delegate::WryNavigationDelegate,
+  download_progress_observer::DownloadProgressObserver,
+  wry_download_delegate::{DownloadState, Downloads, WryDownloadDelegate},
+  wry_navigation_delegate::WryNavigationDelegate,
 };
+use crate::{util::Counter, Download, DownloadHandler};
 
 // Download action handler
 pub(crate) fn navigation_download_action(
@@ -71,32 +84,56 @@ pub(crate) fn download_policy(
       counter += 1;
     }
 
-    let started_fn = &this.ivars().started;
-    if let Some(started_fn) = started_fn {
-      let mut started_fn = started_fn.borrow_mut();
-      match started_fn(url.to_string(), &mut download_destination) {
-        true => {
-          let path = NSString::from_str(&download_destination.display().to_string());
-          let ns_url = NSURL::fileURLWithPath_isDirectory(&path, false);
-          (*completion_handler).call((Retained::as_ptr(&ns_url),))
-        }
-        false => (*completion_handler).call((null_mut(),)),
-      };
-    } else {
-      #[cfg(feature = "tracing")]
-      tracing::warn!("WebView instance is dropped! This navigation handler shouldn't be called.");
-      (*completion_handler).call((null_mut(),));
+    static COUNTER: Counter = Counter::new();
+    let id = COUNTER.next();
+    let handler = this.ivars().handler.clone();
+    let downloads = Rc::downgrade(&this.ivars().downloads);
+    let handle = Download::new(url.to_string(), {
+      let (downloads, handler) = (downloads.clone(), Rc::downgrade(&handler));
+      move || cancel(&downloads, &handler, id)
+    })
+    .with_pause(
+      {
+        let downloads = downloads.clone();
+        move || pause(&downloads, id)
+      },
+      {
+        let (downloads, handler) = (downloads.clone(), Rc::downgrade(&handler));
+        move || resume(&downloads, &handler, id)
+      },
+    );
+
+    match handler.started(&handle, download_destination) {
+      Some(destination) => {
+        let path = NSString::from_str(&destination.display().to_string());
+        this.ivars().downloads.borrow_mut().insert(
+          id,
+          DownloadState {
+            wk_download: download.retain(),
+            progress_observer: progress_observer(&handler, download, &handle),
+            download: handle,
+            destination,
+            paused: false,
+            resume_data: None,
+          },
+        );
+
+        let ns_url = NSURL::fileURLWithPath_isDirectory(&path, false);
+        (*completion_handler).call((Retained::as_ptr(&ns_url),))
+      }
+      None => (*completion_handler).call((null_mut(),)),
     }
   }
 }
 
 pub(crate) fn download_did_finish(this: &WryDownloadDelegate, download: &WKDownload) {
-  unsafe {
-    let original_request = download.originalRequest().unwrap();
-    let url = original_request.URL().unwrap().absoluteString().unwrap();
-    if let Some(completed_fn) = this.ivars().completed.clone() {
-      completed_fn(url.to_string(), None, true);
-    }
+  let downloads = &this.ivars().downloads;
+  let state = download_id(downloads, download).and_then(|id| downloads.borrow_mut().remove(&id));
+  if let Some(state) = state {
+    this
+      .ivars()
+      .handler
+      .completed(&state.download, Some(state.destination));
   }
 }
 
@@ -104,19 +141,161 @@ pub(crate) fn download_did_fail(
   this: &WryDownloadDelegate,
   download: &WKDownload,
   error: &NSError,
-  _resume_data: &NSData,
+  resume_data: &NSData,
 ) {
   unsafe {
+    let downloads = &this.ivars().downloads;
+    let id = download_id(downloads, download);
+
+    // Pausing cancels the download, it's kept until it's resumed
+    if let Some(id) = id {
+      if let Some(state) = downloads
+        .borrow_mut()
+        .get_mut(&id)
+        .filter(|state| state.paused)
+      {
+        if state.resume_data.is_none() && resume_data.length() > 0 {
+          state.resume_data = Some(resume_data.retain());
+        }
+        return;
+      }
+    }
+
     #[cfg(debug_assertions)]
     {
       let description = error.localizedDescription().to_string();
       eprintln!("Download failed with error: {description}");
     }
 
-    let original_request = download.originalRequest().unwrap();
-    let url = original_request.URL().unwrap().absoluteString().unwrap();
-    if let Some(completed_fn) = this.ivars().completed.clone() {
-      completed_fn(url.to_string(), None, false);
+    let state = id.and_then(|id| downloads.borrow_mut().remove(&id));
+    // Downloads cancelled before they got a destination aren't tracked
+    let handle = match state {
+      Some(state) => state.download,
+      None => {
+        let original_request = download.originalRequest().unwrap();
+        let url = original_request.URL().unwrap().absoluteString().unwrap();
+        Download::new(url.to_string(), || ())
+      }
+    };
+    this.ivars().handler.completed(&handle, None);
+  }
+}
+
+fn download_id(downloads: &Downloads, download: &WKDownload) -> Option<u32> {
+  downloads
+    .borrow()
+    .iter()
+    .find(|(_, state)| std::ptr::eq(&*state.wk_download, download))
+    .map(|(id, _)| *id)
+}
+
+fn progress_observer(
+  handler: &Rc<DownloadHandler>,
+  download: &WKDownload,
+  handle: &Download,
+) -> Option<Retained<DownloadProgressObserver>> {
+  handler.has_progress().then(|| {
+    let progress: Retained<NSObject> = unsafe { msg_send![download, progress] };
+    let handle = handle.clone();
+    let handler = handler.clone();
+    DownloadProgressObserver::new(
+      progress,
+      Box::new(move |received_bytes, total_bytes| {
+        handler.progress(&handle, received_bytes, total_bytes)
+      }),
+    )
+  })
+}
+
+fn cancel(downloads: &Weak<Downloads>, handler: &Weak<DownloadHandler>, id: u32) {
+  let (Some(downloads), Some(handler)) = (downloads.upgrade(), handler.upgrade()) else {
+    return;
+  };
+
+  let state = downloads
+    .borrow()
+    .get(&id)
+    .map(|state| (state.paused, state.wk_download.clone()));
+  match state {
+    // Completed once WebKit reports the failure
+    Some((false, wk_download)) => unsafe { wk_download.cancel(None) },
+    // Paused downloads were already cancelled by WebKit
+    Some((true, _)) => {
+      let state = downloads.borrow_mut().remove(&id);
+      if let Some(state) = state {
+        handler.completed(&state.download, None);
+      }
+    }
+    None => {}
+  }
+}
+
+fn pause(downloads: &Weak<Downloads>, id: u32) {
+  let Some(downloads) = downloads.upgrade() else {
+    return;
+  };
+
+  let wk_download = match downloads.borrow_mut().get_mut(&id) {
+    Some(state) if !state.paused => {
+      state.paused = true;
+      state.progress_observer = None;
+      state.wk_download.clone()
+    }
+    _ => return,
+  };
+
+  let downloads = Rc::downgrade(&downloads);
+  let completion_handler = RcBlock::new(move |resume_data: *mut NSData| {
+    let Some(downloads) = downloads.upgrade() else {
+      return;
+    };
+    if let Some(state) = downloads.borrow_mut().get_mut(&id) {
+      if let Some(resume_data) = unsafe { Retained::retain(resume_data) } {
+        state.resume_data = Some(resume_data);
+      }
     }
+  });
+  unsafe { wk_download.cancel(Some(&*completion_handler)) };
+}
+
+fn resume(downloads: &Weak<Downloads>, handler: &Weak<DownloadHandler>, id: u32) {
+  let (Some(downloads), Some(handler)) = (downloads.upgrade(), handler.upgrade()) else {
+    return;
+  };
+
+  let (wk_download, resume_data) = match downloads.borrow_mut().get_mut(&id) {
+    // Without resume data, e.g. when the server doesn't support range requests, the download
+    // can't be resumed
+    Some(state) if state.paused && state.resume_data.is_some() => {
+      (state.wk_download.clone(), state.resume_data.take().unwrap())
+    }
+    _ => return,
+  };
+
+  unsafe {
+    let webview: Option<Retained<WKWebView>> = msg_send![&*wk_download, webView];
+    let Some(webview) = webview else {
+      if let Some(state) = downloads.borrow_mut().get_mut(&id) {
+        state.resume_data = Some(resume_data);
+      }
+      return;
+    };
+    let delegate = wk_download.delegate();
+
+    let downloads = Rc::downgrade(&downloads);
+    let completion_handler = RcBlock::new(move |resumed: NonNull<WKDownload>| {
+      let resumed = resumed.as_ref();
+      resumed.setDelegate(delegate.as_deref());
+
+      let Some(downloads) = downloads.upgrade() else {
+        return;
+      };
+      if let Some(state) = downloads.borrow_mut().get_mut(&id) {
+        state.progress_observer = progress_observer(&handler, resumed, &state.download);
+        state.wk_download = resumed.retain();
+        state.paused = false;
+      }
+    });
+    webview.resumeDownloadFromResumeData_completionHandler(&resume_data, &completion_handler);
   }
 }
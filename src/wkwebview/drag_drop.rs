@@ -8,10 +8,13 @@ use objc2::{
   runtime::{Bool, ProtocolObject},
   DeclaredClass,
 };
-use objc2_app_kit::{NSDragOperation, NSDraggingInfo, NSFilenamesPboardType};
+use objc2_app_kit::{
+  NSDragOperation, NSDraggingInfo, NSFilenamesPboardType, NSPasteboardTypeString,
+  NSPasteboardTypeURL,
+};
 use objc2_foundation::{NSArray, NSPoint, NSRect, NSString};
 
-use crate::DragDropEvent;
+use crate::{DragData, DragDropEvent, DragOperation, DragOperations};
 
 use super::WryWebView;
 
@@ -20,11 +23,21 @@ pub(crate) unsafe fn collect_paths(drag_info: &ProtocolObject<dyn NSDraggingInfo
   let mut drag_drop_paths = Vec::new();
   let types = NSArray::arrayWithObject(NSFilenamesPboardType);
 
-  if pb.availableTypeFromArray(&types).is_some() {
-    let paths = pb.propertyListForType(NSFilenamesPboardType).unwrap();
-    let paths = paths.downcast::<NSArray>().unwrap();
-    for path in paths {
-      let path = path.downcast::<NSString>().unwrap();
+  if pb.availableTypeFromArray(&types).is_none() {
+    return drag_drop_paths;
+  }
+
+  // The property list may be missing or of an unexpected type (e.g. an empty or
+  // promised drag), in which case we report no paths instead of panicking.
+  let Some(paths) = pb
+    .propertyListForType(NSFilenamesPboardType)
+    .and_then(|paths| paths.downcast::<NSArray>().ok())
+  else {
+    return drag_drop_paths;
+  };
+
+  for path in paths {
+    if let Ok(path) = path.downcast::<NSString>() {
       let path = CStr::from_ptr(path.UTF8String()).to_string_lossy();
       drag_drop_paths.push(PathBuf::from(path.into_owned()));
     }
@@ -32,6 +45,50 @@ pub(crate) unsafe fn collect_paths(drag_info: &ProtocolObject<dyn NSDraggingInfo
   drag_drop_paths
 }
 
+pub(crate) unsafe fn collect_data(drag_info: &ProtocolObject<dyn NSDraggingInfo>) -> DragData {
+  let pb = drag_info.draggingPasteboard();
+  let mut data = DragData {
+    text: pb
+      .stringForType(NSPasteboardTypeString)
+      .map(|text| text.to_string()),
+    urls: pb
+      .stringForType(NSPasteboardTypeURL)
+      .map(|url| vec![url.to_string()])
+      .unwrap_or_default(),
+    ..Default::default()
+  };
+
+  if let Some(types) = pb.types() {
+    for ty in types {
+      if let Some(bytes) = pb.dataForType(&ty) {
+        data.raw.insert(ty.to_string(), bytes.to_vec());
+      }
+    }
+  }
+
+  data
+}
+
+fn source_operations(drag_info: &ProtocolObject<dyn NSDraggingInfo>) -> DragOperations {
+  let mask = unsafe { drag_info.draggingSourceOperationMask() };
+  DragOperations::new(
+    mask.contains(NSDragOperation::Copy),
+    mask.contains(NSDragOperation::Move),
+    mask.contains(NSDragOperation::Link),
+  )
+}
+
+/// Maps the operation returned by the handler, `None` means the drag should be handled natively.
+fn ns_drag_operation(operation: DragOperation) -> Option<NSDragOperation> {
+  match operation {
+    DragOperation::Native => None,
+    DragOperation::None => Some(NSDragOperation::None),
+    DragOperation::Copy => Some(NSDragOperation::Copy),
+    DragOperation::Move => Some(NSDragOperation::Move),
+    DragOperation::Link => Some(NSDragOperation::Link),
+  }
+}
+
 pub(crate) fn dragging_entered(
   this: &WryWebView,
   drag_info: &ProtocolObject<dyn NSDraggingInfo>,
@@ -39,14 +96,27 @@ pub(crate) fn dragging_entered(
   let paths = unsafe { collect_paths(drag_info) };
   let dl: NSPoint = unsafe { drag_info.draggingLocation() };
   let frame: NSRect = this.frame();
+  // `draggingLocation` is in points with a bottom-left origin, which matches
+  // the CSS pixels of an unzoomed page once flipped, so no backing scale factor is applied.
   let position = (dl.x as i32, (frame.size.height - dl.y) as i32);
 
+  let operations = source_operations(drag_info);
+  let data = unsafe { collect_data(drag_info) };
+
+  this.ivars().drag_active.set(true);
+  // The pasteboard doesn't change during the drag, `dragging_updated` reuses the paths
+  this.ivars().drag_paths.replace(paths.clone());
+
   let listener = &this.ivars().drag_drop_handler;
-  if !listener(DragDropEvent::Enter { paths, position }) {
+  match ns_drag_operation(listener.call(DragDropEvent::Enter {
+    paths,
+    position,
+    operations,
+    data,
+  })) {
+    Some(operation) => operation,
     // Reject the Wry file drop (invoke the OS default behaviour)
-    unsafe { objc2::msg_send![super(this), draggingEntered: drag_info] }
-  } else {
-    NSDragOperation::Copy
+    None => unsafe { objc2::msg_send![super(this), draggingEntered: drag_info] },
   }
 }
 
@@ -54,12 +124,22 @@ pub(crate) fn dragging_updated(
   this: &WryWebView,
   drag_info: &ProtocolObject<dyn NSDraggingInfo>,
 ) -> NSDragOperation {
+  let paths = this.ivars().drag_paths.borrow().clone();
   let dl: NSPoint = unsafe { drag_info.draggingLocation() };
   let frame: NSRect = this.frame();
   let position = (dl.x as i32, (frame.size.height - dl.y) as i32);
 
+  let operations = source_operations(drag_info);
+
   let listener = &this.ivars().drag_drop_handler;
-  if !listener(DragDropEvent::Over { position }) {
+  let operation = listener.call(DragDropEvent::Over {
+    paths,
+    position,
+    operations,
+  });
+  if let Some(operation) = ns_drag_operation(operation) {
+    operation
+  } else {
     unsafe {
       let os_operation = objc2::msg_send![super(this), draggingUpdated: drag_info];
       if os_operation == NSDragOperation::None {
@@ -72,8 +152,6 @@ pub(crate) fn dragging_updated(
         os_operation
       }
     }
-  } else {
-    NSDragOperation::Copy
   }
 }
 
@@ -94,39 +172,39 @@ pub(crate) fn perform_drag_operation(
     tiddlydesktop_has_internal_drag() != 0
   };
 
-  // TiddlyDesktop: For external file drops, store paths via FFI for JavaScript to retrieve.
-  // This allows native HTML5 drop events to fire, and JS retrieves paths afterward.
-  // DON'T call the listener for file drops - that would cause duplicate processing.
-  if !is_internal_drag && !paths.is_empty() {
-    unsafe {
-      extern "C" {
-        fn tiddlydesktop_store_drop_paths(paths_json: *const std::ffi::c_char);
-      }
-      // Convert paths to JSON array string
-      let json_parts: Vec<String> = paths
-        .iter()
-        .map(|p| {
-          let s = p.to_string_lossy();
-          let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
-          format!("\"{}\"", escaped)
-        })
-        .collect();
-      let json = format!("[{}]", json_parts.join(","));
-      if let Ok(cstr) = std::ffi::CString::new(json) {
-        tiddlydesktop_store_drop_paths(cstr.as_ptr());
-      }
-    }
-    // Don't call listener - let native handling fire HTML5 events
-  }
+  this.ivars().drag_active.set(false);
+  this.ivars().drag_paths.take();
 
-  // For external drops WITHOUT file paths (text, html, url), still call listener
-  // so our td-drag-content event system can handle them
-  if !is_internal_drag && paths.is_empty() {
+  if !is_internal_drag {
     let listener = &this.ivars().drag_drop_handler;
-    listener(DragDropEvent::Drop {
-      paths: paths.clone(),
-      position,
-    });
+    if dispatch_drop(&|event| listener.call(event), paths.clone(), position) {
+      // The handler took over the drop, skip the native insertion
+      return Bool::YES;
+    }
+
+    // TiddlyDesktop: For external file drops handled natively, store paths via FFI for
+    // JavaScript to retrieve. This allows native HTML5 drop events to fire, and JS retrieves
+    // paths afterward.
+    if !paths.is_empty() {
+      unsafe {
+        extern "C" {
+          fn tiddlydesktop_store_drop_paths(paths_json: *const std::ffi::c_char);
+        }
+        // Convert paths to JSON array string
+        let json_parts: Vec<String> = paths
+          .iter()
+          .map(|p| {
+            let s = p.to_string_lossy();
+            let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("\"{}\"", escaped)
+          })
+          .collect();
+        let json = format!("[{}]", json_parts.join(","));
+        if let Ok(cstr) = std::ffi::CString::new(json) {
+          tiddlydesktop_store_drop_paths(cstr.as_ptr());
+        }
+      }
+    }
   }
 
   // TiddlyDesktop: For internal drags, fix the pasteboard data before native handling.
@@ -169,15 +247,57 @@ pub(crate) fn perform_drag_operation(
     }
   }
 
-  // TiddlyDesktop: Always invoke native WKWebView handling
+  // TiddlyDesktop: Invoke native WKWebView handling unless the handler took over the drop
   // This allows text/file paths to be inserted into inputs natively
   unsafe { objc2::msg_send![super(this), performDragOperation: drag_info] }
 }
 
+/// Emits [`DragDropEvent::Drop`] and returns whether the handler took over the drop,
+/// in which case the native handling must be skipped.
+fn dispatch_drop(
+  listener: &dyn Fn(DragDropEvent) -> DragOperation,
+  paths: Vec<PathBuf>,
+  position: (i32, i32),
+) -> bool {
+  listener(DragDropEvent::Drop { paths, position }) != DragOperation::Native
+}
+
 pub(crate) fn dragging_exited(this: &WryWebView, drag_info: &ProtocolObject<dyn NSDraggingInfo>) {
   let listener = &this.ivars().drag_drop_handler;
-  if !listener(DragDropEvent::Leave) {
+  if listener.call(DragDropEvent::Leave) == DragOperation::Native {
     // Reject the Wry drop (invoke the OS default behaviour)
     unsafe { objc2::msg_send![super(this), draggingExited: drag_info] }
   }
+
+  // The drag left without a drop, either because the cursor moved out
+  // of the webview or because the drop was denied
+  this.ivars().drag_paths.take();
+  if this.ivars().drag_active.replace(false) {
+    listener.call(DragDropEvent::Cancelled);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, path::PathBuf};
+
+  use super::dispatch_drop;
+  use crate::{DragDropEvent, DragOperation};
+
+  #[test]
+  fn drop_delivers_all_paths() {
+    let received = RefCell::new(None);
+    let listener = |event: DragDropEvent| {
+      if let DragDropEvent::Drop { paths, position } = event {
+        received.replace(Some((paths, position)));
+      }
+      DragOperation::Native
+    };
+
+    let paths = vec![PathBuf::from("/tmp/a.txt"), PathBuf::from("/tmp/b.txt")];
+    assert!(!dispatch_drop(&listener, paths.clone(), (10, 20)));
+    assert_eq!(received.take(), Some((paths, (10, 20))));
+
+    assert!(dispatch_drop(&|_| DragOperation::Copy, Vec::new(), (0, 0)));
+  }
 }
@@ -370,7 +370,14 @@ impl WKWebView {
       completion_handler: &block2::Block<dyn Fn(*mut NSImage, *mut NSError)>,
     );
 
-    #[cfg(target_os = "macos")]
+    #[cfg(target_os = "ios")]
+    #[unsafe(method(takeSnapshotWithConfiguration:completionHandler:))]
+    pub unsafe fn takeSnapshotWithConfiguration_completionHandler(
+      &self,
+      snapshot_configuration: Option<&WKSnapshotConfiguration>,
+      completion_handler: &block2::Block<dyn Fn(*mut UIImage, *mut NSError)>,
+    );
+
     // #[cfg(all(feature = "WKPDFConfiguration", feature = "block2"))]
     #[unsafe(method(createPDFWithConfiguration:completionHandler:))]
     pub unsafe fn createPDFWithConfiguration_completionHandler(
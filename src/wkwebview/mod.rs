@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+mod certificate;
+#[cfg(target_os = "macos")]
+mod context_menu;
 mod download;
 #[cfg(target_os = "macos")]
 mod drag_drop;
@@ -21,6 +24,9 @@ pub use class::wry_web_view::WryWebView;
 use class::wry_web_view_parent::WryWebViewParent;
 use class::{
   document_title_changed_observer::*,
+  fullscreen_observer::FullscreenObserver,
+  load_progress_observer::LoadProgressObserver,
+  url_changed_observer::UrlChangedObserver,
   url_scheme_handler,
   wry_download_delegate::WryDownloadDelegate,
   wry_navigation_delegate::WryNavigationDelegate,
@@ -33,25 +39,26 @@ use dpi::{LogicalPosition, LogicalSize};
 #[cfg(target_os = "macos")]
 use objc2::runtime::Bool;
 use objc2::{
-  rc::Retained,
+  rc::{Retained, Weak},
   runtime::{AnyObject, NSObject, ProtocolObject},
-  AllocAnyThread, DeclaredClass, MainThreadOnly, Message,
+  AllocAnyThread, ClassType, DeclaredClass, MainThreadOnly, Message,
 };
 #[cfg(target_os = "macos")]
-use objc2_app_kit::{NSApplication, NSAutoresizingMaskOptions, NSTitlebarSeparatorStyle, NSView};
-#[cfg(target_os = "macos")]
-use objc2_core_foundation::CGSize;
-use objc2_core_foundation::{CGPoint, CGRect};
+use objc2_app_kit::{
+  NSApplication, NSAutoresizingMaskOptions, NSBitmapImageFileType, NSBitmapImageRep, NSImage,
+  NSTitlebarSeparatorStyle, NSView, NSWindowOrderingMode,
+};
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use objc2_foundation::{
-  ns_string, MainThreadMarker, NSArray, NSBundle, NSDate, NSError, NSHTTPCookie,
-  NSHTTPCookieDomain, NSHTTPCookieExpires, NSHTTPCookieMaximumAge, NSHTTPCookieName,
+  ns_string, MainThreadMarker, NSArray, NSBundle, NSData, NSDate, NSDictionary, NSError,
+  NSHTTPCookie, NSHTTPCookieDomain, NSHTTPCookieExpires, NSHTTPCookieMaximumAge, NSHTTPCookieName,
   NSHTTPCookiePath, NSHTTPCookiePropertyKey, NSHTTPCookieSameSiteLax, NSHTTPCookieSameSitePolicy,
   NSHTTPCookieSameSiteStrict, NSHTTPCookieSecure, NSHTTPCookieValue, NSHTTPCookieVersion,
   NSJSONSerialization, NSMutableDictionary, NSMutableURLRequest, NSNumber,
-  NSObjectNSKeyValueCoding, NSObjectProtocol, NSString, NSUTF8StringEncoding, NSURL, NSUUID,
+  NSObjectNSKeyValueCoding, NSObjectProtocol, NSSet, NSString, NSUTF8StringEncoding, NSURL, NSUUID,
 };
 #[cfg(target_os = "ios")]
-use objc2_ui_kit::{UIScrollView, UIViewAutoresizing};
+use objc2_ui_kit::{UIImage, UIImagePNGRepresentation, UIScrollView, UIViewAutoresizing};
 
 #[cfg(target_os = "macos")]
 use objc2_app_kit::NSWindow;
@@ -70,8 +77,14 @@ use crate::wkwebview::util::operating_system_version;
 use objc2_web_kit::WKWebView;
 
 use objc2_web_kit::{
-  WKAudiovisualMediaTypes, WKInactiveSchedulingPolicy, WKURLSchemeHandler, WKUserContentController,
-  WKUserScript, WKUserScriptInjectionTime, WKWebViewConfiguration, WKWebsiteDataStore,
+  WKAudiovisualMediaTypes, WKBackForwardListItem, WKContentRuleList, WKContentRuleListStore,
+  WKFindConfiguration, WKFindResult, WKInactiveSchedulingPolicy, WKPDFConfiguration, WKProcessPool,
+  WKSnapshotConfiguration, WKURLSchemeHandler, WKUserContentController, WKUserScript,
+  WKUserScriptInjectionTime, WKWebViewConfiguration, WKWebsiteDataStore, WKWebsiteDataTypeCookies,
+  WKWebsiteDataTypeDiskCache, WKWebsiteDataTypeIndexedDBDatabases, WKWebsiteDataTypeLocalStorage,
+  WKWebsiteDataTypeMemoryCache, WKWebsiteDataTypeOfflineWebApplicationCache,
+  WKWebsiteDataTypeServiceWorkerRegistrations, WKWebsiteDataTypeSessionStorage,
+  WKWebsiteDataTypeWebSQLDatabases,
 };
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 
@@ -79,26 +92,31 @@ use std::{
   cell::RefCell,
   collections::HashMap,
   ffi::CString,
-  net::Ipv4Addr,
   os::raw::c_char,
   panic::AssertUnwindSafe,
   ptr::NonNull,
   rc::Rc,
-  str::{self, FromStr},
   sync::{Arc, Mutex, RwLock},
   time::Duration,
 };
 
 #[cfg(feature = "mac-proxy")]
-use crate::{
-  proxy::ProxyConfig,
-  wkwebview::proxy::{
-    nw_endpoint_t, nw_proxy_config_create_http_connect, nw_proxy_config_create_socksv5,
-  },
+use crate::wkwebview::proxy::{
+  nw_endpoint_t, nw_proxy_config_create_http_connect, nw_proxy_config_create_socksv5,
+  set_proxy_auth,
 };
 
+#[cfg(target_os = "macos")]
+use crate::drag_drop::DragDropHandler;
 use crate::{
-  BackgroundThrottlingPolicy, Error, Rect, RequestAsyncResponder, Result, WebViewAttributes, RGBA,
+  find,
+  permission::PermissionHandler,
+  proxy::ProxyConfig,
+  script::{self, InitScripts, PendingScripts},
+  AutoplayPolicy, BackgroundThrottlingPolicy, BrowsingDataFlags, ContentMode, DragOperation, Error,
+  FindOptions, History, HistoryEntry, InjectionTime, MemoryUsage, MemoryUsageLevel, PrintSettings,
+  PrintToPdfOptions, Rect, RequestAsyncResponder, Result, StylesheetLevel, Theme,
+  WebViewAttributes, RGBA,
 };
 
 use http::Request;
@@ -107,6 +125,9 @@ use crate::util::Counter;
 
 static COUNTER: Counter = Counter::new();
 
+/// `_WKMediaAudioMuted` of the private `_WKMediaMutedState` options.
+const MEDIA_AUDIO_MUTED: usize = 1 << 0;
+
 static WEBVIEW_STATE: Lazy<RwLock<HashMap<String, WebViewState>>> = Lazy::new(Default::default);
 
 struct WebViewState {
@@ -138,15 +159,30 @@ pub(crate) struct InnerWebView {
   ns_view: Retained<NSView>,
   #[allow(dead_code)]
   is_child: bool,
-  pending_scripts: Arc<Mutex<Option<Vec<String>>>>,
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  devtools: bool,
+  pending_scripts: PendingScripts,
   // Note that if following functions signatures are changed in the future,
   // all functions pointer declarations in objc callbacks below all need to get updated.
   ipc_handler_delegate: Option<Retained<WryWebViewDelegate>>,
+  // The delegates of the handlers added with `add_message_handler`, by name
+  message_handler_delegates: RefCell<HashMap<String, Retained<WryWebViewDelegate>>>,
+  #[cfg(target_os = "macos")]
+  context_menu_delegate: Option<Retained<WryWebViewDelegate>>,
   #[allow(dead_code)]
   // We need this the keep the reference count
   document_title_changed_observer: Option<Retained<DocumentTitleChangedObserver>>,
   #[allow(dead_code)]
   // We need this the keep the reference count
+  url_changed_observer: Option<Retained<UrlChangedObserver>>,
+  #[allow(dead_code)]
+  // We need this the keep the reference count
+  load_progress_observer: Option<Retained<LoadProgressObserver>>,
+  #[allow(dead_code)]
+  // We need this the keep the reference count
+  fullscreen_observer: Option<Retained<FullscreenObserver>>,
+  #[allow(dead_code)]
+  // We need this the keep the reference count
   navigation_policy_delegate: Retained<WryNavigationDelegate>,
   #[allow(dead_code)]
   // We need this the keep the reference count
@@ -157,6 +193,14 @@ pub(crate) struct InnerWebView {
   #[cfg(target_os = "macos")]
   // We need this to update the traffic light inset
   parent_view: Option<Retained<WryWebViewParent>>,
+  init_scripts: InitScripts<Retained<WKUserScript>>,
+  stylesheets: InitScripts<Retained<WKUserScript>>,
+  // The query and options of the last `find`, for `find_next` and `find_previous`
+  find_query: RefCell<Option<(String, FindOptions)>>,
+  // The `Accept-Language` header of the requests loaded by wry
+  accept_language: Option<String>,
+  // The header set with `set_accept_language`, replacing `accept_language`
+  accept_language_override: RefCell<Option<String>>,
 }
 
 impl InnerWebView {
@@ -194,7 +238,7 @@ impl InnerWebView {
 
   fn new_ns_view(
     ns_view: &NSView,
-    attributes: WebViewAttributes,
+    mut attributes: WebViewAttributes,
     pl_attrs: super::PlatformSpecificWebViewAttributes,
     is_child: bool,
   ) -> Result<Self> {
@@ -228,10 +272,26 @@ impl InnerWebView {
       let data_store = if using_existing_config {
         config.websiteDataStore()
       } else {
+        // each web context data directory gets its own data store
+        let data_store_identifier = pl_attrs.data_store_identifier.or_else(|| {
+          attributes
+            .context
+            .as_ref()
+            .and_then(|context| context.data_directory())
+            .map(crate::web_context::data_store_identifier)
+        });
+        #[cfg(feature = "tracing")]
+        if !attributes.incognito && !custom_data_store_available && data_store_identifier.is_some()
+        {
+          tracing::warn!(
+            "Separate data stores require macOS 14 or iOS 17, using the default data store instead"
+          );
+        }
+
         let data_store = match (
           attributes.incognito,
           custom_data_store_available,
-          pl_attrs.data_store_identifier,
+          data_store_identifier,
         ) {
           (true, _, _) => WKWebsiteDataStore::nonPersistentDataStore(mtm),
           // if data_store_identifier is given and custom data stores are available, use custom store
@@ -246,6 +306,20 @@ impl InnerWebView {
         data_store
       };
 
+      // the webviews of a context share its process pool unless it asks for isolation
+      if !using_existing_config {
+        if let Some(context) = attributes.context.as_deref_mut() {
+          if !context.os.isolated_processes {
+            let pool = context
+              .os
+              .process_pool
+              .get_or_insert_with(|| WKProcessPool::new(mtm));
+            #[allow(deprecated)]
+            config.setProcessPool(pool);
+          }
+        }
+      }
+
       // Disable Intelligent Tracking Prevention (ITP) so that third-party cookies
       // are allowed. Required for embedded content like YouTube iframes to function
       // when loaded from custom URI schemes (e.g. wikifile://).
@@ -258,6 +332,9 @@ impl InnerWebView {
         );
       }));
 
+      // Before the attributes are moved out
+      let pending_scripts = script::pending_scripts(&attributes);
+
       // Register Custom Protocols
       let mut protocol_ptrs = Vec::new();
       for (name, function) in attributes.custom_protocols {
@@ -272,6 +349,18 @@ impl InnerWebView {
           continue;
         }
 
+        // Check up front rather than relying on the exception thrown by
+        // `setURLSchemeHandler:forURLScheme:`, which is not always recoverable.
+        let scheme = NSString::from_str(&name);
+        let handled_by_webkit: bool =
+          objc2::msg_send![WryWebView::class(), handlesURLScheme: &*scheme];
+        if handled_by_webkit {
+          return Err(Error::UrlSchemeRegisterError(name));
+        }
+        if config.urlSchemeHandlerForURLScheme(&scheme).is_some() {
+          return Err(Error::DuplicateCustomProtocol(name));
+        }
+
         let url_scheme_handler_cls = url_scheme_handler::create(&name);
         let handler: *mut AnyObject = objc2::msg_send![url_scheme_handler_cls, new];
         let protocol_index = protocol_ptrs.len();
@@ -291,7 +380,7 @@ impl InnerWebView {
         let set_result = objc2::exception::catch(AssertUnwindSafe(|| {
           config.setURLSchemeHandler_forURLScheme(
             Some(&*(handler.cast::<ProtocolObject<dyn WKURLSchemeHandler>>())),
-            &NSString::from_str(&name),
+            &scheme,
           );
         }));
         if set_result.is_err() {
@@ -316,17 +405,51 @@ impl InnerWebView {
         .unwrap()
         .insert(webview_id.clone(), WebViewState { protocol_ptrs });
 
-      // WebView and manager
+      // What the context menus are opened on, shared with its script message handler
+      #[cfg(target_os = "macos")]
+      let context_menu_target = Rc::new(RefCell::new(None));
+
+      // WebView and manager, webviews sharing a manager add their scripts and handlers to it once
+      let shared_manager = pl_attrs.user_content_controller.clone().or_else(|| {
+        attributes
+          .context
+          .as_ref()
+          .and_then(|context| context.os.user_content_controller.clone())
+      });
+      if let Some(shared_manager) = &shared_manager {
+        config.setUserContentController(shared_manager);
+      }
       let manager = config.userContentController();
+      if let Some(context) = &attributes.context {
+        for (_, rule_list) in &context.os.content_rule_lists {
+          manager.addContentRuleList(rule_list);
+        }
+      }
       let webview = WryWebView::alloc(mtm).set_ivars(WryWebViewIvars {
         is_child,
         #[cfg(target_os = "macos")]
-        drag_drop_handler: match attributes.drag_drop_handler {
-          Some(handler) => handler,
-          None => Box::new(|_| false),
-        },
+        drag_drop_handler: DragDropHandler::new(
+          attributes
+            .drag_drop_handler
+            .unwrap_or_else(|| Box::new(|_| DragOperation::Native)),
+          attributes.drag_drop_handler_mode,
+        ),
+        #[cfg(target_os = "macos")]
+        drag_active: Default::default(),
+        #[cfg(target_os = "macos")]
+        drag_paths: Default::default(),
         #[cfg(target_os = "macos")]
         accept_first_mouse: Bool::new(attributes.accept_first_mouse),
+        #[cfg(target_os = "macos")]
+        on_focus_changed_handler: attributes.on_focus_changed_handler,
+        #[cfg(target_os = "macos")]
+        accelerator_passthrough: attributes.accelerator_passthrough,
+        #[cfg(target_os = "macos")]
+        context_menu_handler: attributes.context_menu_handler,
+        #[cfg(target_os = "macos")]
+        context_menu_target: context_menu_target.clone(),
+        #[cfg(target_os = "macos")]
+        context_menu_items: Default::default(),
         #[cfg(target_os = "ios")]
         input_accessory_view_builder: pl_attrs.input_accessory_view_builder,
         custom_protocol_task_ids: Default::default(),
@@ -343,19 +466,7 @@ impl InnerWebView {
       }
       #[cfg(feature = "mac-proxy")]
       if let Some(proxy_config) = attributes.proxy_config {
-        let proxy_config = match proxy_config {
-          ProxyConfig::Http(endpoint) => {
-            let nw_endpoint = nw_endpoint_t::try_from(endpoint).unwrap();
-            nw_proxy_config_create_http_connect(nw_endpoint, std::ptr::null_mut())
-          }
-          ProxyConfig::Socks5(endpoint) => {
-            let nw_endpoint = nw_endpoint_t::try_from(endpoint).unwrap();
-            nw_proxy_config_create_socksv5(nw_endpoint)
-          }
-        };
-
-        let proxies: Retained<NSArray<NSObject>> = NSArray::arrayWithObject(&*proxy_config);
-        data_store.setValue_forKey(Some(&proxies), ns_string!("proxyConfigurations"));
+        set_proxy_configurations(&data_store, Some(proxy_config))?;
       }
 
       _preference.setValue_forKey(
@@ -368,11 +479,24 @@ impl InnerWebView {
         web_page_preferences.setAllowsContentJavaScript(false);
       }
 
+      if attributes.content_mode != ContentMode::Recommended {
+        config
+          .defaultWebpagePreferences()
+          .setPreferredContentMode(navigation::content_mode(attributes.content_mode));
+      }
+
       #[cfg(target_os = "ios")]
       config.setValue_forKey(Some(&_yes), ns_string!("allowsInlineMediaPlayback"));
 
-      if attributes.autoplay {
-        config.setMediaTypesRequiringUserActionForPlayback(WKAudiovisualMediaTypes::None);
+      let autoplay_policy = attributes
+        .autoplay_policy
+        .or(attributes.autoplay.then_some(AutoplayPolicy::AllowAll));
+      if let Some(autoplay_policy) = autoplay_policy {
+        config.setMediaTypesRequiringUserActionForPlayback(match autoplay_policy {
+          AutoplayPolicy::AllowAll => WKAudiovisualMediaTypes::None,
+          AutoplayPolicy::RequireUserGesture => WKAudiovisualMediaTypes::All,
+          AutoplayPolicy::AllowMutedOnly => WKAudiovisualMediaTypes::Audio,
+        });
       }
 
       #[cfg(feature = "transparent")]
@@ -395,7 +519,7 @@ impl InnerWebView {
           .bounds
           .map(|b| b.position.to_logical::<f64>(scale_factor))
           .map(Into::into)
-          .unwrap_or((0, 0));
+          .unwrap_or((0.0, 0.0));
         let (w, h) = if is_child {
           attributes
             .bounds
@@ -417,7 +541,7 @@ impl InnerWebView {
           origin: if is_child {
             window_position(ns_view, x, y, h as f64)
           } else {
-            CGPoint::new(x as f64, (0 - y - h as i32) as f64)
+            CGPoint::new(x, -y - h as f64)
           },
           size: CGSize::new(w as f64, h as f64),
         };
@@ -477,6 +601,9 @@ impl InnerWebView {
         }
       }
 
+      // allowsBackForwardNavigation
+      webview.setAllowsBackForwardNavigationGestures(attributes.back_forward_navigation_gestures);
+
       #[cfg(target_os = "macos")]
       {
         if is_child {
@@ -490,11 +617,12 @@ impl InnerWebView {
           );
         }
 
-        // allowsBackForwardNavigation
-        webview.setAllowsBackForwardNavigationGestures(attributes.back_forward_navigation_gestures);
-
         // tabFocusesLinks
         _preference.setValue_forKey(Some(&_yes), ns_string!("tabFocusesLinks"));
+
+        if let Some(pinch_zoom) = attributes.pinch_zoom {
+          webview.setAllowsMagnification(pinch_zoom);
+        }
       }
       #[cfg(target_os = "ios")]
       {
@@ -507,7 +635,15 @@ impl InnerWebView {
         // But not exist in objc2-web-kit
         let scroll_view: Retained<UIScrollView> = objc2::msg_send![&webview, scrollView];
         // let scroll_view: Retained<UIScrollView> = webview.ivars().scrollView; // FIXME: not test yet
-        scroll_view.setBounces(false)
+        scroll_view.setBounces(false);
+
+        if let Some(pinch_zoom) = attributes.pinch_zoom {
+          let pinch: Option<Retained<AnyObject>> =
+            objc2::msg_send![&scroll_view, pinchGestureRecognizer];
+          if let Some(pinch) = pinch {
+            let () = objc2::msg_send![&pinch, setEnabled: pinch_zoom];
+          }
+        }
       }
 
       if !attributes.visible {
@@ -528,7 +664,30 @@ impl InnerWebView {
 
       // Message handler
       let ipc_handler_delegate = if let Some(ipc_handler) = attributes.ipc_handler {
-        let delegate = WryWebViewDelegate::new(manager.clone(), ipc_handler, mtm);
+        let delegate = WryWebViewDelegate::new(
+          manager.clone(),
+          IPC_MESSAGE_HANDLER_NAME,
+          Retained::as_ptr(&webview).cast(),
+          ipc_handler,
+          mtm,
+        );
+        Some(delegate)
+      } else {
+        None
+      };
+
+      // Context menu handler, the script tells what the menu is opened on
+      #[cfg(target_os = "macos")]
+      let context_menu_delegate = if webview.ivars().context_menu_handler.is_some() {
+        let delegate = WryWebViewDelegate::new(
+          manager.clone(),
+          crate::context_menu::MESSAGE_HANDLER_NAME,
+          Retained::as_ptr(&webview).cast(),
+          Box::new(move |request| {
+            context_menu_target.replace(Some(crate::context_menu::parse_message(request.body())));
+          }),
+          mtm,
+        );
         Some(delegate)
       } else {
         None
@@ -543,38 +702,55 @@ impl InnerWebView {
           None
         };
 
-      let pending_scripts = Arc::new(Mutex::new(Some(Vec::new())));
-      let has_download_handler = attributes.download_started_handler.is_some();
+      // URL changed handler
+      let url_changed_observer = attributes
+        .on_url_change_handler
+        .map(|handler| UrlChangedObserver::new(webview.clone(), handler));
+
+      // Load progress handler
+      let load_progress_observer = attributes
+        .load_progress_handler
+        .map(|handler| LoadProgressObserver::new(webview.clone(), handler));
+
+      // Fullscreen handler, `fullscreenState` is only available on macOS 13.3+ and iOS 16.4+
+      let fullscreen_observer = attributes
+        .fullscreen_handler
+        .filter(|_| NSObject::respondsToSelector(&webview, objc2::sel!(fullscreenState)))
+        .map(|handler| FullscreenObserver::new(webview.clone(), handler));
+
+      let has_download_handler = attributes.download_handler.is_some();
       // Download handler
-      let download_delegate = if attributes.download_started_handler.is_some()
-        || attributes.download_completed_handler.is_some()
-      {
-        let delegate = WryDownloadDelegate::new(
-          attributes.download_started_handler,
-          attributes.download_completed_handler,
-          mtm,
-        );
-        Some(delegate)
-      } else {
-        None
-      };
+      let download_delegate = attributes
+        .download_handler
+        .map(|handler| WryDownloadDelegate::new(handler, mtm));
 
       let navigation_policy_delegate = WryNavigationDelegate::new(
         webview.clone(),
         pending_scripts.clone(),
         has_download_handler,
-        attributes.navigation_handler,
+        attributes.navigation_handler_ext,
         download_delegate.clone(),
         attributes.on_page_load_handler,
+        attributes.on_first_paint_handler,
+        attributes.on_navigation_error_handler,
         pl_attrs.on_web_content_process_terminate_handler,
+        attributes.proxy_auth_handler,
+        attributes.authentication_handler,
+        attributes.client_certificate_handler,
+        attributes.tls_error_handler,
         mtm,
       );
 
       let proto_navigation_policy_delegate = ProtocolObject::from_ref(&*navigation_policy_delegate);
       webview.setNavigationDelegate(Some(proto_navigation_policy_delegate));
 
-      let ui_delegate: Retained<WryWebViewUIDelegate> =
-        WryWebViewUIDelegate::new(mtm, attributes.new_window_req_handler);
+      let ui_delegate: Retained<WryWebViewUIDelegate> = WryWebViewUIDelegate::new(
+        mtm,
+        attributes.new_window_request_handler,
+        attributes.file_open_handler,
+        attributes.dialog_handler,
+        attributes.permission_handler.map(PermissionHandler::new),
+      );
       let proto_ui_delegate = ProtocolObject::from_ref(&*ui_delegate);
       webview.setUIDelegate(Some(proto_ui_delegate));
 
@@ -599,36 +775,82 @@ impl InnerWebView {
         data_store,
         pending_scripts,
         ipc_handler_delegate,
+        message_handler_delegates: Default::default(),
+        #[cfg(target_os = "macos")]
+        context_menu_delegate,
         document_title_changed_observer,
+        url_changed_observer,
+        load_progress_observer,
+        fullscreen_observer,
         navigation_policy_delegate,
         download_delegate,
         ui_delegate,
         is_child,
+        #[cfg(any(debug_assertions, feature = "devtools"))]
+        devtools: attributes.devtools,
         #[cfg(target_os = "macos")]
         parent_view: None,
+        init_scripts: InitScripts::new(),
+        stylesheets: InitScripts::new(),
+        find_query: RefCell::new(None),
+        accept_language: attributes.accept_language.clone(),
+        accept_language_override: RefCell::new(None),
       };
 
-      // Initialize scripts
-      w.init(
-r#"Object.defineProperty(window, 'ipc', {
-  value: Object.freeze({postMessage: function(s) {window.webkit.messageHandlers.ipc.postMessage(s);}})
-});"#,
-      true
+      // Initialize scripts, a shared manager may have them already
+      let init = |js: &str, for_main_only: bool, injection_time: InjectionTime| {
+        if shared_manager.is_none() || !w.has_user_script(js, for_main_only, injection_time) {
+          w.init(js, for_main_only, injection_time);
+        }
+      };
+      init(
+        &crate::ipc::script(
+          "window.webkit.messageHandlers.ipc.postMessage(s);",
+          attributes.ipc_invoke_timeout,
+        ),
+        true,
+        InjectionTime::DocumentStart,
       );
+      #[cfg(target_os = "macos")]
+      if w.context_menu_delegate.is_some() {
+        init(
+          &crate::context_menu::script(),
+          true,
+          InjectionTime::DocumentStart,
+        );
+      }
+      if let Some(accept_language) = &attributes.accept_language {
+        init(
+          &crate::language::script(accept_language),
+          false,
+          InjectionTime::DocumentStart,
+        );
+      }
       for init_script in attributes.initialization_scripts {
-        w.init(&init_script.script, init_script.for_main_frame_only);
+        init(
+          &init_script.script,
+          init_script.for_main_frame_only,
+          init_script.injection_time,
+        );
       }
 
       // Set user agent
       if let Some(user_agent) = attributes.user_agent {
-        w.set_user_agent(user_agent.as_str())
+        w.set_user_agent(user_agent.as_str())?;
+      }
+
+      // Background color, a transparent webview keeps drawing no background
+      #[cfg(target_os = "macos")]
+      if let (Some(background_color), false) = (attributes.background_color, attributes.transparent)
+      {
+        w.set_background_color(background_color)?;
       }
 
       // Navigation
       if let Some(url) = attributes.url {
         w.navigate_to_url(url.as_str(), attributes.headers)?;
       } else if let Some(html) = attributes.html {
-        w.navigate_to_string(&html);
+        w.navigate_to_string(&html, attributes.html_base_url.as_deref());
       }
 
       // Allow Link Preview
@@ -678,6 +900,12 @@ r#"Object.defineProperty(window, 'ipc', {
         ns_view.addSubview(&webview);
       }
 
+      // The inspector can only be shown once the webview is in a window
+      #[cfg(any(debug_assertions, feature = "devtools"))]
+      if attributes.devtools && attributes.devtools_open_on_start {
+        w.open_devtools()?;
+      }
+
       Ok(w)
     }
   }
@@ -690,74 +918,133 @@ r#"Object.defineProperty(window, 'ipc', {
     url_from_webview(&self.webview)
   }
 
-  pub fn eval(&self, js: &str, callback: Option<impl Fn(String) + Send + 'static>) -> Result<()> {
-    if let Some(scripts) = &mut *self.pending_scripts.lock().unwrap() {
-      scripts.push(js.into());
-    } else {
-      // Safety: objc runtime calls are unsafe
-      unsafe {
-        #[cfg(feature = "tracing")]
-        let span = Mutex::new(Some(tracing::debug_span!("wry::eval").entered()));
-
-        // we need to check if the callback exists outside the handler otherwise it's a segfault
-        if let Some(callback) = callback {
-          let handler = block2::RcBlock::new(move |val: *mut AnyObject, _err: *mut NSError| {
-            #[cfg(feature = "tracing")]
-            span.lock().unwrap().take();
-
-            let mut result = String::new();
-
-            if !val.is_null() {
-              let json_ns_data = NSJSONSerialization::dataWithJSONObject_options_error(
-                &*val,
-                objc2_foundation::NSJSONWritingOptions::FragmentsAllowed,
-              )
-              .unwrap();
-              let json_string = NSString::alloc();
-              let json_string =
-                NSString::initWithData_encoding(json_string, &json_ns_data, NSUTF8StringEncoding)
-                  .unwrap();
-
-              result = json_string.to_string();
-            }
-
-            callback(result);
-          });
-
-          self
-            .webview
-            .evaluateJavaScript_completionHandler(&NSString::from_str(js), Some(&handler));
-        } else {
-          #[cfg(feature = "tracing")]
-          let handler = Some(block2::RcBlock::new(
-            move |_val: *mut AnyObject, _err: *mut NSError| {
-              span.lock().unwrap().take();
-            },
-          ));
-          #[cfg(not(feature = "tracing"))]
-          let handler: Option<block2::RcBlock<dyn Fn(*mut AnyObject, *mut NSError)>> = None;
-
-          self
-            .webview
-            .evaluateJavaScript_completionHandler(&NSString::from_str(js), handler.as_deref());
-        }
+  /// Evaluates scripts without a completion handler for as long as the webview is alive.
+  pub(crate) fn script_evaluator(&self) -> Box<dyn Fn(&str)> {
+    let webview = Weak::from_retained(&self.webview);
+    Box::new(move |js| {
+      if let Some(webview) = webview.load() {
+        // Safety: objc runtime calls are unsafe
+        unsafe { webview.evaluateJavaScript_completionHandler(&NSString::from_str(js), None) };
       }
+    })
+  }
+
+  pub fn eval(&self, js: &str, callback: Option<impl Fn(String) + Send + 'static>) -> Result<()> {
+    if let Err(callback) = script::defer_script(&self.pending_scripts, js, callback) {
+      evaluate_javascript(&self.webview, js, callback);
     }
 
     Ok(())
   }
 
-  fn init(&self, js: &str, for_main_only: bool) {
+  fn init(
+    &self,
+    js: &str,
+    for_main_only: bool,
+    injection_time: InjectionTime,
+  ) -> Retained<WKUserScript> {
     // Safety: objc runtime calls are unsafe
     unsafe {
       let userscript = WKUserScript::alloc(self.mtm);
       let script = WKUserScript::initWithSource_injectionTime_forMainFrameOnly(
         userscript,
         &NSString::from_str(js),
-        WKUserScriptInjectionTime::AtDocumentStart,
+        user_script_injection_time(injection_time),
         for_main_only,
       );
       self.manager.addUserScript(&script);
+      script
+    }
+  }
+
+  /// Whether the manager has the script, e.g. added by another webview sharing it.
+  fn has_user_script(&self, js: &str, for_main_only: bool, injection_time: InjectionTime) -> bool {
+    let injection_time = user_script_injection_time(injection_time);
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      self.manager.userScripts().iter().any(|script| {
+        script.isForMainFrameOnly() == for_main_only
+          && script.injectionTime() == injection_time
+          && script.source().to_string() == js
+      })
+    }
+  }
+
+  pub fn add_init_script(&self, id: &str, script: &str) -> Result<()> {
+    let script = self.init(script, true, InjectionTime::DocumentStart);
+    if let Some(replaced) = self.init_scripts.insert(id, script) {
+      self.remove_user_script(&replaced);
+    }
+    Ok(())
+  }
+
+  pub fn remove_init_script(&self, id: &str) -> Result<()> {
+    if let Some(script) = self.init_scripts.remove(id) {
+      self.remove_user_script(&script);
+    }
+    Ok(())
+  }
+
+  pub fn init_scripts(&self) -> Vec<String> {
+    self.init_scripts.ids()
+  }
+
+  pub fn add_user_stylesheet(
+    &self,
+    id: &str,
+    css: &str,
+    main_frame_only: bool,
+    _level: StylesheetLevel,
+  ) -> Result<()> {
+    let script = script::stylesheet(id, css, main_frame_only);
+    let user_script = self.init(&script, main_frame_only, InjectionTime::DocumentStart);
+    self.stylesheets.insert(id, user_script);
+    self.eval(&script, None::<Box<dyn Fn(String) + Send + 'static>>)
+  }
+
+  pub fn set_proxy(&self, proxy: Option<ProxyConfig>) -> Result<()> {
+    #[cfg(feature = "mac-proxy")]
+    {
+      let has_proxy_configurations =
+        NSObject::respondsToSelector(&self.data_store, objc2::sel!(setProxyConfigurations:));
+      if !has_proxy_configurations {
+        return Err(Error::ProxyChangeUnsupported(
+          "proxies require macOS 14 or iOS 17".into(),
+        ));
+      }
+      unsafe { set_proxy_configurations(&self.data_store, proxy) }
+    }
+
+    #[cfg(not(feature = "mac-proxy"))]
+    {
+      let _ = proxy;
+      Err(Error::ProxyChangeUnsupported(
+        "the `mac-proxy` feature is disabled".into(),
+      ))
+    }
+  }
+
+  pub fn remove_user_stylesheet(&self, id: &str) -> Result<()> {
+    if let Some(user_script) = self.stylesheets.remove(id) {
+      self.remove_user_script(&user_script);
+      self.eval(
+        &script::remove_stylesheet(id),
+        None::<Box<dyn Fn(String) + Send + 'static>>,
+      )?;
+    }
+    Ok(())
+  }
+
+  /// `WKUserContentController` can only remove all its scripts, the others are added back.
+  fn remove_user_script(&self, script: &WKUserScript) {
+    unsafe {
+      let scripts = self.manager.userScripts();
+      self.manager.removeAllUserScripts();
+      for script_ in scripts.iter() {
+        if !std::ptr::eq(&*script_, script) {
+          self.manager.addUserScript(&script_);
+        }
+      }
     }
   }
 
@@ -769,8 +1056,26 @@ r#"Object.defineProperty(window, 'ipc', {
     self.navigate_to_url(url, Some(headers))
   }
 
-  pub fn load_html(&self, html: &str) -> crate::Result<()> {
-    self.navigate_to_string(html);
+  pub fn load_url_with_body(
+    &self,
+    url: &str,
+    method: http::Method,
+    body: Vec<u8>,
+    headers: http::HeaderMap,
+  ) -> crate::Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let request = self.url_request(url, Some(headers));
+      request.setHTTPMethod(&NSString::from_str(method.as_str()));
+      request.setHTTPBody(Some(&NSData::with_bytes(&body)));
+      self.webview.loadRequest(&request);
+    }
+
+    Ok(())
+  }
+
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) -> crate::Result<()> {
+    self.navigate_to_string(html, base_url);
     Ok(())
   }
 
@@ -781,6 +1086,66 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  pub fn reload_ignoring_cache(&self) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe { self.webview.reloadFromOrigin() };
+    Ok(())
+  }
+
+  pub fn stop_loading(&self) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe { self.webview.stopLoading() };
+    Ok(())
+  }
+
+  pub fn can_go_back(&self) -> Result<bool> {
+    // Safety: objc runtime calls are unsafe
+    Ok(unsafe { self.webview.canGoBack() })
+  }
+
+  pub fn can_go_forward(&self) -> Result<bool> {
+    // Safety: objc runtime calls are unsafe
+    Ok(unsafe { self.webview.canGoForward() })
+  }
+
+  pub fn go_back(&self) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe { self.webview.goBack() };
+    Ok(())
+  }
+
+  pub fn go_forward(&self) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe { self.webview.goForward() };
+    Ok(())
+  }
+
+  pub fn history_list(&self) -> Result<History> {
+    let entry = |item: &WKBackForwardListItem| unsafe {
+      HistoryEntry {
+        url: item
+          .URL()
+          .absoluteString()
+          .map(|url| url.to_string())
+          .unwrap_or_default(),
+        title: item
+          .title()
+          .map(|title| title.to_string())
+          .unwrap_or_default(),
+      }
+    };
+
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let list = self.webview.backForwardList();
+      Ok(History {
+        back: list.backList().iter().map(|item| entry(&item)).collect(),
+        current: list.currentItem().map(|item| entry(&item)),
+        forward: list.forwardList().iter().map(|item| entry(&item)).collect(),
+      })
+    }
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     unsafe {
       let config = self.webview.configuration();
@@ -793,39 +1158,200 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
-  fn navigate_to_url(&self, url: &str, headers: Option<http::HeaderMap>) -> crate::Result<()> {
-    // Safety: objc runtime calls are unsafe
+  pub fn capture_png<F>(&self, rect: Option<Rect>, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    unsafe {
+      let configuration = WKSnapshotConfiguration::new(self.mtm);
+      if let Some(rect) = rect {
+        #[cfg(target_os = "macos")]
+        let scale_factor = self
+          .webview
+          .window()
+          .map_or(1.0, |window| window.backingScaleFactor());
+        #[cfg(target_os = "ios")]
+        let scale_factor = self.webview.contentScaleFactor();
+        let (x, y) = rect.position.to_logical::<f64>(scale_factor).into();
+        let (width, height) = rect.size.to_logical::<f64>(scale_factor).into();
+        configuration.setRect(CGRect::new(CGPoint::new(x, y), CGSize::new(width, height)));
+      }
+
+      let completion = RefCell::new(Some(completion));
+      let handler = block2::RcBlock::new(move |image: *mut SnapshotImage, error: *mut NSError| {
+        let Some(completion) = completion.take() else {
+          return;
+        };
+        let png = match image.as_ref() {
+          Some(image) => png_from_image(image)
+            .ok_or_else(|| Error::CaptureFailed("failed to encode the snapshot".into())),
+          None => Err(Error::CaptureFailed(error.as_ref().map_or_else(
+            || "no snapshot was taken".into(),
+            |error| error.localizedDescription().to_string(),
+          ))),
+        };
+        completion(png);
+      });
+      self
+        .webview
+        .takeSnapshotWithConfiguration_completionHandler(Some(&configuration), &handler);
+    }
+
+    Ok(())
+  }
+
+  pub fn clear_browsing_data<F>(&self, data: BrowsingDataFlags, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
     unsafe {
-      let url = NSURL::URLWithString(&NSString::from_str(url)).unwrap();
-      let request = NSMutableURLRequest::requestWithURL(&url);
-      if let Some(headers) = headers {
-        for (name, value) in headers.iter() {
-          let key = NSString::from_str(name.as_str());
-          let value = NSString::from_str(value.to_str().unwrap_or_default());
-          request.addValue_forHTTPHeaderField(&value, &key);
+      let mut types: Vec<&NSString> = Vec::new();
+      for (flag, types_) in [
+        (BrowsingDataFlags::COOKIES, &[WKWebsiteDataTypeCookies][..]),
+        (
+          BrowsingDataFlags::LOCAL_STORAGE,
+          &[
+            WKWebsiteDataTypeLocalStorage,
+            WKWebsiteDataTypeSessionStorage,
+          ],
+        ),
+        (
+          BrowsingDataFlags::INDEXED_DB,
+          &[WKWebsiteDataTypeIndexedDBDatabases],
+        ),
+        (
+          BrowsingDataFlags::CACHE,
+          &[
+            WKWebsiteDataTypeDiskCache,
+            WKWebsiteDataTypeMemoryCache,
+            WKWebsiteDataTypeOfflineWebApplicationCache,
+          ],
+        ),
+        (
+          BrowsingDataFlags::SERVICE_WORKERS,
+          &[WKWebsiteDataTypeServiceWorkerRegistrations],
+        ),
+        (
+          BrowsingDataFlags::WEB_SQL,
+          &[WKWebsiteDataTypeWebSQLDatabases],
+        ),
+      ] {
+        if data.contains(flag) {
+          types.extend(types_);
         }
       }
+
+      let types = NSSet::from_slice(&types);
+      let date = NSDate::dateWithTimeIntervalSince1970(0.0);
+      let completion = RefCell::new(Some(completion));
+      let handler = block2::RcBlock::new(move || {
+        if let Some(completion) = completion.take() {
+          completion(Ok(()));
+        }
+      });
+      self
+        .data_store
+        .removeDataOfTypes_modifiedSince_completionHandler(&types, &date, &handler);
+    }
+
+    Ok(())
+  }
+
+  fn navigate_to_url(&self, url: &str, headers: Option<http::HeaderMap>) -> crate::Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let request = self.url_request(url, headers);
       self.webview.loadRequest(&request);
     }
 
     Ok(())
   }
 
-  fn navigate_to_string(&self, html: &str) {
+  unsafe fn url_request(
+    &self,
+    url: &str,
+    headers: Option<http::HeaderMap>,
+  ) -> Retained<NSMutableURLRequest> {
+    let url = NSURL::URLWithString(&NSString::from_str(url)).unwrap();
+    let request = NSMutableURLRequest::requestWithURL(&url);
+    // WebKit only sends the languages of the system, the headers can still replace it
+    let accept_language_override = self.accept_language_override.borrow();
+    if let Some(accept_language) = accept_language_override
+      .as_ref()
+      .or(self.accept_language.as_ref())
+    {
+      request.setValue_forHTTPHeaderField(
+        Some(&NSString::from_str(accept_language)),
+        ns_string!("Accept-Language"),
+      );
+    }
+    if let Some(headers) = headers {
+      for (name, value) in crate::navigation::request_headers(&headers) {
+        let key = NSString::from_str(name);
+        let value = NSString::from_str(&value);
+        request.setValue_forHTTPHeaderField(Some(&value), &key);
+      }
+    }
+    request
+  }
+
+  fn navigate_to_string(&self, html: &str, base_url: Option<&str>) {
+    let base_url =
+      base_url.and_then(|base_url| NSURL::URLWithString(&NSString::from_str(base_url)));
     // Safety: objc runtime calls are unsafe
     unsafe {
       self
         .webview
-        .loadHTMLString_baseURL(&NSString::from_str(html), None);
+        .loadHTMLString_baseURL(&NSString::from_str(html), base_url.as_deref());
     }
   }
 
-  fn set_user_agent(&self, user_agent: &str) {
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
     unsafe {
       self
         .webview
         .setCustomUserAgent(Some(&NSString::from_str(user_agent)));
     }
+    Ok(())
+  }
+
+  pub fn add_message_handler(
+    &self,
+    name: &str,
+    handler: Box<dyn Fn(Request<String>)>,
+  ) -> Result<()> {
+    self.remove_message_handler(name)?;
+    let delegate = WryWebViewDelegate::new(
+      self.manager.clone(),
+      name,
+      Retained::as_ptr(&self.webview).cast(),
+      handler,
+      self.mtm,
+    );
+    self
+      .message_handler_delegates
+      .borrow_mut()
+      .insert(name.to_string(), delegate);
+    Ok(())
+  }
+
+  pub fn remove_message_handler(&self, name: &str) -> Result<()> {
+    let delegate = self.message_handler_delegates.borrow_mut().remove(name);
+    if let Some(delegate) = delegate {
+      delegate.remove(Retained::as_ptr(&self.webview).cast());
+    }
+    Ok(())
+  }
+
+  pub fn set_accept_language(&self, languages: &[&str]) -> Result<()> {
+    *self.accept_language_override.borrow_mut() =
+      Some(crate::language::header(languages)).filter(|header| !header.is_empty());
+    Ok(())
+  }
+
+  pub fn set_navigation_gestures(&self, enabled: bool) -> Result<()> {
+    unsafe { self.webview.setAllowsBackForwardNavigationGestures(enabled) };
+    Ok(())
   }
 
   pub fn print(&self) -> crate::Result<()> {
@@ -868,19 +1394,203 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn open_devtools(&self) {
+  pub fn print_silent<F>(&self, _settings: PrintSettings, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
     #[cfg(target_os = "macos")]
     unsafe {
-      // taken from <https://github.com/WebKit/WebKit/blob/784f93cb80a386c29186c510bba910b67ce3adc1/Source/WebKit/UIProcess/API/Cocoa/WKWebView.mm#L1939>
-      let tool: Retained<AnyObject> = objc2::msg_send![&self.webview, _inspector];
-      let () = objc2::msg_send![&tool, show];
-    }
-  }
+      use objc2_app_kit::{
+        NSPrintAllPages, NSPrintCopies, NSPrintFirstPage, NSPrintInfo, NSPrintLastPage, NSPrinter,
+      };
 
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn close_devtools(&self) {
-    #[cfg(target_os = "macos")]
+      let Some(window) = self.webview.window() else {
+        completion(Err(Error::PrintFailed(
+          "the webview is not in a window".into(),
+        )));
+        return Ok(());
+      };
+
+      // A copy, so the job settings don't leak into the dialog of `print`
+      let print_info = NSPrintInfo::initWithDictionary(
+        NSPrintInfo::alloc(),
+        &NSPrintInfo::sharedPrintInfo().dictionary(),
+      );
+      if let Some(name) = &_settings.printer {
+        let Some(printer) = NSPrinter::printerWithName(&NSString::from_str(name)) else {
+          completion(Err(Error::PrinterNotFound(name.clone())));
+          return Ok(());
+        };
+        print_info.setPrinter(&printer);
+      }
+      let dictionary = print_info.dictionary();
+      dictionary.setValue_forKey(Some(&NSNumber::new_u32(_settings.copies)), NSPrintCopies);
+      if let Some((first, last)) = _settings.page_range {
+        dictionary.setValue_forKey(Some(&NSNumber::new_bool(false)), NSPrintAllPages);
+        dictionary.setValue_forKey(Some(&NSNumber::new_u32(first)), NSPrintFirstPage);
+        dictionary.setValue_forKey(Some(&NSNumber::new_u32(last)), NSPrintLastPage);
+      }
+
+      let print_operation = self.webview.printOperationWithPrintInfo(&print_info);
+      print_operation.setShowsPrintPanel(false);
+      print_operation.setShowsProgressPanel(false);
+      print_operation.setCanSpawnSeparateThread(true);
+      print_operation.runOperationModalForWindow_delegate_didRunSelector_contextInfo(
+        &window,
+        None,
+        None,
+        std::ptr::null_mut(),
+      );
+      completion(Ok(()));
+    }
+
+    #[cfg(target_os = "ios")]
+    completion(Err(Error::PrintFailed(
+      "silent printing is not supported on iOS".into(),
+    )));
+
+    Ok(())
+  }
+
+  pub fn print_to_pdf<F>(&self, _options: PrintToPdfOptions, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<Vec<u8>>) + Send + 'static,
+  {
+    unsafe {
+      // Only available on macOS 11+ and iOS 14+
+      if !self
+        .webview
+        .respondsToSelector(objc2::sel!(createPDFWithConfiguration:completionHandler:))
+      {
+        return Err(Error::PrintFailed(
+          "printing to PDF requires macOS 11 or iOS 14".into(),
+        ));
+      }
+
+      let configuration = WKPDFConfiguration::new(self.mtm);
+      let completion = RefCell::new(Some(completion));
+      let handler = block2::RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+        let Some(completion) = completion.take() else {
+          return;
+        };
+        let pdf = match data.as_ref() {
+          Some(data) => Ok(data.to_vec()),
+          None => Err(Error::PrintFailed(error.as_ref().map_or_else(
+            || "no document was created".into(),
+            |error| error.localizedDescription().to_string(),
+          ))),
+        };
+        completion(pdf);
+      });
+      self
+        .webview
+        .createPDFWithConfiguration_completionHandler(Some(&configuration), &handler);
+    }
+
+    Ok(())
+  }
+
+  pub fn find(
+    &self,
+    query: &str,
+    options: FindOptions,
+    callback: impl Fn(u32) + Send + 'static,
+  ) -> Result<()> {
+    if query.is_empty() {
+      self.clear_matches()?;
+      callback(0);
+      return Ok(());
+    }
+
+    self.find_query.replace(Some((query.to_string(), options)));
+    self.find_string(false);
+    // WKFindResult only tells whether there was a match
+    let options = FindOptions {
+      whole_word: false,
+      ..options
+    };
+    self.eval(
+      &find::find(query, options, false),
+      Some(move |count: String| callback(count.parse().unwrap_or(0))),
+    )
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    self.find_string(false);
+    Ok(())
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    self.find_string(true);
+    Ok(())
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    self.find_query.replace(None);
+    self.eval(
+      "window.getSelection().removeAllRanges()",
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn select_all(&self) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    unsafe {
+      let _: () = objc2::msg_send![&self.webview, selectAll: None::<&AnyObject>];
+    }
+    #[cfg(target_os = "ios")]
+    self.eval(
+      crate::selection::SELECT_ALL,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )?;
+    Ok(())
+  }
+
+  /// Selects the next or previous match of the last `find`.
+  fn find_string(&self, backwards: bool) {
+    let find_query = self.find_query.borrow();
+    let Some((query, options)) = &*find_query else {
+      return;
+    };
+    unsafe {
+      // Only available on macOS 11+ and iOS 14+
+      if !self
+        .webview
+        .respondsToSelector(objc2::sel!(findString:withConfiguration:completionHandler:))
+      {
+        return;
+      }
+
+      let configuration = WKFindConfiguration::new(self.mtm);
+      configuration.setCaseSensitive(options.case_sensitive);
+      configuration.setWraps(options.wrap_around);
+      configuration.setBackwards(backwards);
+      let handler = block2::RcBlock::new(|_: NonNull<WKFindResult>| {});
+      self.webview.findString_withConfiguration_completionHandler(
+        &NSString::from_str(query),
+        Some(&configuration),
+        &handler,
+      );
+    }
+  }
+
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn open_devtools(&self) -> crate::Result<()> {
+    if !self.devtools {
+      return Err(crate::Error::DevToolsNotEnabled);
+    }
+    #[cfg(target_os = "macos")]
+    unsafe {
+      // taken from <https://github.com/WebKit/WebKit/blob/784f93cb80a386c29186c510bba910b67ce3adc1/Source/WebKit/UIProcess/API/Cocoa/WKWebView.mm#L1939>
+      let tool: Retained<AnyObject> = objc2::msg_send![&self.webview, _inspector];
+      let () = objc2::msg_send![&tool, show];
+    }
+    Ok(())
+  }
+
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn close_devtools(&self) {
+    #[cfg(target_os = "macos")]
     unsafe {
       // taken from <https://github.com/WebKit/WebKit/blob/784f93cb80a386c29186c510bba910b67ce3adc1/Source/WebKit/UIProcess/API/Cocoa/WKWebView.mm#L1939>
       let tool: Retained<AnyObject> = objc2::msg_send![&self.webview, _inspector];
@@ -903,13 +1613,50 @@ r#"Object.defineProperty(window, 'ipc', {
 
   pub fn zoom(&self, scale_factor: f64) -> crate::Result<()> {
     unsafe {
-      self.webview.setPageZoom(scale_factor);
+      if self.webview.respondsToSelector(objc2::sel!(setPageZoom:)) {
+        self.webview.setPageZoom(scale_factor);
+      }
     }
 
     Ok(())
   }
 
+  pub fn zoom_level(&self) -> crate::Result<f64> {
+    unsafe {
+      if self.webview.respondsToSelector(objc2::sel!(pageZoom)) {
+        return Ok(self.webview.pageZoom());
+      }
+    }
+
+    Ok(1.0)
+  }
+
   pub fn set_background_color(&self, _background_color: RGBA) -> Result<()> {
+    // A translucent color needs the webview not to draw its own background
+    #[cfg(target_os = "macos")]
+    #[allow(unused_unsafe)]
+    unsafe {
+      let (red, green, blue, alpha) = _background_color;
+
+      let draws_background = NSNumber::numberWithBool(alpha == 255);
+      self
+        .webview
+        .setValue_forKey(Some(&draws_background), ns_string!("drawsBackground"));
+
+      if self
+        .webview
+        .respondsToSelector(objc2::sel!(setUnderPageBackgroundColor:))
+      {
+        let color = objc2_app_kit::NSColor::colorWithSRGBRed_green_blue_alpha(
+          red as f64 / 255.0,
+          green as f64 / 255.0,
+          blue as f64 / 255.0,
+          alpha as f64 / 255.0,
+        );
+        self.webview.setUnderPageBackgroundColor(Some(&color));
+      }
+    }
+
     #[cfg(target_os = "ios")]
     unsafe {
       let (red, green, blue, alpha) = _background_color;
@@ -932,6 +1679,35 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  pub fn set_theme(&self, theme: Theme) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    unsafe {
+      use objc2_app_kit::{
+        NSAppearance, NSAppearanceCustomization, NSAppearanceNameAqua, NSAppearanceNameDarkAqua,
+      };
+
+      let appearance = match theme {
+        Theme::Dark => NSAppearance::appearanceNamed(NSAppearanceNameDarkAqua),
+        Theme::Light => NSAppearance::appearanceNamed(NSAppearanceNameAqua),
+        Theme::Auto => None,
+      };
+      self.webview.setAppearance(appearance.as_deref());
+    }
+
+    #[cfg(target_os = "ios")]
+    unsafe {
+      // UIUserInterfaceStyleUnspecified, UIUserInterfaceStyleLight and UIUserInterfaceStyleDark
+      let style: isize = match theme {
+        Theme::Auto => 0,
+        Theme::Light => 1,
+        Theme::Dark => 2,
+      };
+      let () = objc2::msg_send![&self.webview, setOverrideUserInterfaceStyle: style];
+    }
+
+    Ok(())
+  }
+
   pub fn bounds(&self) -> crate::Result<Rect> {
     #[allow(unused_unsafe)]
     unsafe {
@@ -956,7 +1732,7 @@ r#"Object.defineProperty(window, 'ipc', {
       let window = self.webview.window().unwrap();
       let scale_factor = window.backingScaleFactor();
       let (x, y) = bounds.position.to_logical::<f64>(scale_factor).into();
-      let (width, height) = bounds.size.to_logical::<i32>(scale_factor).into();
+      let (width, height) = bounds.size.to_logical::<f64>(scale_factor).into();
 
       unsafe {
         let parent_view = self.webview.superview().unwrap();
@@ -976,6 +1752,162 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  pub fn is_visible(&self) -> Result<bool> {
+    Ok(!self.webview.isHidden())
+  }
+
+  pub fn set_corner_radius(&self, radius: f64) -> Result<()> {
+    unsafe {
+      #[cfg(target_os = "macos")]
+      self.webview.setWantsLayer(true);
+
+      let layer: Option<Retained<AnyObject>> = objc2::msg_send![&self.webview, layer];
+      if let Some(layer) = layer {
+        let _: () = objc2::msg_send![&layer, setCornerRadius: radius];
+        let _: () = objc2::msg_send![&layer, setMasksToBounds: radius > 0.];
+      }
+    }
+    Ok(())
+  }
+
+  pub fn bring_to_front(&self) -> Result<()> {
+    #[allow(unused_unsafe)]
+    unsafe {
+      if let Some(parent) = self.webview.superview() {
+        #[cfg(target_os = "macos")]
+        parent.addSubview_positioned_relativeTo(&self.webview, NSWindowOrderingMode::Above, None);
+        #[cfg(target_os = "ios")]
+        parent.bringSubviewToFront(&self.webview);
+      }
+    }
+    Ok(())
+  }
+
+  pub fn send_to_back(&self) -> Result<()> {
+    #[allow(unused_unsafe)]
+    unsafe {
+      if let Some(parent) = self.webview.superview() {
+        #[cfg(target_os = "macos")]
+        parent.addSubview_positioned_relativeTo(&self.webview, NSWindowOrderingMode::Below, None);
+        #[cfg(target_os = "ios")]
+        parent.sendSubviewToBack(&self.webview);
+      }
+    }
+    Ok(())
+  }
+
+  pub fn suspend<F>(&self, completion: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + Send + 'static,
+  {
+    let completion = RefCell::new(Some(completion));
+    unsafe {
+      self.webview.setAllMediaPlaybackSuspended_completionHandler(
+        true,
+        Some(&block2::RcBlock::new(move || {
+          if let Some(completion) = completion.take() {
+            completion(Ok(()));
+          }
+        })),
+      );
+    }
+
+    Ok(())
+  }
+
+  pub fn resume(&self) -> Result<()> {
+    unsafe {
+      self
+        .webview
+        .setAllMediaPlaybackSuspended_completionHandler(false, None);
+    }
+
+    Ok(())
+  }
+
+  pub fn set_muted(&self, muted: bool) -> Result<()> {
+    // Private API, the audio bit of `_WKMediaMutedState` keeps the capture devices state
+    unsafe {
+      if self.webview.respondsToSelector(objc2::sel!(_setPageMuted:))
+        && self
+          .webview
+          .respondsToSelector(objc2::sel!(_mediaMutedState))
+      {
+        let state: usize = objc2::msg_send![&self.webview, _mediaMutedState];
+        let state = if muted {
+          state | MEDIA_AUDIO_MUTED
+        } else {
+          state & !MEDIA_AUDIO_MUTED
+        };
+        let _: () = objc2::msg_send![&self.webview, _setPageMuted: state];
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn is_muted(&self) -> Result<bool> {
+    unsafe {
+      if self
+        .webview
+        .respondsToSelector(objc2::sel!(_mediaMutedState))
+      {
+        let state: usize = objc2::msg_send![&self.webview, _mediaMutedState];
+        return Ok(state & MEDIA_AUDIO_MUTED != 0);
+      }
+    }
+
+    Ok(false)
+  }
+
+  pub fn is_loading(&self) -> Result<bool> {
+    Ok(unsafe { self.webview.isLoading() })
+  }
+
+  pub fn memory_usage(&self) -> Result<MemoryUsage> {
+    let pid: libc::pid_t = unsafe {
+      if !self
+        .webview
+        .respondsToSelector(objc2::sel!(_webProcessIdentifier))
+      {
+        return Err(Error::Unsupported(
+          "this WebKit version doesn't expose the web content process".into(),
+        ));
+      }
+      objc2::msg_send![&self.webview, _webProcessIdentifier]
+    };
+    if pid == 0 {
+      return Err(Error::Unsupported(
+        "the web content process isn't running".into(),
+      ));
+    }
+
+    let mut info = std::mem::MaybeUninit::<libc::rusage_info_v4>::zeroed();
+    let result =
+      unsafe { libc::proc_pid_rusage(pid, libc::RUSAGE_INFO_V4, info.as_mut_ptr().cast()) };
+    if result != 0 {
+      return Err(Error::Unsupported(format!(
+        "can't read the memory of the web content process: {}",
+        std::io::Error::last_os_error()
+      )));
+    }
+    let info = unsafe { info.assume_init() };
+    Ok(MemoryUsage {
+      resident: info.ri_phys_footprint,
+      peak: info.ri_lifetime_max_phys_footprint,
+    })
+  }
+
+  pub fn set_memory_usage_level(&self, _level: MemoryUsageLevel) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("Memory usage levels are unsupported on this platform");
+    Ok(())
+  }
+
+  pub fn memory_usage_level(&self) -> Result<MemoryUsageLevel> {
+    Ok(MemoryUsageLevel::Normal)
+  }
+
   pub fn focus(&self) -> Result<()> {
     #[cfg(target_os = "macos")]
     {
@@ -998,6 +1930,44 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  pub fn blur(&self) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    if let Some(window) = self.webview.window() {
+      window.makeFirstResponder(None);
+    }
+    #[cfg(target_os = "ios")]
+    unsafe {
+      let _: objc2::runtime::Bool =
+        objc2::msg_send![&self.webview, endEditing: objc2::runtime::Bool::YES];
+    }
+
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop")]
+  pub fn set_drag_drop_handler(
+    &self,
+    handler: Box<dyn Fn(crate::DragDropEvent) -> DragOperation>,
+  ) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    self.webview.ivars().drag_drop_handler.set(handler);
+    #[cfg(target_os = "ios")]
+    let _ = handler;
+
+    Ok(())
+  }
+
+  #[cfg(feature = "drag-drop-simulation")]
+  pub fn simulate_drag_drop(&self, event: crate::DragDropEvent) -> DragOperation {
+    #[cfg(target_os = "macos")]
+    return self.webview.ivars().drag_drop_handler.call(event);
+    #[cfg(target_os = "ios")]
+    {
+      let _ = event;
+      DragOperation::Native
+    }
+  }
+
   unsafe fn cookie_from_wkwebview(cookie: &NSHTTPCookie) -> cookie::Cookie<'static> {
     let name = cookie.name().to_string();
     let value = cookie.value().to_string();
@@ -1108,30 +2078,21 @@ r#"Object.defineProperty(window, 'ipc', {
   }
 
   pub fn cookies_for_url(&self, url: &str) -> Result<Vec<cookie::Cookie<'static>>> {
-    let url = url::Url::parse(url)?;
+    self
+      .cookies_matching_url(url)
+      .map(|cookies| cookies.into_iter().map(|(_, cookie)| cookie).collect())
+  }
 
-    self.cookies().map(|cookies| {
-      cookies.into_iter().filter(|cookie: &cookie::Cookie| {
-        let secure = cookie.secure().unwrap_or_default();
-        // domain is the same
-        cookie.domain() == url.domain()
-          // and one of
-          && (
-            // cookie is secure and url is https
-            (secure && url.scheme() == "https") ||
-            // or cookie is secure and is localhost
-            (
-              secure && url.scheme() == "http" &&
-              (url.domain() == Some("localhost") || url.domain().and_then(|d| Ipv4Addr::from_str(d).ok()).map(|ip| ip.is_loopback()).unwrap_or(false))
-            ) ||
-            // or cookie is not secure
-            (!secure)
-          )
-      }).collect()
+  pub fn cookies(&self) -> Result<Vec<cookie::Cookie<'static>>> {
+    self.all_cookies().map(|cookies| {
+      cookies
+        .iter()
+        .map(|cookie| unsafe { Self::cookie_from_wkwebview(cookie) })
+        .collect()
     })
   }
 
-  pub fn cookies(&self) -> Result<Vec<cookie::Cookie<'static>>> {
+  fn all_cookies(&self) -> Result<Vec<Retained<NSHTTPCookie>>> {
     let (tx, rx) = std::sync::mpsc::channel();
 
     unsafe {
@@ -1140,13 +2101,7 @@ r#"Object.defineProperty(window, 'ipc', {
         .httpCookieStore()
         .getAllCookies(&block2::RcBlock::new(
           move |cookies: NonNull<NSArray<NSHTTPCookie>>| {
-            let cookies = cookies.as_ref();
-            let cookies = cookies
-              .to_vec()
-              .into_iter()
-              .map(|cookie| Self::cookie_from_wkwebview(&cookie))
-              .collect();
-            let _ = tx.send(cookies);
+            let _ = tx.send(cookies.as_ref().to_vec());
           },
         ));
 
@@ -1154,6 +2109,29 @@ r#"Object.defineProperty(window, 'ipc', {
     }
   }
 
+  /// The cookies sent with requests to `url`, along with the `NSHTTPCookie` they were read from.
+  #[allow(clippy::type_complexity)]
+  fn cookies_matching_url(
+    &self,
+    url: &str,
+  ) -> Result<Vec<(Retained<NSHTTPCookie>, cookie::Cookie<'static>)>> {
+    let url = url::Url::parse(url)?;
+    let host = url.host_str().unwrap_or_default();
+
+    self.all_cookies().map(|cookies| {
+      cookies
+        .into_iter()
+        .filter_map(|ns_cookie| {
+          let cookie = unsafe { Self::cookie_from_wkwebview(&ns_cookie) };
+          // `cookie::Cookie` strips the leading `.` domain cookies keep on `NSHTTPCookie`
+          let host_only = unsafe { !ns_cookie.domain().to_string().starts_with('.') };
+          crate::cookies::matches_url(&cookie, host_only, url.scheme(), host, url.path())
+            .then_some((ns_cookie, cookie))
+        })
+        .collect()
+    })
+  }
+
   pub fn set_cookie(&self, cookie: &cookie::Cookie<'_>) -> Result<()> {
     let (tx, rx) = std::sync::mpsc::channel();
 
@@ -1190,6 +2168,31 @@ r#"Object.defineProperty(window, 'ipc', {
     }
   }
 
+  pub fn delete_cookies_for_url(&self, url: &str, name: &str) -> Result<()> {
+    for (ns_cookie, _) in self
+      .cookies_matching_url(url)?
+      .into_iter()
+      .filter(|(_, cookie)| cookie.name() == name)
+    {
+      // delete the `NSHTTPCookie` itself, converting back would lose whether it is host-only
+      let (tx, rx) = std::sync::mpsc::channel();
+      unsafe {
+        self
+          .data_store
+          .httpCookieStore()
+          .deleteCookie_completionHandler(
+            &ns_cookie,
+            Some(&block2::RcBlock::new(move || {
+              let _ = tx.send(());
+            })),
+          );
+        wait_for_blocking_operation(rx)?;
+      }
+    }
+
+    Ok(())
+  }
+
   #[cfg(target_os = "macos")]
   pub(crate) fn reparent(&self, window: *mut NSWindow) -> crate::Result<()> {
     unsafe {
@@ -1263,11 +2266,129 @@ r#"Object.defineProperty(window, 'ipc', {
       WKWebsiteDataStore::removeDataStoreForIdentifier_completionHandler(&identifier, &block, mtm);
     }
   }
+
+  /// Compiles a content blocker JSON into a rule list stored under `identifier`.
+  ///
+  /// Needs to run on main thread, the run loop is spun until the compilation is done.
+  pub fn compile_content_rule_list(
+    identifier: &str,
+    json: &str,
+  ) -> Result<Retained<WKContentRuleList>> {
+    if MainThreadMarker::new().is_none() {
+      return Err(Error::NotMainThread);
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let block = block2::RcBlock::new(
+      move |rule_list: *mut WKContentRuleList, error: *mut NSError| {
+        let result = match Retained::retain(rule_list) {
+          Some(rule_list) => Ok(rule_list),
+          None => Err(Error::ContentRulesFailed(error.as_ref().map_or_else(
+            || "no rule list was created".into(),
+            |error| error.localizedDescription().to_string(),
+          ))),
+        };
+        let _ = tx.send(result);
+      },
+    );
+
+    unsafe {
+      let store: Option<Retained<WKContentRuleListStore>> =
+        objc2::msg_send![WKContentRuleListStore::class(), defaultStore];
+      let Some(store) = store else {
+        return Err(Error::ContentRulesFailed(
+          "content blocking requires macOS 10.13 or iOS 11".into(),
+        ));
+      };
+      let identifier = NSString::from_str(identifier);
+      let json = NSString::from_str(json);
+      let _: () = objc2::msg_send![
+        &store,
+        compileContentRuleListForIdentifier: &*identifier,
+        encodedContentRuleList: &*json,
+        completionHandler: &*block,
+      ];
+
+      wait_for_blocking_operation_timeout(rx, Duration::from_secs(30), "content rules")?
+    }
+  }
+}
+
+/// Evaluates `js`, passing its result as JSON to `callback`.
+pub(crate) fn evaluate_javascript(
+  webview: &WKWebView,
+  js: &str,
+  callback: Option<impl FnOnce(String) + Send + 'static>,
+) {
+  // Safety: objc runtime calls are unsafe
+  unsafe {
+    #[cfg(feature = "tracing")]
+    let span = Mutex::new(Some(tracing::debug_span!("wry::eval").entered()));
+
+    // we need to check if the callback exists outside the handler otherwise it's a segfault
+    if let Some(callback) = callback {
+      let callback = RefCell::new(Some(callback));
+      let handler = block2::RcBlock::new(move |val: *mut AnyObject, err: *mut NSError| {
+        #[cfg(feature = "tracing")]
+        span.lock().unwrap().take();
+
+        let mut result = String::new();
+
+        if let Some(err) = err.as_ref() {
+          let message = err
+            .userInfo()
+            .objectForKey(ns_string!("WKJavaScriptExceptionMessage"))
+            .and_then(|message| message.downcast::<NSString>().ok())
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| err.localizedDescription().to_string());
+          result = crate::script::error_json(&message);
+        } else if !val.is_null() {
+          let json_ns_data = NSJSONSerialization::dataWithJSONObject_options_error(
+            &*val,
+            objc2_foundation::NSJSONWritingOptions::FragmentsAllowed,
+          )
+          .unwrap();
+          let json_string = NSString::alloc();
+          let json_string =
+            NSString::initWithData_encoding(json_string, &json_ns_data, NSUTF8StringEncoding)
+              .unwrap();
+
+          result = json_string.to_string();
+        }
+
+        if let Some(callback) = callback.take() {
+          callback(result);
+        }
+      });
+
+      webview.evaluateJavaScript_completionHandler(&NSString::from_str(js), Some(&handler));
+    } else {
+      #[cfg(feature = "tracing")]
+      let handler = Some(block2::RcBlock::new(
+        move |_val: *mut AnyObject, _err: *mut NSError| {
+          span.lock().unwrap().take();
+        },
+      ));
+      #[cfg(not(feature = "tracing"))]
+      let handler: Option<block2::RcBlock<dyn Fn(*mut AnyObject, *mut NSError)>> = None;
+
+      webview.evaluateJavaScript_completionHandler(&NSString::from_str(js), handler.as_deref());
+    }
+  }
+}
+
+fn user_script_injection_time(injection_time: InjectionTime) -> WKUserScriptInjectionTime {
+  match injection_time {
+    InjectionTime::DocumentStart => WKUserScriptInjectionTime::AtDocumentStart,
+    InjectionTime::DocumentEnd => WKUserScriptInjectionTime::AtDocumentEnd,
+  }
 }
 
 pub fn url_from_webview(webview: &WKWebView) -> Result<String> {
-  let url_obj = unsafe { webview.URL().unwrap() };
-  let absolute_url = unsafe { url_obj.absoluteString().unwrap() };
+  // The URL is nil before the first navigation
+  let Some(absolute_url) = (unsafe { webview.URL().and_then(|url| url.absoluteString()) }) else {
+    return Ok(String::new());
+  };
 
   let bytes = {
     let bytes: *const c_char = absolute_url.UTF8String();
@@ -1317,15 +2438,26 @@ impl Drop for InnerWebView {
   fn drop(&mut self) {
     WEBVIEW_STATE.write().unwrap().remove(&self.id);
 
+    // Remove the KVO observers while the webview is still alive
+    self.document_title_changed_observer.take();
+    self.url_changed_observer.take();
+    self.load_progress_observer.take();
+    self.fullscreen_observer.take();
+
     // We need to drop handler closures here
     unsafe {
-      if let Some(ipc_handler) = self.ipc_handler_delegate.take() {
-        let ipc = ns_string!(IPC_MESSAGE_HANDLER_NAME);
-        // this will decrease the retain count of the ipc handler and trigger the drop
-        ipc_handler
-          .ivars()
-          .controller
-          .removeScriptMessageHandlerForName(ipc);
+      #[cfg(target_os = "macos")]
+      let context_menu_delegate = self.context_menu_delegate.take();
+      #[cfg(target_os = "ios")]
+      let context_menu_delegate = None;
+      // The handlers are kept for the other webviews sharing the user content controller
+      let message_handler_delegates = self.message_handler_delegates.take().into_values();
+      for delegate in [self.ipc_handler_delegate.take(), context_menu_delegate]
+        .into_iter()
+        .flatten()
+        .chain(message_handler_delegates)
+      {
+        delegate.remove(Retained::as_ptr(&self.webview).cast());
       }
 
       // Remove webview from window's NSView before dropping.
@@ -1336,13 +2468,61 @@ impl Drop for InnerWebView {
   }
 }
 
+#[cfg(target_os = "macos")]
+type SnapshotImage = NSImage;
+#[cfg(target_os = "ios")]
+type SnapshotImage = UIImage;
+
+#[cfg(target_os = "macos")]
+unsafe fn png_from_image(image: &NSImage) -> Option<Vec<u8>> {
+  let tiff = image.TIFFRepresentation()?;
+  let bitmap = NSBitmapImageRep::imageRepWithData(&tiff)?;
+  let png =
+    bitmap.representationUsingType_properties(NSBitmapImageFileType::PNG, &NSDictionary::new())?;
+  Some(png.to_vec())
+}
+
+#[cfg(target_os = "ios")]
+unsafe fn png_from_image(image: &UIImage) -> Option<Vec<u8>> {
+  UIImagePNGRepresentation(image).map(|png| png.to_vec())
+}
+
 /// Converts from wry screen-coordinates to macOS screen-coordinates.
 /// wry: top-left is (0, 0) and y increasing downwards
 /// macOS:
 ///   Default coordinate system: a bottom-left is (0, 0) and y increasing upwards.
 ///   Flipped coordinate system: a top-left is (0, 0) and y increasing downwards.
 #[allow(dead_code)]
-unsafe fn window_position(view: &NSView, x: i32, y: i32, height: f64) -> CGPoint {
+/// Sets the proxy of all the webviews of `data_store`, `None` uses the system proxy.
+#[cfg(feature = "mac-proxy")]
+unsafe fn set_proxy_configurations(
+  data_store: &WKWebsiteDataStore,
+  proxy: Option<ProxyConfig>,
+) -> Result<()> {
+  let proxies: Retained<NSArray<NSObject>> = match proxy {
+    Some(proxy) => {
+      let auth = proxy.endpoint().auth.clone();
+      let proxy_config = match proxy {
+        ProxyConfig::Http(endpoint) => nw_proxy_config_create_http_connect(
+          nw_endpoint_t::try_from(endpoint)?,
+          std::ptr::null_mut(),
+        ),
+        ProxyConfig::Socks5(endpoint) => {
+          nw_proxy_config_create_socksv5(nw_endpoint_t::try_from(endpoint)?)
+        }
+      };
+      if let Some(auth) = auth {
+        set_proxy_auth(proxy_config, auth)?;
+      }
+      NSArray::arrayWithObject(&*proxy_config)
+    }
+    None => NSArray::new(),
+  };
+  data_store.setValue_forKey(Some(&proxies), ns_string!("proxyConfigurations"));
+  Ok(())
+}
+
+unsafe fn window_position(view: &NSView, x: f64, y: f64, height: f64) -> CGPoint {
   let is_flipped = {
     #[cfg(target_os = "macos")]
     {
@@ -1355,20 +2535,29 @@ unsafe fn window_position(view: &NSView, x: i32, y: i32, height: f64) -> CGPoint
   };
 
   if is_flipped {
-    CGPoint::new(x as f64, y as f64)
+    CGPoint::new(x, y)
   } else {
     let frame: CGRect = view.frame();
-    CGPoint::new(x as f64, frame.size.height - y as f64 - height)
+    CGPoint::new(x, frame.size.height - y - height)
   }
 }
 
 /// Wait synchronously for the NSRunLoop to run until a receiver has a message.
 unsafe fn wait_for_blocking_operation<T>(rx: std::sync::mpsc::Receiver<T>) -> Result<T> {
+  wait_for_blocking_operation_timeout(rx, Duration::from_secs(1), "cookies response")
+}
+
+/// Like [`wait_for_blocking_operation`], giving up after `timeout` waiting for `what`.
+unsafe fn wait_for_blocking_operation_timeout<T>(
+  rx: std::sync::mpsc::Receiver<T>,
+  timeout: Duration,
+  what: &str,
+) -> Result<T> {
   let interval = Duration::from_millis(2);
   let interval_as_secs = interval.as_secs_f64();
-  let limit = 1.;
+  let limit = timeout.as_secs_f64();
   let mut elapsed = 0.;
-  // run event loop until we get the response back, blocking for at most 3 seconds
+  // run event loop until we get the response back, blocking for at most `timeout`
   loop {
     if let Ok(response) = rx.recv_timeout(interval) {
       return Ok(response);
@@ -1377,7 +2566,7 @@ unsafe fn wait_for_blocking_operation<T>(rx: std::sync::mpsc::Receiver<T>) -> Re
     if elapsed >= limit {
       return Err(Error::Io(std::io::Error::new(
         std::io::ErrorKind::TimedOut,
-        "timed out waiting for cookies response",
+        format!("timed out waiting for {what}"),
       )));
     }
 
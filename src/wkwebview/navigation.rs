@@ -1,7 +1,12 @@
-use objc2::DeclaredClass;
-use objc2_foundation::{NSObjectProtocol, NSString};
+use objc2::{rc::Retained, DeclaredClass};
+use objc2_foundation::{
+  ns_string, NSError, NSObjectProtocol, NSString, NSURLAuthenticationChallenge,
+  NSURLAuthenticationMethodClientCertificate, NSURLAuthenticationMethodServerTrust,
+  NSURLCredential, NSURLCredentialPersistence, NSURLRequest, NSURLSessionAuthChallengeDisposition,
+  NSURL,
+};
 use objc2_web_kit::{
-  WKNavigation, WKNavigationAction, WKNavigationActionPolicy, WKNavigationResponse,
+  WKContentMode, WKNavigation, WKNavigationAction, WKNavigationActionPolicy, WKNavigationResponse,
   WKNavigationResponsePolicy,
 };
 
@@ -10,9 +15,16 @@ use crate::wkwebview::ios::WKWebView::WKWebView;
 #[cfg(target_os = "macos")]
 use objc2_web_kit::WKWebView;
 
-use crate::PageLoadEvent;
+use crate::{
+  AuthChallenge, AuthChallengeKind, CertRequest, ContentMode, NavigationDecision, NavigationError,
+  PageLoadEvent,
+};
 
-use super::class::wry_navigation_delegate::WryNavigationDelegate;
+use super::{
+  certificate::{certificate_credential, keychain_identities, untrusted_server_certificate},
+  class::wry_navigation_delegate::WryNavigationDelegate,
+  evaluate_javascript,
+};
 
 pub(crate) fn did_commit_navigation(
   this: &WryNavigationDelegate,
@@ -26,12 +38,9 @@ pub(crate) fn did_commit_navigation(
     }
 
     // Inject scripts
-    let mut pending_scripts = this.ivars().pending_scripts.lock().unwrap();
-    if let Some(scripts) = &*pending_scripts {
-      for script in scripts {
-        webview.evaluateJavaScript_completionHandler(&NSString::from_str(script), None);
-      }
-      *pending_scripts = None;
+    let pending_scripts = this.ivars().pending_scripts.lock().unwrap().take();
+    for (script, callback) in pending_scripts.into_iter().flatten() {
+      evaluate_javascript(webview, &script, callback);
     }
   }
 }
@@ -44,14 +53,57 @@ pub(crate) fn did_finish_navigation(
   if let Some(on_page_load) = &this.ivars().on_page_load_handler {
     on_page_load(PageLoadEvent::Finished);
   }
+
+  // Only the first finished navigation is reported
+  let on_first_paint = this.ivars().on_first_paint_handler.borrow_mut().take();
+  if let Some(on_first_paint) = on_first_paint {
+    on_first_paint();
+  }
 }
 
-// Navigation handler
+pub(crate) fn did_fail_navigation(
+  this: &WryNavigationDelegate,
+  webview: &WKWebView,
+  _navigation: &WKNavigation,
+  error: &NSError,
+  provisional: bool,
+) {
+  let Some(on_navigation_error) = &this.ivars().on_navigation_error_handler else {
+    return;
+  };
+
+  // Provisional navigations fail before the webview URL changes, so prefer the failing URL
+  let url = error
+    .userInfo()
+    .objectForKey(ns_string!("NSErrorFailingURLStringKey"))
+    .and_then(|url| url.downcast::<NSString>().ok())
+    .or_else(|| unsafe { webview.URL()?.absoluteString() })
+    .map(|url| url.to_string())
+    .unwrap_or_default();
+
+  on_navigation_error(NavigationError {
+    url,
+    code: error.code() as i64,
+    message: error.localizedDescription().to_string(),
+    provisional,
+  });
+}
+
+pub(crate) fn content_mode(mode: ContentMode) -> WKContentMode {
+  match mode {
+    ContentMode::Recommended => WKContentMode::Recommended,
+    ContentMode::Mobile => WKContentMode::Mobile,
+    ContentMode::Desktop => WKContentMode::Desktop,
+  }
+}
+
+// Navigation handler, `handler` also receives the content mode the navigation must use if it
+// changed
 pub(crate) fn navigation_policy(
   this: &WryNavigationDelegate,
-  _webview: &WKWebView,
+  webview: &WKWebView,
   action: &WKNavigationAction,
-  handler: &block2::Block<dyn Fn(WKNavigationActionPolicy)>,
+  handler: &dyn Fn(WKNavigationActionPolicy, Option<ContentMode>),
 ) {
   unsafe {
     // shouldPerformDownload is only available on macOS 11.3+
@@ -67,15 +119,24 @@ pub(crate) fn navigation_policy(
     if should_download {
       let has_download_handler = this.ivars().has_download_handler;
       if has_download_handler {
-        (*handler).call((WKNavigationActionPolicy::Download,));
+        handler(WKNavigationActionPolicy::Download, None);
       } else {
-        (*handler).call((WKNavigationActionPolicy::Cancel,));
+        handler(WKNavigationActionPolicy::Cancel, None);
       }
     } else {
       let function = &this.ivars().navigation_policy_function;
       match function(url.to_string()) {
-        true => (*handler).call((WKNavigationActionPolicy::Allow,)),
-        false => (*handler).call((WKNavigationActionPolicy::Cancel,)),
+        NavigationDecision::Allow => handler(WKNavigationActionPolicy::Allow, None),
+        NavigationDecision::Deny => handler(WKNavigationActionPolicy::Cancel, None),
+        NavigationDecision::AllowWithContentMode(mode) => {
+          handler(WKNavigationActionPolicy::Allow, Some(mode))
+        }
+        NavigationDecision::Redirect(url) => {
+          handler(WKNavigationActionPolicy::Cancel, None);
+          if let Some(url) = NSURL::URLWithString(&NSString::from_str(&url)) {
+            webview.loadRequest(&NSURLRequest::requestWithURL(&url));
+          }
+        }
       };
     }
   }
@@ -113,3 +174,109 @@ pub(crate) fn web_content_process_did_terminate(
     on_web_content_process_terminate();
   }
 }
+
+// Authentication handler
+pub(crate) fn did_receive_authentication_challenge(
+  this: &WryNavigationDelegate,
+  _webview: &WKWebView,
+  challenge: &NSURLAuthenticationChallenge,
+  handler: &block2::Block<dyn Fn(NSURLSessionAuthChallengeDisposition, *mut NSURLCredential)>,
+) {
+  unsafe {
+    let protection_space = challenge.protectionSpace();
+    let method = protection_space.authenticationMethod();
+
+    if method.isEqualToString(NSURLAuthenticationMethodServerTrust) {
+      if let Some(tls_error_handler) = &this.ivars().tls_error_handler {
+        let host = protection_space.host().to_string();
+        match untrusted_server_certificate(&protection_space) {
+          // WebKit keeps the accepted trust until the webview is dropped
+          Some((info, credential)) if tls_error_handler(&host, &info) => (*handler).call((
+            NSURLSessionAuthChallengeDisposition::UseCredential,
+            Retained::as_ptr(&credential) as *mut _,
+          )),
+          _ => (*handler).call((
+            NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+            std::ptr::null_mut(),
+          )),
+        }
+        return;
+      }
+    }
+
+    if method.isEqualToString(NSURLAuthenticationMethodClientCertificate) {
+      if let Some(client_certificate_handler) = &this.ivars().client_certificate_handler {
+        let certificate = client_certificate_handler(CertRequest {
+          host: protection_space.host().to_string(),
+          port: protection_space.port() as u16,
+          allowed_issuers: Vec::new(),
+          certificates: keychain_identities(protection_space.distinguishedNames().as_deref()),
+        });
+        // The default handling continues without a certificate
+        match certificate {
+          Some(certificate) => (*handler).call((
+            NSURLSessionAuthChallengeDisposition::UseCredential,
+            Retained::as_ptr(&certificate_credential(&certificate)) as *mut _,
+          )),
+          None => (*handler).call((
+            NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+            std::ptr::null_mut(),
+          )),
+        }
+        return;
+      }
+    }
+
+    let kind = if protection_space.isProxy() {
+      AuthChallengeKind::Proxy
+    } else {
+      AuthChallengeKind::Server
+    };
+    // Server trust and client certificate challenges keep the default handling
+    let auth_handler = this
+      .ivars()
+      .auth_handler
+      .as_ref()
+      .and_then(|auth_handler| auth_handler.handler(kind))
+      .filter(|_| {
+        !method.isEqualToString(NSURLAuthenticationMethodServerTrust)
+          && !method.isEqualToString(NSURLAuthenticationMethodClientCertificate)
+      });
+    let Some(auth_handler) = auth_handler else {
+      (*handler).call((
+        NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+        std::ptr::null_mut(),
+      ));
+      return;
+    };
+
+    let auth_challenge = AuthChallenge {
+      kind,
+      host: protection_space.host().to_string(),
+      port: protection_space.port() as u16,
+      realm: protection_space
+        .realm()
+        .map(|realm| realm.to_string())
+        .unwrap_or_default(),
+      previous_failure_count: challenge.previousFailureCount() as u32,
+    };
+
+    match auth_handler(auth_challenge) {
+      Some(credentials) => {
+        let credential = NSURLCredential::credentialWithUser_password_persistence(
+          &NSString::from_str(&credentials.username),
+          &NSString::from_str(&credentials.password),
+          NSURLCredentialPersistence::ForSession,
+        );
+        (*handler).call((
+          NSURLSessionAuthChallengeDisposition::UseCredential,
+          Retained::as_ptr(&credential) as *mut _,
+        ));
+      }
+      None => (*handler).call((
+        NSURLSessionAuthChallengeDisposition::CancelAuthenticationChallenge,
+        std::ptr::null_mut(),
+      )),
+    }
+  }
+}
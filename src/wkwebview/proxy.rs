@@ -5,7 +5,10 @@
 use objc2_foundation::NSObject;
 use std::ffi::{c_char, CString};
 
-use crate::{proxy::ProxyEndpoint, Error};
+use crate::{
+  proxy::{ProxyAuth, ProxyEndpoint},
+  Error,
+};
 
 #[allow(non_camel_case_types)]
 pub type nw_endpoint_t = *mut NSObject;
@@ -22,6 +25,22 @@ extern "C" {
     proxy_endpoint: nw_endpoint_t,
     proxy_tls_options: nw_protocol_options_t,
   ) -> nw_proxy_config_t;
+  fn nw_proxy_config_set_username_and_password(
+    proxy_config: nw_proxy_config_t,
+    username: *const c_char,
+    password: *const c_char,
+  );
+}
+
+/// Sets the credentials of `proxy_config` from `auth`.
+pub unsafe fn set_proxy_auth(
+  proxy_config: nw_proxy_config_t,
+  auth: ProxyAuth,
+) -> Result<(), Error> {
+  let username = CString::new(auth.username).map_err(|_| Error::ProxyEndpointCreationFailed)?;
+  let password = CString::new(auth.password).map_err(|_| Error::ProxyEndpointCreationFailed)?;
+  nw_proxy_config_set_username_and_password(proxy_config, username.as_ptr(), password.as_ptr());
+  Ok(())
 }
 
 impl TryFrom<ProxyEndpoint> for nw_endpoint_t {